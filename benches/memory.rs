@@ -0,0 +1,163 @@
+//! Peak memory of the main API calls, measured with a counting global allocator.
+//!
+//! ```text
+//! cargo bench --bench memory [filter]
+//! ```
+//!
+//! Every call is run once while all allocations are tracked. The report lists the peak number of bytes allocated at the same time during the call (on top of what was allocated before), the total number of bytes allocated and the number of allocations. Each call has a budget for its peak, so memory regressions make the benchmark fail: lower a budget whenever an optimization lands, to protect it. Only calls whose name contains the filter are run.
+
+use sainte_lague::constraints::{Constraint, Rules};
+use sainte_lague::sparse::{self, SparseVotes};
+use sainte_lague::{distribute, Method};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::env;
+use std::hint::black_box;
+use std::process::exit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The system allocator, counting current, peak and total usage.
+struct Counting;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static TOTAL: AtomicUsize = AtomicUsize::new(0);
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(current, Ordering::SeqCst);
+            TOTAL.fetch_add(layout.size(), Ordering::SeqCst);
+            COUNT.fetch_add(1, Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// The memory used by a call.
+struct Usage {
+    peak: usize,
+    total: usize,
+    count: usize,
+}
+
+/// Run `call` and measure its memory usage, including its result.
+fn measure<T, F: FnOnce() -> T>(call: F) -> Usage {
+    let before = CURRENT.load(Ordering::SeqCst);
+    PEAK.store(before, Ordering::SeqCst);
+    let (total, count) = (TOTAL.load(Ordering::SeqCst), COUNT.load(Ordering::SeqCst));
+    let result = black_box(call());
+    let usage = Usage {
+        peak: PEAK.load(Ordering::SeqCst) - before,
+        total: TOTAL.load(Ordering::SeqCst) - total,
+        count: COUNT.load(Ordering::SeqCst) - count,
+    };
+    drop(result);
+    usage
+}
+
+/// A named API call and the most bytes it may use at the same time.
+struct Call {
+    name: &'static str,
+    budget: usize,
+    run: Box<dyn Fn() -> Usage>,
+}
+
+/// Votes of `party_count` parties, all different so there are no ties.
+fn votes(party_count: usize) -> Vec<f64> {
+    (0..party_count)
+        .map(|p| 1_000_000.0 + p as f64 * 7.0)
+        .collect()
+}
+
+fn calls() -> Vec<Call> {
+    vec![
+        // the quotient table of the allocation grows with parties times seats
+        Call {
+            name: "distribute 1000 parties, 1000 seats",
+            budget: 30_000_000,
+            run: Box::new(|| {
+                let votes = votes(1_000);
+                measure(|| distribute(&votes, &1_000, &false))
+            }),
+        },
+        Call {
+            name: "distribute 10 parties, 100 seats",
+            budget: 50_000,
+            run: Box::new(|| {
+                let votes = votes(10);
+                measure(|| distribute(&votes, &100, &false))
+            }),
+        },
+        // only the parties with votes may take up memory, not the whole universe
+        Call {
+            name: "sparse 1M universe, 50 parties, 500 seats",
+            budget: 1_200_000,
+            run: Box::new(|| {
+                let entries = votes(50)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(p, v)| (p * 20_000, v));
+                let votes = SparseVotes::new(1_000_000, entries.collect()).expect("valid entries");
+                measure(|| sparse::distribute(&Method::SainteLague, &votes, &500, &false))
+            }),
+        },
+        // the result itself holds one distribution per seat count
+        Call {
+            name: "sweep 20 parties, 1000 seats",
+            budget: 230_000,
+            run: Box::new(|| {
+                let votes = votes(20);
+                measure(|| Method::SainteLague.sweep_seats(&votes, &1_000))
+            }),
+        },
+        Call {
+            name: "rules 1000 parties, 1000 seats",
+            budget: 30_000_000,
+            run: Box::new(|| {
+                let votes = votes(1_000);
+                let rules = Rules::default().with(Constraint::Threshold(0.0009));
+                measure(|| rules.distribute(&votes, &1_000, &false))
+            }),
+        },
+    ]
+}
+
+fn main() {
+    // cargo passes `--bench`, everything else is a filter
+    let filter = env::args().skip(1).find(|a| !a.starts_with("--"));
+    let mut over_budget = vec![];
+    println!(
+        "{:<44} {:>12} {:>12} {:>12} {:>8}",
+        "call", "peak", "budget", "total", "allocs"
+    );
+    for call in calls() {
+        if let Some(filter) = &filter {
+            if !call.name.contains(filter.as_str()) {
+                continue;
+            }
+        }
+        let usage = (call.run)();
+        println!(
+            "{:<44} {:>12} {:>12} {:>12} {:>8}",
+            call.name, usage.peak, call.budget, usage.total, usage.count
+        );
+        if usage.peak > call.budget {
+            over_budget.push(call.name);
+        }
+    }
+    if !over_budget.is_empty() {
+        eprintln!("over budget: {}", over_budget.join(", "));
+        exit(1);
+    }
+}
@@ -0,0 +1,138 @@
+//! Macro-benchmarks of workloads users actually run, as opposed to single distributions.
+//!
+//! ```text
+//! cargo bench --features experimental --bench workloads [filter]
+//! ```
+//!
+//! Every workload is built once from [synthetic elections](sainte_lague::synthetic), run once to warm up and then timed repeatedly. The report lists the fastest and the median run, since the fastest run is least affected by noise and the median shows how stable the timing is. Only workloads whose name contains the filter are run.
+
+use sainte_lague::constraints::Constraint;
+use sainte_lague::methods::Stationary;
+use sainte_lague::simulation::{simulate, PollAverage};
+use sainte_lague::synthetic::Generator;
+use sainte_lague::system::{Ballots, ElectoralSystem, Tier};
+use sainte_lague::Method;
+use std::env;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// How often each workload is timed.
+const RUNS: usize = 10;
+
+/// A named workload, returning the number of seats or trials it produced, which is passed to [`black_box`] so the work isn't optimized away.
+struct Workload {
+    name: &'static str,
+    run: Box<dyn Fn() -> usize>,
+}
+
+/// A Bundestag-sized parallel election: 299 single-member districts won by plurality and 299 list seats with a threshold of 5 %, for 30 parties. Compensation of district seats isn't modelled by this crate, so this is the closest workload to a full MMP run.
+fn districts_and_list() -> Workload {
+    let generator = Generator {
+        party_count: 30,
+        district_count: 299,
+        region_count: 16,
+        seats: (1, 1),
+        votes: (120_000, 200_000),
+        ..Generator::default()
+    };
+    let election = generator.generate(486);
+    let districts: Vec<Vec<f64>> = election.districts.iter().map(|(v, _)| v.clone()).collect();
+    let ballots = Ballots::new(election.national_votes());
+    let system = ElectoralSystem::new(
+        "Bundestag",
+        Stationary::new(0.5).expect("0.5 is a valid offset"),
+        Tier::National(299),
+    )
+    .with(Constraint::Threshold(0.05))
+    .with_plurality_seats(299)
+    .with_draw_on_tie(true);
+    Workload {
+        name: "299 districts and list",
+        run: Box::new(move || {
+            let outcome = system.distribute_districts(&ballots, &districts);
+            outcome.map_or(0, |o| o.seats().iter().sum())
+        }),
+    }
+}
+
+/// 10 000 simulated polls of a 630-seat parliament with 8 parties.
+fn monte_carlo() -> Workload {
+    let average = PollAverage {
+        shares: vec![0.3, 0.25, 0.15, 0.1, 0.08, 0.05, 0.04, 0.03],
+        sample_size: 2_000.0,
+    };
+    Workload {
+        name: "monte carlo 10k trials",
+        run: Box::new(move || {
+            let summary = simulate(&Method::SainteLague, &average, &630, 10_000, 486);
+            summary.map_or(0, |s| s.trials())
+        }),
+    }
+}
+
+/// A canton-sized parliament of 180 seats in 16 districts of 4 to 20 seats with 15 parties, each district distributed separately among the parties passing a cantonwide threshold of 3 %. Biproportional apportionment isn't implemented by this crate, so this covers the district-level work of a canton without the matrix adjustment.
+fn canton() -> Workload {
+    let generator = Generator {
+        party_count: 15,
+        district_count: 16,
+        region_count: 4,
+        seats: (4, 20),
+        votes: (10_000, 150_000),
+        ..Generator::default()
+    };
+    let election = generator.generate(486);
+    let tier = Tier::Districts(
+        election
+            .districts
+            .iter()
+            .enumerate()
+            .map(|(i, (_, seats))| (format!("district {}", i + 1), *seats))
+            .collect(),
+    );
+    let ballots = Ballots::districts(election.districts.iter().map(|(v, _)| v.clone()).collect());
+    let system = ElectoralSystem::new(
+        "Kantonsrat",
+        Stationary::new(0.5).expect("0.5 is a valid offset"),
+        tier,
+    )
+    .with(Constraint::Threshold(0.03))
+    .with_draw_on_tie(true);
+    Workload {
+        name: "canton districts",
+        run: Box::new(move || {
+            let outcome = system.distribute(&ballots);
+            outcome.map_or(0, |o| o.seats().iter().sum())
+        }),
+    }
+}
+
+fn main() {
+    // cargo passes `--bench`, everything else is a filter
+    let filter = env::args().skip(1).find(|a| !a.starts_with("--"));
+    let workloads = [districts_and_list(), monte_carlo(), canton()];
+    println!("{:<28} {:>12} {:>12}", "workload", "fastest", "median");
+    for workload in workloads.iter() {
+        if let Some(filter) = &filter {
+            if !workload.name.contains(filter.as_str()) {
+                continue;
+            }
+        }
+        // a failing workload would measure the error path only
+        let warmup = black_box((workload.run)());
+        assert!(warmup > 0, "{} failed", workload.name);
+        let mut times: Vec<Duration> = (0..RUNS)
+            .map(|_| {
+                let start = Instant::now();
+                black_box((workload.run)());
+                start.elapsed()
+            })
+            .collect();
+        times.sort();
+        println!(
+            "{:<28} {:>12.3?} {:>12.3?}",
+            workload.name,
+            times[0],
+            times[RUNS / 2]
+        );
+    }
+}
@@ -0,0 +1,313 @@
+//! Alerts when a condition on the distribution starts or stops being true.
+//!
+//! While votes are being counted, the distribution is recalculated after every update, but most updates change nothing anyone reports on. [`Alerts`] evaluates registered [`Predicate`]s after each update and only reports a [`Flip`] when the truth value of one of them changes, e.g. when a coalition gains or loses its majority.
+//!
+//! ```
+//! use sainte_lague::alerts::{Alerts, Flip, Predicate};
+//! use sainte_lague::distribute;
+//!
+//! let mut alerts = Alerts::new();
+//! alerts.add("A governs alone", Predicate::AtLeast { party: 0, seats: 6 });
+//! alerts.add("A+C majority", Predicate::Majority(vec![0, 2]));
+//!
+//! // the first update only establishes the state of each predicate
+//! let seats = distribute(&[5_000.0, 3_000.0, 1_000.0], &10, &false).unwrap();
+//! assert_eq!(alerts.update(&seats), vec![]);
+//! assert_eq!(alerts.state(1), Some(true));
+//!
+//! let seats = distribute(&[5_000.0, 4_500.0, 600.0], &10, &false).unwrap();
+//! assert_eq!(alerts.update(&seats), vec![Flip { alert: 0, label: "A governs alone".to_string(), holds: false }]);
+//! assert_eq!(alerts.state(1), Some(true));
+//! ```
+//!
+//! [`explain`] describes why the distribution changed between two updates in terms of the quotients of the parties.
+//!
+//! ```
+//! use sainte_lague::alerts::explain;
+//! use sainte_lague::Method;
+//!
+//! let changes = explain(&Method::SainteLague, &[5_000.0, 3_000.0, 1_000.0], &[5_000.0, 4_500.0, 600.0], &10).unwrap().unwrap();
+//! assert_eq!(changes.len(), 1);
+//! assert_eq!(changes[0].to_string(), "party 1's 4th quotient overtook party 0's 6th");
+//! ```
+
+use crate::{DistributionError, Method};
+use std::fmt;
+
+/// A condition on the seats of the parties, identified by their indices.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Predicate {
+    /// The party holds at least the given number of seats.
+    AtLeast {
+        /// The index of the party.
+        party: usize,
+        /// The number of seats.
+        seats: usize,
+    },
+    /// The party holds at most the given number of seats.
+    AtMost {
+        /// The index of the party.
+        party: usize,
+        /// The number of seats.
+        seats: usize,
+    },
+    /// The parties together hold more than half of all seats.
+    Majority(Vec<usize>),
+}
+
+impl Predicate {
+    /// Whether the predicate is true for the given seats of each party. Parties that don't exist hold no seats.
+    pub fn holds(&self, seats: &[usize]) -> bool {
+        let seats_of = |party: &usize| seats.get(*party).cloned().unwrap_or(0);
+        match self {
+            Predicate::AtLeast { party, seats } => seats_of(party) >= *seats,
+            Predicate::AtMost { party, seats } => seats_of(party) <= *seats,
+            Predicate::Majority(coalition) => {
+                let total: usize = seats.iter().sum();
+                2 * coalition.iter().map(seats_of).sum::<usize>() > total
+            }
+        }
+    }
+}
+
+/// The change of the truth value of a registered predicate, see [`Alerts::update`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Flip {
+    /// The index of the alert, as returned by [`Alerts::add`].
+    pub alert: usize,
+    /// The label the alert was registered with.
+    pub label: String,
+    /// Whether the predicate holds after the update.
+    pub holds: bool,
+}
+
+/// A set of labelled predicates and their truth values after the last update, see the [module documentation](self).
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct Alerts {
+    alerts: Vec<(String, Predicate, Option<bool>)>,
+}
+
+impl Alerts {
+    /// Create an empty set of alerts.
+    pub fn new() -> Alerts {
+        Alerts::default()
+    }
+
+    /// Register a predicate under the given label and return the index of the new alert. The predicate is first evaluated on the next update.
+    pub fn add(&mut self, label: &str, predicate: Predicate) -> usize {
+        self.alerts.push((label.to_string(), predicate, None));
+        self.alerts.len() - 1
+    }
+
+    /// The number of registered alerts.
+    pub fn len(&self) -> usize {
+        self.alerts.len()
+    }
+
+    /// Whether no alerts are registered.
+    pub fn is_empty(&self) -> bool {
+        self.alerts.is_empty()
+    }
+
+    /// Whether the predicate of the alert with index `alert` held after the last update, or [`None`] if it hasn't been evaluated yet or doesn't exist.
+    pub fn state(&self, alert: usize) -> Option<bool> {
+        self.alerts.get(alert).and_then(|(_, _, state)| *state)
+    }
+
+    /// Evaluate all predicates on the new seats of each party and return the alerts whose predicate changed its truth value, in the order they were registered. Predicates evaluated for the first time never flip.
+    pub fn update(&mut self, seats: &[usize]) -> Vec<Flip> {
+        let mut flips = vec![];
+        for (alert, (label, predicate, state)) in self.alerts.iter_mut().enumerate() {
+            let holds = predicate.holds(seats);
+            if *state == Some(!holds) {
+                flips.push(Flip {
+                    alert,
+                    label: label.clone(),
+                    holds,
+                });
+            }
+            *state = Some(holds);
+        }
+        flips
+    }
+}
+
+/// A seat that changed hands between two updates, see [`explain`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Overtake {
+    /// The index of the party that won the seat.
+    pub winner: usize,
+    /// The seat number of the winning party whose quotient won the seat, starting at 1.
+    pub winner_seat: usize,
+    /// The index of the party that lost the seat.
+    pub loser: usize,
+    /// The seat number of the losing party whose quotient no longer wins a seat, starting at 1.
+    pub loser_seat: usize,
+}
+
+/// E.g. `party 2's 12th quotient overtook party 3's 9th`.
+impl fmt::Display for Overtake {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "party {}'s {} quotient overtook party {}'s {}",
+            self.winner,
+            ordinal(self.winner_seat),
+            self.loser,
+            ordinal(self.loser_seat)
+        )
+    }
+}
+
+/// The seats that changed hands when the votes changed from `previous` to `current`, with the same `seat_count` before and after. Every seat a party gained is paired with a seat another party lost: the strongest newly winning quotient is paired with the weakest quotient that no longer wins, and so on. The changes are ordered from the strongest to the weakest winning quotient. Thresholds and other [constraints](crate::constraints) are not taken into account.
+///
+/// Fails with the same errors as [`Method::distribute`] for either votes, ties are not resolved. Returns `Ok(None)` if the votes contain different numbers of parties.
+pub fn explain(
+    method: &Method,
+    previous: &[f64],
+    current: &[f64],
+    seat_count: &usize,
+) -> Result<Option<Vec<Overtake>>, DistributionError> {
+    if previous.len() != current.len() {
+        return Ok(None);
+    }
+    let before = method.distribute(previous, seat_count, &false)?;
+    let after = method.distribute(current, seat_count, &false)?;
+    // (quotient in the current votes, party, seat number)
+    let mut won = vec![];
+    let mut lost = vec![];
+    for (party, ((b, a), v)) in before.iter().zip(after.iter()).zip(current).enumerate() {
+        for seat in (*b + 1)..=*a {
+            won.push((v / method.divisor(seat - 1), party, seat));
+        }
+        for seat in (*a + 1)..=*b {
+            lost.push((v / method.divisor(seat - 1), party, seat));
+        }
+    }
+    // among equal quotients, later seats of a party are the weaker ones
+    won.sort_by(|x, y| y.0.total_cmp(&x.0).then(x.2.cmp(&y.2)).then(x.1.cmp(&y.1)));
+    lost.sort_by(|x, y| x.0.total_cmp(&y.0).then(y.2.cmp(&x.2)).then(x.1.cmp(&y.1)));
+    Ok(Some(
+        won.iter()
+            .zip(lost.iter())
+            .map(|(w, l)| Overtake {
+                winner: w.1,
+                winner_seat: w.2,
+                loser: l.1,
+                loser_seat: l.2,
+            })
+            .collect(),
+    ))
+}
+
+/// The English ordinal of `n`, e.g. `21st`.
+pub(crate) fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{explain, ordinal, Alerts, Flip, Overtake, Predicate};
+    use crate::{DistributionError, Method};
+
+    #[test]
+    fn predicates() {
+        let seats = [3, 2, 1];
+        assert!(Predicate::AtLeast { party: 0, seats: 3 }.holds(&seats));
+        assert!(!Predicate::AtLeast { party: 1, seats: 3 }.holds(&seats));
+        assert!(Predicate::AtMost { party: 7, seats: 0 }.holds(&seats));
+        assert!(!Predicate::Majority(vec![0]).holds(&seats));
+        assert!(Predicate::Majority(vec![0, 2]).holds(&seats));
+        assert!(!Predicate::Majority(vec![]).holds(&[]));
+    }
+
+    #[test]
+    fn flips() {
+        let mut alerts = Alerts::new();
+        assert!(alerts.is_empty());
+        assert_eq!(
+            alerts.add("first", Predicate::AtLeast { party: 0, seats: 2 }),
+            0
+        );
+        assert_eq!(alerts.update(&[1, 1]), vec![]);
+        assert_eq!(alerts.add("second", Predicate::Majority(vec![1])), 1);
+        assert_eq!(
+            (alerts.len(), alerts.state(0), alerts.state(1)),
+            (2, Some(false), None)
+        );
+
+        // the second alert is evaluated for the first time and doesn't flip
+        let flip = |alert: usize, label: &str, holds: bool| Flip {
+            alert,
+            label: label.to_string(),
+            holds,
+        };
+        assert_eq!(alerts.update(&[2, 0]), vec![flip(0, "first", true)]);
+        assert_eq!(alerts.update(&[2, 0]), vec![]);
+        assert_eq!(
+            alerts.update(&[0, 3]),
+            vec![flip(0, "first", false), flip(1, "second", true)]
+        );
+        assert_eq!(alerts.state(2), None);
+    }
+
+    #[test]
+    fn explanations() {
+        let unchanged = explain(&Method::SainteLague, &[3.0, 2.0], &[3.1, 2.0], &5);
+        assert_eq!(unchanged, Ok(Some(vec![])));
+
+        let changes = explain(
+            &Method::SainteLague,
+            &[100.0, 0.0, 0.0],
+            &[0.0, 60.0, 40.0],
+            &3,
+        );
+        assert_eq!(
+            changes,
+            Ok(Some(vec![
+                Overtake {
+                    winner: 1,
+                    winner_seat: 1,
+                    loser: 0,
+                    loser_seat: 3
+                },
+                Overtake {
+                    winner: 2,
+                    winner_seat: 1,
+                    loser: 0,
+                    loser_seat: 2
+                },
+                Overtake {
+                    winner: 1,
+                    winner_seat: 2,
+                    loser: 0,
+                    loser_seat: 1
+                },
+            ]))
+        );
+        assert_eq!(
+            explain(&Method::SainteLague, &[1.0, 1.0], &[2.0, 1.0], &1),
+            Err(DistributionError::Tied)
+        );
+        assert_eq!(
+            explain(&Method::SainteLague, &[1.0, 1.0], &[2.0], &1),
+            Ok(None)
+        );
+
+        let ordinals: Vec<String> = [1, 2, 3, 4, 11, 12, 13, 21, 102, 111]
+            .iter()
+            .map(|n| ordinal(*n))
+            .collect();
+        assert_eq!(
+            ordinals,
+            vec!["1st", "2nd", "3rd", "4th", "11th", "12th", "13th", "21st", "102nd", "111th"]
+        );
+    }
+}
@@ -0,0 +1,190 @@
+//! [`Arbitrary`] implementations for property-based testing with [quickcheck](https://docs.rs/quickcheck), enabled by the `quickcheck` feature.
+//!
+//! Generated values are always valid input: votes are finite and non-negative with at least one party receiving votes, and seat counts are larger than zero. Ties are still possible, so scenarios without draws may fail with [`DistributionError::Tied`](crate::DistributionError::Tied).
+//!
+//! ```
+//! use quickcheck::{quickcheck, TestResult};
+//! use sainte_lague::Scenario;
+//!
+//! fn all_seats_distributed(scenario: Scenario) -> TestResult {
+//!     match scenario.evaluate() {
+//!         Ok(outcome) => TestResult::from_bool(
+//!             outcome.seats().iter().sum::<usize>() == scenario.seat_count,
+//!         ),
+//!         Err(_) => TestResult::discard(),
+//!     }
+//! }
+//! quickcheck(all_seats_distributed as fn(Scenario) -> TestResult);
+//! ```
+
+use crate::election::Election;
+use crate::locale::Locale;
+use crate::party::{Color, Include, Party};
+use crate::{Method, Scenario};
+use quickcheck::{Arbitrary, Gen};
+
+/// Votes of up to `g.size()` parties, at least one of them non-zero.
+fn votes(g: &mut Gen) -> Vec<f64> {
+    let party_count = 1 + usize::arbitrary(g) % g.size().max(1);
+    let mut votes: Vec<f64> = (0..party_count)
+        .map(|_| match u8::arbitrary(g) % 8 {
+            0 => 0.0,
+            1 => f64::from(u16::arbitrary(g)),
+            _ => f64::from(u32::arbitrary(g) % 10_000_000),
+        })
+        .collect();
+    if votes.iter().all(|v| *v == 0.0) {
+        votes[0] = 1.0;
+    }
+    votes
+}
+
+/// Shrink votes by removing parties and halving vote counts, keeping at least one non-zero entry.
+fn shrink_votes(votes: &[f64]) -> Box<dyn Iterator<Item = Vec<f64>>> {
+    let mut candidates = vec![];
+    for i in 0..votes.len() {
+        let mut fewer = votes.to_vec();
+        fewer.remove(i);
+        candidates.push(fewer);
+    }
+    candidates.push(votes.iter().map(|v| (v / 2.0).floor()).collect());
+    Box::new(
+        candidates
+            .into_iter()
+            .filter(|v| v.iter().sum::<f64>() > 0.0),
+    )
+}
+
+impl Arbitrary for Method {
+    fn arbitrary(g: &mut Gen) -> Method {
+        *g.choose(&[
+            Method::SainteLague,
+            Method::DHondt,
+            Method::HuntingtonHill,
+            Method::Adams,
+            Method::Dean,
+            Method::Danish,
+            Method::Imperiali,
+        ])
+        .unwrap()
+    }
+}
+
+impl Arbitrary for Locale {
+    fn arbitrary(g: &mut Gen) -> Locale {
+        *g.choose(&[Locale::English, Locale::German]).unwrap()
+    }
+}
+
+impl Arbitrary for Include {
+    fn arbitrary(g: &mut Gen) -> Include {
+        *g.choose(&[
+            Include::AllParties,
+            Include::PartiesWithVotes,
+            Include::PartiesWithSeats,
+        ])
+        .unwrap()
+    }
+}
+
+impl Arbitrary for Color {
+    fn arbitrary(g: &mut Gen) -> Color {
+        Color::new(u8::arbitrary(g), u8::arbitrary(g), u8::arbitrary(g))
+    }
+}
+
+impl Arbitrary for Party {
+    fn arbitrary(g: &mut Gen) -> Party {
+        let id = u32::arbitrary(g).to_string();
+        let mut party = Party::new(&id, &String::arbitrary(g));
+        party.color = Option::arbitrary(g);
+        party
+    }
+}
+
+impl Arbitrary for Scenario {
+    fn arbitrary(g: &mut Gen) -> Scenario {
+        Scenario {
+            votes: votes(g),
+            seat_count: 1 + usize::arbitrary(g) % 1000,
+            draw_on_tie: bool::arbitrary(g),
+            method: Method::arbitrary(g),
+        }
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Scenario>> {
+        let with_seats = self.clone();
+        let with_votes = self.clone();
+        let seat_counts = self
+            .seat_count
+            .shrink()
+            .filter(|s| *s > 0)
+            .map(move |seat_count| Scenario {
+                seat_count,
+                ..with_seats.clone()
+            });
+        let votes = shrink_votes(&self.votes).map(move |votes| Scenario {
+            votes,
+            ..with_votes.clone()
+        });
+        Box::new(seat_counts.chain(votes))
+    }
+}
+
+impl Arbitrary for Election {
+    fn arbitrary(g: &mut Gen) -> Election {
+        let mut election = Election::new(votes(g));
+        election.blank = f64::from(u16::arbitrary(g));
+        election.invalid = f64::from(u16::arbitrary(g));
+        election.blank_is_valid = bool::arbitrary(g);
+        let ballots = election.ballots_cast();
+        election.electorate =
+            Option::<u32>::arbitrary(g).map(|extra| ballots + f64::from(extra % 1_000_000));
+        election
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::election::Election;
+    use crate::{distribute, Scenario};
+    use quickcheck::{quickcheck, Arbitrary, Gen};
+
+    #[test]
+    fn valid_scenarios() {
+        fn valid(scenario: Scenario) -> bool {
+            scenario.seat_count > 0
+                && scenario.votes.iter().all(|v| v.is_finite() && *v >= 0.0)
+                && scenario.votes.iter().sum::<f64>() > 0.0
+        }
+        quickcheck(valid as fn(Scenario) -> bool);
+    }
+
+    #[test]
+    fn shrunk_scenarios_stay_valid() {
+        let mut g = Gen::new(20);
+        for _ in 0..20 {
+            let scenario = Scenario::arbitrary(&mut g);
+            for shrunk in scenario.shrink().take(50) {
+                assert!(shrunk.seat_count > 0);
+                assert!(shrunk.votes.iter().sum::<f64>() > 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn drawn_scenarios_distribute() {
+        fn distributes(scenario: Scenario) -> bool {
+            distribute(&scenario.votes, &scenario.seat_count, &true).is_ok()
+        }
+        quickcheck(distributes as fn(Scenario) -> bool);
+    }
+
+    #[test]
+    fn turnout() {
+        fn at_most_one(election: Election) -> bool {
+            !matches!(election.turnout(), Some(t) if t > 1.0)
+        }
+        quickcheck(at_most_one as fn(Election) -> bool);
+    }
+}
@@ -0,0 +1,396 @@
+//! Distributions of many scenarios at once, in a struct-of-arrays layout.
+//!
+//! A [`Batch`] stores the votes of all scenarios in one contiguous column, together with the offset of each scenario and its seat count. The seats of a [`BatchOutcome`] use the same layout. Both can be handed to other processes or devices as plain slices, and any [`Kernel`] can distribute them, e.g. a future GPU backend. Every [`Allocator`] is a kernel that distributes the scenarios one after another.
+//!
+//! ```
+//! use sainte_lague::batch::{Batch, Kernel};
+//! use sainte_lague::{DistributionError, Method};
+//!
+//! let mut batch = Batch::new();
+//! batch.push(&[362.0, 318.0, 126.0], 10);
+//! batch.push(&[1.0, 1.0], 1);
+//!
+//! let outcome = Method::SainteLague.run(&batch);
+//! assert_eq!(outcome.seats(0), Ok(&[4, 4, 2][..]));
+//! assert_eq!(outcome.seats(1), Err(&DistributionError::Tied));
+//! assert_eq!(outcome.seats_column(), &[4, 4, 2, 0, 0]);
+//! ```
+//!
+//! For very large batches, [`Kernel::run_into`] writes the seats into a buffer owned by the caller instead, e.g. memory shared with another process or the linear memory of a WebAssembly host, and returns a [`BatchView`] of it. Bindings to other languages only need to turn the caller's pointer and length into a slice, the seats are never copied.
+//!
+//! ```
+//! use sainte_lague::batch::{Batch, Kernel};
+//! use sainte_lague::Method;
+//!
+//! let mut batch = Batch::new();
+//! batch.push(&[362.0, 318.0, 126.0], 10);
+//!
+//! let mut seats = [0; 3];
+//! let view = Method::SainteLague.run_into(&batch, &mut seats).unwrap();
+//! assert_eq!(view.seats(0), Ok(&[4, 4, 2][..]));
+//! assert_eq!(seats, [4, 4, 2]);
+//! ```
+
+use crate::Allocator;
+
+/// Votes and seat counts of many scenarios, see the [module documentation](self).
+#[derive(Clone, PartialEq, Debug)]
+pub struct Batch {
+    votes: Vec<f64>,
+    offsets: Vec<usize>,
+    seat_counts: Vec<usize>,
+}
+
+impl Batch {
+    /// Create an empty batch.
+    pub fn new() -> Batch {
+        Batch {
+            votes: vec![],
+            offsets: vec![0],
+            seat_counts: vec![],
+        }
+    }
+
+    /// Create a batch from its columns: the votes of all scenarios, the offsets at which the votes of each scenario start followed by the total number of votes, and the seat count of each scenario. Returns [`None`] if the columns are inconsistent, i.e. there isn't exactly one more offset than seat counts, or the offsets don't start at zero, decrease or don't end at the number of votes.
+    pub fn from_columns(
+        votes: Vec<f64>,
+        offsets: Vec<usize>,
+        seat_counts: Vec<usize>,
+    ) -> Option<Batch> {
+        let consistent = offsets.len() == seat_counts.len() + 1
+            && offsets.first() == Some(&0)
+            && offsets.last() == Some(&votes.len())
+            && offsets.windows(2).all(|w| w[0] <= w[1]);
+        if !consistent {
+            return None;
+        }
+        Some(Batch {
+            votes,
+            offsets,
+            seat_counts,
+        })
+    }
+
+    /// Append a scenario.
+    pub fn push(&mut self, votes: &[f64], seat_count: usize) {
+        self.votes.extend_from_slice(votes);
+        self.offsets.push(self.votes.len());
+        self.seat_counts.push(seat_count);
+    }
+
+    /// The number of scenarios.
+    pub fn len(&self) -> usize {
+        self.seat_counts.len()
+    }
+
+    /// Whether the batch contains no scenarios.
+    pub fn is_empty(&self) -> bool {
+        self.seat_counts.is_empty()
+    }
+
+    /// The votes of the scenario with the given index. Panics if the index is out of range.
+    pub fn votes(&self, scenario: usize) -> &[f64] {
+        &self.votes[self.offsets[scenario]..self.offsets[scenario + 1]]
+    }
+
+    /// The seat count of the scenario with the given index. Panics if the index is out of range.
+    pub fn seat_count(&self, scenario: usize) -> usize {
+        self.seat_counts[scenario]
+    }
+
+    /// The votes of all scenarios.
+    pub fn votes_column(&self) -> &[f64] {
+        &self.votes
+    }
+
+    /// The offsets of the scenarios in the votes column, followed by its length.
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+
+    /// The seat counts of all scenarios.
+    pub fn seat_counts(&self) -> &[usize] {
+        &self.seat_counts
+    }
+}
+
+impl Default for Batch {
+    fn default() -> Batch {
+        Batch::new()
+    }
+}
+
+/// The seats of all scenarios of a [`Batch`], in the same layout as its votes.
+#[derive(Clone, PartialEq, Debug)]
+pub struct BatchOutcome<E> {
+    seats: Vec<usize>,
+    offsets: Vec<usize>,
+    errors: Vec<Option<E>>,
+}
+
+impl<E> BatchOutcome<E> {
+    /// Create an outcome from its columns: the seats of all scenarios, in the layout of `batch`, and for each scenario the error that occurred, if any. The seats of failed scenarios should be zero. Returns [`None`] if the length of a column doesn't match the batch.
+    pub fn from_columns(
+        batch: &Batch,
+        seats: Vec<usize>,
+        errors: Vec<Option<E>>,
+    ) -> Option<BatchOutcome<E>> {
+        if seats.len() != batch.votes_column().len() || errors.len() != batch.len() {
+            return None;
+        }
+        Some(BatchOutcome {
+            seats,
+            offsets: batch.offsets().to_vec(),
+            errors,
+        })
+    }
+
+    /// The number of scenarios.
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Whether the outcome contains no scenarios.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// The seats of the scenario with the given index, or the error that occurred. Panics if the index is out of range.
+    pub fn seats(&self, scenario: usize) -> Result<&[usize], &E> {
+        match &self.errors[scenario] {
+            Some(e) => Err(e),
+            None => Ok(&self.seats[self.offsets[scenario]..self.offsets[scenario + 1]]),
+        }
+    }
+
+    /// The seats of all scenarios, zero for failed ones.
+    pub fn seats_column(&self) -> &[usize] {
+        &self.seats
+    }
+
+    /// For each scenario, the error that occurred, if any.
+    pub fn errors(&self) -> &[Option<E>] {
+        &self.errors
+    }
+}
+
+/// The seats of all scenarios of a [`Batch`] in a buffer owned by the caller, see [`Kernel::run_into`].
+#[derive(PartialEq, Debug)]
+pub struct BatchView<'a, E> {
+    seats: &'a [usize],
+    offsets: &'a [usize],
+    errors: Vec<Option<E>>,
+}
+
+impl<'a, E> BatchView<'a, E> {
+    /// The number of scenarios.
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Whether the view contains no scenarios.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// The seats of the scenario with the given index, or the error that occurred. Panics if the index is out of range.
+    pub fn seats(&self, scenario: usize) -> Result<&'a [usize], &E> {
+        match &self.errors[scenario] {
+            Some(e) => Err(e),
+            None => Ok(&self.seats[self.offsets[scenario]..self.offsets[scenario + 1]]),
+        }
+    }
+
+    /// The seats of all scenarios, zero for failed ones. This is the buffer passed to [`Kernel::run_into`].
+    pub fn seats_column(&self) -> &'a [usize] {
+        self.seats
+    }
+
+    /// For each scenario, the error that occurred, if any.
+    pub fn errors(&self) -> &[Option<E>] {
+        &self.errors
+    }
+}
+
+/// Anything that distributes the seats of a whole [`Batch`].
+pub trait Kernel {
+    /// The error type of a single scenario.
+    type Error;
+
+    /// Distribute the seats of every scenario in the batch.
+    fn run(&self, batch: &Batch) -> BatchOutcome<Self::Error>;
+
+    /// Same as [`Kernel::run`], but writes the seats into `seats`, in the layout of the votes of the batch, overwriting all of its previous content. Returns [`None`] if the length of `seats` doesn't match the batch.
+    ///
+    /// The default implementation runs the kernel and copies its seats into the buffer. Every [`Allocator`] writes into the buffer directly.
+    fn run_into<'a>(
+        &self,
+        batch: &'a Batch,
+        seats: &'a mut [usize],
+    ) -> Option<BatchView<'a, Self::Error>> {
+        if seats.len() != batch.votes_column().len() {
+            return None;
+        }
+        let outcome = self.run(batch);
+        seats.copy_from_slice(outcome.seats_column());
+        Some(BatchView {
+            seats,
+            offsets: batch.offsets(),
+            errors: outcome.errors,
+        })
+    }
+}
+
+impl<A: Allocator> Kernel for A {
+    type Error = A::Error;
+
+    fn run(&self, batch: &Batch) -> BatchOutcome<A::Error> {
+        let mut seats = vec![0; batch.votes_column().len()];
+        let errors = fill(self, batch, &mut seats);
+        BatchOutcome {
+            seats,
+            offsets: batch.offsets().to_vec(),
+            errors,
+        }
+    }
+
+    fn run_into<'a>(
+        &self,
+        batch: &'a Batch,
+        seats: &'a mut [usize],
+    ) -> Option<BatchView<'a, A::Error>> {
+        if seats.len() != batch.votes_column().len() {
+            return None;
+        }
+        let errors = fill(self, batch, seats);
+        Some(BatchView {
+            seats,
+            offsets: batch.offsets(),
+            errors,
+        })
+    }
+}
+
+/// Write the seats of every scenario into `seats`, zero for failed ones, and return the errors.
+fn fill<A: Allocator>(allocator: &A, batch: &Batch, seats: &mut [usize]) -> Vec<Option<A::Error>> {
+    let mut errors = Vec::with_capacity(batch.len());
+    for scenario in 0..batch.len() {
+        let range = batch.offsets()[scenario]..batch.offsets()[scenario + 1];
+        match allocator.allocate(batch.votes(scenario), &batch.seat_count(scenario)) {
+            Ok(distribution) => {
+                seats[range].copy_from_slice(&distribution);
+                errors.push(None);
+            }
+            Err(e) => {
+                seats[range].iter_mut().for_each(|s| *s = 0);
+                errors.push(Some(e));
+            }
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Batch, BatchOutcome, BatchView, Kernel};
+    use crate::constraints::{Constraint, Rules};
+    use crate::{distribute, DistributionError, Method};
+
+    #[test]
+    fn same_as_single_distributions() {
+        let scenarios: Vec<(Vec<f64>, usize)> = vec![
+            (vec![41.5, 25.7, 8.6, 8.4], 631),
+            (vec![], 5),
+            (vec![3.0, 3.0, 1.0], 8),
+            (vec![1.0], 0),
+            (vec![70.0, 20.0, 10.0], 10),
+        ];
+        let mut batch = Batch::new();
+        for (votes, seat_count) in scenarios.iter() {
+            batch.push(votes, *seat_count);
+        }
+        assert_eq!(batch.len(), 5);
+        assert_eq!(batch.offsets(), &[0, 4, 4, 7, 8, 11]);
+
+        let outcome = Method::SainteLague.run(&batch);
+        for (i, (votes, seat_count)) in scenarios.iter().enumerate() {
+            let expected = distribute(votes, seat_count, &false);
+            assert_eq!(
+                outcome.seats(i).map(|s| s.to_vec()),
+                expected.as_ref().map(|s| s.to_vec())
+            );
+        }
+        assert_eq!(
+            outcome.errors()[3],
+            Some(DistributionError::InvalidSeatCount)
+        );
+
+        let rules = Rules::default().with(Constraint::Threshold(0.15));
+        assert_eq!(rules.run(&batch).seats(4), Ok(&[8, 2, 0][..]));
+    }
+
+    #[test]
+    fn columns() {
+        let batch = Batch::from_columns(vec![1.0, 2.0, 3.0], vec![0, 1, 3], vec![1, 2]).unwrap();
+        assert_eq!(batch.votes(1), &[2.0, 3.0]);
+        assert_eq!(batch.seat_count(1), 2);
+        assert_eq!(batch, {
+            let mut b = Batch::default();
+            b.push(&[1.0], 1);
+            b.push(&[2.0, 3.0], 2);
+            b
+        });
+
+        assert_eq!(Batch::from_columns(vec![1.0], vec![0, 1], vec![]), None);
+        assert_eq!(Batch::from_columns(vec![1.0], vec![1, 1], vec![1]), None);
+        assert_eq!(Batch::from_columns(vec![1.0], vec![0, 2], vec![1]), None);
+        assert_eq!(
+            Batch::from_columns(vec![1.0, 1.0], vec![0, 2, 1, 2], vec![1, 1, 1]),
+            None
+        );
+        assert!(Batch::default().is_empty());
+        assert_eq!(Batch::default().offsets(), &[0]);
+
+        let outcome: Option<BatchOutcome<()>> =
+            BatchOutcome::from_columns(&batch, vec![1, 1, 1], vec![None, None]);
+        assert_eq!(outcome.unwrap().seats(1), Ok(&[1, 1][..]));
+        assert_eq!(
+            BatchOutcome::<()>::from_columns(&batch, vec![1], vec![None, None]),
+            None
+        );
+    }
+
+    #[test]
+    fn caller_owned_seats() {
+        let mut batch = Batch::new();
+        batch.push(&[3.0, 1.0], 4);
+        batch.push(&[1.0, 1.0], 1);
+        batch.push(&[2.0], 2);
+
+        // previous content is overwritten, including the seats of failed scenarios
+        let mut seats = [9; 5];
+        let view = Method::SainteLague.run_into(&batch, &mut seats).unwrap();
+        assert_eq!(view.len(), 3);
+        assert_eq!(view.seats(0), Ok(&[3, 1][..]));
+        assert_eq!(view.seats(1), Err(&DistributionError::Tied));
+        assert_eq!(view.seats_column(), &[3, 1, 0, 0, 2]);
+        assert_eq!(view.errors(), Method::SainteLague.run(&batch).errors());
+        assert_eq!(seats, [3, 1, 0, 0, 2]);
+
+        assert!(Method::SainteLague.run_into(&batch, &mut [0; 4]).is_none());
+
+        // kernels that aren't allocators copy their outcome into the buffer
+        struct Copying;
+        impl Kernel for Copying {
+            type Error = DistributionError;
+
+            fn run(&self, batch: &Batch) -> BatchOutcome<DistributionError> {
+                Method::SainteLague.run(batch)
+            }
+        }
+        let mut copied = [9; 5];
+        let view: BatchView<_> = Copying.run_into(&batch, &mut copied).unwrap();
+        assert_eq!(view.seats(2), Ok(&[2][..]));
+        assert_eq!(copied, seats);
+    }
+}
@@ -0,0 +1,126 @@
+//! Generates reproducible datasets of [synthetic elections](sainte_lague::synthetic) in the [scenario format](sainte_lague::io::validation), for benchmarks, fuzzing and comparisons between implementations.
+//!
+//! ```text
+//! benchmark-data [--elections N] [--seed S] [--parties N] [--districts N] [--regions N]
+//!                [--seats MIN-MAX] [--votes MIN-MAX] [--regional-variation X] [--local-variation X]
+//!                [--output <directory>]
+//! ```
+//!
+//! Every district of every election is one scenario. Election `i` is generated with the seed `S + i`, so the same arguments always produce the same dataset, and a dataset can be extended by more elections without changing the existing ones. The other options set the fields of the [`Generator`], whose defaults they share.
+//!
+//! By default, the scenarios are written to the standard output, one per line. `--output` writes each scenario to its own file `election-<i>-district-<d>.json` in the directory instead, which is created if needed, e.g. to check them with `sainte-lague validate`.
+//!
+//! Exits with status 2 on invalid arguments or if a file can't be written.
+
+use sainte_lague::io::validation::write_scenario;
+use sainte_lague::synthetic::Generator;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::process::exit;
+use std::str::FromStr;
+use std::{env, fs};
+
+/// The synopsis, printed by `--help` and on invalid arguments.
+const USAGE: &str = "usage: benchmark-data [--elections N] [--seed S] [--parties N] [--districts N] [--regions N] [--seats MIN-MAX] [--votes MIN-MAX] [--regional-variation X] [--local-variation X] [--output <directory>]";
+
+fn usage(message: &str) -> ! {
+    eprintln!("{}\n{}", message, USAGE);
+    exit(2)
+}
+
+fn parse<T: FromStr>(value: &str, name: &str) -> T {
+    value
+        .parse()
+        .unwrap_or_else(|_| usage(&format!("invalid value for {}", name)))
+}
+
+/// Parse an inclusive range written as `MIN-MAX`, or as a single number for both.
+fn range<T: FromStr + PartialOrd + Copy>(value: &str, name: &str) -> (T, T) {
+    let (min, max) = match value.split_once('-') {
+        Some((min, max)) => (parse(min, name), parse(max, name)),
+        None => {
+            let both = parse(value, name);
+            (both, both)
+        }
+    };
+    if min > max {
+        usage(&format!("empty range for {}", name));
+    }
+    (min, max)
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let (mut elections, mut seed) = (1, 0);
+    let mut generator = Generator::default();
+    let mut output = None;
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .unwrap_or_else(|| usage(&format!("missing value for {}", arg)))
+        };
+        match arg.as_str() {
+            "--elections" => elections = parse(&value(), &arg),
+            "--seed" => seed = parse(&value(), &arg),
+            "--parties" => generator.party_count = parse(&value(), &arg),
+            "--districts" => generator.district_count = parse(&value(), &arg),
+            "--regions" => generator.region_count = parse(&value(), &arg),
+            "--seats" => generator.seats = range(&value(), &arg),
+            "--votes" => generator.votes = range(&value(), &arg),
+            "--regional-variation" => generator.regional_variation = parse(&value(), &arg),
+            "--local-variation" => generator.local_variation = parse(&value(), &arg),
+            "--output" => output = Some(value()),
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                return;
+            }
+            other => usage(&format!("unknown argument {}", other)),
+        }
+    }
+    if generator.party_count == 0 || generator.district_count == 0 || generator.region_count == 0 {
+        usage("at least one party, district and region are needed");
+    }
+    if generator.seats.0 == 0 {
+        usage("every district needs at least one seat");
+    }
+    if !(generator.regional_variation >= 0.0 && generator.local_variation >= 0.0) {
+        usage("variations must be non-negative");
+    }
+
+    let result = match output {
+        Some(directory) => write_files(&generator, elections, seed, Path::new(&directory)),
+        None => write_lines(&generator, elections, seed),
+    };
+    if let Err(e) = result {
+        eprintln!("couldn't write the dataset: {}", e);
+        exit(2);
+    }
+}
+
+fn write_lines(generator: &Generator, elections: u64, seed: u64) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    for i in 0..elections {
+        for (votes, seat_count) in generator.generate(seed.wrapping_add(i)).districts {
+            writeln!(out, "{}", write_scenario(&votes, seat_count))?;
+        }
+    }
+    out.flush()
+}
+
+fn write_files(
+    generator: &Generator,
+    elections: u64,
+    seed: u64,
+    directory: &Path,
+) -> io::Result<()> {
+    fs::create_dir_all(directory)?;
+    for i in 0..elections {
+        let election = generator.generate(seed.wrapping_add(i));
+        for (d, (votes, seat_count)) in election.districts.iter().enumerate() {
+            let path = directory.join(format!("election-{}-district-{}.json", i, d));
+            fs::write(path, write_scenario(votes, *seat_count) + "\n")?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,141 @@
+//! Runs random inputs through this crate and another implementation of the Sainte-Laguë method, such as the JavaScript `sainte-lague` package, and reports every input for which their results differ.
+//!
+//! ```text
+//! divergence [--trials N] [--seed S] -- <command> [arguments…]
+//! divergence [--trials N] [--seed S] --export
+//! divergence [--trials N] [--seed S] --check <results file>
+//! divergence --runner
+//! ```
+//!
+//! The first form writes the [standard](sainte_lague::io::vectors::standard) and `N` [random](sainte_lague::io::vectors::random) test vectors as JSON to the standard input of the command and compares its output, one line per vector, see [`compare`](sainte_lague::io::vectors::compare). `--export` and `--check` do the same in two steps, for implementations that can't be run from here. `--runner` prints a Node.js script for the JavaScript package, to be used as `divergence -- node runner.js`.
+//!
+//! Exits with status 1 if any divergence was found and with status 2 on invalid arguments or if the command couldn't be run.
+
+use sainte_lague::io::vectors::{compare, random, standard, to_json, TestVector};
+use std::io::Write;
+use std::process::{exit, Command, Stdio};
+use std::{env, fs};
+
+/// The runner printed by `--runner`.
+const RUNNER: &str = r#"// Prints the result of the sainte-lague package for every test vector read from the standard input, one line each.
+const sainteLague = require('sainte-lague')
+
+let input = ''
+process.stdin.on('data', chunk => { input += chunk })
+process.stdin.on('end', () => {
+	for (const vector of JSON.parse(input).vectors) {
+		let line
+		try {
+			const seats = sainteLague(vector.votes, vector.seatCount)
+			line = Array.isArray(seats) ? seats.join(',') : `error: ${seats}`
+		} catch (error) {
+			line = `error: ${String(error.message).replace(/\n/g, ' ')}`
+		}
+		console.log(line)
+	}
+})
+"#;
+
+enum Mode {
+    Run(Vec<String>),
+    Export,
+    Check(String),
+    Runner,
+}
+
+/// The synopsis, printed by `--help` and on invalid arguments.
+const USAGE: &str = "usage: divergence [--trials N] [--seed S] (-- <command> [arguments…] | --export | --check <results file> | --runner)";
+
+fn usage(message: &str) -> ! {
+    eprintln!("{}\n{}", message, USAGE);
+    exit(2)
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let (mut trials, mut seed) = (1_000, 0);
+    let mut mode = None;
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .unwrap_or_else(|| usage(&format!("missing value for {}", name)))
+        };
+        match arg.as_str() {
+            "--trials" => {
+                trials = value("--trials")
+                    .parse()
+                    .unwrap_or_else(|_| usage("invalid number of trials"))
+            }
+            "--seed" => {
+                seed = value("--seed")
+                    .parse()
+                    .unwrap_or_else(|_| usage("invalid seed"))
+            }
+            "--export" => mode = Some(Mode::Export),
+            "--check" => mode = Some(Mode::Check(value("--check"))),
+            "--runner" => mode = Some(Mode::Runner),
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                return;
+            }
+            "--" => mode = Some(Mode::Run(args.by_ref().collect())),
+            other => usage(&format!("unknown argument {}", other)),
+        }
+    }
+
+    let vectors: Vec<TestVector> = standard().into_iter().chain(random(trials, seed)).collect();
+    let results = match mode {
+        None => usage("no mode given"),
+        Some(Mode::Runner) => {
+            print!("{}", RUNNER);
+            return;
+        }
+        Some(Mode::Export) => {
+            print!("{}", to_json(&vectors));
+            return;
+        }
+        Some(Mode::Check(path)) => fs::read_to_string(&path)
+            .unwrap_or_else(|e| usage(&format!("couldn't read {}: {}", path, e))),
+        Some(Mode::Run(command)) => run(&command, &to_json(&vectors)),
+    };
+
+    let divergences = compare(&vectors, &results);
+    for divergence in divergences.iter() {
+        println!("{}", divergence);
+    }
+    eprintln!(
+        "{} of {} test vectors diverged",
+        divergences.len(),
+        vectors.len()
+    );
+    if !divergences.is_empty() {
+        exit(1);
+    }
+}
+
+/// Run the command with `input` as its standard input and return its standard output.
+fn run(command: &[String], input: &str) -> String {
+    let (program, arguments) = command
+        .split_first()
+        .unwrap_or_else(|| usage("no command given"));
+    let mut child = Command::new(program)
+        .args(arguments)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap_or_else(|e| usage(&format!("couldn't run {}: {}", program, e)));
+    let mut stdin = child.stdin.take().expect("piped standard input");
+    let input = input.to_string();
+    // write from another thread, so a command printing before reading everything can't block
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let output = child
+        .wait_with_output()
+        .unwrap_or_else(|e| usage(&format!("couldn't run {}: {}", program, e)));
+    if let Ok(Err(e)) = writer.join() {
+        usage(&format!("couldn't write to {}: {}", program, e));
+    }
+    if !output.status.success() {
+        usage(&format!("{} failed with {}", program, output.status));
+    }
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
@@ -0,0 +1,169 @@
+//! Command line interface for distributing seats from a results file.
+//!
+//! ```text
+//! sainte-lague watch <results file> --seats N [--threshold T] [--interval MS]
+//! sainte-lague validate <scenario file>
+//! ```
+//!
+//! `watch` reads the [results](sainte_lague::io::results) from the file, prints the seats of every party, and then checks the file for changes every `MS` milliseconds (500 by default). Whenever its content changed, the seats are distributed again and the parties whose seats changed are printed. Content that can't be distributed, e.g. a line that is still being typed, is reported and compared against the last valid content once it's fixed. The file is polled instead of relying on file system notifications, so it works the same on every platform, with network drives and with editors that replace the file on saving. `--threshold` is a share of all votes between `0.0` and `1.0`.
+//!
+//! Runs until interrupted. Exits with status 2 on invalid arguments or if the file can't be read initially.
+//!
+//! `validate` checks a [scenario file](sainte_lague::io::validation) without distributing any seats, and prints the diagnostics as JSON. Exits with status 1 if there are any errors, and with status 2 on invalid arguments or if the file can't be read.
+
+use sainte_lague::constraints::{Constraint, Rules};
+use sainte_lague::io::results::{self, changes};
+use sainte_lague::io::validation::{self, Severity};
+use sainte_lague::Method;
+use std::process::exit;
+use std::thread::sleep;
+use std::time::Duration;
+use std::{env, fs};
+
+/// The synopsis, printed by `--help` and on invalid arguments.
+const USAGE: &str =
+    "usage: sainte-lague watch <results file> --seats N [--threshold T] [--interval MS]
+       sainte-lague validate <scenario file>";
+
+fn usage(message: &str) -> ! {
+    eprintln!("{}\n{}", message, USAGE);
+    exit(2)
+}
+
+/// Parsed results and their seats.
+type Distributed = (Vec<(String, f64)>, Vec<usize>);
+
+struct Watch {
+    path: String,
+    rules: Rules,
+    seat_count: usize,
+    interval: Duration,
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    match args.next().as_deref() {
+        Some("watch") => watch(&parse_watch(args)),
+        Some("validate") => validate(args),
+        Some("--help") | Some("-h") => println!("{}", USAGE),
+        Some(other) => usage(&format!("unknown command {}", other)),
+        None => usage("no command given"),
+    }
+}
+
+fn parse_watch(mut args: impl Iterator<Item = String>) -> Watch {
+    let (mut path, mut seat_count, mut interval) = (None, None, 500);
+    let mut rules = Rules::new(Method::SainteLague);
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .unwrap_or_else(|| usage(&format!("missing value for {}", name)))
+        };
+        match arg.as_str() {
+            "--seats" => {
+                seat_count = Some(
+                    value("--seats")
+                        .parse()
+                        .unwrap_or_else(|_| usage("invalid number of seats")),
+                )
+            }
+            "--threshold" => {
+                let threshold = value("--threshold")
+                    .parse()
+                    .unwrap_or_else(|_| usage("invalid threshold"));
+                rules = rules.with(Constraint::Threshold(threshold));
+            }
+            "--interval" => {
+                interval = value("--interval")
+                    .parse()
+                    .unwrap_or_else(|_| usage("invalid interval"))
+            }
+            other if path.is_none() && !other.starts_with("--") => path = Some(other.to_string()),
+            other => usage(&format!("unknown argument {}", other)),
+        }
+    }
+    Watch {
+        path: path.unwrap_or_else(|| usage("no results file given")),
+        rules,
+        seat_count: seat_count.unwrap_or_else(|| usage("no number of seats given")),
+        interval: Duration::from_millis(interval),
+    }
+}
+
+fn watch(options: &Watch) -> ! {
+    let mut content: Option<String> = None;
+    let mut read_error = None;
+    // the last results that could be distributed, and their seats
+    let mut last = None;
+    loop {
+        match fs::read_to_string(&options.path) {
+            Err(e) if content.is_none() => usage(&format!("couldn't read {}: {}", options.path, e)),
+            Err(e) => {
+                // report errors once, not on every poll
+                let message = format!("couldn't read {}: {}", options.path, e);
+                if read_error.as_ref() != Some(&message) {
+                    eprintln!("{}", message);
+                    read_error = Some(message);
+                }
+            }
+            Ok(current) => {
+                read_error = None;
+                if content.as_ref() != Some(&current) {
+                    update(options, &current, &mut last);
+                    content = Some(current);
+                }
+            }
+        }
+        sleep(options.interval);
+    }
+}
+
+fn validate(mut args: impl Iterator<Item = String>) -> ! {
+    let path = match (args.next(), args.next()) {
+        (Some(path), None) if !path.starts_with("--") => path,
+        (None, _) => usage("no scenario file given"),
+        (_, Some(arg)) | (Some(arg), None) => usage(&format!("unknown argument {}", arg)),
+    };
+    let scenario = fs::read_to_string(&path)
+        .unwrap_or_else(|e| usage(&format!("couldn't read {}: {}", path, e)));
+    let diagnostics = validation::validate(&scenario);
+    print!("{}", validation::to_json(&diagnostics));
+    match diagnostics.iter().any(|d| d.severity == Severity::Error) {
+        true => exit(1),
+        false => exit(0),
+    }
+}
+
+/// Distribute the seats for the `content` of the results file and print them, or the seats that changed since the `last` valid results.
+fn update(options: &Watch, content: &str, last: &mut Option<Distributed>) {
+    let current = match results::parse(content) {
+        Ok(current) => current,
+        Err(e) => return eprintln!("{}", e),
+    };
+    let votes: Vec<f64> = current.iter().map(|(_, v)| *v).collect();
+    let seats = match options
+        .rules
+        .distribute(&votes, &options.seat_count, &false)
+    {
+        Ok(seats) => seats,
+        Err(e) => return eprintln!("{}", e),
+    };
+    match last {
+        None => {
+            for ((party, _), s) in current.iter().zip(seats.iter()) {
+                println!("{}: {}", party, s);
+            }
+        }
+        Some((previous, previous_seats)) => {
+            let changes = changes(previous, previous_seats, &current, &seats);
+            println!("---");
+            if changes.is_empty() {
+                println!("no seats changed");
+            }
+            for change in changes {
+                println!("{}", change);
+            }
+        }
+    }
+    *last = Some((current, seats));
+}
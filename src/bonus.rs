@@ -0,0 +1,146 @@
+//! Majority bonuses: extra seats for the strongest list, followed by a proportional distribution.
+//!
+//! Systems such as French regional and municipal councils award a fixed number of bonus seats to the list with the most votes, to ensure a workable majority. The remaining seats are distributed proportionally among all lists passing a threshold, including the winning list.
+//!
+//! ```
+//! use sainte_lague::bonus::MajorityBonus;
+//! use sainte_lague::Method;
+//!
+//! let system = MajorityBonus::new(Method::SainteLague, 20, 5).with_threshold(0.05);
+//! let outcome = system.distribute(&[40.0, 35.0, 21.0, 4.0], &false).unwrap();
+//! assert_eq!((outcome.winner, outcome.bonus), (0, 5));
+//! assert_eq!(outcome.proportional, vec![6, 6, 3, 0]);
+//! assert_eq!(outcome.seats(), vec![11, 6, 3, 0]);
+//! ```
+
+use crate::constraints::{Constraint, ConstraintError, Rules};
+use crate::pipeline::{Context, PipelineError};
+use crate::{plurality, Allocator};
+
+/// An electoral system with a majority bonus, see the [module documentation](self).
+#[derive(Clone, PartialEq, Debug)]
+pub struct MajorityBonus<A> {
+    /// The allocator distributing the seats that remain after the bonus.
+    pub allocator: A,
+    /// The total number of seats, including the bonus.
+    pub seat_count: usize,
+    /// The number of bonus seats for the list with the most votes.
+    pub bonus: usize,
+    /// The share of all votes (between `0.0` and `1.0`) lists need to take part in the proportional distribution. The winning list always receives its bonus.
+    pub threshold: f64,
+}
+
+/// The seats of each list of a [`MajorityBonus`] system.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct BonusOutcome {
+    /// The index of the list with the most votes.
+    pub winner: usize,
+    /// The number of bonus seats of the winning list.
+    pub bonus: usize,
+    /// The proportional seats of each list.
+    pub proportional: Vec<usize>,
+}
+
+impl BonusOutcome {
+    /// The total number of seats of each list.
+    pub fn seats(&self) -> Vec<usize> {
+        let mut seats = self.proportional.clone();
+        seats[self.winner] += self.bonus;
+        seats
+    }
+}
+
+impl<A> MajorityBonus<A>
+where
+    A: Allocator,
+    ConstraintError: From<A::Error>,
+{
+    /// Create a system with `seat_count` seats, `bonus` of which go to the list with the most votes, and without a threshold.
+    pub fn new(allocator: A, seat_count: usize, bonus: usize) -> MajorityBonus<A> {
+        MajorityBonus {
+            allocator,
+            seat_count,
+            bonus,
+            threshold: 0.0,
+        }
+    }
+
+    /// Require lists to receive at least the given share of all votes for proportional seats.
+    pub fn with_threshold(self, threshold: f64) -> MajorityBonus<A> {
+        MajorityBonus { threshold, ..self }
+    }
+
+    /// Award the bonus and distribute the remaining seats according to `votes`. A tie for the most votes is resolved according to `draw_on_tie`, ties of the proportional seats are handled by the allocator.
+    ///
+    /// Errors are reported with step 1 for the bonus, e.g. [`ConstraintError::Infeasible`] if the bonus exceeds the number of seats, and with step 2 for the proportional seats.
+    pub fn distribute(
+        &self,
+        votes: &[f64],
+        draw_on_tie: &bool,
+    ) -> Result<BonusOutcome, PipelineError> {
+        if self.bonus > self.seat_count {
+            return Err(ConstraintError::Infeasible).context(1, "majority bonus");
+        }
+        let winner = plurality::winner(votes, draw_on_tie).context(1, "majority bonus")?;
+        let remaining = self.seat_count - self.bonus;
+        let proportional = if remaining == 0 {
+            vec![0; votes.len()]
+        } else {
+            let qualified = Rules::default()
+                .with(Constraint::Threshold(self.threshold))
+                .qualified(votes)
+                .context(2, "proportional seats")?;
+            let eligible: Vec<f64> = votes
+                .iter()
+                .zip(qualified.iter())
+                .map(|(v, q)| if *q { *v } else { 0.0 })
+                .collect();
+            self.allocator
+                .allocate(&eligible, &remaining)
+                .map_err(ConstraintError::from)
+                .context(2, "proportional seats")?
+        };
+        Ok(BonusOutcome {
+            winner,
+            bonus: self.bonus,
+            proportional,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MajorityBonus;
+    use crate::constraints::ConstraintError;
+    use crate::{DistributionError, Method};
+
+    #[test]
+    fn bonus_only() {
+        let system = MajorityBonus::new(Method::SainteLague, 3, 3);
+        let outcome = system.distribute(&[1.0, 2.0], &false).unwrap();
+        assert_eq!(outcome.seats(), vec![0, 3]);
+        assert_eq!(outcome.proportional, vec![0, 0]);
+    }
+
+    #[test]
+    fn errors() {
+        let system = MajorityBonus::new(Method::SainteLague, 3, 4);
+        let error = system.distribute(&[1.0, 2.0], &false).unwrap_err();
+        assert_eq!((error.step(), error.label()), (1, "majority bonus"));
+        assert_eq!(error.root_cause(), &ConstraintError::Infeasible);
+
+        let system = MajorityBonus::new(Method::SainteLague, 4, 1);
+        let error = system.distribute(&[2.0, 2.0], &false).unwrap_err();
+        assert_eq!(error.step(), 1);
+        assert_eq!(error.root_cause(), &DistributionError::Tied.into());
+        let outcome = system.distribute(&[2.0, 2.0, 1.0], &true).unwrap();
+        assert!(outcome.winner < 2);
+
+        let error = system
+            .with_threshold(1.5)
+            .distribute(&[2.0, 1.0], &false)
+            .unwrap_err();
+        assert_eq!(error.step(), 2);
+        assert_eq!(error.root_cause(), &ConstraintError::InvalidThreshold);
+    }
+}
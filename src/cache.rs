@@ -0,0 +1,305 @@
+//! Memoization of repeated distributions.
+//!
+//! Services showing a live election to many clients calculate the same distribution over and over again. A [`Cache`] keeps the most recently used results, keyed by the votes, the seat count, the method, all constraints and the zero-vote tolerance, and evicts the least recently used entry once it is full.
+//!
+//! Draws are never cached: if a tie has to be resolved by drawing lots, each request makes a new draw, exactly as an uncached distribution would. Only the knowledge that the inputs are tied is cached.
+//!
+//! ```
+//! use sainte_lague::cache::Cache;
+//! use sainte_lague::constraints::Rules;
+//!
+//! let rules = Rules::default();
+//! let mut cache = Cache::new(100);
+//! let votes = [362.0, 318.0, 126.0, 62.0, 53.0];
+//! assert_eq!(cache.distribute(&rules, &votes, &101, &false), Ok(vec![39, 35, 14, 7, 6]));
+//! assert_eq!(cache.distribute(&rules, &votes, &101, &false), Ok(vec![39, 35, 14, 7, 6]));
+//! assert_eq!((cache.hits(), cache.misses()), (1, 1));
+//! ```
+
+use crate::constraints::{Constraint, ConstraintError, Rules};
+use crate::io::fingerprint::canonical_bits;
+use crate::{DistributionError, Outcome};
+use std::collections::{BTreeMap, HashMap};
+
+/// A bounded cache of distributions, see the [module documentation](self).
+#[derive(Clone, Debug)]
+pub struct Cache {
+    capacity: usize,
+    entries: HashMap<Vec<u8>, Entry>,
+    // keys by the time they were last used, oldest first
+    recency: BTreeMap<u64, Vec<u8>>,
+    time: u64,
+    hits: u64,
+    misses: u64,
+}
+
+#[derive(Clone, Debug)]
+struct Entry {
+    result: Result<Outcome, ConstraintError>,
+    last_used: u64,
+}
+
+impl Cache {
+    /// Create an empty cache holding at most `capacity` results. A capacity of zero disables caching.
+    pub fn new(capacity: usize) -> Cache {
+        Cache {
+            capacity,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            time: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// The maximum number of cached results.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of cached results.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no results are cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The number of requests answered from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// The number of requests that had to be calculated.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Remove all cached results. The statistics are kept.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+
+    /// Same as [`Rules::distribute`], but answered from the cache if possible.
+    pub fn distribute(
+        &mut self,
+        rules: &Rules,
+        votes: &[f64],
+        seat_count: &usize,
+        draw_on_tie: &bool,
+    ) -> Result<Vec<usize>, ConstraintError> {
+        self.evaluate(rules, votes, seat_count, draw_on_tie)
+            .map(Outcome::into_seats)
+    }
+
+    /// Same as [`Cache::distribute`], but returns the full [`Outcome`] including information about draws.
+    pub fn evaluate(
+        &mut self,
+        rules: &Rules,
+        votes: &[f64],
+        seat_count: &usize,
+        draw_on_tie: &bool,
+    ) -> Result<Outcome, ConstraintError> {
+        let key = key(rules, votes, seat_count);
+        self.time += 1;
+        // the cached result is always the one without drawing lots, which is deterministic
+        let result = match self.entries.get_mut(&key) {
+            Some(entry) => {
+                self.hits += 1;
+                self.recency.remove(&entry.last_used);
+                entry.last_used = self.time;
+                self.recency.insert(self.time, key);
+                entry.result.clone()
+            }
+            None => {
+                self.misses += 1;
+                let result = rules.evaluate(votes, seat_count, &false);
+                self.insert(key, result.clone());
+                result
+            }
+        };
+        match result {
+            Err(ConstraintError::Distribution(DistributionError::Tied)) if *draw_on_tie => {
+                rules.evaluate(votes, seat_count, &true)
+            }
+            result => result,
+        }
+    }
+
+    fn insert(&mut self, key: Vec<u8>, result: Result<Outcome, ConstraintError>) {
+        if self.capacity == 0 {
+            return;
+        }
+        while self.entries.len() >= self.capacity {
+            let oldest = match self.recency.keys().next() {
+                Some(time) => *time,
+                None => break,
+            };
+            if let Some(evicted) = self.recency.remove(&oldest) {
+                self.entries.remove(&evicted);
+            }
+        }
+        self.recency.insert(self.time, key.clone());
+        self.entries.insert(
+            key,
+            Entry {
+                result,
+                last_used: self.time,
+            },
+        );
+    }
+}
+
+/// A canonical encoding of all inputs except the tie policy.
+fn key(rules: &Rules, votes: &[f64], seat_count: &usize) -> Vec<u8> {
+    let mut key = vec![];
+    key.extend_from_slice(rules.method.name().as_bytes());
+    key.push(0);
+    int(&mut key, *seat_count);
+    int(&mut key, votes.len());
+    for v in votes.iter() {
+        float(&mut key, *v);
+    }
+    float(&mut key, rules.tolerance());
+    for constraint in rules.constraints() {
+        match constraint {
+            Constraint::Threshold(share) => {
+                key.push(0);
+                float(&mut key, *share);
+            }
+            Constraint::Exemption(p) => {
+                key.push(1);
+                int(&mut key, *p);
+            }
+            Constraint::Alliance(parties) => {
+                key.push(2);
+                int(&mut key, parties.len());
+                for p in parties.iter() {
+                    int(&mut key, *p);
+                }
+            }
+            Constraint::MinSeats(p, seats) => {
+                key.push(3);
+                int(&mut key, *p);
+                int(&mut key, *seats);
+            }
+            Constraint::MaxSeats(p, seats) => {
+                key.push(4);
+                int(&mut key, *p);
+                int(&mut key, *seats);
+            }
+            Constraint::Others(p) => {
+                key.push(5);
+                int(&mut key, *p);
+            }
+            Constraint::Weight(p, weight) => {
+                key.push(6);
+                int(&mut key, *p);
+                float(&mut key, *weight);
+            }
+        }
+    }
+    key
+}
+
+fn int(key: &mut Vec<u8>, value: usize) {
+    key.extend_from_slice(&(value as u64).to_be_bytes())
+}
+
+fn float(key: &mut Vec<u8>, value: f64) {
+    key.extend_from_slice(&canonical_bits(value).to_be_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cache;
+    use crate::constraints::{Constraint, ConstraintError, Rules};
+    use crate::DistributionError;
+
+    #[test]
+    fn least_recently_used() {
+        let rules = Rules::default();
+        let mut cache = Cache::new(2);
+        cache.distribute(&rules, &[1.0, 2.0], &3, &false).unwrap();
+        cache.distribute(&rules, &[2.0, 1.0], &3, &false).unwrap();
+        // use the first entry again, so the second one is evicted next
+        cache.distribute(&rules, &[1.0, 2.0], &3, &false).unwrap();
+        cache.distribute(&rules, &[1.0, 3.0], &3, &false).unwrap();
+        assert_eq!(cache.len(), 2);
+        assert_eq!((cache.hits(), cache.misses()), (1, 3));
+
+        cache.distribute(&rules, &[1.0, 2.0], &3, &false).unwrap();
+        assert_eq!(cache.hits(), 2);
+        cache.distribute(&rules, &[2.0, 1.0], &3, &false).unwrap();
+        assert_eq!(cache.misses(), 4);
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn keys() {
+        let rules = Rules::default();
+        let threshold = rules.clone().with(Constraint::Threshold(0.4));
+        let mut cache = Cache::new(10);
+        assert_eq!(
+            cache.distribute(&rules, &[7.0, 3.0], &10, &false),
+            Ok(vec![7, 3])
+        );
+        assert_eq!(
+            cache.distribute(&threshold, &[7.0, 3.0], &10, &false),
+            Ok(vec![10, 0])
+        );
+        assert_eq!(
+            cache.distribute(&rules, &[7.0, 3.0], &11, &false),
+            Ok(vec![8, 3])
+        );
+        assert_eq!(
+            cache.distribute(&rules, &[7.0, -0.0], &10, &false),
+            Ok(vec![10, 0])
+        );
+        assert_eq!(
+            cache.distribute(&rules, &[7.0, 0.0], &10, &false),
+            Ok(vec![10, 0])
+        );
+        assert_eq!((cache.hits(), cache.misses()), (1, 4));
+
+        let tolerant = rules.clone().with_tolerance(1e-9);
+        assert_eq!(cache.distribute(&rules, &[1e-12], &1, &false), Ok(vec![1]));
+        assert_eq!(
+            cache.distribute(&tolerant, &[1e-12], &1, &false),
+            Err(ConstraintError::Distribution(
+                DistributionError::EffectivelyZeroVotes
+            ))
+        );
+    }
+
+    #[test]
+    fn draws_are_not_cached() {
+        let rules = Rules::default();
+        let mut cache = Cache::new(10);
+        let tied = Err(ConstraintError::Distribution(DistributionError::Tied));
+        assert_eq!(cache.distribute(&rules, &[1.0, 1.0], &1, &false), tied);
+        let mut winners = [0, 0];
+        for _ in 0..100 {
+            let outcome = cache.evaluate(&rules, &[1.0, 1.0], &1, &true).unwrap();
+            assert_eq!(outcome.draw().map(|d| d.candidates()), Some(&[0, 1][..]));
+            winners[outcome.seats()[1]] += 1;
+        }
+        assert!(winners[0] > 0 && winners[1] > 0);
+        assert_eq!(cache.distribute(&rules, &[1.0, 1.0], &1, &false), tied);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn disabled() {
+        let mut cache = Cache::new(0);
+        let rules = Rules::default();
+        cache.distribute(&rules, &[1.0], &1, &false).unwrap();
+        cache.distribute(&rules, &[1.0], &1, &false).unwrap();
+        assert_eq!((cache.len(), cache.hits(), cache.misses()), (0, 0, 2));
+    }
+}
@@ -0,0 +1,249 @@
+//! Certificates that prove a distribution correct, and an independent verifier for them.
+//!
+//! Finding a distribution takes a sort over all quotients, checking one only takes a divisor: the distribution is correct if dividing the votes of every party by the divisor and rounding (with the rounding of the method) yields its seats. A [`Certificate`] is the interval of all such divisors. [`prove`] computes it from the votes and the seats, [`verify`] checks it with two comparisons per party, without sharing any code with the allocation. Official uses can run the verifier separately, or reimplement it, to trust a distribution without trusting the software that produced it.
+//!
+//! ```
+//! use sainte_lague::certificate::{self, Certificate, Violation};
+//! use sainte_lague::{distribute, Method};
+//!
+//! let votes = [362.0, 318.0, 126.0];
+//! let seats = distribute(&votes, &10, &false).unwrap();
+//! let certificate = certificate::prove(&Method::SainteLague, &votes, &seats).unwrap();
+//! assert_eq!(certificate.to_string(), "lower: 80.44444444444444\nupper: 84\n");
+//!
+//! // the verifier only needs the text of the certificate
+//! let parsed = Certificate::parse("lower: 80.44444444444444\nupper: 84\n").unwrap();
+//! assert_eq!(certificate::verify(&Method::SainteLague, &votes, &seats, &parsed), Ok(()));
+//! assert_eq!(
+//!     certificate::verify(&Method::SainteLague, &votes, &[5, 3, 2], &parsed),
+//!     Err(Violation::TooManySeats(0))
+//! );
+//! ```
+
+use crate::io::snapshot::float;
+use crate::locale::{text, text_with, Locale, Localize, Text};
+use crate::Method;
+use std::cmp::Ordering;
+use std::{error, fmt};
+
+/// The interval of divisors reproducing a distribution, see the [module documentation](self).
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Certificate {
+    /// The largest quotient that didn't win a seat. Every divisor must be at least as large.
+    pub lower: f64,
+    /// The smallest quotient that won a seat. Every divisor must be at most as large.
+    pub upper: f64,
+}
+
+impl Certificate {
+    /// Parse a certificate in the format written by its [`Display`](fmt::Display) implementation, or return [`None`] if the text isn't one.
+    pub fn parse(text: &str) -> Option<Certificate> {
+        let mut lines = text.lines();
+        let mut value = |key: &str| {
+            lines
+                .next()?
+                .strip_prefix(key)?
+                .strip_prefix(": ")?
+                .parse::<f64>()
+                .ok()
+        };
+        let certificate = Certificate {
+            lower: value("lower")?,
+            upper: value("upper")?,
+        };
+        match lines.next() {
+            None => Some(certificate),
+            Some(_) => None,
+        }
+    }
+
+    /// Whether lower and upper bound are equal, i.e. the last seats were tied and the distribution is only one of several correct ones.
+    pub fn is_tied(&self) -> bool {
+        self.lower == self.upper
+    }
+}
+
+/// E.g. `lower: 80.44444444444444\nupper: 84\n`, with floats written such that they parse to the same value.
+impl fmt::Display for Certificate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "lower: {}\nupper: {}\n",
+            float(self.lower),
+            float(self.upper)
+        )
+    }
+}
+
+/// A reason a certificate doesn't prove a distribution, see [`verify`].
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Violation {
+    /// The votes and seats differ in length, or the votes contain negative values.
+    InvalidInput,
+
+    /// The lower bound of the certificate exceeds its upper bound.
+    EmptyInterval,
+
+    /// The party with this index would receive more seats for any divisor in the interval.
+    TooFewSeats(usize),
+
+    /// The party with this index would receive fewer seats for any divisor in the interval.
+    TooManySeats(usize),
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.localize(&Locale::English))
+    }
+}
+
+impl Localize for Violation {
+    fn localize(&self, locale: &Locale) -> String {
+        match self {
+            Violation::InvalidInput => text(locale, Text::InvalidCertificateInput).to_string(),
+            Violation::EmptyInterval => text(locale, Text::EmptyInterval).to_string(),
+            Violation::TooFewSeats(p) => text_with(locale, Text::TooFewSeatsFor, p),
+            Violation::TooManySeats(p) => text_with(locale, Text::TooManySeatsFor, p),
+        }
+    }
+}
+
+impl error::Error for Violation {}
+
+/// Compute the certificate of the `seats` for the `votes` under `method`, or return [`None`] if they aren't a correct distribution, i.e. no divisor reproduces them. A distribution that resolved a tie by drawing lots is correct, its certificate [is tied](Certificate::is_tied). Seats of parties without votes are never correct.
+pub fn prove(method: &Method, votes: &[f64], seats: &[usize]) -> Option<Certificate> {
+    if votes.len() != seats.len() {
+        return None;
+    }
+    let mut certificate = Certificate {
+        lower: 0.0,
+        upper: f64::INFINITY,
+    };
+    for (v, s) in votes.iter().zip(seats) {
+        if *v > 0.0 {
+            certificate.lower = certificate.lower.max(v / method.divisor(*s));
+        }
+        if *s > 0 {
+            certificate.upper = certificate.upper.min(v / method.divisor(s - 1));
+        }
+    }
+    match verify(method, votes, seats, &certificate) {
+        Ok(()) => Some(certificate),
+        Err(_) => None,
+    }
+}
+
+/// Check that the `certificate` proves the `seats` correct for the `votes` under `method`: every party's quotient for its next seat is at most the lower bound, and its quotient for its last seat at least the upper bound. Runs in linear time and only uses the divisors of the method.
+pub fn verify(
+    method: &Method,
+    votes: &[f64],
+    seats: &[usize],
+    certificate: &Certificate,
+) -> Result<(), Violation> {
+    if votes.len() != seats.len() || votes.iter().any(|v| v.is_nan() || *v < 0.0) {
+        return Err(Violation::InvalidInput);
+    }
+    match certificate.lower.partial_cmp(&certificate.upper) {
+        Some(Ordering::Less) | Some(Ordering::Equal) => {}
+        _ => return Err(Violation::EmptyInterval),
+    }
+    for (party, (v, s)) in votes.iter().zip(seats).enumerate() {
+        if *v > 0.0 && v / method.divisor(*s) > certificate.lower {
+            return Err(Violation::TooFewSeats(party));
+        }
+        // with a first divisor of 0, a party without votes has no quotient for its first seat
+        if *s > 0 && (*v == 0.0 || v / method.divisor(s - 1) < certificate.upper) {
+            return Err(Violation::TooManySeats(party));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{prove, verify, Certificate, Violation};
+    use crate::{distribute, Method};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn distributions_are_proven() {
+        let method = Method::SainteLague;
+        let mut rng = StdRng::seed_from_u64(499);
+        for _ in 0..500 {
+            let votes: Vec<f64> = (0..rng.gen_range(1..8))
+                .map(|_| rng.gen_range(0..30) as f64)
+                .collect();
+            let seat_count = rng.gen_range(1..40);
+            let seats = match distribute(&votes, &seat_count, &true) {
+                Ok(seats) => seats,
+                Err(_) => continue,
+            };
+            let certificate = prove(&method, &votes, &seats).unwrap();
+            assert_eq!(
+                Certificate::parse(&certificate.to_string()),
+                Some(certificate)
+            );
+
+            // moving any seat between two parties breaks the proof
+            let (from, to) = (rng.gen_range(0..votes.len()), rng.gen_range(0..votes.len()));
+            if from != to && seats[from] > 0 && !certificate.is_tied() {
+                let mut moved = seats.clone();
+                moved[from] -= 1;
+                moved[to] += 1;
+                assert!(verify(&method, &votes, &moved, &certificate).is_err());
+                assert_eq!(prove(&method, &votes, &moved), None);
+            }
+        }
+    }
+
+    #[test]
+    fn violations() {
+        let method = Method::SainteLague;
+        let certificate = Certificate {
+            lower: 2.0,
+            upper: 2.0,
+        };
+        assert!(certificate.is_tied());
+        // 3 / 1.5 and 1 / 0.5 tie for the last seat
+        assert_eq!(verify(&method, &[3.0, 1.0], &[2, 0], &certificate), Ok(()));
+        assert_eq!(verify(&method, &[3.0, 1.0], &[1, 1], &certificate), Ok(()));
+        assert_eq!(
+            verify(&method, &[3.0, 1.0], &[0, 1], &certificate),
+            Err(Violation::TooFewSeats(0))
+        );
+        assert_eq!(
+            verify(&method, &[3.0, 1.0], &[1], &certificate),
+            Err(Violation::InvalidInput)
+        );
+        assert_eq!(
+            verify(&method, &[3.0, f64::NAN], &[2, 0], &certificate),
+            Err(Violation::InvalidInput)
+        );
+        let empty = Certificate {
+            lower: 3.0,
+            upper: 2.0,
+        };
+        assert_eq!(
+            verify(&method, &[3.0, 1.0], &[2, 0], &empty),
+            Err(Violation::EmptyInterval)
+        );
+        assert_eq!(prove(&method, &[3.0, 0.0], &[1, 1]), None);
+
+        // the first seats of Adams are guaranteed, so the upper bound can be infinite
+        let certificate = prove(&Method::Adams, &[3.0, 1.0], &[1, 1]).unwrap();
+        assert_eq!((certificate.lower, certificate.upper), (3.0, f64::INFINITY));
+        assert_eq!(prove(&Method::Adams, &[3.0, 1.0], &[2, 0]), None);
+        assert_eq!(prove(&Method::Adams, &[3.0, 0.0], &[1, 1]), None);
+
+        assert_eq!(Certificate::parse("lower: 1\nupper: 2\nextra"), None);
+        assert_eq!(Certificate::parse("upper: 1\nlower: 2\n"), None);
+        assert_eq!(
+            Certificate::parse("lower: 0\nupper: inf"),
+            Some(Certificate {
+                lower: 0.0,
+                upper: f64::INFINITY
+            })
+        );
+    }
+}
@@ -0,0 +1,1314 @@
+//! Composable electoral rules on top of the allocation methods.
+//!
+//! Instead of an ever-growing list of optional parameters, electoral rules are expressed as a list of [`Constraint`]s collected in [`Rules`]. Regardless of the order in which they were added, constraints are always applied in the following order:
+//!
+//! 1. [`Alliance`](Constraint::Alliance): the votes of allied parties are added up for the threshold check.
+//! 2. [`Threshold`](Constraint::Threshold): parties (or alliances) below the threshold are excluded from the distribution.
+//! 3. [`Exemption`](Constraint::Exemption): exempted parties take part in the distribution even if they failed the threshold.
+//! 4. [`Others`](Constraint::Others): pseudo-parties representing all other parties are excluded from the distribution, but their votes count towards the total for the threshold.
+//! 5. [`Weight`](Constraint::Weight): the quotients of the remaining parties are multiplied by their weight.
+//! 6. [`MinSeats`](Constraint::MinSeats) and [`MaxSeats`](Constraint::MaxSeats): the seats of the remaining parties are distributed within the given bounds.
+//!
+//! Contradicting constraints, e.g. a minimum above a maximum for the same party, are detected by [`Rules::validate`] before anything is distributed.
+//!
+//! ```
+//! use sainte_lague::constraints::{Constraint, Rules};
+//! use sainte_lague::Method;
+//!
+//! let votes = [308.0, 304.0, 132.0, 82.0, 82.0, 46.0, 30.0];
+//! let rules = Rules::new(Method::SainteLague)
+//!     .with(Constraint::Threshold(0.05))
+//!     // the party of the danish minority is exempt from the threshold
+//!     .with(Constraint::Exemption(6));
+//!
+//! let distribution = rules.distribute(&votes, &69, &false);
+//! assert_eq!(distribution, Ok(vec![23, 22, 10, 6, 6, 0, 2]));
+//! ```
+
+use crate::locale::{text, text_with, text_with_all, Locale, Localize, Text};
+use crate::{validate, validate_with_tolerance, Allocator, DistributionError, Method, Outcome};
+use std::error;
+use std::fmt;
+
+/// A single electoral rule, see the [module documentation](self) for the order in which they are applied.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Constraint {
+    /// Parties need at least this share of all votes (between `0.0` and `1.0`) to take part in the distribution.
+    Threshold(f64),
+
+    /// The party with this index takes part in the distribution even if it failed the threshold, e.g. because it represents a national minority.
+    Exemption(usize),
+
+    /// The votes of the parties with these indices are added up for the threshold check, so either all of them pass it or none does. Seats are still distributed to each party individually.
+    Alliance(Vec<usize>),
+
+    /// The party with this index is a pseudo-party collecting the votes of all other parties, as commonly found in polls. It never receives any seats, but its votes count towards the total the threshold is applied to. Removing it from the votes instead would make the threshold easier to pass.
+    Others(usize),
+
+    /// The party with the given index (first value) receives at least the given number of seats (second value).
+    MinSeats(usize, usize),
+
+    /// The party with the given index (first value) receives at most the given number of seats (second value).
+    MaxSeats(usize, usize),
+
+    /// All quotients of the party with the given index (first value) are multiplied by the given positive weight (second value), e.g. `1.1` to boost a list by 10 % or `0.5` to halve its weight. For divisor methods this is the same as multiplying its votes by the weight for the distribution only, the threshold still applies to its actual votes.
+    Weight(usize, f64),
+}
+
+/// Possible error cases of [`Rules`].
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum ConstraintError {
+    /// A constraint refers to a party index that is out of range.
+    UnknownParty(usize),
+
+    /// The threshold is not between `0.0` and `1.0`.
+    InvalidThreshold,
+
+    /// More than one threshold was given.
+    ConflictingThresholds,
+
+    /// The seat bounds of the party with this index contradict each other: there are multiple minimums or maximums, or the minimum exceeds the maximum.
+    ConflictingBounds(usize),
+
+    /// The party with this index is part of multiple alliances.
+    OverlappingAlliances(usize),
+
+    /// The party with this index has a minimum number of seats, but failed the threshold.
+    ExcludedWithMinimum(usize),
+
+    /// The party with this index is marked as [`Others`](Constraint::Others), but is also exempt from the threshold, part of an alliance or has a minimum number of seats.
+    ConflictingOthers(usize),
+
+    /// The weight of the party with this index is not positive and finite, or there are multiple weights for it.
+    InvalidWeight(usize),
+
+    /// The seat bounds can't be satisfied with the given number of seats, see [`Rules::check_feasibility`] for an explanation.
+    Infeasible,
+
+    /// The list votes of an [`ElectoralSystem`](crate::system::ElectoralSystem) are missing for the district with this index, or given for a district the tier doesn't have.
+    UnknownDistrict(usize),
+
+    /// The underlying distribution failed.
+    Distribution(DistributionError),
+}
+
+impl fmt::Display for ConstraintError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.localize(&Locale::English))
+    }
+}
+
+impl Localize for ConstraintError {
+    fn localize(&self, locale: &Locale) -> String {
+        match self {
+            ConstraintError::UnknownParty(p) => text_with(locale, Text::UnknownParty, p),
+            ConstraintError::InvalidThreshold => text(locale, Text::InvalidThreshold).to_string(),
+            ConstraintError::ConflictingThresholds => {
+                text(locale, Text::ConflictingThresholds).to_string()
+            }
+            ConstraintError::ConflictingBounds(p) => text_with(locale, Text::ConflictingBounds, p),
+            ConstraintError::OverlappingAlliances(p) => {
+                text_with(locale, Text::OverlappingAlliances, p)
+            }
+            ConstraintError::ExcludedWithMinimum(p) => {
+                text_with(locale, Text::ExcludedWithMinimum, p)
+            }
+            ConstraintError::ConflictingOthers(p) => text_with(locale, Text::ConflictingOthers, p),
+            ConstraintError::InvalidWeight(p) => text_with(locale, Text::InvalidWeight, p),
+            ConstraintError::Infeasible => text(locale, Text::Infeasible).to_string(),
+            ConstraintError::UnknownDistrict(d) => text_with(locale, Text::UnknownDistrict, d),
+            ConstraintError::Distribution(e) => e.localize(locale),
+        }
+    }
+}
+
+impl error::Error for ConstraintError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            ConstraintError::Distribution(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<DistributionError> for ConstraintError {
+    fn from(error: DistributionError) -> ConstraintError {
+        ConstraintError::Distribution(error)
+    }
+}
+
+/// The reason a party received no seats, see [`Rules::why_zero`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ZeroSeats {
+    /// The party received no votes.
+    NoVotes,
+
+    /// The party represents [`Others`](Constraint::Others).
+    Others,
+
+    /// The party (or its alliance) failed the threshold. With the given number of additional votes for the party, and the votes of all other parties unchanged, it would have passed. This is infinite for a threshold of `1.0`.
+    FailedThreshold {
+        /// The number of additional votes needed to pass the threshold.
+        missing_votes: f64,
+    },
+
+    /// The party is limited to zero seats by a [`MaxSeats`](Constraint::MaxSeats) constraint.
+    MaxSeats,
+
+    /// The party took part in the distribution, but its votes divided by the first divisor are below the lowest quotient that won a seat. With the given number of additional votes, it would have tied for the last seat, ignoring the seat bounds of all other parties.
+    TooFewVotes {
+        /// The number of additional votes needed to tie for the first seat.
+        missing_votes: f64,
+    },
+}
+
+/// An allocation method combined with a set of [`Constraint`]s.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Rules {
+    /// The method used to distribute the seats.
+    pub method: Method,
+    constraints: Vec<Constraint>,
+    tolerance: f64,
+}
+
+impl Rules {
+    /// Create rules without any constraints.
+    pub fn new(method: Method) -> Rules {
+        Rules {
+            method,
+            constraints: vec![],
+            tolerance: 0.0,
+        }
+    }
+
+    /// Add a constraint.
+    pub fn with(mut self, constraint: Constraint) -> Rules {
+        self.constraints.push(constraint);
+        self
+    }
+
+    /// Treat votes adding up to at most `tolerance` as effectively zero and fail with [`DistributionError::EffectivelyZeroVotes`] instead of distributing seats based on vanishing shares. The default tolerance is zero.
+    ///
+    /// ```
+    /// use sainte_lague::constraints::{ConstraintError, Rules};
+    /// use sainte_lague::DistributionError;
+    ///
+    /// let rules = Rules::default().with_tolerance(1e-9);
+    /// assert_eq!(
+    ///     rules.distribute(&[1e-12, 2e-12], &3, &false),
+    ///     Err(ConstraintError::Distribution(DistributionError::EffectivelyZeroVotes))
+    /// );
+    /// assert_eq!(
+    ///     rules.distribute(&[0.0, 0.0], &3, &false),
+    ///     Err(ConstraintError::Distribution(DistributionError::NoVotes))
+    /// );
+    /// assert_eq!(Rules::default().distribute(&[1e-12, 2e-12], &3, &false), Ok(vec![1, 2]));
+    /// ```
+    pub fn with_tolerance(mut self, tolerance: f64) -> Rules {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// The tolerance below which votes are effectively zero, see [`Rules::with_tolerance`].
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance
+    }
+
+    /// All constraints, in the order they were added.
+    pub fn constraints(&self) -> &[Constraint] {
+        &self.constraints
+    }
+
+    /// Check the constraints for contradictions, given the number of parties.
+    pub fn validate(&self, party_count: usize) -> Result<(), ConstraintError> {
+        let check_party = |p: &usize| {
+            if *p < party_count {
+                Ok(())
+            } else {
+                Err(ConstraintError::UnknownParty(*p))
+            }
+        };
+        let mut threshold_seen = false;
+        let mut in_alliance = vec![false; party_count];
+        let mut min_seen = vec![false; party_count];
+        let mut max_seen = vec![false; party_count];
+        let mut weight_seen = vec![false; party_count];
+        let mut others = vec![];
+        for constraint in self.constraints.iter() {
+            match constraint {
+                Constraint::Threshold(share) => {
+                    if !(0.0..=1.0).contains(share) {
+                        return Err(ConstraintError::InvalidThreshold);
+                    }
+                    if threshold_seen {
+                        return Err(ConstraintError::ConflictingThresholds);
+                    }
+                    threshold_seen = true;
+                }
+                Constraint::Exemption(p) => check_party(p)?,
+                Constraint::Others(p) => {
+                    check_party(p)?;
+                    others.push(*p);
+                }
+                Constraint::Alliance(parties) => {
+                    for p in parties.iter() {
+                        check_party(p)?;
+                        if in_alliance[*p] {
+                            return Err(ConstraintError::OverlappingAlliances(*p));
+                        }
+                        in_alliance[*p] = true;
+                    }
+                }
+                Constraint::MinSeats(p, _) | Constraint::MaxSeats(p, _) => {
+                    check_party(p)?;
+                    let seen = match constraint {
+                        Constraint::MinSeats(..) => &mut min_seen,
+                        _ => &mut max_seen,
+                    };
+                    if seen[*p] {
+                        return Err(ConstraintError::ConflictingBounds(*p));
+                    }
+                    seen[*p] = true;
+                }
+                Constraint::Weight(p, weight) => {
+                    check_party(p)?;
+                    let positive = weight.is_finite() && *weight > 0.0;
+                    if !positive || weight_seen[*p] {
+                        return Err(ConstraintError::InvalidWeight(*p));
+                    }
+                    weight_seen[*p] = true;
+                }
+            }
+        }
+        let bounds = self.bounds(party_count);
+        for (p, (min, max)) in bounds.iter().enumerate() {
+            if min > max {
+                return Err(ConstraintError::ConflictingBounds(p));
+            }
+        }
+        for p in others {
+            let exempt = self.constraints.contains(&Constraint::Exemption(p));
+            if exempt || in_alliance[p] || bounds[p].0 > 0 {
+                return Err(ConstraintError::ConflictingOthers(p));
+            }
+        }
+        Ok(())
+    }
+
+    /// Determine which parties take part in the distribution, i.e. passed the threshold (possibly as part of an alliance) or are exempt from it, and don't represent [`Others`](Constraint::Others).
+    ///
+    /// ```
+    /// use sainte_lague::constraints::{Constraint, Rules};
+    /// use sainte_lague::Method;
+    ///
+    /// let rules = Rules::new(Method::SainteLague)
+    ///     .with(Constraint::Threshold(0.05))
+    ///     .with(Constraint::Alliance(vec![1, 2]));
+    /// let qualified = rules.qualified(&[90.0, 4.0, 3.0, 3.0]).unwrap();
+    /// assert_eq!(qualified, vec![true, true, true, false]);
+    /// ```
+    pub fn qualified(&self, votes: &[f64]) -> Result<Vec<bool>, ConstraintError> {
+        self.qualified_with_total(votes, votes.iter().sum())
+    }
+
+    /// Like [`Rules::qualified`], but with the threshold applied to `total_votes` instead of the sum of `votes`, e.g. to include blank ballots.
+    pub(crate) fn qualified_with_total(
+        &self,
+        votes: &[f64],
+        total_votes: f64,
+    ) -> Result<Vec<bool>, ConstraintError> {
+        self.validate(votes.len())?;
+        let threshold = self.constraints.iter().find_map(|c| match c {
+            Constraint::Threshold(share) => Some(*share),
+            _ => None,
+        });
+        let threshold = threshold.unwrap_or(0.0);
+
+        let mut threshold_votes = votes.to_vec();
+        for constraint in self.constraints.iter() {
+            if let Constraint::Alliance(parties) = constraint {
+                let alliance_votes: f64 = parties.iter().map(|p| votes[*p]).sum();
+                for p in parties.iter() {
+                    threshold_votes[*p] = alliance_votes;
+                }
+            }
+        }
+        let mut qualified: Vec<bool> = threshold_votes
+            .iter()
+            .map(|v| *v >= threshold * total_votes)
+            .collect();
+        for constraint in self.constraints.iter() {
+            if let Constraint::Exemption(p) = constraint {
+                qualified[*p] = true;
+            }
+        }
+        for constraint in self.constraints.iter() {
+            if let Constraint::Others(p) = constraint {
+                qualified[*p] = false;
+            }
+        }
+        Ok(qualified)
+    }
+
+    /// Distribute `seat_count` seats according to `votes`, respecting all constraints. Arguments are the same as for [`distribute`](crate::distribute).
+    pub fn distribute(
+        &self,
+        votes: &[f64],
+        seat_count: &usize,
+        draw_on_tie: &bool,
+    ) -> Result<Vec<usize>, ConstraintError> {
+        self.evaluate(votes, seat_count, draw_on_tie)
+            .map(Outcome::into_seats)
+    }
+
+    /// Explain why the party with index `party` received no seats, or return [`None`] if it received at least one. Arguments are the same as for [`Rules::distribute`], fails with the same errors, or with [`ConstraintError::UnknownParty`] for an unknown party. Ties are not resolved, as the explanation should be the same for every draw.
+    ///
+    /// ```
+    /// use sainte_lague::constraints::{Constraint, Rules, ZeroSeats};
+    ///
+    /// let votes = [600.0, 300.0, 60.0, 40.0];
+    /// let rules = Rules::default().with(Constraint::Threshold(0.05));
+    /// assert_eq!(rules.distribute(&votes, &10, &false), Ok(vec![6, 3, 1, 0]));
+    ///
+    /// assert_eq!(rules.why_zero(&votes, &10, 0), Ok(None));
+    /// assert_eq!(
+    ///     rules.why_zero(&votes, &10, 3),
+    ///     Ok(Some(ZeroSeats::FailedThreshold { missing_votes: 10.0 / 0.95 }))
+    /// );
+    /// assert_eq!(
+    ///     rules.why_zero(&votes, &5, 2),
+    ///     Ok(Some(ZeroSeats::TooFewVotes { missing_votes: 40.0 }))
+    /// );
+    /// ```
+    pub fn why_zero(
+        &self,
+        votes: &[f64],
+        seat_count: &usize,
+        party: usize,
+    ) -> Result<Option<ZeroSeats>, ConstraintError> {
+        if party >= votes.len() {
+            return Err(ConstraintError::UnknownParty(party));
+        }
+        let seats = self.distribute(votes, seat_count, &false)?;
+        if seats[party] > 0 {
+            return Ok(None);
+        }
+        if votes[party] == 0.0 {
+            return Ok(Some(ZeroSeats::NoVotes));
+        }
+        if self.constraints.contains(&Constraint::Others(party)) {
+            return Ok(Some(ZeroSeats::Others));
+        }
+        if !self.qualified(votes)?[party] {
+            let threshold = self.constraints.iter().find_map(|c| match c {
+                Constraint::Threshold(share) => Some(*share),
+                _ => None,
+            });
+            let threshold = threshold.unwrap_or(0.0);
+            let alliance = self.constraints.iter().find_map(|c| match c {
+                Constraint::Alliance(parties) if parties.contains(&party) => Some(parties),
+                _ => None,
+            });
+            let threshold_votes = match alliance {
+                Some(parties) => parties.iter().map(|p| votes[*p]).sum(),
+                None => votes[party],
+            };
+            let total_votes: f64 = votes.iter().sum();
+            // additional votes also increase the total the threshold is applied to
+            let missing_votes = (threshold * total_votes - threshold_votes) / (1.0 - threshold);
+            return Ok(Some(ZeroSeats::FailedThreshold {
+                missing_votes: missing_votes.max(0.0),
+            }));
+        }
+        if self.bounds(votes.len())[party].1 == 0 {
+            return Ok(Some(ZeroSeats::MaxSeats));
+        }
+        let weighted = self.weighted(votes);
+        let lowest_quotient = weighted
+            .iter()
+            .zip(seats.iter())
+            .filter(|(_, s)| **s > 0)
+            .map(|(v, s)| v / self.method.divisor(s - 1))
+            .fold(f64::INFINITY, f64::min);
+        let weight = weighted[party] / votes[party];
+        let missing_votes = lowest_quotient * self.method.divisor(0) / weight - votes[party];
+        Ok(Some(ZeroSeats::TooFewVotes {
+            missing_votes: missing_votes.max(0.0),
+        }))
+    }
+
+    pub(crate) fn evaluate(
+        &self,
+        votes: &[f64],
+        seat_count: &usize,
+        draw_on_tie: &bool,
+    ) -> Result<Outcome, ConstraintError> {
+        self.evaluate_with_total(votes, votes.iter().sum(), seat_count, draw_on_tie)
+    }
+
+    pub(crate) fn evaluate_with_total(
+        &self,
+        votes: &[f64],
+        total_votes: f64,
+        seat_count: &usize,
+        draw_on_tie: &bool,
+    ) -> Result<Outcome, ConstraintError> {
+        validate_with_tolerance(votes, seat_count, self.tolerance)?;
+        let qualified = self.qualified_with_total(votes, total_votes)?;
+        self.evaluate_qualified(votes, &qualified, seat_count, draw_on_tie)
+    }
+
+    /// Distribute the seats among the `qualified` parties, which have already been determined.
+    pub(crate) fn evaluate_qualified(
+        &self,
+        votes: &[f64],
+        qualified: &[bool],
+        seat_count: &usize,
+        draw_on_tie: &bool,
+    ) -> Result<Outcome, ConstraintError> {
+        let bounds = self.qualified_bounds(votes, qualified)?;
+        if infeasibility(votes, &bounds, seat_count).is_some() {
+            return Err(ConstraintError::Infeasible);
+        }
+
+        let outcome = self.method.evaluate_bounded(
+            &self.weighted(votes),
+            seat_count,
+            draw_on_tie,
+            &bounds,
+        )?;
+        Ok(outcome)
+    }
+
+    /// Check whether the seat bounds can be satisfied, before distributing any seats. Returns an explanation if they can't, in which case [`Rules::distribute`] fails with [`ConstraintError::Infeasible`]. Other errors are the same as for [`Rules::distribute`], except for ties.
+    ///
+    /// ```
+    /// use sainte_lague::constraints::{Constraint, Infeasibility, Rules};
+    ///
+    /// let rules = Rules::default()
+    ///     .with(Constraint::MinSeats(0, 3))
+    ///     .with(Constraint::MinSeats(2, 2));
+    /// assert_eq!(
+    ///     rules.check_feasibility(&[5.0, 4.0, 1.0], &4),
+    ///     Ok(Some(Infeasibility::TooManyMinimums {
+    ///         minimums: vec![(0, 3), (2, 2)],
+    ///         seat_count: 4,
+    ///     }))
+    /// );
+    /// assert_eq!(rules.check_feasibility(&[5.0, 4.0, 1.0], &5), Ok(None));
+    /// ```
+    pub fn check_feasibility(
+        &self,
+        votes: &[f64],
+        seat_count: &usize,
+    ) -> Result<Option<Infeasibility>, ConstraintError> {
+        validate_with_tolerance(votes, seat_count, self.tolerance)?;
+        let qualified = self.qualified(votes)?;
+        let bounds = self.qualified_bounds(votes, &qualified)?;
+        Ok(infeasibility(votes, &bounds, seat_count))
+    }
+
+    /// The seat bounds of each party, with the maximum of parties that don't take part in the distribution set to zero.
+    pub(crate) fn qualified_bounds(
+        &self,
+        votes: &[f64],
+        qualified: &[bool],
+    ) -> Result<Vec<(usize, usize)>, ConstraintError> {
+        let mut bounds = self.bounds(votes.len());
+        for (p, (min, max)) in bounds.iter_mut().enumerate() {
+            if !qualified[p] {
+                if *min > 0 {
+                    return Err(ConstraintError::ExcludedWithMinimum(p));
+                }
+                *max = 0;
+            }
+        }
+        Ok(bounds)
+    }
+
+    /// The votes multiplied by the [`Weight`](Constraint::Weight) of each party, which multiplies all quotients of divisor methods.
+    pub(crate) fn weighted(&self, votes: &[f64]) -> Vec<f64> {
+        let mut weighted = votes.to_vec();
+        for constraint in self.constraints.iter() {
+            if let Constraint::Weight(p, weight) = constraint {
+                if let Some(v) = weighted.get_mut(*p) {
+                    *v *= weight;
+                }
+            }
+        }
+        weighted
+    }
+
+    /// The minimum and maximum number of seats of each party, ignoring the threshold.
+    fn bounds(&self, party_count: usize) -> Vec<(usize, usize)> {
+        let mut bounds = vec![(0, usize::MAX); party_count];
+        for constraint in self.constraints.iter() {
+            match constraint {
+                Constraint::MinSeats(p, seats) if *p < party_count => bounds[*p].0 = *seats,
+                Constraint::MaxSeats(p, seats) if *p < party_count => bounds[*p].1 = *seats,
+                _ => (),
+            }
+        }
+        bounds
+    }
+}
+
+/// Why the seat bounds of [`Rules`] can't be satisfied, see [`Rules::check_feasibility`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Infeasibility {
+    /// The minimums of these parties (index and number of seats) add up to more than the `seat_count`.
+    TooManyMinimums {
+        /// The parties with a minimum number of seats, and their minimum.
+        minimums: Vec<(usize, usize)>,
+        /// The number of seats to distribute.
+        seat_count: usize,
+    },
+
+    /// The parties that may receive seats (index and maximum number of seats) can't fill all of the `seat_count` seats, e.g. because of [`MaxSeats`](Constraint::MaxSeats) constraints or because too many parties failed the threshold. Parties without votes only receive their minimum.
+    TooFewSeats {
+        /// The parties that may receive seats, and the maximum they may receive.
+        capacities: Vec<(usize, usize)>,
+        /// The number of seats to distribute.
+        seat_count: usize,
+    },
+}
+
+impl fmt::Display for Infeasibility {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.localize(&Locale::English))
+    }
+}
+
+impl Localize for Infeasibility {
+    fn localize(&self, locale: &Locale) -> String {
+        match self {
+            Infeasibility::TooManyMinimums {
+                minimums,
+                seat_count,
+            } => {
+                let seats: usize = minimums.iter().map(|(_, s)| s).sum();
+                text_with_all(locale, Text::TooManyMinimums, &[&seats, seat_count])
+            }
+            Infeasibility::TooFewSeats {
+                capacities,
+                seat_count,
+            } => {
+                let seats: usize = capacities.iter().map(|(_, s)| s).sum();
+                text_with_all(locale, Text::TooFewSeats, &[&seats, seat_count])
+            }
+        }
+    }
+}
+
+pub(crate) fn infeasibility(
+    votes: &[f64],
+    bounds: &[(usize, usize)],
+    seat_count: &usize,
+) -> Option<Infeasibility> {
+    let minimums: Vec<(usize, usize)> = bounds
+        .iter()
+        .enumerate()
+        .filter(|(_, (min, _))| *min > 0)
+        .map(|(p, (min, _))| (p, *min))
+        .collect();
+    let guaranteed_seats: usize = minimums.iter().map(|(_, min)| min).sum();
+    if guaranteed_seats > *seat_count {
+        return Some(Infeasibility::TooManyMinimums {
+            minimums,
+            seat_count: *seat_count,
+        });
+    }
+    let capacities: Vec<(usize, usize)> = bounds
+        .iter()
+        .zip(votes)
+        .map(|((min, max), v)| if *v > 0.0 { *max } else { *min })
+        .enumerate()
+        .filter(|(_, capacity)| *capacity > 0)
+        .collect();
+    let possible_seats = capacities
+        .iter()
+        .fold(0usize, |sum, (_, capacity)| sum.saturating_add(*capacity));
+    if possible_seats < *seat_count {
+        return Some(Infeasibility::TooFewSeats {
+            capacities,
+            seat_count: *seat_count,
+        });
+    }
+    None
+}
+
+/// The result of [`redistribute`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Redistribution {
+    /// The number of seats of each party.
+    pub seats: Vec<usize>,
+    /// The number of rounds that allocated the remaining seats, see [`redistribute`] for the bound.
+    pub rounds: usize,
+}
+
+/// Distribute the seats with any [`Allocator`] such that every party receives between the minimum and maximum number of seats given in `bounds`, using the iterative fix-and-redistribute algorithm:
+///
+/// 1. Parties without votes are fixed at their minimum.
+/// 2. The remaining seats are allocated among all parties that aren't fixed.
+/// 3. If any of them received more than their maximum, they are fixed at it. Otherwise, if any of them received less than their minimum, they are fixed at it.
+/// 4. Otherwise, or if the fixed parties alone take too many or too few seats, the first fixed party whose bound is no longer binding is released: a party fixed at its maximum that would receive fewer seats, or a party fixed at its minimum that would receive more seats, if the remaining seats were allocated among it and the other parties that aren't fixed. If there is none, the distribution is complete.
+/// 5. Continue with step 2.
+///
+/// Every party is released at most twice, so it is fixed at most three times, and every round except the last one fixes or releases a party. The algorithm terminates after at most `5n + 1` rounds for `n` parties, and fails with [`ConstraintError::Infeasible`] if it can't satisfy the bounds within them.
+///
+/// For divisor methods, the result is the same as with [`MinSeats`](Constraint::MinSeats) and [`MaxSeats`](Constraint::MaxSeats) constraints, which [`Rules`] distribute exactly.
+///
+/// ```
+/// use sainte_lague::constraints::redistribute;
+/// use sainte_lague::Method;
+///
+/// let votes = [700.0, 200.0, 100.0];
+/// let redistribution = redistribute(&Method::SainteLague, &votes, &10, &[(0, 5), (0, 10), (2, 10)]).unwrap();
+/// assert_eq!(redistribution.seats, vec![5, 3, 2]);
+/// assert_eq!(redistribution.rounds, 2);
+///
+/// // the maximum of the first party is released once the minimum of the second party takes a seat from it
+/// let redistribution = redistribute(&Method::SainteLague, &[90.0, 10.0], &10, &[(0, 8), (3, 10)]).unwrap();
+/// assert_eq!(redistribution.seats, vec![7, 3]);
+/// ```
+///
+/// Fails with [`ConstraintError::UnknownParty`] if there aren't exactly as many bounds as parties, [`ConstraintError::ConflictingBounds`] if a minimum is above the maximum, [`ConstraintError::Infeasible`] if the bounds can't be satisfied, or with any error of the allocator.
+pub fn redistribute<A: Allocator>(
+    allocator: &A,
+    votes: &[f64],
+    seat_count: &usize,
+    bounds: &[(usize, usize)],
+) -> Result<Redistribution, ConstraintError>
+where
+    ConstraintError: From<A::Error>,
+{
+    if bounds.len() != votes.len() {
+        return Err(ConstraintError::UnknownParty(bounds.len().min(votes.len())));
+    }
+    if let Some(p) = bounds.iter().position(|(min, max)| min > max) {
+        return Err(ConstraintError::ConflictingBounds(p));
+    }
+    validate(votes, seat_count)?;
+    if infeasibility(votes, bounds, seat_count).is_some() {
+        return Err(ConstraintError::Infeasible);
+    }
+
+    let mut fixed: Vec<Option<usize>> = votes
+        .iter()
+        .zip(bounds.iter())
+        .map(|(v, (min, _))| if *v > 0.0 { None } else { Some(*min) })
+        .collect();
+    let mut releases = vec![0; votes.len()];
+    let mut rounds = 0;
+    loop {
+        let fixed_seats: usize = fixed.iter().flatten().sum();
+        let free: Vec<usize> = (0..votes.len()).filter(|p| fixed[*p].is_none()).collect();
+        if fixed_seats <= *seat_count && !(free.is_empty() && fixed_seats < *seat_count) {
+            let remaining = seat_count - fixed_seats;
+            let allocation = if free.is_empty() || remaining == 0 {
+                vec![0; free.len()]
+            } else {
+                rounds += 1;
+                let free_votes: Vec<f64> = free.iter().map(|p| votes[*p]).collect();
+                allocator.allocate(&free_votes, &remaining)?
+            };
+
+            let above: Vec<usize> = (0..free.len())
+                .filter(|i| allocation[*i] > bounds[free[*i]].1)
+                .collect();
+            let below: Vec<usize> = (0..free.len())
+                .filter(|i| allocation[*i] < bounds[free[*i]].0)
+                .collect();
+            if !above.is_empty() {
+                for i in above {
+                    fixed[free[i]] = Some(bounds[free[i]].1);
+                }
+                continue;
+            }
+            if !below.is_empty() {
+                for i in below {
+                    fixed[free[i]] = Some(bounds[free[i]].0);
+                }
+                continue;
+            }
+            if let Some(p) = release(allocator, votes, seat_count, bounds, &fixed, &releases) {
+                fixed[p] = None;
+                releases[p] += 1;
+                continue;
+            }
+            let mut seats: Vec<usize> = fixed.iter().map(|f| f.unwrap_or(0)).collect();
+            for (i, p) in free.iter().enumerate() {
+                seats[*p] = allocation[i];
+            }
+            return Ok(Redistribution { seats, rounds });
+        }
+        match release(allocator, votes, seat_count, bounds, &fixed, &releases) {
+            Some(p) => {
+                fixed[p] = None;
+                releases[p] += 1;
+            }
+            None => return Err(ConstraintError::Infeasible),
+        }
+    }
+}
+
+/// The first party of [`redistribute`] that is fixed at a bound which is no longer binding, and wasn't released twice yet.
+fn release<A: Allocator>(
+    allocator: &A,
+    votes: &[f64],
+    seat_count: &usize,
+    bounds: &[(usize, usize)],
+    fixed: &[Option<usize>],
+    releases: &[usize],
+) -> Option<usize> {
+    let fixed_seats: usize = fixed.iter().flatten().sum();
+    (0..votes.len()).find(|p| {
+        let (min, max) = bounds[*p];
+        let seats = match fixed[*p] {
+            Some(seats) if votes[*p] > 0.0 && min < max && releases[*p] < 2 => seats,
+            _ => return false,
+        };
+        let remaining = match (seat_count + seats).checked_sub(fixed_seats) {
+            Some(remaining) if remaining > 0 => remaining,
+            // the other fixed parties alone take all seats
+            _ => return seats == max,
+        };
+        let candidates: Vec<usize> = (0..votes.len())
+            .filter(|q| q == p || fixed[*q].is_none())
+            .collect();
+        let candidate_votes: Vec<f64> = candidates.iter().map(|q| votes[*q]).collect();
+        // a tie or other failure of this trial allocation keeps the party fixed
+        let allocation = match allocator.allocate(&candidate_votes, &remaining) {
+            Ok(allocation) => allocation,
+            Err(_) => return false,
+        };
+        let received = allocation[candidates.iter().position(|q| q == p).unwrap_or(0)];
+        (seats == max && received < max) || (seats == min && received > min)
+    })
+}
+
+impl Allocator for Rules {
+    type Error = ConstraintError;
+
+    fn allocate(&self, votes: &[f64], seat_count: &usize) -> Result<Vec<usize>, ConstraintError> {
+        self.distribute(votes, seat_count, &false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{redistribute, Constraint, ConstraintError, Infeasibility, Rules, ZeroSeats};
+    use crate::locale::{Locale, Localize};
+    use crate::{distribute, DistributionError, Method};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn rules() -> Rules {
+        Rules::new(Method::SainteLague)
+    }
+
+    #[test]
+    fn without_constraints() {
+        let votes = [41.5, 25.7, 8.6, 8.4];
+        assert_eq!(
+            rules().distribute(&votes, &631, &false),
+            distribute(&votes, &631, &false).map_err(ConstraintError::from)
+        );
+    }
+
+    #[test]
+    fn threshold() {
+        let votes = [50.0, 30.0, 15.0, 5.0, 4.9];
+        let rules = rules().with(Constraint::Threshold(0.05));
+        let qualified = rules.qualified(&votes).unwrap();
+        // 5.0 out of 104.9 votes is less than 5 %
+        assert_eq!(qualified, vec![true, true, true, false, false]);
+        assert_eq!(
+            rules.distribute(&votes, &20, &false),
+            Ok(vec![11, 6, 3, 0, 0])
+        );
+
+        let rules = rules.with(Constraint::Exemption(4));
+        assert_eq!(
+            rules.distribute(&votes, &20, &false),
+            Ok(vec![10, 6, 3, 0, 1])
+        );
+    }
+
+    #[test]
+    fn alliance() {
+        let votes = [60.0, 30.0, 4.0, 3.0, 3.0];
+        let rules = rules()
+            .with(Constraint::Alliance(vec![2, 3]))
+            .with(Constraint::Threshold(0.05));
+        assert_eq!(
+            rules.qualified(&votes),
+            Ok(vec![true, true, true, true, false])
+        );
+    }
+
+    #[test]
+    fn seat_bounds() {
+        let votes = [70.0, 20.0, 10.0, 0.0];
+        let rules = rules()
+            .with(Constraint::MaxSeats(0, 5))
+            .with(Constraint::MinSeats(2, 2))
+            .with(Constraint::MinSeats(3, 1));
+        assert_eq!(rules.distribute(&votes, &10, &false), Ok(vec![5, 2, 2, 1]));
+
+        let unconstrained = distribute(&votes, &10, &false).unwrap();
+        assert_eq!(unconstrained, vec![7, 2, 1, 0]);
+    }
+
+    #[test]
+    fn bounds_are_applied_before_ties() {
+        let votes = [3.0, 3.0, 1.0];
+        let rules = rules().with(Constraint::MaxSeats(0, 3));
+        assert_eq!(rules.distribute(&votes, &8, &false), Ok(vec![3, 4, 1]));
+    }
+
+    #[test]
+    fn conflicts() {
+        let votes = [1.0, 1.0];
+        let check = |rules: Rules| rules.distribute(&votes, &2, &false);
+
+        assert_eq!(
+            check(rules().with(Constraint::Exemption(2))),
+            Err(ConstraintError::UnknownParty(2))
+        );
+        assert_eq!(
+            check(rules().with(Constraint::Threshold(5.0))),
+            Err(ConstraintError::InvalidThreshold)
+        );
+        assert_eq!(
+            check(
+                rules()
+                    .with(Constraint::Threshold(0.05))
+                    .with(Constraint::Threshold(0.03))
+            ),
+            Err(ConstraintError::ConflictingThresholds)
+        );
+        assert_eq!(
+            check(
+                rules()
+                    .with(Constraint::MinSeats(1, 2))
+                    .with(Constraint::MaxSeats(1, 1))
+            ),
+            Err(ConstraintError::ConflictingBounds(1))
+        );
+        assert_eq!(
+            check(
+                rules()
+                    .with(Constraint::MaxSeats(0, 2))
+                    .with(Constraint::MaxSeats(0, 1))
+            ),
+            Err(ConstraintError::ConflictingBounds(0))
+        );
+        assert_eq!(
+            check(
+                rules()
+                    .with(Constraint::Alliance(vec![0, 1]))
+                    .with(Constraint::Alliance(vec![1]))
+            ),
+            Err(ConstraintError::OverlappingAlliances(1))
+        );
+        assert_eq!(
+            check(
+                rules()
+                    .with(Constraint::MinSeats(0, 2))
+                    .with(Constraint::MinSeats(1, 1))
+            ),
+            Err(ConstraintError::Infeasible)
+        );
+        assert_eq!(
+            check(
+                rules()
+                    .with(Constraint::MaxSeats(0, 0))
+                    .with(Constraint::MaxSeats(1, 1))
+            ),
+            Err(ConstraintError::Infeasible)
+        );
+    }
+
+    #[test]
+    fn excluded_with_minimum() {
+        let votes = [99.0, 1.0];
+        let rules = rules()
+            .with(Constraint::Threshold(0.05))
+            .with(Constraint::MinSeats(1, 1));
+        assert_eq!(
+            rules.distribute(&votes, &10, &false),
+            Err(ConstraintError::ExcludedWithMinimum(1))
+        );
+    }
+
+    #[test]
+    fn others() {
+        let votes = [50.0, 30.0, 14.0, 6.0];
+        let rules = rules()
+            .with(Constraint::Threshold(0.15))
+            .with(Constraint::Others(3));
+        // 14 out of 100 votes, the votes of the others are part of the total
+        assert_eq!(rules.qualified(&votes), Ok(vec![true, true, false, false]));
+        assert_eq!(rules.distribute(&votes, &10, &false), Ok(vec![6, 4, 0, 0]));
+
+        let rules = self::rules().with(Constraint::Others(3));
+        assert_eq!(rules.distribute(&votes, &10, &false), Ok(vec![5, 3, 2, 0]));
+        assert_eq!(
+            rules.distribute(&[0.0, 0.0, 0.0, 1.0], &10, &false),
+            Err(ConstraintError::Infeasible)
+        );
+
+        for conflict in [
+            Constraint::Exemption(3),
+            Constraint::Alliance(vec![2, 3]),
+            Constraint::MinSeats(3, 1),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                rules.clone().with(conflict.clone()).validate(4),
+                Err(ConstraintError::ConflictingOthers(3))
+            );
+        }
+    }
+
+    #[test]
+    fn distribution_errors() {
+        let rules = rules().with(Constraint::Threshold(0.05));
+        assert_eq!(
+            rules.distribute(&[1.0, -1.0], &2, &false),
+            Err(ConstraintError::Distribution(
+                DistributionError::NegativeVotes
+            ))
+        );
+        assert_eq!(
+            rules.distribute(&[1.0, 1.0], &1, &false),
+            Err(ConstraintError::Distribution(DistributionError::Tied))
+        );
+    }
+
+    #[test]
+    fn why_zero() {
+        let votes = [500.0, 300.0, 0.0, 35.0, 20.0, 60.0, 100.0, 10.0];
+        let rules = Rules::default()
+            .with(Constraint::Threshold(0.05))
+            .with(Constraint::Alliance(vec![3, 4]))
+            .with(Constraint::Others(6))
+            .with(Constraint::MaxSeats(5, 0))
+            .with(Constraint::Exemption(7));
+        let seats = rules.distribute(&votes, &10, &false).unwrap();
+        assert_eq!(seats, vec![6, 4, 0, 0, 0, 0, 0, 0]);
+
+        let reasons: Vec<Option<ZeroSeats>> = (0..votes.len())
+            .map(|p| rules.why_zero(&votes, &10, p).unwrap())
+            .collect();
+        assert_eq!(reasons[..2], [None, None]);
+        assert_eq!(reasons[2], Some(ZeroSeats::NoVotes));
+        assert_eq!(reasons[6], Some(ZeroSeats::Others));
+        assert_eq!(reasons[5], Some(ZeroSeats::MaxSeats));
+        // the alliance has 55 of 1025 votes, so it passes the threshold
+        let tie = 300.0 / 3.5 * 0.5;
+        assert_eq!(
+            reasons[3],
+            Some(ZeroSeats::TooFewVotes {
+                missing_votes: tie - 35.0
+            })
+        );
+        assert_eq!(
+            reasons[7],
+            Some(ZeroSeats::TooFewVotes {
+                missing_votes: tie - 10.0
+            })
+        );
+
+        let without_alliance = Rules::default().with(Constraint::Threshold(0.05));
+        match without_alliance.why_zero(&votes, &10, 4).unwrap() {
+            Some(ZeroSeats::FailedThreshold { missing_votes }) => {
+                assert!((missing_votes - (0.05 * 1025.0 - 20.0) / 0.95).abs() < 1e-9);
+                let mut more = votes.to_vec();
+                more[4] += missing_votes + 1e-6;
+                assert!(without_alliance.qualified(&more).unwrap()[4]);
+            }
+            reason => panic!("unexpected reason {:?}", reason),
+        }
+
+        assert_eq!(
+            rules.why_zero(&votes, &10, 8),
+            Err(ConstraintError::UnknownParty(8))
+        );
+        assert_eq!(
+            Rules::default().why_zero(&[1.0, 1.0], &1, 0),
+            Err(ConstraintError::Distribution(DistributionError::Tied))
+        );
+    }
+
+    #[test]
+    fn weights() {
+        let votes = [600.0, 300.0, 60.0, 40.0];
+        let boosted = |weight: f64| {
+            Rules::default()
+                .with(Constraint::Threshold(0.05))
+                .with(Constraint::Weight(3, weight))
+        };
+        // the weight doesn't help to pass the threshold
+        assert_eq!(
+            boosted(2.0).distribute(&votes, &10, &false),
+            Ok(vec![6, 3, 1, 0])
+        );
+        assert_eq!(
+            boosted(2.0).why_zero(&votes, &10, 3),
+            Ok(Some(ZeroSeats::FailedThreshold {
+                missing_votes: 10.0 / 0.95
+            }))
+        );
+
+        let rules = Rules::default().with(Constraint::Weight(2, 0.5));
+        assert_eq!(
+            rules.distribute(&votes, &10, &false),
+            Rules::default().distribute(&[600.0, 300.0, 30.0, 40.0], &10, &false)
+        );
+        // twice the votes are missing with half the weight
+        assert_eq!(
+            rules.why_zero(&votes, &5, 2),
+            Ok(Some(ZeroSeats::TooFewVotes {
+                missing_votes: 140.0
+            }))
+        );
+
+        for weight in [0.0, -1.0, f64::INFINITY, f64::NAN].iter() {
+            assert_eq!(
+                boosted(*weight).distribute(&votes, &10, &false),
+                Err(ConstraintError::InvalidWeight(3))
+            );
+        }
+        assert_eq!(
+            boosted(2.0)
+                .with(Constraint::Weight(3, 2.0))
+                .distribute(&votes, &10, &false),
+            Err(ConstraintError::InvalidWeight(3))
+        );
+        assert_eq!(
+            Rules::default()
+                .with(Constraint::Weight(4, 2.0))
+                .distribute(&votes, &10, &false),
+            Err(ConstraintError::UnknownParty(4))
+        );
+    }
+
+    #[test]
+    fn feasibility() {
+        let votes = [50.0, 30.0, 20.0, 0.0];
+        let rules = rules()
+            .with(Constraint::Threshold(0.25))
+            .with(Constraint::MaxSeats(0, 3))
+            .with(Constraint::MaxSeats(1, 2))
+            .with(Constraint::MinSeats(3, 1))
+            .with(Constraint::Exemption(3));
+        let infeasibility = Infeasibility::TooFewSeats {
+            capacities: vec![(0, 3), (1, 2), (3, 1)],
+            seat_count: 7,
+        };
+        assert_eq!(
+            rules.check_feasibility(&votes, &7),
+            Ok(Some(infeasibility.clone()))
+        );
+        assert_eq!(
+            rules.distribute(&votes, &7, &false),
+            Err(ConstraintError::Infeasible)
+        );
+        assert_eq!(rules.check_feasibility(&votes, &6), Ok(None));
+        assert_eq!(
+            infeasibility.to_string(),
+            "Infeasible constraints, at most 6 seats can be distributed, but there are 7."
+        );
+        assert_eq!(
+            infeasibility.localize(&Locale::German),
+            "Unerfüllbare Bedingungen, höchstens 6 Sitze können vergeben werden, es gibt aber 7."
+        );
+
+        let minimums = Infeasibility::TooManyMinimums {
+            minimums: vec![(0, 1), (3, 2)],
+            seat_count: 2,
+        };
+        assert_eq!(
+            minimums.to_string(),
+            "Infeasible constraints, the minimums add up to 3 seats, but there are only 2."
+        );
+
+        assert_eq!(
+            rules
+                .clone()
+                .with(Constraint::MinSeats(2, 1))
+                .check_feasibility(&votes, &6),
+            Err(ConstraintError::ExcludedWithMinimum(2))
+        );
+        assert_eq!(
+            rules.check_feasibility(&votes, &0),
+            Err(ConstraintError::Distribution(
+                DistributionError::InvalidSeatCount
+            ))
+        );
+    }
+
+    fn random_bounds(rng: &mut StdRng, party_count: usize, minimums: bool) -> Vec<(usize, usize)> {
+        (0..party_count)
+            .map(|_| match (minimums, rng.gen_bool(0.5)) {
+                (_, false) => (0, usize::MAX),
+                (true, true) => (rng.gen_range(0..5), usize::MAX),
+                (false, true) => (0, rng.gen_range(0..10)),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn redistribute_matches_exact_bounds() {
+        let mut rng = StdRng::seed_from_u64(458);
+        let mut compared = 0;
+        for i in 0..2000 {
+            let party_count = rng.gen_range(1..8);
+            let votes: Vec<f64> = (0..party_count)
+                .map(|_| rng.gen_range(0..1000) as f64)
+                .collect();
+            let seat_count = rng.gen_range(1..40);
+            let bounds = random_bounds(&mut rng, party_count, i % 2 == 0);
+            let mut rules = rules();
+            for (p, (min, max)) in bounds.iter().enumerate() {
+                rules = rules
+                    .with(Constraint::MinSeats(p, *min))
+                    .with(Constraint::MaxSeats(p, *max));
+            }
+            let exact = rules.distribute(&votes, &seat_count, &false);
+            let iterative = redistribute(&Method::SainteLague, &votes, &seat_count, &bounds);
+            match (exact, iterative) {
+                (Ok(exact), Ok(iterative)) => {
+                    assert_eq!(iterative.seats, exact, "{:?} {:?}", votes, bounds);
+                    assert!(iterative.rounds <= 5 * party_count + 1);
+                    compared += 1;
+                }
+                // ties may be detected in different rounds
+                (Err(ConstraintError::Distribution(DistributionError::Tied)), _)
+                | (_, Err(ConstraintError::Distribution(DistributionError::Tied))) => (),
+                (exact, iterative) => assert_eq!(exact, iterative.map(|r| r.seats)),
+            }
+        }
+        assert!(compared > 1000);
+    }
+
+    #[test]
+    fn redistribute_matches_exact_with_both_bounds() {
+        let mut rng = StdRng::seed_from_u64(4581);
+        let mut compared = 0;
+        for _ in 0..2000 {
+            let party_count = rng.gen_range(1..8);
+            let votes: Vec<f64> = (0..party_count)
+                .map(|_| rng.gen_range(0..1000) as f64)
+                .collect();
+            let seat_count = rng.gen_range(1..40);
+            let bounds: Vec<(usize, usize)> = (0..party_count)
+                .map(|_| {
+                    let min = rng.gen_range(0..5);
+                    (min, min + rng.gen_range(0..10))
+                })
+                .collect();
+            let mut rules = rules();
+            for (p, (min, max)) in bounds.iter().enumerate() {
+                rules = rules
+                    .with(Constraint::MinSeats(p, *min))
+                    .with(Constraint::MaxSeats(p, *max));
+            }
+            let exact = rules.distribute(&votes, &seat_count, &false);
+            let iterative = redistribute(&Method::SainteLague, &votes, &seat_count, &bounds);
+            match (exact, iterative) {
+                (Ok(exact), Ok(iterative)) => {
+                    assert_eq!(iterative.seats, exact, "{:?} {:?}", votes, bounds);
+                    compared += 1;
+                }
+                (Err(ConstraintError::Distribution(DistributionError::Tied)), _)
+                | (_, Err(ConstraintError::Distribution(DistributionError::Tied))) => (),
+                (exact, iterative) => assert_eq!(
+                    exact,
+                    iterative.map(|r| r.seats),
+                    "{:?} {} {:?}",
+                    votes,
+                    seat_count,
+                    bounds
+                ),
+            }
+        }
+        assert!(compared > 500);
+    }
+
+    #[test]
+    fn redistribute_terminates_with_both_bounds() {
+        let mut rng = StdRng::seed_from_u64(4580);
+        let allocator = |votes: &[f64], seat_count: &usize| distribute(votes, seat_count, &true);
+        for _ in 0..2000 {
+            let party_count = rng.gen_range(1..8);
+            let votes: Vec<f64> = (0..party_count)
+                .map(|_| rng.gen_range(0..4) as f64)
+                .collect();
+            let seat_count = rng.gen_range(1..20);
+            let bounds: Vec<(usize, usize)> = (0..party_count)
+                .map(|_| {
+                    let min = rng.gen_range(0..4);
+                    (min, min + rng.gen_range(0..6))
+                })
+                .collect();
+            if let Ok(r) = redistribute(&allocator, &votes, &seat_count, &bounds) {
+                assert!(r.rounds <= 5 * party_count + 1);
+                assert_eq!(r.seats.iter().sum::<usize>(), seat_count);
+                for (s, (min, max)) in r.seats.iter().zip(bounds.iter()) {
+                    assert!(min <= s && s <= max);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn redistribute_with_binding_minimums_and_maximums() {
+        let votes = [70.0, 16.0, 14.0];
+        let bounds = [(0, 6), (4, 10), (0, 10)];
+        let exact = rules()
+            .with(Constraint::MaxSeats(0, 6))
+            .with(Constraint::MinSeats(1, 4));
+        assert_eq!(exact.distribute(&votes, &10, &false), Ok(vec![5, 4, 1]));
+        // the maximum of the first party is released once the minimum of the second party takes a seat
+        let iterative = redistribute(&Method::SainteLague, &votes, &10, &bounds).unwrap();
+        assert_eq!(iterative.seats, vec![5, 4, 1]);
+
+        assert_eq!(
+            redistribute(&Method::SainteLague, &[90.0, 10.0], &10, &[(0, 8), (3, 10)])
+                .map(|r| r.seats),
+            Ok(vec![7, 3])
+        );
+    }
+
+    #[test]
+    fn redistribute_errors() {
+        let method = Method::SainteLague;
+        assert_eq!(
+            redistribute(&method, &[1.0, 1.0], &2, &[(0, 2)]),
+            Err(ConstraintError::UnknownParty(1))
+        );
+        assert_eq!(
+            redistribute(&method, &[1.0, 1.0], &2, &[(0, 2), (2, 1)]),
+            Err(ConstraintError::ConflictingBounds(1))
+        );
+        assert_eq!(
+            redistribute(&method, &[1.0, 1.0], &3, &[(0, 1), (0, 1)]),
+            Err(ConstraintError::Infeasible)
+        );
+        assert_eq!(
+            redistribute(&method, &[1.0, 1.0], &1, &[(0, 1), (0, 1)]),
+            Err(ConstraintError::Distribution(DistributionError::Tied))
+        );
+        assert_eq!(
+            redistribute(&method, &[1.0, 0.0], &3, &[(0, 2), (1, 1)]),
+            Ok(super::Redistribution {
+                seats: vec![2, 1],
+                rounds: 1
+            })
+        );
+    }
+}
@@ -0,0 +1,343 @@
+//! Counterfactual results for mergers and splits of parties.
+//!
+//! "What if these two parties had run on a joint list?" is easy to get wrong by hand: the votes have to be moved in every district, and thresholds, alliances and other [constraints](crate::constraints) referring to either party have to be carried over. A [`Change`] does both for an [`ElectoralSystem`] and its [`Ballots`], and [`counterfactual`] compares the actual result with the changed one.
+//!
+//! Party indices stay the same: a party merged into another keeps its index with zero votes, and the new party of a split is appended after all others.
+//!
+//! ```
+//! use sainte_lague::constraints::Constraint;
+//! use sainte_lague::counterfactual::{counterfactual, Change};
+//! use sainte_lague::methods::Stationary;
+//! use sainte_lague::system::{Ballots, ElectoralSystem, Tier};
+//!
+//! let system = ElectoralSystem::new("Example", Stationary::new(0.5).unwrap(), Tier::National(20))
+//!     .with(Constraint::Threshold(0.05));
+//! let ballots = Ballots::new(vec![48.0, 40.0, 4.0, 4.0, 4.0]);
+//!
+//! // separately, the small parties fail the threshold, together they pass it
+//! let merger = counterfactual(&system, &ballots, &Change::Merge { into: 2, from: 3 }).unwrap();
+//! assert_eq!(merger.before.seats(), vec![11, 9, 0, 0, 0]);
+//! assert_eq!(merger.after.seats(), vec![10, 8, 2, 0, 0]);
+//! assert_eq!(merger.gains(), vec![-1, -1, 2, 0, 0]);
+//! ```
+
+use crate::constraints::{Constraint, ConstraintError, Rules};
+use crate::pipeline::{Context, PipelineError};
+use crate::system::{Ballots, ElectoralSystem, SystemOutcome};
+
+/// A change of the parties taking part in an election, see the [module documentation](self).
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Change {
+    /// The party `from` joins the party `into`: its votes and district seats are added to those of `into` in every district, and it keeps its index with zero votes.
+    ///
+    /// Constraints are carried over to the merged party: an exemption of either party applies to it, alliances of either party become a single alliance including it, and minimums add up. A maximum only remains if both parties had one, as their sum. The weight of `from` is dropped. Merging a party marked as [`Others`](Constraint::Others) fails with [`ConstraintError::ConflictingOthers`].
+    Merge {
+        /// The index of the party receiving the votes.
+        into: usize,
+        /// The index of the party giving up its votes.
+        from: usize,
+    },
+
+    /// The share `ratio` (between `0.0` and `1.0`) of the votes of `party` in every district goes to a new party, appended after all others. District seats stay with `party`.
+    ///
+    /// The new party shares the exemption, alliance and weight of `party`, its seat bounds stay with `party`. Splitting a party marked as [`Others`](Constraint::Others) fails with [`ConstraintError::ConflictingOthers`], a `ratio` outside of `0.0..=1.0` with [`ConstraintError::InvalidWeight`].
+    Split {
+        /// The index of the party that splits.
+        party: usize,
+        /// The share of its votes going to the new party.
+        ratio: f64,
+    },
+}
+
+impl Change {
+    /// A description of the change, used in errors.
+    pub fn label(&self) -> &str {
+        match self {
+            Change::Merge { .. } => "merger",
+            Change::Split { .. } => "split",
+        }
+    }
+
+    /// Apply the change to the `system` and its `ballots`. Fails with [`ConstraintError::UnknownParty`] if a party doesn't exist in the ballots, or if the rows of list votes or the district winners differ in length.
+    pub fn apply(
+        &self,
+        system: &ElectoralSystem,
+        ballots: &Ballots,
+    ) -> Result<(ElectoralSystem, Ballots), ConstraintError> {
+        let party_count = ballots.votes.first().map_or(0, Vec::len);
+        let check_party = |p: usize| match p < party_count {
+            true => Ok(()),
+            false => Err(ConstraintError::UnknownParty(p)),
+        };
+        let rows = ballots.votes.iter().map(Vec::len);
+        let winners = Some(ballots.district_winners.len()).filter(|l| *l > 0);
+        if let Some(len) = rows.chain(winners).find(|l| *l != party_count) {
+            return Err(ConstraintError::UnknownParty(len.min(party_count)));
+        }
+        let mut system = system.clone();
+        let mut ballots = ballots.clone();
+        match *self {
+            Change::Merge { into, from } => {
+                check_party(into)?;
+                check_party(from)?;
+                if into == from {
+                    return Ok((system, ballots));
+                }
+                system.rules = merge_rules(&system.rules, into, from)?;
+                for row in ballots.votes.iter_mut() {
+                    row[into] += row[from];
+                    row[from] = 0.0;
+                }
+                if !ballots.district_winners.is_empty() {
+                    ballots.district_winners[into] += ballots.district_winners[from];
+                    ballots.district_winners[from] = 0;
+                }
+            }
+            Change::Split { party, ratio } => {
+                check_party(party)?;
+                if !(0.0..=1.0).contains(&ratio) {
+                    return Err(ConstraintError::InvalidWeight(party));
+                }
+                system.rules = split_rules(&system.rules, party, party_count)?;
+                for row in ballots.votes.iter_mut() {
+                    let moved = row[party] * ratio;
+                    row[party] -= moved;
+                    row.push(moved);
+                }
+                if !ballots.district_winners.is_empty() {
+                    ballots.district_winners.push(0);
+                }
+            }
+        }
+        Ok((system, ballots))
+    }
+}
+
+/// The actual and the changed result, see [`counterfactual`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Comparison {
+    /// The result of the unchanged election.
+    pub before: SystemOutcome,
+    /// The result after the change.
+    pub after: SystemOutcome,
+}
+
+impl Comparison {
+    /// The change of the total seats of each party, in the party order after the change. The new party of a split had no seats before.
+    pub fn gains(&self) -> Vec<isize> {
+        let before = self.before.seats();
+        self.after
+            .seats()
+            .iter()
+            .enumerate()
+            .map(|(p, s)| *s as isize - before.get(p).cloned().unwrap_or(0) as isize)
+            .collect()
+    }
+}
+
+/// Distribute the seats of `system` for the `ballots` before and after the `change`. Fails with a [`PipelineError`] for step 1 if the actual distribution fails, step 2 if the change can't be applied and step 3 if the changed distribution fails.
+pub fn counterfactual(
+    system: &ElectoralSystem,
+    ballots: &Ballots,
+    change: &Change,
+) -> Result<Comparison, PipelineError> {
+    let before = system.distribute(ballots).context(1, "actual result")?;
+    let (changed_system, changed_ballots) =
+        change.apply(system, ballots).context(2, change.label())?;
+    let after = changed_system
+        .distribute(&changed_ballots)
+        .context(3, "counterfactual result")?;
+    Ok(Comparison { before, after })
+}
+
+fn merge_rules(rules: &Rules, into: usize, from: usize) -> Result<Rules, ConstraintError> {
+    let mut merged = Rules::new(rules.method).with_tolerance(rules.tolerance());
+    let mut alliance = vec![];
+    let (mut min, mut max) = (0, (0, 0));
+    for constraint in rules.constraints() {
+        match constraint {
+            Constraint::Others(p) if *p == into || *p == from => {
+                return Err(ConstraintError::ConflictingOthers(*p))
+            }
+            Constraint::Exemption(p) if *p == into || *p == from => {
+                if !merged.constraints().contains(&Constraint::Exemption(into)) {
+                    merged = merged.with(Constraint::Exemption(into));
+                }
+            }
+            Constraint::Alliance(parties) if parties.contains(&into) || parties.contains(&from) => {
+                for p in parties.iter().chain([into].iter()) {
+                    if *p != from && !alliance.contains(p) {
+                        alliance.push(*p);
+                    }
+                }
+            }
+            Constraint::MinSeats(p, seats) if *p == into || *p == from => min += seats,
+            Constraint::MaxSeats(p, seats) if *p == into || *p == from => {
+                max.0 += 1;
+                max.1 += seats;
+            }
+            Constraint::Weight(p, _) if *p == from => {}
+            constraint => merged = merged.with(constraint.clone()),
+        }
+    }
+    if alliance.len() > 1 {
+        merged = merged.with(Constraint::Alliance(alliance));
+    }
+    if min > 0 {
+        merged = merged.with(Constraint::MinSeats(into, min));
+    }
+    if max.0 >= 2 {
+        merged = merged.with(Constraint::MaxSeats(into, max.1));
+    }
+    Ok(merged)
+}
+
+fn split_rules(rules: &Rules, party: usize, new: usize) -> Result<Rules, ConstraintError> {
+    let mut split = Rules::new(rules.method).with_tolerance(rules.tolerance());
+    for constraint in rules.constraints() {
+        split = match constraint {
+            Constraint::Others(p) if *p == party => {
+                return Err(ConstraintError::ConflictingOthers(*p))
+            }
+            Constraint::Exemption(p) if *p == party => split
+                .with(Constraint::Exemption(party))
+                .with(Constraint::Exemption(new)),
+            Constraint::Alliance(parties) if parties.contains(&party) => {
+                let mut parties = parties.clone();
+                parties.push(new);
+                split.with(Constraint::Alliance(parties))
+            }
+            Constraint::Weight(p, weight) if *p == party => split
+                .with(Constraint::Weight(party, *weight))
+                .with(Constraint::Weight(new, *weight)),
+            constraint => split.with(constraint.clone()),
+        };
+    }
+    Ok(split)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{counterfactual, Change};
+    use crate::constraints::{Constraint, ConstraintError};
+    use crate::methods::Stationary;
+    use crate::system::{Ballots, ElectoralSystem, Tier};
+
+    fn system(tier: Tier) -> ElectoralSystem {
+        ElectoralSystem::new("Test", Stationary::new(0.5).unwrap(), tier)
+    }
+
+    fn districts() -> Tier {
+        Tier::Districts(vec![("A".to_string(), 5), ("B".to_string(), 5)])
+    }
+
+    #[test]
+    fn mergers() {
+        let ballots = Ballots::districts(vec![
+            vec![52.0, 30.0, 13.0, 5.0],
+            vec![40.0, 30.0, 20.0, 11.0],
+        ]);
+        let bounded = system(districts())
+            .with(Constraint::Threshold(0.1))
+            .with(Constraint::Alliance(vec![1, 3]))
+            .with(Constraint::MinSeats(2, 1))
+            .with(Constraint::MaxSeats(3, 2));
+        let (merged, changed) = Change::Merge { into: 2, from: 3 }
+            .apply(&bounded, &ballots)
+            .unwrap();
+        assert_eq!(
+            changed.votes,
+            vec![vec![52.0, 30.0, 18.0, 0.0], vec![40.0, 30.0, 31.0, 0.0]]
+        );
+        assert_eq!(
+            merged.rules.constraints(),
+            &[
+                Constraint::Threshold(0.1),
+                Constraint::Alliance(vec![1, 2]),
+                Constraint::MinSeats(2, 1),
+            ]
+        );
+
+        // party 3 only passes the threshold through the alliance
+        let system = system(districts())
+            .with(Constraint::Threshold(0.1))
+            .with(Constraint::Alliance(vec![2, 3]));
+        let comparison =
+            counterfactual(&system, &ballots, &Change::Merge { into: 0, from: 3 }).unwrap();
+        assert_eq!(comparison.after.seats().iter().sum::<usize>(), 10);
+        assert_eq!(comparison.after.seats()[3], 0);
+        assert_eq!(comparison.gains().iter().sum::<isize>(), 0);
+    }
+
+    #[test]
+    fn splits() {
+        let system = system(Tier::National(10))
+            .with(Constraint::Threshold(0.05))
+            .with(Constraint::Exemption(1))
+            .with(Constraint::MinSeats(1, 1));
+        let ballots = Ballots::new(vec![80.0, 20.0]).with_district_winners(vec![0, 0]);
+        let comparison = counterfactual(
+            &system,
+            &ballots,
+            &Change::Split {
+                party: 0,
+                ratio: 0.25,
+            },
+        )
+        .unwrap();
+        assert_eq!(comparison.before.seats(), vec![8, 2]);
+        assert_eq!(comparison.after.seats(), vec![6, 2, 2]);
+        assert_eq!(comparison.gains(), vec![-2, 0, 2]);
+
+        let (split, _) = Change::Split {
+            party: 1,
+            ratio: 0.5,
+        }
+        .apply(&system, &ballots)
+        .unwrap();
+        assert!(split
+            .rules
+            .constraints()
+            .contains(&Constraint::Exemption(2)));
+        assert!(!split
+            .rules
+            .constraints()
+            .contains(&Constraint::MinSeats(2, 1)));
+    }
+
+    #[test]
+    fn invalid_changes() {
+        let system = system(Tier::National(10)).with(Constraint::Others(2));
+        let ballots = Ballots::new(vec![50.0, 30.0, 20.0]);
+        let step = |change: Change| {
+            let error = counterfactual(&system, &ballots, &change).unwrap_err();
+            (error.step(), *error.root_cause())
+        };
+        assert_eq!(
+            step(Change::Merge { into: 0, from: 3 }),
+            (2, ConstraintError::UnknownParty(3))
+        );
+        assert_eq!(
+            step(Change::Merge { into: 0, from: 2 }),
+            (2, ConstraintError::ConflictingOthers(2))
+        );
+        assert_eq!(
+            step(Change::Split {
+                party: 1,
+                ratio: 1.5
+            }),
+            (2, ConstraintError::InvalidWeight(1))
+        );
+        let short = ballots.clone().with_district_winners(vec![1, 0]);
+        let error = Change::Merge { into: 0, from: 2 }.apply(&system, &short);
+        assert_eq!(error.unwrap_err(), ConstraintError::UnknownParty(2));
+        let ragged = Ballots::districts(vec![vec![1.0, 2.0, 3.0], vec![1.0]]);
+        let error = Change::Merge { into: 0, from: 2 }.apply(&system, &ragged);
+        assert_eq!(error.unwrap_err(), ConstraintError::UnknownParty(1));
+        assert_eq!(
+            counterfactual(&system, &ballots, &Change::Merge { into: 1, from: 1 })
+                .map(|c| c.gains()),
+            Ok(vec![0, 0, 0])
+        );
+    }
+}
@@ -0,0 +1,278 @@
+//! Ballots giving several votes to candidates, as in Swiss and German local elections.
+//!
+//! With cumulative voting, every voter has as many votes as there are seats, their budget, and writes candidates of any list onto their ballot, each line being one vote. A candidate may appear on several lines, but only up to the cumulation limit, usually two or three lines. A ballot may also name a list, which receives the votes of all empty lines. The votes of a party are the votes of its candidates plus the votes of the empty lines of ballots naming it.
+//!
+//! [`Cumulative::tally`] adds up the votes of all ballots. Lines exceeding the limit or the budget don't invalidate the ballot, only these lines are struck. Every struck line is reported as a [`Rejection`], so the count can be audited before the seats are distributed with the resulting [`Election`].
+//!
+//! ```
+//! use sainte_lague::constraints::Rules;
+//! use sainte_lague::cumulative::{Ballot, Cumulative, Reason};
+//!
+//! // four seats, candidates 0 and 1 on list 0, candidates 2 and 3 on list 1
+//! let system = Cumulative::new(vec![0, 0, 1, 1], 2, 4, 2).unwrap();
+//! let ballots = [
+//!     Ballot::new(vec![0, 0, 1, 1]).with_weight(30.0),
+//!     // two empty lines for list 1
+//!     Ballot::new(vec![2, 3]).with_list(1).with_weight(25.0),
+//!     // the third vote for candidate 2 is struck, and its line stays empty
+//!     Ballot::new(vec![2, 2, 2]).with_weight(10.0),
+//! ];
+//! let tally = system.tally(&ballots);
+//! assert_eq!(tally.candidates, vec![60.0, 60.0, 45.0, 25.0]);
+//! assert_eq!(tally.parties, vec![120.0, 120.0]);
+//! assert_eq!(tally.rejected[0].reason, Reason::OverLimit(2));
+//! assert_eq!(tally.rejected_votes, 10.0);
+//! assert_eq!(tally.election().distribute(&Rules::default(), &4, &false), Ok(vec![2, 2]));
+//! ```
+
+use crate::election::Election;
+use crate::locale::{text, text_with, Locale, Localize, Text};
+use std::fmt;
+
+/// The rules of a cumulative vote, see the [module documentation](self).
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Cumulative {
+    parties: Vec<usize>,
+    party_count: usize,
+    budget: usize,
+    limit: usize,
+}
+
+impl Cumulative {
+    /// Create the rules for candidates belonging to the parties with the given indices, e.g. `vec![0, 0, 1]` for two candidates of party 0 and one of party 1, out of `party_count` parties. Every voter has `budget` votes and may give up to `limit` of them to the same candidate. Returns [`None`] if a candidate belongs to a party out of range, or the budget or limit is zero.
+    pub fn new(
+        parties: Vec<usize>,
+        party_count: usize,
+        budget: usize,
+        limit: usize,
+    ) -> Option<Cumulative> {
+        if budget == 0 || limit == 0 || parties.iter().any(|p| *p >= party_count) {
+            return None;
+        }
+        Some(Cumulative {
+            parties,
+            party_count,
+            budget,
+            limit,
+        })
+    }
+
+    /// The number of votes of every voter.
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    /// The maximum number of votes of one voter for the same candidate.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Add up the votes of all `ballots`, striking the lines that are invalid, see the [module documentation](self). Lines after the first `budget` ones are struck, as are lines naming an unknown candidate and lines exceeding the limit of their candidate. Struck lines count as empty lines, except for those exceeding the budget. Ballots with a weight that isn't positive and finite, or naming an unknown list, are rejected as a whole.
+    pub fn tally(&self, ballots: &[Ballot]) -> Tally {
+        let mut tally = Tally {
+            candidates: vec![0.0; self.parties.len()],
+            parties: vec![0.0; self.party_count],
+            list_votes: vec![0.0; self.party_count],
+            rejected: vec![],
+            rejected_votes: 0.0,
+        };
+        for (b, ballot) in ballots.iter().enumerate() {
+            let rejection = |line, reason| Rejection {
+                ballot: b,
+                line,
+                reason,
+            };
+            if !(ballot.weight.is_finite() && ballot.weight > 0.0) {
+                tally.rejected.push(rejection(None, Reason::InvalidWeight));
+                continue;
+            }
+            if let Some(list) = ballot.list.filter(|l| *l >= self.party_count) {
+                tally
+                    .rejected
+                    .push(rejection(None, Reason::UnknownList(list)));
+                tally.rejected_votes += ballot.lines.len().min(self.budget) as f64 * ballot.weight;
+                continue;
+            }
+
+            let mut cumulated = vec![0; self.parties.len()];
+            let mut valid = 0;
+            for (line, candidate) in ballot.lines.iter().enumerate() {
+                let reason = if line >= self.budget {
+                    Reason::OverBudget
+                } else if *candidate >= self.parties.len() {
+                    Reason::UnknownCandidate(*candidate)
+                } else if cumulated[*candidate] >= self.limit {
+                    Reason::OverLimit(*candidate)
+                } else {
+                    cumulated[*candidate] += 1;
+                    valid += 1;
+                    tally.candidates[*candidate] += ballot.weight;
+                    tally.parties[self.parties[*candidate]] += ballot.weight;
+                    continue;
+                };
+                tally.rejected.push(rejection(Some(line), reason));
+                tally.rejected_votes += ballot.weight;
+            }
+            if let Some(list) = ballot.list {
+                let empty = (self.budget - valid) as f64 * ballot.weight;
+                tally.list_votes[list] += empty;
+                tally.parties[list] += empty;
+            }
+        }
+        tally
+    }
+}
+
+/// Identical ballots cast by one or more voters, see the [module documentation](self).
+#[derive(Clone, PartialEq, Debug)]
+pub struct Ballot {
+    /// The candidate written on each line, by index.
+    pub lines: Vec<usize>,
+    /// The list named on the ballot, which receives the votes of the empty lines.
+    pub list: Option<usize>,
+    /// The number of voters who cast this ballot.
+    pub weight: f64,
+}
+
+impl Ballot {
+    /// Create a ballot of one voter with the given lines and no list.
+    pub fn new(lines: Vec<usize>) -> Ballot {
+        Ballot {
+            lines,
+            list: None,
+            weight: 1.0,
+        }
+    }
+
+    /// Name the list with the given index on the ballot.
+    pub fn with_list(mut self, list: usize) -> Ballot {
+        self.list = Some(list);
+        self
+    }
+
+    /// Set the number of voters who cast this ballot.
+    pub fn with_weight(mut self, weight: f64) -> Ballot {
+        self.weight = weight;
+        self
+    }
+}
+
+/// Why a line or ballot was struck, see [`Cumulative::tally`].
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Reason {
+    /// The weight of the ballot isn't positive and finite.
+    InvalidWeight,
+
+    /// The ballot names a list with this index, which doesn't exist.
+    UnknownList(usize),
+
+    /// The line names a candidate with this index, which doesn't exist.
+    UnknownCandidate(usize),
+
+    /// The candidate with this index already received the maximum number of votes from this ballot.
+    OverLimit(usize),
+
+    /// The ballot already used all votes of the budget.
+    OverBudget,
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.localize(&Locale::English))
+    }
+}
+
+impl Localize for Reason {
+    fn localize(&self, locale: &Locale) -> String {
+        match self {
+            Reason::InvalidWeight => text(locale, Text::InvalidBallotWeight).to_string(),
+            Reason::UnknownList(l) => text_with(locale, Text::UnknownList, l),
+            Reason::UnknownCandidate(c) => text_with(locale, Text::UnknownCandidate, c),
+            Reason::OverLimit(c) => text_with(locale, Text::OverLimit, c),
+            Reason::OverBudget => text(locale, Text::OverBudget).to_string(),
+        }
+    }
+}
+
+/// A struck line or ballot, see [`Cumulative::tally`].
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Rejection {
+    /// The index of the ballot.
+    pub ballot: usize,
+    /// The index of the struck line, or [`None`] if the whole ballot was rejected.
+    pub line: Option<usize>,
+    /// The reason.
+    pub reason: Reason,
+}
+
+/// The votes of all ballots, see [`Cumulative::tally`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct Tally {
+    /// The votes of each candidate.
+    pub candidates: Vec<f64>,
+    /// The votes of each party, i.e. of its candidates and of the empty lines of ballots naming it.
+    pub parties: Vec<f64>,
+    /// The votes of each party from empty lines alone.
+    pub list_votes: Vec<f64>,
+    /// The struck lines and ballots, in the order of the ballots and their lines.
+    pub rejected: Vec<Rejection>,
+    /// The number of votes on struck lines, weighted by their ballots. Ballots with an invalid weight don't count.
+    pub rejected_votes: f64,
+}
+
+impl Tally {
+    /// The election between the parties. Rejected votes aren't counted as invalid ballots, as they are votes and not ballots.
+    pub fn election(&self) -> Election {
+        Election::new(self.parties.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Ballot, Cumulative, Reason, Rejection};
+
+    #[test]
+    fn struck_lines() {
+        let system = Cumulative::new(vec![0, 1, 1], 3, 3, 2).unwrap();
+        let ballots = [
+            Ballot::new(vec![1, 7, 1, 1, 0]).with_list(2),
+            Ballot::new(vec![0, 0, 0, 0, 2]).with_weight(2.0),
+            Ballot::new(vec![0]).with_list(5),
+            Ballot::new(vec![0]).with_weight(f64::NAN),
+        ];
+        let tally = system.tally(&ballots);
+        let rejection = |ballot, line, reason| Rejection {
+            ballot,
+            line,
+            reason,
+        };
+        assert_eq!(
+            tally.rejected,
+            vec![
+                rejection(0, Some(1), Reason::UnknownCandidate(7)),
+                rejection(0, Some(3), Reason::OverBudget),
+                rejection(0, Some(4), Reason::OverBudget),
+                rejection(1, Some(2), Reason::OverLimit(0)),
+                rejection(1, Some(3), Reason::OverBudget),
+                rejection(1, Some(4), Reason::OverBudget),
+                rejection(2, None, Reason::UnknownList(5)),
+                rejection(3, None, Reason::InvalidWeight),
+            ]
+        );
+        assert_eq!(tally.rejected_votes, 10.0);
+        assert_eq!(tally.candidates, vec![4.0, 2.0, 0.0]);
+        // the unknown candidate's line is empty and goes to list 2
+        assert_eq!(tally.list_votes, vec![0.0, 0.0, 1.0]);
+        assert_eq!(tally.parties, vec![4.0, 2.0, 1.0]);
+        assert_eq!(tally.election().votes, tally.parties);
+    }
+
+    #[test]
+    fn invalid_rules() {
+        assert_eq!(Cumulative::new(vec![0, 1], 1, 2, 1), None);
+        assert_eq!(Cumulative::new(vec![0], 1, 0, 1), None);
+        assert_eq!(Cumulative::new(vec![0], 1, 2, 0), None);
+        let system = Cumulative::new(vec![], 1, 2, 1).unwrap();
+        assert_eq!((system.budget(), system.limit()), (2, 1));
+    }
+}
@@ -0,0 +1,214 @@
+//! Recomputed distributions after parties were disqualified.
+//!
+//! When a court disqualifies a party after the seats have been allocated, e.g. because its list was submitted unlawfully, it also orders how its seats are to be recomputed. The two common orders are a full [`Recount`](Recomputation::Recount) as if the party had never run, which applies the threshold to the remaining votes and may let other parties pass it, and a [`Reallocation`](Recomputation::Reallocation) of only the seats of the disqualified party, which leaves all other seats untouched and continues the distribution among the parties that originally qualified. [`disqualify`] computes either one and reports the differences to the original result.
+//!
+//! ```
+//! use sainte_lague::constraints::{Constraint, Rules};
+//! use sainte_lague::disqualification::{disqualify, Recomputation};
+//!
+//! let votes = [480.0, 310.0, 150.0, 60.0];
+//! let rules = Rules::default().with(Constraint::Threshold(0.065));
+//!
+//! // without the first party, the last one passes the threshold
+//! let recount = disqualify(&rules, &votes, &20, &[0], Recomputation::Recount).unwrap();
+//! assert_eq!(recount.before, vec![10, 7, 3, 0]);
+//! assert_eq!(recount.after, vec![0, 12, 6, 2]);
+//!
+//! // only the ten seats of the first party are reallocated, among the parties that passed the threshold
+//! let reallocation = disqualify(&rules, &votes, &20, &[0], Recomputation::Reallocation).unwrap();
+//! assert_eq!(reallocation.after, vec![0, 13, 7, 0]);
+//! assert_eq!(reallocation.gains(), vec![-10, 6, 4, 0]);
+//! ```
+
+use crate::constraints::{Constraint, ConstraintError, Rules};
+use crate::pipeline::{Context, PipelineError};
+
+/// How the seats are recomputed after parties were disqualified, see the [module documentation](self).
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Recomputation {
+    /// Distribute all seats again, with the votes of the disqualified parties void. The threshold applies to the remaining votes. Constraints referring to disqualified parties are dropped, and they are removed from their alliances.
+    Recount,
+
+    /// Every other party keeps its seats, and the seats of the disqualified parties go to the parties that originally qualified, continuing the distribution with the method, weights and seat maximums of the rules. For divisor methods, this is the same as handing out the seats one by one to the highest remaining quotients.
+    Reallocation,
+}
+
+impl Recomputation {
+    /// A description of the recomputation, used in errors.
+    pub fn label(&self) -> &str {
+        match self {
+            Recomputation::Recount => "recount",
+            Recomputation::Reallocation => "reallocation",
+        }
+    }
+}
+
+/// The original and the recomputed distribution, see [`disqualify`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Disqualification {
+    /// The seats of each party before the disqualification.
+    pub before: Vec<usize>,
+    /// The seats of each party after the recomputation. Disqualified parties have no seats.
+    pub after: Vec<usize>,
+}
+
+impl Disqualification {
+    /// The change of the seats of each party.
+    pub fn gains(&self) -> Vec<isize> {
+        self.before
+            .iter()
+            .zip(self.after.iter())
+            .map(|(before, after)| *after as isize - *before as isize)
+            .collect()
+    }
+
+    /// The indices of the parties whose seats changed, including the disqualified ones if they had any.
+    pub fn changed(&self) -> Vec<usize> {
+        (0..self.before.len())
+            .filter(|p| self.before[*p] != self.after[*p])
+            .collect()
+    }
+}
+
+/// Distribute `seat_count` seats for the `votes` under `rules`, then disqualify the parties with the given indices and recompute the seats according to `recomputation`. Ties are not resolved, as a court can't order a draw after the fact.
+///
+/// Fails with a [`PipelineError`] for step 1 if the original distribution fails, and for step 2 if the recomputation fails, e.g. with [`ConstraintError::UnknownParty`] for a disqualified party that doesn't exist, or with [`ConstraintError::Infeasible`] if no party is left to take the seats.
+pub fn disqualify(
+    rules: &Rules,
+    votes: &[f64],
+    seat_count: &usize,
+    disqualified: &[usize],
+    recomputation: Recomputation,
+) -> Result<Disqualification, PipelineError> {
+    let before = rules
+        .distribute(votes, seat_count, &false)
+        .context(1, "original result")?;
+    let after = match recomputation {
+        Recomputation::Recount => recount(rules, votes, seat_count, disqualified),
+        Recomputation::Reallocation => reallocate(rules, votes, seat_count, disqualified, &before),
+    }
+    .context(2, recomputation.label())?;
+    Ok(Disqualification { before, after })
+}
+
+fn check_parties(votes: &[f64], disqualified: &[usize]) -> Result<(), ConstraintError> {
+    match disqualified.iter().find(|p| **p >= votes.len()) {
+        Some(p) => Err(ConstraintError::UnknownParty(*p)),
+        None => Ok(()),
+    }
+}
+
+fn recount(
+    rules: &Rules,
+    votes: &[f64],
+    seat_count: &usize,
+    disqualified: &[usize],
+) -> Result<Vec<usize>, ConstraintError> {
+    check_parties(votes, disqualified)?;
+    let mut remaining = Rules::new(rules.method).with_tolerance(rules.tolerance());
+    for constraint in rules.constraints() {
+        match constraint {
+            Constraint::Exemption(p)
+            | Constraint::Others(p)
+            | Constraint::MinSeats(p, _)
+            | Constraint::MaxSeats(p, _)
+            | Constraint::Weight(p, _)
+                if disqualified.contains(p) => {}
+            Constraint::Alliance(parties) => {
+                let parties: Vec<usize> = parties
+                    .iter()
+                    .filter(|p| !disqualified.contains(p))
+                    .cloned()
+                    .collect();
+                if parties.len() > 1 {
+                    remaining = remaining.with(Constraint::Alliance(parties));
+                }
+            }
+            constraint => remaining = remaining.with(constraint.clone()),
+        }
+    }
+    let mut votes = votes.to_vec();
+    for p in disqualified {
+        votes[*p] = 0.0;
+    }
+    remaining.distribute(&votes, seat_count, &false)
+}
+
+fn reallocate(
+    rules: &Rules,
+    votes: &[f64],
+    seat_count: &usize,
+    disqualified: &[usize],
+    before: &[usize],
+) -> Result<Vec<usize>, ConstraintError> {
+    check_parties(votes, disqualified)?;
+    let mut qualified = rules.qualified(votes)?;
+    for p in disqualified {
+        qualified[*p] = false;
+    }
+    // everything but the method, weights and maximums has already been applied to the original result
+    let mut continued = Rules::new(rules.method).with_tolerance(rules.tolerance());
+    for constraint in rules.constraints() {
+        if let Constraint::Weight(..) | Constraint::MaxSeats(..) = constraint {
+            continued = continued.with(constraint.clone());
+        }
+    }
+    for (p, seats) in before.iter().enumerate() {
+        if *seats > 0 && qualified[p] {
+            continued = continued.with(Constraint::MinSeats(p, *seats));
+        }
+    }
+    continued
+        .evaluate_qualified(votes, &qualified, seat_count, &false)
+        .map(|outcome| outcome.into_seats())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{disqualify, Recomputation};
+    use crate::constraints::{Constraint, ConstraintError, Rules};
+    use crate::DistributionError;
+
+    #[test]
+    fn recomputations() {
+        let votes = [30.0, 25.0, 20.0, 15.0, 10.0];
+        let rules = Rules::default()
+            .with(Constraint::Alliance(vec![0, 4]))
+            .with(Constraint::Threshold(0.15))
+            .with(Constraint::MaxSeats(1, 4));
+        let recount = disqualify(&rules, &votes, &12, &[0], Recomputation::Recount).unwrap();
+        assert_eq!(recount.before, vec![4, 3, 2, 2, 1]);
+        // the last party loses its ally and fails the threshold
+        assert_eq!(recount.after, vec![0, 4, 5, 3, 0]);
+        assert_eq!(recount.changed(), vec![0, 1, 2, 3, 4]);
+
+        let reallocation =
+            disqualify(&rules, &votes, &12, &[0], Recomputation::Reallocation).unwrap();
+        assert_eq!(reallocation.after, vec![0, 4, 3, 3, 2]);
+        assert_eq!(reallocation.gains(), vec![-4, 1, 1, 1, 1]);
+
+        let nothing = disqualify(&rules, &votes, &12, &[], Recomputation::Reallocation).unwrap();
+        assert!(nothing.changed().is_empty());
+    }
+
+    #[test]
+    fn failing_steps() {
+        let rules = Rules::default();
+        let step = |votes: &[f64], disqualified: &[usize], recomputation| {
+            let error = disqualify(&rules, votes, &2, disqualified, recomputation).unwrap_err();
+            (error.step(), *error.root_cause())
+        };
+        assert_eq!(
+            step(&[1.0, 1.0, 1.0], &[], Recomputation::Recount),
+            (1, DistributionError::Tied.into())
+        );
+        assert_eq!(
+            step(&[2.0, 1.0], &[2], Recomputation::Reallocation),
+            (2, ConstraintError::UnknownParty(2))
+        );
+        assert_eq!(
+            step(&[2.0, 1.0], &[0, 1], Recomputation::Reallocation),
+            (2, ConstraintError::Infeasible)
+        );
+    }
+}
@@ -0,0 +1,288 @@
+//! Election results including blank and invalid ballots.
+//!
+//! Thresholds are usually defined as a share of the valid votes, turnout as a share of the electorate. Whether blank ballots count as valid differs between countries: in Spain they do, so they make it harder for small parties to pass the threshold, while in Germany they are counted as invalid. An [`Election`] keeps track of all of these numbers, so they don't have to be computed by the caller.
+//!
+//! ```
+//! use sainte_lague::constraints::{Constraint, Rules};
+//! use sainte_lague::election::Election;
+//! use sainte_lague::Method;
+//!
+//! let rules = Rules::new(Method::SainteLague).with(Constraint::Threshold(0.03));
+//! let mut election = Election::new(vec![60.0, 30.0, 2.9]);
+//! election.blank = 7.1;
+//! assert_eq!(election.distribute(&rules, &20, &false), Ok(vec![13, 7, 0]));
+//!
+//! election.blank_is_valid = false;
+//! assert_eq!(election.distribute(&rules, &20, &false), Ok(vec![13, 6, 1]));
+//! ```
+//!
+//! # Pre-filters
+//!
+//! In some systems, only parties surviving an earlier stage, such as a first round or regional primaries, take part in the proportional allocation. A [`PreFilter`] determines the parties eliminated by such a stage, and [`Election::distribute_filtered`] runs it before the allocation. Eliminated parties receive no seats, but their votes remain valid votes. Errors of either stage are reported as a [`PipelineError`] with step 1 for the pre-filter and step 2 for the allocation.
+//!
+//! ```
+//! use sainte_lague::election::{Election, TopParties};
+//! use sainte_lague::constraints::Rules;
+//!
+//! let election = Election::new(vec![50.0, 30.0, 15.0, 5.0]);
+//! let seats = election.distribute_filtered(&Rules::default(), &TopParties(2), &10, &false);
+//! assert_eq!(seats, Ok(vec![6, 4, 0, 0]));
+//! ```
+
+use crate::constraints::{ConstraintError, Rules};
+use crate::pipeline::{Context, PipelineError};
+use crate::{validate_with_tolerance, DistributionError, Outcome};
+use std::cmp::Ordering;
+
+/// The result of an election, see the [module documentation](self).
+#[derive(Clone, PartialEq, Debug)]
+pub struct Election {
+    /// The votes of each party.
+    pub votes: Vec<f64>,
+    /// The number of blank ballots.
+    pub blank: f64,
+    /// The number of invalid ballots, not including blank ones.
+    pub invalid: f64,
+    /// Whether blank ballots count as valid votes, e.g. for thresholds.
+    pub blank_is_valid: bool,
+    /// The number of registered voters, if known.
+    pub electorate: Option<f64>,
+}
+
+impl Election {
+    /// Create an election without blank or invalid ballots and an unknown electorate. Blank ballots count as valid once they are added.
+    pub fn new(votes: Vec<f64>) -> Election {
+        Election {
+            votes,
+            blank: 0.0,
+            invalid: 0.0,
+            blank_is_valid: true,
+            electorate: None,
+        }
+    }
+
+    /// The number of valid votes, i.e. the sum of the party votes and, if they count as valid, the blank ballots.
+    pub fn valid_votes(&self) -> f64 {
+        let party_votes: f64 = self.votes.iter().sum();
+        if self.blank_is_valid {
+            party_votes + self.blank
+        } else {
+            party_votes
+        }
+    }
+
+    /// The number of ballots cast, valid or not.
+    pub fn ballots_cast(&self) -> f64 {
+        self.votes.iter().sum::<f64>() + self.blank + self.invalid
+    }
+
+    /// The share of the electorate that cast a ballot, or [`None`] if the electorate is unknown or empty.
+    pub fn turnout(&self) -> Option<f64> {
+        match self.electorate {
+            Some(electorate) if electorate > 0.0 => Some(self.ballots_cast() / electorate),
+            _ => None,
+        }
+    }
+
+    /// The share of the valid votes received by each party, or [`None`] if there are no valid votes.
+    pub fn shares(&self) -> Option<Vec<f64>> {
+        let valid_votes = self.valid_votes();
+        if valid_votes > 0.0 {
+            Some(self.votes.iter().map(|v| v / valid_votes).collect())
+        } else {
+            None
+        }
+    }
+
+    /// Determine which parties take part in the distribution, see [`Rules::qualified`]. The threshold is applied to the [valid votes](Election::valid_votes).
+    pub fn qualified(&self, rules: &Rules) -> Result<Vec<bool>, ConstraintError> {
+        self.check()?;
+        rules.qualified_with_total(&self.votes, self.valid_votes())
+    }
+
+    /// Distribute `seat_count` seats among the parties according to `rules`, with the threshold applied to the [valid votes](Election::valid_votes). Blank and invalid ballots never receive any seats.
+    pub fn distribute(
+        &self,
+        rules: &Rules,
+        seat_count: &usize,
+        draw_on_tie: &bool,
+    ) -> Result<Vec<usize>, ConstraintError> {
+        self.check()?;
+        rules
+            .evaluate_with_total(&self.votes, self.valid_votes(), seat_count, draw_on_tie)
+            .map(Outcome::into_seats)
+    }
+
+    /// Same as [`Election::distribute`], but the parties eliminated by `filter` don't take part in the distribution, see the [module documentation](self#pre-filters).
+    pub fn distribute_filtered<F: PreFilter>(
+        &self,
+        rules: &Rules,
+        filter: &F,
+        seat_count: &usize,
+        draw_on_tie: &bool,
+    ) -> Result<Vec<usize>, PipelineError> {
+        let eliminated = self.eliminated(filter).context(1, filter.label())?;
+        self.distribute_eliminated(rules, &eliminated, seat_count, draw_on_tie)
+            .context(2, "proportional allocation")
+    }
+
+    fn eliminated<F: PreFilter>(&self, filter: &F) -> Result<Vec<usize>, ConstraintError> {
+        let eliminated = filter.eliminated(self)?;
+        if let Some(p) = eliminated.iter().find(|p| **p >= self.votes.len()) {
+            return Err(ConstraintError::UnknownParty(*p));
+        }
+        Ok(eliminated)
+    }
+
+    fn distribute_eliminated(
+        &self,
+        rules: &Rules,
+        eliminated: &[usize],
+        seat_count: &usize,
+        draw_on_tie: &bool,
+    ) -> Result<Vec<usize>, ConstraintError> {
+        self.check()?;
+        validate_with_tolerance(&self.votes, seat_count, rules.tolerance())?;
+        let mut qualified = rules.qualified_with_total(&self.votes, self.valid_votes())?;
+        for p in eliminated.iter() {
+            qualified[*p] = false;
+        }
+        rules
+            .evaluate_qualified(&self.votes, &qualified, seat_count, draw_on_tie)
+            .map(Outcome::into_seats)
+    }
+
+    fn check(&self) -> Result<(), DistributionError> {
+        if self.blank < 0.0 || self.invalid < 0.0 {
+            return Err(DistributionError::NegativeVotes);
+        }
+        Ok(())
+    }
+}
+
+/// A stage eliminating parties before the proportional allocation, see the [module documentation](self#pre-filters).
+pub trait PreFilter {
+    /// A description of the stage, used in errors.
+    fn label(&self) -> &str;
+
+    /// The indices of the parties eliminated by this stage.
+    fn eliminated(&self, election: &Election) -> Result<Vec<usize>, ConstraintError>;
+}
+
+/// Only the parties with the most votes survive, e.g. the ones advancing from a first round. Parties tied with the last surviving party survive as well.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct TopParties(pub usize);
+
+impl PreFilter for TopParties {
+    fn label(&self) -> &str {
+        "first round"
+    }
+
+    fn eliminated(&self, election: &Election) -> Result<Vec<usize>, ConstraintError> {
+        let mut sorted = election.votes.clone();
+        sorted.sort_by(|a, b| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+        let cutoff = match self.0 {
+            0 => f64::INFINITY,
+            n => match sorted.get(n - 1) {
+                Some(v) => *v,
+                None => return Ok(vec![]),
+            },
+        };
+        Ok((0..election.votes.len())
+            .filter(|p| election.votes[*p] < cutoff)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Election, PreFilter, TopParties};
+    use crate::constraints::{Constraint, ConstraintError, Rules};
+    use crate::{DistributionError, Method};
+
+    #[test]
+    fn totals() {
+        let mut election = Election::new(vec![300.0, 150.0, 50.0]);
+        election.blank = 20.0;
+        election.invalid = 30.0;
+        assert_eq!(election.turnout(), None);
+        election.electorate = Some(1100.0);
+
+        assert_eq!(election.valid_votes(), 520.0);
+        assert_eq!(election.ballots_cast(), 550.0);
+        assert_eq!(election.turnout(), Some(0.5));
+        election.blank_is_valid = false;
+        assert_eq!(election.valid_votes(), 500.0);
+        assert_eq!(election.shares(), Some(vec![0.6, 0.3, 0.1]));
+    }
+
+    #[test]
+    fn invalid_ballots_dont_affect_threshold() {
+        let rules = Rules::new(Method::SainteLague).with(Constraint::Threshold(0.05));
+        let mut election = Election::new(vec![900.0, 50.0, 50.0]);
+        election.invalid = 500.0;
+        assert_eq!(election.qualified(&rules), Ok(vec![true, true, true]));
+        election.blank = 1.0;
+        assert_eq!(election.qualified(&rules), Ok(vec![true, false, false]));
+        assert_eq!(election.distribute(&rules, &10, &false), Ok(vec![10, 0, 0]));
+    }
+
+    #[test]
+    fn negative_ballots() {
+        let rules = Rules::default();
+        let mut election = Election::new(vec![1.0]);
+        election.invalid = -1.0;
+        assert_eq!(
+            election.distribute(&rules, &1, &false),
+            Err(ConstraintError::Distribution(
+                DistributionError::NegativeVotes
+            ))
+        );
+    }
+
+    struct Primaries(Vec<usize>);
+
+    impl PreFilter for Primaries {
+        fn label(&self) -> &str {
+            "regional primaries"
+        }
+
+        fn eliminated(&self, _: &Election) -> Result<Vec<usize>, ConstraintError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[test]
+    fn pre_filters() {
+        let rules = Rules::new(Method::SainteLague).with(Constraint::Threshold(0.1));
+        let election = Election::new(vec![40.0, 30.0, 20.0, 10.0]);
+        assert_eq!(
+            election.distribute_filtered(&rules, &Primaries(vec![]), &10, &false),
+            election
+                .distribute(&rules, &10, &false)
+                .map_err(|_| unreachable!())
+        );
+        // eliminated votes still count towards the threshold
+        assert_eq!(
+            election.distribute_filtered(&rules, &Primaries(vec![0]), &10, &false),
+            Ok(vec![0, 5, 3, 2])
+        );
+        assert_eq!(
+            TopParties(2).eliminated(&Election::new(vec![1.0, 3.0, 2.0, 2.0])),
+            Ok(vec![0])
+        );
+        assert_eq!(TopParties(5).eliminated(&election), Ok(vec![]));
+
+        let error = election
+            .distribute_filtered(&rules, &Primaries(vec![4]), &10, &false)
+            .unwrap_err();
+        assert_eq!((error.step(), error.label()), (1, "regional primaries"));
+        assert_eq!(error.root_cause(), &ConstraintError::UnknownParty(4));
+
+        let error = election
+            .distribute_filtered(&rules, &TopParties(0), &10, &false)
+            .unwrap_err();
+        assert_eq!(error.step(), 2);
+        assert_eq!(error.root_cause(), &ConstraintError::Infeasible);
+    }
+}
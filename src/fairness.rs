@@ -0,0 +1,379 @@
+//! Party by party and pairwise comparisons of two distributions of the same seats.
+//!
+//! Disproportionality [metrics](crate::metrics) condense a distribution into a single number, which is hard to explain to the public. A [`Comparison`] instead lists which parties are over- or underrepresented relative to perfect proportionality, and for each pair of parties how their seats per vote relate to each other. Perfectly proportional representation gives every voter the same weight, so the closer the ratio of seats per vote of two parties is to `1.0`, the fairer the distribution is for this pair.
+//!
+//! ```
+//! use sainte_lague::fairness;
+//! use std::cmp::Ordering;
+//!
+//! let votes = [62.0, 27.0, 11.0];
+//! // Sainte-Laguë and D'Hondt
+//! let comparison = fairness::compare(&votes, &[6, 3, 1], &[7, 3, 0]).unwrap();
+//! assert_eq!(comparison.ideal_seats, vec![6.2, 2.7, 1.1]);
+//! assert_eq!(comparison.pairwise_wins(), (2, 1));
+//! // only the largest two parties are represented more evenly by D'Hondt
+//! assert_eq!(comparison.pairs[0].parties, (0, 1));
+//! assert_eq!(comparison.pairs[0].fairer(), Ordering::Greater);
+//! ```
+//!
+//! The [`Representation`] of a single distribution lists the votes per seat of each party and the resulting weight of each vote, as used in constitutional court rulings on the equality of votes.
+//!
+//! When two divisor methods distribute the same votes differently, [`explain_methods`] names the quotient comparisons that decide each seat, e.g. for communicating a reform proposal:
+//!
+//! ```
+//! use sainte_lague::fairness::explain_methods;
+//! use sainte_lague::methods::Stationary;
+//!
+//! let dhondt = Stationary::new(1.0).unwrap();
+//! let sainte_lague = Stationary::new(0.5).unwrap();
+//! let shifts = explain_methods(&[700.0, 200.0, 60.0, 40.0], &dhondt, &sainte_lague, &10).unwrap();
+//! assert_eq!(shifts.len(), 1);
+//! assert_eq!(
+//!     shifts[0].to_string(),
+//!     "party 2's 1st seat (quotient 120.00, was 60.00) beats party 0's 8th seat (quotient 93.33, was 87.50)"
+//! );
+//! ```
+
+use crate::alerts::ordinal;
+use crate::methods::Stationary;
+use crate::DistributionError;
+use std::cmp::Ordering;
+use std::fmt;
+
+/// The comparison of two distributions, see the [module documentation](self).
+#[derive(Clone, PartialEq, Debug)]
+pub struct Comparison {
+    /// The perfectly proportional number of seats of each party.
+    pub ideal_seats: Vec<f64>,
+    /// The over- (positive) or underrepresentation (negative) of each party in the first distribution, in seats.
+    pub first_deviations: Vec<f64>,
+    /// The over- or underrepresentation of each party in the second distribution, in seats.
+    pub second_deviations: Vec<f64>,
+    /// Every pair of parties with votes, in order.
+    pub pairs: Vec<Pair>,
+}
+
+/// The relative representation of two parties in both distributions.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Pair {
+    /// The indices of the two parties.
+    pub parties: (usize, usize),
+    /// The seats per vote of the first party divided by those of the second party, in the first distribution. This is infinite if only the first party received seats, and `1.0` if neither did.
+    pub first_ratio: f64,
+    /// The same ratio in the second distribution.
+    pub second_ratio: f64,
+}
+
+impl Pair {
+    /// Which distribution is fairer for this pair: [`Ordering::Less`] if the ratio of the first distribution is closer to `1.0`, [`Ordering::Greater`] if the ratio of the second one is. Ratios are compared on a logarithmic scale, so twice as many seats per vote is as unfair as half as many.
+    pub fn fairer(&self) -> Ordering {
+        let first = self.first_ratio.ln().abs();
+        let second = self.second_ratio.ln().abs();
+        first.partial_cmp(&second).unwrap_or(Ordering::Equal)
+    }
+}
+
+impl Comparison {
+    /// The number of pairs for which the first and the second distribution is [fairer](Pair::fairer), respectively.
+    pub fn pairwise_wins(&self) -> (usize, usize) {
+        let count =
+            |ordering: Ordering| self.pairs.iter().filter(|p| p.fairer() == ordering).count();
+        (count(Ordering::Less), count(Ordering::Greater))
+    }
+}
+
+/// Compare two distributions of seats among the parties with the given votes. Returns [`None`] if the slices differ in length, the votes are negative or sum up to zero, no seats were distributed or both distributions contain a different number of seats.
+pub fn compare(votes: &[f64], first: &[usize], second: &[usize]) -> Option<Comparison> {
+    let total_votes: f64 = votes.iter().sum();
+    let total_seats: usize = first.iter().sum();
+    let valid = votes.len() == first.len()
+        && votes.len() == second.len()
+        && votes.iter().all(|v| *v >= 0.0)
+        && total_votes > 0.0
+        && total_seats > 0
+        && second.iter().sum::<usize>() == total_seats;
+    if !valid {
+        return None;
+    }
+
+    let ideal_seats: Vec<f64> = votes
+        .iter()
+        .map(|v| v / total_votes * (total_seats as f64))
+        .collect();
+    let deviations = |seats: &[usize]| -> Vec<f64> {
+        seats
+            .iter()
+            .zip(ideal_seats.iter())
+            .map(|(s, i)| (*s as f64) - i)
+            .collect()
+    };
+    let mut pairs = vec![];
+    for a in 0..votes.len() {
+        for b in a + 1..votes.len() {
+            if votes[a] > 0.0 && votes[b] > 0.0 {
+                pairs.push(Pair {
+                    parties: (a, b),
+                    first_ratio: ratio(votes, first, a, b),
+                    second_ratio: ratio(votes, second, a, b),
+                });
+            }
+        }
+    }
+    Some(Comparison {
+        first_deviations: deviations(first),
+        second_deviations: deviations(second),
+        ideal_seats,
+        pairs,
+    })
+}
+
+/// The votes per seat and representation weights of a single distribution, see [`representation`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct Representation {
+    /// The number of votes per seat of each party, or [`None`] for parties without seats.
+    pub votes_per_seat: Vec<Option<f64>>,
+    /// The weight of a vote for each party, also known as its success value: the seats per vote of the party relative to the seats per vote of the whole parliament. A weight of `1.0` is perfectly proportional, votes for parties without seats have a weight of `0.0`.
+    pub weights: Vec<f64>,
+}
+
+impl Representation {
+    /// The largest number of votes per seat divided by the smallest one, among all parties with seats. This is `1.0` if all represented voters have the same weight.
+    pub fn max_min_ratio(&self) -> Option<f64> {
+        let represented = self.votes_per_seat.iter().filter_map(|v| *v);
+        let (min, max) = represented.fold((f64::INFINITY, 0.0), |(min, max): (f64, f64), v| {
+            (min.min(v), max.max(v))
+        });
+        if min.is_finite() {
+            Some(max / min)
+        } else {
+            None
+        }
+    }
+}
+
+/// The votes per seat and representation weights of the `seats` distributed according to `votes`. Returns [`None`] for the same invalid inputs as the [metrics](crate::metrics).
+///
+/// ```
+/// use sainte_lague::fairness::representation;
+///
+/// let representation = representation(&[6000.0, 3000.0, 1500.0, 500.0], &[6, 3, 2, 0]).unwrap();
+/// assert_eq!(representation.votes_per_seat, vec![Some(1000.0), Some(1000.0), Some(750.0), None]);
+/// assert_eq!(representation.weights[3], 0.0);
+/// assert_eq!(representation.max_min_ratio(), Some(1000.0 / 750.0));
+/// ```
+pub fn representation(votes: &[f64], seats: &[usize]) -> Option<Representation> {
+    let total_votes: f64 = votes.iter().sum();
+    let total_seats: usize = seats.iter().sum();
+    let valid = votes.len() == seats.len()
+        && votes.iter().all(|v| *v >= 0.0)
+        && total_votes > 0.0
+        && total_seats > 0;
+    if !valid {
+        return None;
+    }
+    let average = total_votes / (total_seats as f64);
+    let votes_per_seat: Vec<Option<f64>> = votes
+        .iter()
+        .zip(seats.iter())
+        .map(|(v, s)| match s {
+            0 => None,
+            s => Some(v / (*s as f64)),
+        })
+        .collect();
+    let weights = votes_per_seat
+        .iter()
+        .map(|v| match v {
+            Some(v) => average / v,
+            None => 0.0,
+        })
+        .collect();
+    Some(Representation {
+        votes_per_seat,
+        weights,
+    })
+}
+
+/// A seat that a party receives with the second method instead of another party, see [`explain_methods`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Shift {
+    /// The index of the party receiving the seat with the second method.
+    pub gainer: usize,
+    /// The seat number of the gaining party, starting at 1.
+    pub gainer_seat: usize,
+    /// The index of the party holding the seat with the first method.
+    pub loser: usize,
+    /// The seat number of the losing party, starting at 1.
+    pub loser_seat: usize,
+    /// The quotients of the gaining and the losing party for these seats with the first method, where the losing party's quotient is at least as high.
+    pub first: (f64, f64),
+    /// The same quotients with the second method, where the gaining party's quotient is at least as high.
+    pub second: (f64, f64),
+}
+
+/// E.g. `party 2's 1st seat (quotient 120.00, was 60.00) beats party 0's 8th seat (quotient 93.33, was 87.50)`.
+impl fmt::Display for Shift {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "party {}'s {} seat (quotient {:.2}, was {:.2}) beats party {}'s {} seat (quotient {:.2}, was {:.2})",
+            self.gainer,
+            ordinal(self.gainer_seat),
+            self.second.0,
+            self.first.0,
+            self.loser,
+            ordinal(self.loser_seat),
+            self.second.1,
+            self.first.1
+        )
+    }
+}
+
+/// The seats that the `second` method awards to different parties than the `first` one, for the same `votes` and `seat_count`. Every seat a party gains is paired with a seat another party loses: the strongest gained quotient of the second method with the weakest lost one, and so on, in this order. Any such pair explains the difference, since every quotient winning a seat beats every quotient that doesn't.
+///
+/// Fails with the same errors as [`Stationary::distribute`] for either method, ties are not resolved.
+pub fn explain_methods(
+    votes: &[f64],
+    first: &Stationary,
+    second: &Stationary,
+    seat_count: &usize,
+) -> Result<Vec<Shift>, DistributionError> {
+    let before = first.distribute(votes, seat_count, &false)?;
+    let after = second.distribute(votes, seat_count, &false)?;
+    // (party, seat number, quotient with the second method)
+    let mut gained = vec![];
+    let mut lost = vec![];
+    for (party, ((b, a), v)) in before.iter().zip(after.iter()).zip(votes).enumerate() {
+        for seat in (*b + 1)..=*a {
+            gained.push((party, seat, v / second.divisor(seat - 1)));
+        }
+        for seat in (*a + 1)..=*b {
+            lost.push((party, seat, v / second.divisor(seat - 1)));
+        }
+    }
+    // among equal quotients, later seats of a party are the weaker ones
+    gained.sort_by(|x, y| y.2.total_cmp(&x.2).then(x.1.cmp(&y.1)).then(x.0.cmp(&y.0)));
+    lost.sort_by(|x, y| x.2.total_cmp(&y.2).then(y.1.cmp(&x.1)).then(x.0.cmp(&y.0)));
+    let first_quotient = |party: usize, seat: usize| votes[party] / first.divisor(seat - 1);
+    Ok(gained
+        .iter()
+        .zip(lost.iter())
+        .map(|(g, l)| Shift {
+            gainer: g.0,
+            gainer_seat: g.1,
+            loser: l.0,
+            loser_seat: l.1,
+            first: (first_quotient(g.0, g.1), first_quotient(l.0, l.1)),
+            second: (g.2, l.2),
+        })
+        .collect())
+}
+
+/// The seats per vote of party `a` divided by those of party `b`.
+fn ratio(votes: &[f64], seats: &[usize], a: usize, b: usize) -> f64 {
+    match (seats[a], seats[b]) {
+        (0, 0) => 1.0,
+        (_, 0) => f64::INFINITY,
+        (sa, sb) => (sa as f64) * votes[b] / ((sb as f64) * votes[a]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, explain_methods, representation, Pair};
+    use crate::methods::Stationary;
+    use crate::DistributionError;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn pairs() {
+        let votes = [50.0, 0.0, 30.0, 20.0];
+        let comparison = compare(&votes, &[5, 0, 3, 2], &[6, 0, 4, 0]).unwrap();
+        assert_eq!(comparison.first_deviations, vec![0.0, 0.0, 0.0, 0.0]);
+        assert_eq!(comparison.second_deviations, vec![1.0, 0.0, 1.0, -2.0]);
+
+        let parties: Vec<(usize, usize)> = comparison.pairs.iter().map(|p| p.parties).collect();
+        assert_eq!(parties, vec![(0, 2), (0, 3), (2, 3)]);
+        assert_eq!(comparison.pairs[0].first_ratio, 1.0);
+        assert_eq!(comparison.pairs[0].second_ratio, 6.0 * 30.0 / (4.0 * 50.0));
+        assert_eq!(comparison.pairs[1].second_ratio, f64::INFINITY);
+        assert_eq!(comparison.pairwise_wins(), (3, 0));
+    }
+
+    #[test]
+    fn logarithmic_scale() {
+        let pair = Pair {
+            parties: (0, 1),
+            first_ratio: 2.0,
+            second_ratio: 0.5,
+        };
+        assert_eq!(pair.fairer(), Ordering::Equal);
+        let pair = Pair {
+            first_ratio: 0.0,
+            second_ratio: 3.0,
+            ..pair
+        };
+        assert_eq!(pair.fairer(), Ordering::Greater);
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert_eq!(compare(&[1.0, 1.0], &[1, 1], &[2, 1]), None);
+        assert_eq!(compare(&[1.0, 1.0], &[1], &[1]), None);
+        assert_eq!(compare(&[-1.0, 1.0], &[0, 1], &[0, 1]), None);
+        assert_eq!(compare(&[0.0, 0.0], &[0, 1], &[0, 1]), None);
+        assert_eq!(compare(&[1.0, 1.0], &[0, 0], &[0, 0]), None);
+    }
+
+    #[test]
+    fn representation_weights() {
+        let votes = [50.0, 30.0, 20.0];
+        let exact = representation(&votes, &[5, 3, 2]).unwrap();
+        assert_eq!(exact.weights, vec![1.0, 1.0, 1.0]);
+        assert_eq!(exact.max_min_ratio(), Some(1.0));
+
+        let skewed = representation(&votes, &[8, 2, 0]).unwrap();
+        assert_eq!(skewed.votes_per_seat, vec![Some(6.25), Some(15.0), None]);
+        assert_eq!(skewed.weights, vec![1.6, 10.0 / 15.0, 0.0]);
+        assert_eq!(skewed.max_min_ratio(), Some(2.4));
+
+        // parties without votes but with seats have a weight of infinity
+        let unearned = representation(&[1.0, 0.0], &[1, 1]).unwrap();
+        assert_eq!(unearned.weights, vec![0.5, f64::INFINITY]);
+        assert_eq!(unearned.max_min_ratio(), Some(f64::INFINITY));
+
+        assert_eq!(representation(&votes, &[0, 0, 0]), None);
+        assert_eq!(representation(&votes, &[1, 1]), None);
+    }
+
+    #[test]
+    fn method_shifts() {
+        let sainte_lague = Stationary::new(0.5).unwrap();
+        let adams = Stationary::new(0.0).unwrap();
+        let dhondt = Stationary::new(1.0).unwrap();
+        let votes = [700.0, 200.0, 60.0, 40.0];
+        assert_eq!(
+            explain_methods(&votes, &sainte_lague, &sainte_lague, &10),
+            Ok(vec![])
+        );
+
+        // Adams gives every party a first seat, at the expense of the largest one
+        let shifts = explain_methods(&votes, &dhondt, &adams, &10).unwrap();
+        let seats: Vec<(usize, usize, usize, usize)> = shifts
+            .iter()
+            .map(|s| (s.gainer, s.gainer_seat, s.loser, s.loser_seat))
+            .collect();
+        assert_eq!(dhondt.distribute(&votes, &10, &false), Ok(vec![8, 2, 0, 0]));
+        assert_eq!(adams.distribute(&votes, &10, &false), Ok(vec![6, 2, 1, 1]));
+        assert_eq!(seats, vec![(2, 1, 0, 8), (3, 1, 0, 7)]);
+        for shift in shifts.iter() {
+            assert!(shift.first.0 <= shift.first.1);
+            assert!(shift.second.0 >= shift.second.1);
+        }
+        assert_eq!(shifts[0].second.0, f64::INFINITY);
+
+        assert_eq!(
+            explain_methods(&[1.0, 1.0], &dhondt, &adams, &1),
+            Err(DistributionError::Tied)
+        );
+    }
+}
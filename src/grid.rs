@@ -0,0 +1,217 @@
+//! Parameter grids: the same votes distributed under every combination of thresholds and seat counts.
+//!
+//! Instead of one script per variant, a [`Grid`] declares the values of each parameter, e.g. thresholds of 3, 4 and 5 percent combined with 598, 630 and 736 seats. [`Grid::run`] distributes the seats for the cross product of all values and collects the results in a [`Table`], which can be written as CSV in a tidy long format with one row per party and combination.
+//!
+//! ```
+//! use sainte_lague::grid::Grid;
+//!
+//! let grid = Grid::parse("threshold = 4%, 5%\nseats = 10, 11").unwrap();
+//! let table = grid.run(&[600.0, 355.0, 45.0]);
+//! assert_eq!(table.points.len(), 4);
+//! assert_eq!(table.points[0].seats, Ok(vec![6, 4, 0]));
+//! assert_eq!(table.points[3].seats, Ok(vec![7, 4, 0]));
+//! assert!(table.to_csv().starts_with("threshold,seat_count,party,votes,seats,error\n0.04,10,0,600,6,\n"));
+//! ```
+
+use crate::constraints::{Constraint, ConstraintError, Rules};
+use crate::io::snapshot::float;
+
+/// The values of each parameter, see the [module documentation](self).
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Grid {
+    /// The rules all combinations are based on. Their threshold, if any, is replaced by each value of [`Grid::thresholds`].
+    pub rules: Rules,
+    /// The thresholds, as shares between `0.0` and `1.0`. If empty, the threshold of the rules is used.
+    pub thresholds: Vec<f64>,
+    /// The seat counts.
+    pub seat_counts: Vec<usize>,
+}
+
+/// The result of a single combination of parameters.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Point {
+    /// The threshold, or [`None`] if the grid has no thresholds.
+    pub threshold: Option<f64>,
+    /// The seat count.
+    pub seat_count: usize,
+    /// The distribution, or why it failed.
+    pub seats: Result<Vec<usize>, ConstraintError>,
+}
+
+/// The results of all combinations, see [`Grid::run`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct Table {
+    /// The votes all combinations were distributed for.
+    pub votes: Vec<f64>,
+    /// The result of each combination, with the thresholds varying slowest.
+    pub points: Vec<Point>,
+}
+
+impl Grid {
+    /// Parse a grid from a declaration with one parameter per line, each given as `name = value, value, …`. The supported parameters are `threshold` (as a share or in percent, e.g. `0.05` or `5%`) and `seats`. Empty lines and lines starting with `#` are ignored. Returns [`None`] for unknown parameters, repeated parameters or malformed values.
+    pub fn parse(declaration: &str) -> Option<Grid> {
+        let mut grid = Grid::default();
+        let (mut thresholds_seen, mut seats_seen) = (false, false);
+        for line in declaration.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, values) = line.split_once('=')?;
+            let values = values.split(',').map(str::trim);
+            match name.trim() {
+                "threshold" if !thresholds_seen => {
+                    thresholds_seen = true;
+                    grid.thresholds = values.map(share).collect::<Option<_>>()?;
+                }
+                "seats" if !seats_seen => {
+                    seats_seen = true;
+                    grid.seat_counts = values.map(|v| v.parse().ok()).collect::<Option<_>>()?;
+                }
+                _ => return None,
+            }
+        }
+        Some(grid)
+    }
+
+    /// Distribute the seats for every combination of parameters.
+    pub fn run(&self, votes: &[f64]) -> Table {
+        let thresholds: Vec<Option<f64>> = match self.thresholds.is_empty() {
+            true => vec![None],
+            false => self.thresholds.iter().map(|t| Some(*t)).collect(),
+        };
+        let mut points = vec![];
+        for threshold in thresholds {
+            let rules = match threshold {
+                Some(t) => self.with_threshold(t),
+                None => self.rules.clone(),
+            };
+            for seat_count in self.seat_counts.iter() {
+                points.push(Point {
+                    threshold,
+                    seat_count: *seat_count,
+                    seats: rules.distribute(votes, seat_count, &false),
+                });
+            }
+        }
+        Table {
+            votes: votes.to_vec(),
+            points,
+        }
+    }
+
+    /// The rules with their threshold replaced.
+    fn with_threshold(&self, threshold: f64) -> Rules {
+        let rules = Rules::new(self.rules.method).with_tolerance(self.rules.tolerance());
+        let others = self.rules.constraints().iter();
+        others
+            .filter(|c| !matches!(c, Constraint::Threshold(_)))
+            .fold(rules, |rules, c| rules.with(c.clone()))
+            .with(Constraint::Threshold(threshold))
+    }
+}
+
+impl Table {
+    /// Write the table as CSV with the columns `threshold`, `seat_count`, `party`, `votes`, `seats` and `error`. Every successful combination has one row per party, every failed one a single row with the error and without party, votes and seats. Missing thresholds are left empty.
+    pub fn to_csv(&self) -> String {
+        let mut csv = "threshold,seat_count,party,votes,seats,error\n".to_string();
+        for point in self.points.iter() {
+            let threshold = point.threshold.map(float).unwrap_or_default();
+            match &point.seats {
+                Ok(seats) => {
+                    for (p, (v, s)) in self.votes.iter().zip(seats).enumerate() {
+                        csv.push_str(&format!(
+                            "{},{},{},{},{},\n",
+                            threshold,
+                            point.seat_count,
+                            p,
+                            float(*v),
+                            s
+                        ));
+                    }
+                }
+                Err(e) => {
+                    csv.push_str(&format!("{},{},,,,{:?}\n", threshold, point.seat_count, e));
+                }
+            }
+        }
+        csv
+    }
+}
+
+/// Parse a share, given as a fraction or in percent.
+fn share(value: &str) -> Option<f64> {
+    match value.strip_suffix('%') {
+        Some(percent) => percent.trim().parse::<f64>().ok().map(|p| p / 100.0),
+        None => value.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use crate::constraints::{Constraint, ConstraintError, Rules};
+    use crate::DistributionError;
+
+    #[test]
+    fn cross_product() {
+        let rules = Rules::default()
+            .with(Constraint::Threshold(0.5))
+            .with(Constraint::Exemption(2));
+        let grid = Grid {
+            rules,
+            thresholds: vec![0.1, 0.35],
+            seat_counts: vec![0, 4],
+        };
+        let table = grid.run(&[60.0, 30.0, 10.0]);
+        let combinations: Vec<(Option<f64>, usize)> = table
+            .points
+            .iter()
+            .map(|p| (p.threshold, p.seat_count))
+            .collect();
+        assert_eq!(
+            combinations,
+            vec![
+                (Some(0.1), 0),
+                (Some(0.1), 4),
+                (Some(0.35), 0),
+                (Some(0.35), 4)
+            ]
+        );
+        // the exemption is kept while the threshold is replaced
+        assert_eq!(table.points[1].seats, Ok(vec![3, 1, 0]));
+        assert_eq!(table.points[3].seats, Ok(vec![3, 0, 1]));
+        assert_eq!(
+            table.points[0].seats,
+            Err(ConstraintError::Distribution(
+                DistributionError::InvalidSeatCount
+            ))
+        );
+        assert_eq!(
+            table.to_csv().lines().nth(1),
+            Some("0.1,0,,,,Distribution(InvalidSeatCount)")
+        );
+
+        let without_thresholds = Grid {
+            seat_counts: vec![2],
+            ..Grid::default()
+        };
+        assert_eq!(
+            without_thresholds.run(&[1.0, 4.0]).to_csv(),
+            "threshold,seat_count,party,votes,seats,error\n,2,0,1,0,\n,2,1,4,2,\n"
+        );
+    }
+
+    #[test]
+    fn declarations() {
+        let grid = Grid::parse("# grid\n\nthreshold = 3%, 0.045 ,5 %\n seats=598,630\n").unwrap();
+        assert_eq!(grid.thresholds, vec![0.03, 0.045, 0.05]);
+        assert_eq!(grid.seat_counts, vec![598, 630]);
+        assert_eq!(Grid::parse(""), Some(Grid::default()));
+
+        assert_eq!(Grid::parse("seats = 1\nseats = 2"), None);
+        assert_eq!(Grid::parse("method = dhondt"), None);
+        assert_eq!(Grid::parse("seats = -1"), None);
+        assert_eq!(Grid::parse("threshold = five"), None);
+        assert_eq!(Grid::parse("seats"), None);
+    }
+}
@@ -0,0 +1,241 @@
+//! Compositions of supranational bodies aggregated from national elections.
+//!
+//! In bodies like the European Parliament, every member state distributes its seats among its national parties under its own rules, and the elected members then sit in groups spanning several states. A [`Member`] describes the national election and maps each national party to its group, [`aggregate`] distributes the seats of every member and adds them up per group.
+//!
+//! ```
+//! use sainte_lague::constraints::{Constraint, Rules};
+//! use sainte_lague::election::Election;
+//! use sainte_lague::groups::{aggregate, Member};
+//! use sainte_lague::Method;
+//!
+//! let germany = Member::new("Germany", Election::new(vec![30.0, 15.0, 12.0, 2.0]), Rules::default(), 10)
+//!     .with_group(0, "EPP")
+//!     .with_group(1, "S&D")
+//!     .with_group(2, "Greens/EFA");
+//! let rules = Rules::new(Method::SainteLague).with(Constraint::Threshold(0.05));
+//! let france = Member::new("France", Election::new(vec![31.0, 14.0, 15.0]), rules, 8)
+//!     .with_group(1, "S&D")
+//!     .with_group(2, "Renew");
+//!
+//! let composition = aggregate(&[germany, france]).unwrap();
+//! let groups: Vec<(&str, usize)> = composition.groups.iter().map(|g| (g.name.as_str(), g.seats)).collect();
+//! assert_eq!(groups, vec![("EPP", 5), ("S&D", 5), ("Greens/EFA", 2), ("Renew", 2)]);
+//! // the largest French party doesn't belong to any group
+//! assert_eq!(composition.non_attached.len(), 2);
+//! assert_eq!(composition.seat_count(), 18);
+//! ```
+
+use crate::constraints::{ConstraintError, Rules};
+use crate::election::Election;
+use crate::metrics;
+use crate::pipeline::{Context, PipelineError};
+
+/// The national election of one member of a supranational body, see the [module documentation](self).
+#[derive(Clone, PartialEq, Debug)]
+pub struct Member {
+    /// The name of the member, used in errors.
+    pub name: String,
+    /// The national election.
+    pub election: Election,
+    /// The electoral rules of the member.
+    pub rules: Rules,
+    /// The number of seats of the member.
+    pub seat_count: usize,
+    /// The group of each national party, or [`None`] for parties that don't belong to any group.
+    pub groups: Vec<Option<String>>,
+}
+
+impl Member {
+    /// Create a member whose national parties don't belong to any group yet.
+    pub fn new(name: &str, election: Election, rules: Rules, seat_count: usize) -> Member {
+        let groups = vec![None; election.votes.len()];
+        Member {
+            name: name.to_string(),
+            election,
+            rules,
+            seat_count,
+            groups,
+        }
+    }
+
+    /// Assign the national party with the given index to a group.
+    pub fn with_group(mut self, party: usize, group: &str) -> Member {
+        if self.groups.len() <= party {
+            self.groups.resize(party + 1, None);
+        }
+        self.groups[party] = Some(group.to_string());
+        self
+    }
+}
+
+/// The aggregated votes and seats of one group, see [`aggregate`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct Group {
+    /// The name of the group.
+    pub name: String,
+    /// The votes of all national parties in the group, summed over all members.
+    pub votes: f64,
+    /// The seats of all national parties in the group.
+    pub seats: usize,
+    /// The number of members in which the group won at least one seat.
+    pub members: usize,
+}
+
+/// A national party that doesn't belong to any group, see [`aggregate`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct NonAttached {
+    /// The index of the member.
+    pub member: usize,
+    /// The index of the party within the election of the member.
+    pub party: usize,
+    /// The votes of the party.
+    pub votes: f64,
+    /// The seats of the party.
+    pub seats: usize,
+}
+
+/// The composition of a supranational body, see [`aggregate`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct Composition {
+    /// The groups in the order they first appear among the members and their parties.
+    pub groups: Vec<Group>,
+    /// The national parties without a group, in the order of the members and their parties.
+    pub non_attached: Vec<NonAttached>,
+    /// The seats of each national party, in the order of the members.
+    pub national: Vec<Vec<usize>>,
+}
+
+impl Composition {
+    /// The total number of seats.
+    pub fn seat_count(&self) -> usize {
+        self.national.iter().flatten().sum()
+    }
+
+    /// The group with the given name.
+    pub fn group(&self, name: &str) -> Option<&Group> {
+        self.groups.iter().find(|g| g.name == name)
+    }
+
+    /// The [Gallagher index](metrics::gallagher) of the body, comparing the votes and seats of every group. Non-attached parties are compared individually.
+    pub fn gallagher(&self) -> Option<f64> {
+        let (votes, seats) = self.entries();
+        metrics::gallagher(&votes, &seats)
+    }
+
+    /// The [Sainte-Laguë index](metrics::sainte_lague_index) of the body, comparing the votes and seats of every group. Non-attached parties are compared individually.
+    pub fn sainte_lague_index(&self) -> Option<f64> {
+        let (votes, seats) = self.entries();
+        metrics::sainte_lague_index(&votes, &seats)
+    }
+
+    fn entries(&self) -> (Vec<f64>, Vec<usize>) {
+        self.groups
+            .iter()
+            .map(|g| (g.votes, g.seats))
+            .chain(self.non_attached.iter().map(|p| (p.votes, p.seats)))
+            .unzip()
+    }
+}
+
+/// Distribute the seats of every member according to its rules and add them up per group. Group votes are the plain sums of the national votes, so members with more voters weigh more in the group-level metrics, regardless of their seat count.
+///
+/// Fails with a [`PipelineError`] whose step is the position of the failing member, starting at 1, and whose label is its name. A group assigned to a party that doesn't exist fails with [`ConstraintError::UnknownParty`].
+pub fn aggregate(members: &[Member]) -> Result<Composition, PipelineError> {
+    let mut composition = Composition {
+        groups: vec![],
+        non_attached: vec![],
+        national: vec![],
+    };
+    for (m, member) in members.iter().enumerate() {
+        let seats = distribute(member).context(m + 1, &member.name)?;
+        let mut won = vec![];
+        for (party, (votes, s)) in member.election.votes.iter().zip(seats.iter()).enumerate() {
+            let name = match member.groups.get(party).cloned().flatten() {
+                Some(name) => name,
+                None => {
+                    composition.non_attached.push(NonAttached {
+                        member: m,
+                        party,
+                        votes: *votes,
+                        seats: *s,
+                    });
+                    continue;
+                }
+            };
+            let index = match composition.groups.iter().position(|g| g.name == name) {
+                Some(index) => index,
+                None => {
+                    composition.groups.push(Group {
+                        name,
+                        votes: 0.0,
+                        seats: 0,
+                        members: 0,
+                    });
+                    composition.groups.len() - 1
+                }
+            };
+            let group = &mut composition.groups[index];
+            group.votes += votes;
+            group.seats += s;
+            if *s > 0 && !won.contains(&index) {
+                won.push(index);
+                group.members += 1;
+            }
+        }
+        composition.national.push(seats);
+    }
+    Ok(composition)
+}
+
+fn distribute(member: &Member) -> Result<Vec<usize>, ConstraintError> {
+    if member.groups.len() > member.election.votes.len() {
+        return Err(ConstraintError::UnknownParty(member.groups.len() - 1));
+    }
+    member
+        .election
+        .distribute(&member.rules, &member.seat_count, &false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{aggregate, Member};
+    use crate::constraints::{ConstraintError, Rules};
+    use crate::election::Election;
+    use crate::DistributionError;
+
+    fn member(name: &str, votes: Vec<f64>, seat_count: usize) -> Member {
+        Member::new(name, Election::new(votes), Rules::default(), seat_count)
+    }
+
+    #[test]
+    fn groups_add_up() {
+        let members = [
+            member("A", vec![6.0, 3.0, 1.0], 10)
+                .with_group(0, "x")
+                .with_group(1, "y")
+                .with_group(2, "x"),
+            member("B", vec![5.0, 5.0], 4).with_group(1, "y"),
+        ];
+        let composition = aggregate(&members).unwrap();
+        assert_eq!(composition.national, vec![vec![6, 3, 1], vec![2, 2]]);
+        let x = composition.group("x").unwrap();
+        assert_eq!((x.votes, x.seats, x.members), (7.0, 7, 1));
+        let y = composition.group("y").unwrap();
+        assert_eq!((y.votes, y.seats, y.members), (8.0, 5, 2));
+        assert_eq!(composition.non_attached[0].member, 1);
+        assert_eq!(composition.seat_count(), 14);
+        assert!(composition.gallagher().unwrap() > 0.0);
+        assert!(composition.sainte_lague_index().unwrap() > 0.0);
+    }
+
+    #[test]
+    fn member_errors() {
+        let members = [member("A", vec![1.0], 1), member("B", vec![1.0, 1.0], 1)];
+        let error = aggregate(&members).unwrap_err();
+        assert_eq!((error.step(), error.label()), (2, "B"));
+        assert_eq!(error.root_cause(), &DistributionError::Tied.into());
+
+        let error = aggregate(&[member("A", vec![1.0], 1).with_group(3, "x")]).unwrap_err();
+        assert_eq!(error.root_cause(), &ConstraintError::UnknownParty(3));
+    }
+}
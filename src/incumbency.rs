@@ -0,0 +1,207 @@
+//! Incumbent protection: settling close races for the last seats in favour of the previous distribution.
+//!
+//! When committees are reshuffled during a term, e.g. after a party switched groups, recomputing the seats from scratch can move seats between parties whose claims to them differ by a few votes, or not at all. Many bodies prefer to keep such seats where they are. [`IncumbentProtection`] is a soft constraint for this: quotients within a relative `margin` of the last winning quotient are considered equally good, and among them the seats go to parties that would otherwise drop below their previous number of seats. Quotients outside of the margin always decide as usual, so the protection never overrides a clear difference in votes.
+//!
+//! ```
+//! use sainte_lague::constraints::Rules;
+//! use sainte_lague::incumbency::IncumbentProtection;
+//!
+//! let votes = [52.0, 27.0, 21.0];
+//! assert_eq!(Rules::default().distribute(&votes, &11, &false), Ok(vec![6, 3, 2]));
+//!
+//! // the third party held a third seat, and its quotient 21 / 2.5 is within 12 % of 52 / 5.5, the last winning one
+//! let protection = IncumbentProtection::new(vec![5, 3, 3], 0.12).unwrap();
+//! let protected = protection.distribute(&Rules::default(), &votes, &11).unwrap();
+//! assert_eq!(protected.seats, vec![5, 3, 3]);
+//! assert_eq!(protected.contested, vec![0, 2]);
+//! assert_eq!(protected.changes, 0);
+//! ```
+
+use crate::constraints::{ConstraintError, Rules};
+use crate::validate_with_tolerance;
+
+/// Distributions preferring a previous one among nearly tied alternatives, see the [module documentation](self).
+#[derive(Clone, PartialEq, Debug)]
+pub struct IncumbentProtection {
+    previous: Vec<usize>,
+    margin: f64,
+}
+
+/// The result of [`IncumbentProtection::distribute`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Protected {
+    /// The number of seats of each party.
+    pub seats: Vec<usize>,
+    /// The parties that competed for the last seats within the margin, by index.
+    pub contested: Vec<usize>,
+    /// The sum of the absolute differences to the previous seats of every party, i.e. twice the number of seats that changed hands.
+    pub changes: usize,
+}
+
+impl IncumbentProtection {
+    /// Protect the `previous` seats of every party, considering quotients within `margin` (at least `0.0` and less than `1.0`) of the last winning quotient as equally good. A margin of `0.0` only decides exact ties, instead of failing or drawing lots. Returns [`None`] for a margin out of range.
+    pub fn new(previous: Vec<usize>, margin: f64) -> Option<IncumbentProtection> {
+        if !(0.0..1.0).contains(&margin) {
+            return None;
+        }
+        Some(IncumbentProtection { previous, margin })
+    }
+
+    /// The protected seats of every party.
+    pub fn previous(&self) -> &[usize] {
+        &self.previous
+    }
+
+    /// The relative margin within which quotients are considered equally good.
+    pub fn margin(&self) -> f64 {
+        self.margin
+    }
+
+    /// Distribute `seat_count` seats according to `votes` and `rules` like [`Rules::distribute`] with a divisor method, but award the contested seats to minimize the changes compared to the previous seats. Among equally small changes, higher quotients win, and among equal quotients lower indices.
+    ///
+    /// Fails with the same errors as [`Rules::distribute`], except for [`Tied`](crate::DistributionError::Tied), and with [`ConstraintError::UnknownParty`] if there aren't exactly as many previous seats as parties.
+    pub fn distribute(
+        &self,
+        rules: &Rules,
+        votes: &[f64],
+        seat_count: &usize,
+    ) -> Result<Protected, ConstraintError> {
+        validate_with_tolerance(votes, seat_count, rules.tolerance())?;
+        if self.previous.len() != votes.len() {
+            return Err(ConstraintError::UnknownParty(
+                self.previous.len().min(votes.len()),
+            ));
+        }
+        if rules.check_feasibility(votes, seat_count)?.is_some() {
+            return Err(ConstraintError::Infeasible);
+        }
+        let qualified = rules.qualified(votes)?;
+        let bounds = rules.qualified_bounds(votes, &qualified)?;
+        let weighted = rules.weighted(votes);
+
+        let mut seats: Vec<usize> = bounds.iter().map(|(min, _)| *min).collect();
+        let remaining = seat_count - seats.iter().sum::<usize>();
+        // every quotient that could win one of the remaining seats, highest first
+        let mut quotients: Vec<(usize, f64)> = vec![];
+        for (p, (min, max)) in bounds.iter().enumerate() {
+            if weighted[p] > 0.0 {
+                for s in *min..(*max).min(min + remaining) {
+                    quotients.push((p, weighted[p] / rules.method.divisor(s)));
+                }
+            }
+        }
+        quotients.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        let cutoff = match remaining {
+            0 => f64::INFINITY,
+            _ => {
+                quotients
+                    .get(remaining - 1)
+                    .ok_or(ConstraintError::Infeasible)?
+                    .1
+            }
+        };
+        let (sure, contested): (Vec<_>, Vec<_>) = quotients
+            .into_iter()
+            .filter(|(_, q)| *q >= cutoff * (1.0 - self.margin))
+            .partition(|(_, q)| *q > cutoff * (1.0 + self.margin));
+        for (p, _) in sure.iter() {
+            seats[*p] += 1;
+        }
+
+        // the quotients of a party have to be taken in order, and each seat below the previous
+        // number reduces the changes, while each seat above increases them
+        let mut taken = vec![false; contested.len()];
+        for _ in sure.len()..remaining {
+            let next: Vec<usize> = (0..contested.len())
+                .filter(|i| {
+                    let party = contested[*i].0;
+                    !taken[*i] && (0..*i).all(|j| taken[j] || contested[j].0 != party)
+                })
+                .collect();
+            let below = next
+                .iter()
+                .find(|i| seats[contested[**i].0] < self.previous[contested[**i].0]);
+            let i = *below.or_else(|| next.first()).expect("enough quotients");
+            taken[i] = true;
+            seats[contested[i].0] += 1;
+        }
+
+        let mut parties: Vec<usize> = contested.iter().map(|(p, _)| *p).collect();
+        parties.sort_unstable();
+        parties.dedup();
+        let changes = seats
+            .iter()
+            .zip(self.previous.iter())
+            .map(|(s, p)| s.abs_diff(*p))
+            .sum();
+        Ok(Protected {
+            seats,
+            contested: parties,
+            changes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IncumbentProtection;
+    use crate::constraints::{Constraint, ConstraintError, Rules};
+    use crate::DistributionError;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn same_as_rules_without_close_races() {
+        let rules = Rules::default().with(Constraint::Threshold(0.05));
+        let mut rng = StdRng::seed_from_u64(503);
+        for _ in 0..200 {
+            let votes: Vec<f64> = (0..rng.gen_range(1..6))
+                .map(|_| rng.gen_range(0..1000) as f64)
+                .collect();
+            let seat_count = rng.gen_range(1..30);
+            let previous = (0..votes.len()).map(|_| rng.gen_range(0..10)).collect();
+            let protection = IncumbentProtection::new(previous, 0.0).unwrap();
+            let protected = protection.distribute(&rules, &votes, &seat_count);
+            match rules.distribute(&votes, &seat_count, &false) {
+                Ok(seats) => assert_eq!(protected.unwrap().seats, seats),
+                Err(ConstraintError::Distribution(DistributionError::Tied)) => {
+                    // any draw is one of the alternatives the protection chooses from
+                    let drawn = rules.distribute(&votes, &seat_count, &true).unwrap();
+                    let changes: usize = drawn
+                        .iter()
+                        .zip(protection.previous())
+                        .map(|(s, p)| (*s as isize - *p as isize).unsigned_abs())
+                        .sum();
+                    assert!(protected.unwrap().changes <= changes);
+                }
+                Err(e) => assert_eq!(protected, Err(e)),
+            }
+        }
+    }
+
+    #[test]
+    fn ties_and_bounds() {
+        // a tie for the last seat goes to the incumbent
+        let tied = [3.0, 1.0];
+        let protect = |previous: Vec<usize>, rules: &Rules, seat_count| {
+            IncumbentProtection::new(previous, 0.0)
+                .unwrap()
+                .distribute(rules, &tied, &seat_count)
+        };
+        let rules = Rules::default();
+        assert_eq!(protect(vec![1, 1], &rules, 2).unwrap().seats, vec![1, 1]);
+        assert_eq!(protect(vec![2, 0], &rules, 2).unwrap().seats, vec![2, 0]);
+        // without an incumbent, the higher quotient and then the lower index wins
+        assert_eq!(protect(vec![0, 0], &rules, 2).unwrap().seats, vec![2, 0]);
+        assert_eq!(protect(vec![5, 5], &rules, 2).unwrap().changes, 8);
+
+        let bounded = Rules::default().with(Constraint::MaxSeats(0, 1));
+        assert_eq!(protect(vec![2, 0], &bounded, 2).unwrap().seats, vec![1, 1]);
+        assert_eq!(
+            protect(vec![2], &rules, 2),
+            Err(ConstraintError::UnknownParty(1))
+        );
+        assert_eq!(IncumbentProtection::new(vec![], 1.0), None);
+    }
+}
@@ -0,0 +1,202 @@
+//! Self-contained audit bundles of a distribution, for archival by electoral administrations.
+//!
+//! A [`Bundle`] collects everything needed to verify a published distribution years later, without access to the software that produced it:
+//!
+//! - `scenario.txt` and `outcome.txt`: the inputs and the result as stable [snapshots](super::snapshot).
+//! - `scenario.bin` and `outcome.bin`: the same values in the exact [wire](super::wire) encoding.
+//! - `trace.txt`: every seat in the order it was awarded, with the party and its winning quotient.
+//! - `certificate.txt`: the range of divisors that reproduce the distribution. Dividing the votes of every party by any divisor in this range and rounding the result (with the rounding of the method) yields its seats, which can be checked with a pocket calculator or [`certificate::verify`](crate::certificate::verify()).
+//! - `fingerprint.txt`: the [fingerprint](super::fingerprint) of the scenario and its outcome.
+//! - `version.txt`: the version of this crate.
+//! - `manifest.txt`: the SHA-256 digest of each of the files above.
+//!
+//! ```
+//! use sainte_lague::io::audit;
+//! use sainte_lague::Scenario;
+//!
+//! let scenario = Scenario::new(vec![362.0, 318.0, 126.0], 10);
+//! let outcome = scenario.evaluate().unwrap();
+//! let bundle = audit::export_audit_bundle(&scenario, &outcome);
+//! let certificate = std::str::from_utf8(bundle.file("certificate.txt").unwrap()).unwrap();
+//! // e.g. 362 / 82 = 4.41, 318 / 82 = 3.88 and 126 / 82 = 1.54 round to 4, 4 and 2 seats
+//! assert_eq!(certificate, "lower: 80.44444444444444\nupper: 84\n");
+//! // bundle.write_to(std::path::Path::new("archive/2024"))?;
+//! ```
+
+use super::snapshot::{float, Snapshot};
+use super::{fingerprint, sha256, wire};
+use crate::{certificate, metrics, Outcome, Scenario};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The files of an audit bundle, see the [module documentation](self).
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Bundle {
+    files: Vec<(String, Vec<u8>)>,
+}
+
+impl Bundle {
+    /// The name and content of every file, in the order listed in the [module documentation](self).
+    pub fn files(&self) -> &[(String, Vec<u8>)] {
+        &self.files
+    }
+
+    /// The content of the file with the given name.
+    pub fn file(&self, name: &str) -> Option<&[u8]> {
+        self.files
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, content)| content.as_slice())
+    }
+
+    /// Write all files into the directory `dir`, which is created if it doesn't exist. Existing files with the same names are overwritten.
+    pub fn write_to(&self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        for (name, content) in self.files.iter() {
+            fs::write(dir.join(name), content)?;
+        }
+        Ok(())
+    }
+}
+
+/// Bundle the scenario and its outcome for archival, see the [module documentation](self).
+pub fn export_audit_bundle(scenario: &Scenario, outcome: &Outcome) -> Bundle {
+    let mut files: Vec<(String, Vec<u8>)> = vec![
+        ("scenario.txt".to_string(), scenario.snapshot().into_bytes()),
+        ("outcome.txt".to_string(), outcome.snapshot().into_bytes()),
+        ("scenario.bin".to_string(), wire::encode_scenario(scenario)),
+        ("outcome.bin".to_string(), wire::encode_outcome(outcome)),
+        (
+            "trace.txt".to_string(),
+            trace(scenario, outcome).into_bytes(),
+        ),
+        (
+            "certificate.txt".to_string(),
+            certificate(scenario, outcome).into_bytes(),
+        ),
+        (
+            "fingerprint.txt".to_string(),
+            format!("{}\n", fingerprint::compute(scenario, outcome)).into_bytes(),
+        ),
+        (
+            "version.txt".to_string(),
+            format!("{}\n", env!("CARGO_PKG_VERSION")).into_bytes(),
+        ),
+    ];
+    let mut manifest = String::new();
+    for (name, content) in files.iter() {
+        let digest: String = sha256::digest(content)
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        manifest.push_str(&format!("{}  {}\n", digest, name));
+    }
+    files.push(("manifest.txt".to_string(), manifest.into_bytes()));
+    Bundle { files }
+}
+
+/// The quotient with which each seat was won, ordered from the highest to the lowest quotient. Seats with equal quotients are listed in the order of their parties, first seats guaranteed by the method are marked as such instead of with an infinite quotient.
+fn trace(scenario: &Scenario, outcome: &Outcome) -> String {
+    let prices = metrics::seat_prices(&scenario.method, &scenario.votes, outcome.seats());
+    prices
+        .map(|prices| prices.seats)
+        .unwrap_or_default()
+        .iter()
+        .map(|s| {
+            if s.quotient.is_finite() {
+                format!(
+                    "seat {}: party {}, quotient {}\n",
+                    s.seat,
+                    s.party,
+                    float(s.quotient)
+                )
+            } else {
+                format!("seat {}: party {}, guaranteed\n", s.seat, s.party)
+            }
+        })
+        .collect()
+}
+
+/// The smallest and largest divisor reproducing the outcome, see [`certificate`](crate::certificate).
+fn certificate(scenario: &Scenario, outcome: &Outcome) -> String {
+    certificate::prove(&scenario.method, &scenario.votes, outcome.seats())
+        .map(|c| c.to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::export_audit_bundle;
+    use crate::io::snapshot::Snapshot;
+    use crate::io::wire;
+    use crate::{Method, Scenario};
+
+    #[test]
+    fn bundle_contents() {
+        let scenario = Scenario::new(vec![6.0, 3.0, 0.9], 5);
+        let outcome = scenario.evaluate().unwrap();
+        let bundle = export_audit_bundle(&scenario, &outcome);
+        let names: Vec<&str> = bundle.files().iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names[0], "scenario.txt");
+        assert_eq!(names.last(), Some(&"manifest.txt"));
+        assert_eq!(
+            bundle.file("outcome.txt"),
+            Some(outcome.snapshot().as_bytes())
+        );
+        let encoded = bundle.file("scenario.bin").unwrap();
+        assert_eq!(wire::decode_scenario(encoded), Ok(scenario));
+        assert_eq!(
+            bundle.file("trace.txt"),
+            Some(
+                &b"seat 1: party 0, quotient 12\n\
+                   seat 2: party 1, quotient 6\n\
+                   seat 3: party 0, quotient 4\n\
+                   seat 4: party 0, quotient 2.4\n\
+                   seat 5: party 1, quotient 2\n"[..]
+            )
+        );
+        assert_eq!(
+            bundle.file("certificate.txt"),
+            Some(&b"lower: 1.8\nupper: 2\n"[..])
+        );
+        let manifest = String::from_utf8(bundle.file("manifest.txt").unwrap().to_vec()).unwrap();
+        assert_eq!(manifest.lines().count(), bundle.files().len() - 1);
+        assert_eq!(bundle.file("missing.txt"), None);
+    }
+
+    #[test]
+    fn guaranteed_seats() {
+        let scenario = Scenario {
+            votes: vec![6.0, 3.0],
+            seat_count: 3,
+            draw_on_tie: false,
+            method: Method::Adams,
+        };
+        let bundle = export_audit_bundle(&scenario, &scenario.evaluate().unwrap());
+        assert_eq!(
+            bundle.file("trace.txt"),
+            Some(
+                &b"seat 1: party 0, guaranteed\n\
+                   seat 2: party 1, guaranteed\n\
+                   seat 3: party 0, quotient 6\n"[..]
+            )
+        );
+        assert_eq!(
+            bundle.file("certificate.txt"),
+            Some(&b"lower: 3\nupper: 6\n"[..])
+        );
+    }
+
+    #[test]
+    fn write_to_directory() {
+        let scenario = Scenario::new(vec![2.0, 1.0], 3);
+        let bundle = export_audit_bundle(&scenario, &scenario.evaluate().unwrap());
+        let dir = std::env::temp_dir().join(format!("sainte_lague_audit_{}", std::process::id()));
+        bundle.write_to(&dir).unwrap();
+        for (name, content) in bundle.files() {
+            assert_eq!(&std::fs::read(dir.join(name)).unwrap(), content);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
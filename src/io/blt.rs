@@ -0,0 +1,93 @@
+//! Exchange with single transferable vote (STV) tally tools in the BLT format.
+//!
+//! The BLT format describes an STV election by its candidates, the number of seats and a list of weighted, ranked ballots. It is read by most STV implementations. [`export`] models a closed-list election as an STV election: every party nominates one candidate per seat, and each voter ranks all candidates of their party in list order. [`seats`] maps the candidates elected by the STV tally back to their parties, so both results can be compared, e.g. in a [`Report`](super::report::Report).
+//!
+//! ```
+//! use sainte_lague::io::blt;
+//! use sainte_lague::party::Party;
+//!
+//! let votes = [(Party::new("a", "Party A"), 60.0), (Party::new("b", "Party B"), 40.0)];
+//! assert_eq!(
+//!     blt::export(&votes, &2, "Example"),
+//!     "4 2\n60 1 2 0\n40 3 4 0\n0\n\"a 1\"\n\"a 2\"\n\"b 1\"\n\"b 2\"\n\"Example\"\n"
+//! );
+//!
+//! // the STV tally elected the candidates 1 and 3
+//! assert_eq!(blt::seats(2, &2, &[1, 3]), Some(vec![1, 1]));
+//! ```
+
+use crate::party::Party;
+use std::fmt::Write;
+
+/// Write the closed-list election as a BLT file with the given title, see the [module documentation](self). Candidate `k` (starting at 1) of the party with index `p` has the number `p * seat_count + k`, and is named after the party's identifier and its list position. The BLT format only supports whole ballots, so the votes are rounded. Parties with less than half a vote cast no ballots. Double quotes in identifiers and the title are replaced with single quotes.
+pub fn export(votes: &[(Party, f64)], seat_count: &usize, title: &str) -> String {
+    let mut blt = String::new();
+    // writing to a string never fails
+    let _ = writeln!(blt, "{} {}", votes.len() * seat_count, seat_count);
+    for (p, (_, v)) in votes.iter().enumerate() {
+        let weight = v.round();
+        if weight.is_nan() || weight < 1.0 {
+            continue;
+        }
+        let _ = write!(blt, "{}", weight);
+        for k in 1..=*seat_count {
+            let _ = write!(blt, " {}", p * seat_count + k);
+        }
+        blt.push_str(" 0\n");
+    }
+    blt.push_str("0\n");
+    for (party, _) in votes.iter() {
+        for k in 1..=*seat_count {
+            let _ = writeln!(blt, "\"{} {}\"", quoted(&party.id), k);
+        }
+    }
+    let _ = writeln!(blt, "\"{}\"", quoted(title));
+    blt
+}
+
+/// The number of seats of each of the `party_count` parties, given the numbers of the candidates `elected` by an STV tally of an [exported](export) election. Returns [`None`] if a candidate number is out of range or appears twice.
+pub fn seats(party_count: usize, seat_count: &usize, elected: &[usize]) -> Option<Vec<usize>> {
+    let mut seen = vec![false; party_count * seat_count];
+    let mut seats = vec![0; party_count];
+    for candidate in elected.iter() {
+        let index = candidate.checked_sub(1)?;
+        if *seen.get(index)? {
+            return None;
+        }
+        seen[index] = true;
+        seats[index / seat_count] += 1;
+    }
+    Some(seats)
+}
+
+fn quoted(text: &str) -> String {
+    text.replace('"', "'")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{export, seats};
+    use crate::party::Party;
+
+    #[test]
+    fn rounded_ballots() {
+        let votes = [
+            (Party::new("x\"y", "X"), 2.6),
+            (Party::new("z", "Z"), 0.4),
+            (Party::new("w", "W"), 1.0),
+        ];
+        assert_eq!(
+            export(&votes, &1, "T\"itle"),
+            "3 1\n3 1 0\n1 3 0\n0\n\"x'y 1\"\n\"z 1\"\n\"w 1\"\n\"T'itle\"\n"
+        );
+    }
+
+    #[test]
+    fn elected_candidates() {
+        assert_eq!(seats(3, &2, &[1, 2, 6]), Some(vec![2, 0, 1]));
+        assert_eq!(seats(3, &2, &[]), Some(vec![0, 0, 0]));
+        assert_eq!(seats(3, &2, &[0]), None);
+        assert_eq!(seats(3, &2, &[7]), None);
+        assert_eq!(seats(3, &2, &[2, 2]), None);
+    }
+}
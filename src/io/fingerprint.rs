@@ -0,0 +1,161 @@
+//! Stable fingerprints of distributions, for referencing published results in audit trails.
+//!
+//! A [`Fingerprint`] is the SHA-256 digest of a canonical serialization of a [`Scenario`] (votes, seat count, method and tie policy) and its [`Outcome`]. The canonical serialization is independent of the [`wire`](super::wire) format version, the platform and the crate version: integers are written as big-endian 64 bit values, `-0.0` is normalized to `0.0` and all NaN values share one bit pattern. The same inputs and results therefore always yield the same fingerprint.
+//!
+//! ```
+//! use sainte_lague::io::fingerprint;
+//! use sainte_lague::Scenario;
+//!
+//! let scenario = Scenario::new(vec![362.0, 318.0, 126.0, 62.0, 53.0], 101);
+//! let outcome = scenario.evaluate().unwrap();
+//!
+//! let published = fingerprint::compute(&scenario, &outcome).to_string();
+//! // … later
+//! let expected = fingerprint::Fingerprint::from_hex(&published).unwrap();
+//! assert_eq!(fingerprint::compute(&scenario, &outcome), expected);
+//! ```
+
+use super::sha256;
+use crate::{Outcome, Scenario};
+use std::fmt;
+
+const DOMAIN: &[u8] = b"sainte_lague fingerprint v1\0";
+
+/// A SHA-256 based fingerprint, see the [module documentation](self).
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Fingerprint([u8; 32]);
+
+impl Fingerprint {
+    /// The raw digest.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Parse a fingerprint from its 64 character hexadecimal representation, as produced by [`Display`](fmt::Display). Returns [`None`] for any other input.
+    pub fn from_hex(hex: &str) -> Option<Fingerprint> {
+        if hex.len() != 64 || !hex.is_ascii() {
+            return None;
+        }
+        let mut bytes = [0u8; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).ok()?;
+        }
+        Some(Fingerprint(bytes))
+    }
+}
+
+/// Formats the fingerprint as 64 lowercase hexadecimal characters.
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in self.0.iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compute the fingerprint of a scenario and its outcome.
+pub fn compute(scenario: &Scenario, outcome: &Outcome) -> Fingerprint {
+    let mut bytes = DOMAIN.to_vec();
+    let method = scenario.method.name().as_bytes();
+    int(&mut bytes, method.len());
+    bytes.extend_from_slice(method);
+    bytes.push(scenario.draw_on_tie as u8);
+    int(&mut bytes, scenario.seat_count);
+    int(&mut bytes, scenario.votes.len());
+    for v in scenario.votes.iter() {
+        bytes.extend_from_slice(&canonical_bits(*v).to_be_bytes());
+    }
+
+    int(&mut bytes, outcome.seats().len());
+    for s in outcome.seats() {
+        int(&mut bytes, *s);
+    }
+    match outcome.draw() {
+        None => bytes.push(0),
+        Some(draw) => {
+            bytes.push(1);
+            int(&mut bytes, draw.candidates().len());
+            for c in draw.candidates() {
+                int(&mut bytes, *c);
+            }
+            int(&mut bytes, draw.seats());
+        }
+    }
+
+    Fingerprint(sha256::digest(&bytes))
+}
+
+fn int(bytes: &mut Vec<u8>, value: usize) {
+    bytes.extend_from_slice(&(value as u64).to_be_bytes())
+}
+
+pub(crate) fn canonical_bits(value: f64) -> u64 {
+    if value.is_nan() {
+        f64::NAN.to_bits()
+    } else if value == 0.0 {
+        0
+    } else {
+        value.to_bits()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compute, Fingerprint};
+    use crate::Scenario;
+
+    #[test]
+    fn stable_value() {
+        let scenario = Scenario::new(vec![41.5, 25.7, 8.6, 8.4], 631);
+        let outcome = scenario.evaluate().unwrap();
+        let fingerprint = compute(&scenario, &outcome);
+
+        // this value must never change, published fingerprints rely on it
+        assert_eq!(
+            fingerprint.to_string(),
+            "99e5c6ac9399018da589b155687dda1ab644cb76b4addd26932f39a2dd9d7468"
+        );
+        assert_eq!(
+            Fingerprint::from_hex(&fingerprint.to_string()),
+            Some(fingerprint)
+        );
+    }
+
+    #[test]
+    fn sensitive_to_every_input() {
+        let scenario = Scenario::new(vec![3.0, 2.0, 1.0], 6);
+        let outcome = scenario.evaluate().unwrap();
+        let fingerprint = compute(&scenario, &outcome);
+
+        let mut other = scenario.clone();
+        other.votes[2] = 1.000_000_000_000_1;
+        assert_ne!(compute(&other, &outcome), fingerprint);
+
+        let mut other = scenario.clone();
+        other.seat_count = 7;
+        assert_ne!(compute(&other, &outcome), fingerprint);
+
+        let mut other = scenario.clone();
+        other.draw_on_tie = true;
+        assert_ne!(compute(&other, &outcome), fingerprint);
+
+        let other_outcome = Scenario::new(vec![3.0, 2.0, 1.0], 5).evaluate().unwrap();
+        assert_ne!(compute(&scenario, &other_outcome), fingerprint);
+    }
+
+    #[test]
+    fn canonical_floats() {
+        let positive = Scenario::new(vec![0.0, 1.0], 1);
+        let negative = Scenario::new(vec![-0.0, 1.0], 1);
+        let outcome = positive.evaluate().unwrap();
+        assert_eq!(compute(&positive, &outcome), compute(&negative, &outcome));
+    }
+
+    #[test]
+    fn invalid_hex() {
+        assert_eq!(Fingerprint::from_hex(""), None);
+        assert_eq!(Fingerprint::from_hex(&"g".repeat(64)), None);
+        assert_eq!(Fingerprint::from_hex(&"0".repeat(63)), None);
+    }
+}
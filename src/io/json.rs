@@ -0,0 +1,271 @@
+//! A small, dependency-free JSON reader (RFC 8259), used to read scenario files.
+
+/// A JSON value. Object members keep their order, and duplicate keys are kept as well.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// The value of the first member with the given key, if this is an object.
+    pub(crate) fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+/// Parse a JSON document, or fail with the byte offset of the first error.
+pub(crate) fn parse(text: &str) -> Result<Value, usize> {
+    let mut parser = Parser {
+        bytes: text.as_bytes(),
+        position: 0,
+    };
+    let value = parser.value(0)?;
+    parser.whitespace();
+    match parser.position == parser.bytes.len() {
+        true => Ok(value),
+        false => Err(parser.position),
+    }
+}
+
+/// Nesting deeper than this fails, instead of overflowing the stack.
+const MAX_DEPTH: usize = 128;
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.position).cloned()
+    }
+
+    fn whitespace(&mut self) {
+        while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') = self.peek() {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, literal: &str) -> Result<(), usize> {
+        match self.bytes[self.position..].starts_with(literal.as_bytes()) {
+            true => {
+                self.position += literal.len();
+                Ok(())
+            }
+            false => Err(self.position),
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value, usize> {
+        self.whitespace();
+        if depth > MAX_DEPTH {
+            return Err(self.position);
+        }
+        match self.peek() {
+            Some(b'n') => self.expect("null").map(|_| Value::Null),
+            Some(b't') => self.expect("true").map(|_| Value::Bool(true)),
+            Some(b'f') => self.expect("false").map(|_| Value::Bool(false)),
+            Some(b'"') => self.string().map(Value::String),
+            Some(b'[') => {
+                self.position += 1;
+                let mut values = vec![];
+                self.whitespace();
+                if self.peek() == Some(b']') {
+                    self.position += 1;
+                    return Ok(Value::Array(values));
+                }
+                loop {
+                    values.push(self.value(depth + 1)?);
+                    self.whitespace();
+                    match self.peek() {
+                        Some(b',') => self.position += 1,
+                        Some(b']') => {
+                            self.position += 1;
+                            return Ok(Value::Array(values));
+                        }
+                        _ => return Err(self.position),
+                    }
+                }
+            }
+            Some(b'{') => {
+                self.position += 1;
+                let mut members = vec![];
+                self.whitespace();
+                if self.peek() == Some(b'}') {
+                    self.position += 1;
+                    return Ok(Value::Object(members));
+                }
+                loop {
+                    self.whitespace();
+                    if self.peek() != Some(b'"') {
+                        return Err(self.position);
+                    }
+                    let key = self.string()?;
+                    self.whitespace();
+                    self.expect(":")?;
+                    members.push((key, self.value(depth + 1)?));
+                    self.whitespace();
+                    match self.peek() {
+                        Some(b',') => self.position += 1,
+                        Some(b'}') => {
+                            self.position += 1;
+                            return Ok(Value::Object(members));
+                        }
+                        _ => return Err(self.position),
+                    }
+                }
+            }
+            Some(b'-') | Some(b'0'..=b'9') => self.number(),
+            _ => Err(self.position),
+        }
+    }
+
+    fn number(&mut self) -> Result<Value, usize> {
+        let start = self.position;
+        let digits = |parser: &mut Parser| {
+            let from = parser.position;
+            while let Some(b'0'..=b'9') = parser.peek() {
+                parser.position += 1;
+            }
+            match parser.position > from {
+                true => Ok(()),
+                false => Err(parser.position),
+            }
+        };
+        if self.peek() == Some(b'-') {
+            self.position += 1;
+        }
+        if self.peek() == Some(b'0') {
+            self.position += 1;
+        } else {
+            digits(self)?;
+        }
+        if self.peek() == Some(b'.') {
+            self.position += 1;
+            digits(self)?;
+        }
+        if let Some(b'e') | Some(b'E') = self.peek() {
+            self.position += 1;
+            if let Some(b'+') | Some(b'-') = self.peek() {
+                self.position += 1;
+            }
+            digits(self)?;
+        }
+        // the grammar above only accepts ASCII, which is valid UTF-8 and a valid float literal
+        let literal = std::str::from_utf8(&self.bytes[start..self.position]).map_err(|_| start)?;
+        literal.parse().map(Value::Number).map_err(|_| start)
+    }
+
+    fn string(&mut self) -> Result<String, usize> {
+        let start = self.position;
+        self.position += 1;
+        let mut bytes = vec![];
+        loop {
+            match self.peek() {
+                None => return Err(start),
+                Some(b'"') => break,
+                Some(b'\\') => {
+                    self.position += 1;
+                    let escaped = match self.peek() {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => self.unicode()?,
+                        _ => return Err(self.position),
+                    };
+                    let mut buffer = [0; 4];
+                    bytes.extend_from_slice(escaped.encode_utf8(&mut buffer).as_bytes());
+                }
+                Some(byte) if byte < 0x20 => return Err(self.position),
+                Some(byte) => bytes.push(byte),
+            }
+            self.position += 1;
+        }
+        self.position += 1;
+        // the input is a str and escapes are encoded as UTF-8, so this can't fail
+        String::from_utf8(bytes).map_err(|_| start)
+    }
+
+    /// Read the hexadecimal digits of a `\u` escape, and of the low surrogate following a high one. Leaves the position on the last digit.
+    fn unicode(&mut self) -> Result<char, usize> {
+        let code = |parser: &mut Parser| -> Result<u32, usize> {
+            let hex = parser
+                .bytes
+                .get(parser.position + 1..parser.position + 5)
+                .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .ok_or(parser.position)?;
+            parser.position += 4;
+            Ok(hex)
+        };
+        let high = code(self)?;
+        let scalar = match high {
+            0xd800..=0xdbff => {
+                if self.bytes.get(self.position + 1..self.position + 3) != Some(b"\\u") {
+                    return Err(self.position + 1);
+                }
+                self.position += 2;
+                let low = code(self)?;
+                if !(0xdc00..=0xdfff).contains(&low) {
+                    return Err(self.position);
+                }
+                0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+            }
+            code => code,
+        };
+        std::char::from_u32(scalar).ok_or(self.position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, Value};
+
+    #[test]
+    fn documents() {
+        let value =
+            parse(r#" {"a": [1, -2.5e1, true, null], "b": "x\"\u00e9\ud83d\ude00", "a": {}} "#);
+        let value = value.unwrap();
+        assert_eq!(
+            value.get("a"),
+            Some(&Value::Array(vec![
+                Value::Number(1.0),
+                Value::Number(-25.0),
+                Value::Bool(true),
+                Value::Null
+            ]))
+        );
+        assert_eq!(value.get("b"), Some(&Value::String("x\"é😀".to_string())));
+        assert_eq!(value.get("c"), None);
+        assert_eq!(parse("[]"), Ok(Value::Array(vec![])));
+        assert_eq!(parse("1e400"), Ok(Value::Number(f64::INFINITY)));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(parse(""), Err(0));
+        assert_eq!(parse("[1,]"), Err(3));
+        assert_eq!(parse("{\"a\" 1}"), Err(5));
+        assert_eq!(parse("01"), Err(1));
+        assert_eq!(parse("\"abc"), Err(0));
+        assert_eq!(parse("\"\\ud800x\""), Err(7));
+        assert_eq!(parse("-"), Err(1));
+        assert_eq!(parse("1."), Err(2));
+        assert_eq!(parse("[1] x"), Err(4));
+        assert!(parse(&"[".repeat(1000)).is_err());
+    }
+}
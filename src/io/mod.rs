@@ -0,0 +1,13 @@
+//! Reading, writing and presenting scenarios and their outcomes.
+
+pub mod audit;
+pub mod blt;
+pub mod fingerprint;
+mod json;
+pub mod report;
+pub mod results;
+mod sha256;
+pub mod snapshot;
+pub mod validation;
+pub mod vectors;
+pub mod wire;
@@ -0,0 +1,229 @@
+//! Side-by-side comparisons of multiple distributions as Markdown or HTML tables.
+//!
+//! Each column of a [`Report`] represents one scenario, e.g. a different method, threshold or set of votes. Rows list the seats of every party, followed by the total number of seats and the [Gallagher index](crate::metrics::gallagher) of each scenario.
+//!
+//! ```
+//! use sainte_lague::party::Party;
+//! use sainte_lague::io::report::Report;
+//!
+//! let parties = vec![Party::new("a", "Party A"), Party::new("b", "Party B")];
+//! let mut report = Report::new(parties);
+//! report.add("10 seats", vec![60.0, 40.0], vec![6, 4]);
+//! report.add("11 seats", vec![60.0, 40.0], vec![7, 4]);
+//!
+//! assert_eq!(
+//!     report.to_markdown(),
+//!     "| Party | 10 seats | 11 seats |\n\
+//!      | --- | ---: | ---: |\n\
+//!      | Party A | 6 | 7 |\n\
+//!      | Party B | 4 | 4 |\n\
+//!      | Total | 10 | 11 |\n\
+//!      | Gallagher index | 0.00 | 3.64 |\n"
+//! );
+//! ```
+
+use crate::locale::{text, Locale, Text};
+use crate::metrics;
+use crate::party::Party;
+
+#[derive(Clone, PartialEq, Debug)]
+struct Column {
+    title: String,
+    votes: Vec<f64>,
+    seats: Vec<usize>,
+}
+
+/// A comparison of multiple distributions for the same parties, see the [module documentation](self).
+#[derive(Clone, PartialEq, Debug)]
+pub struct Report {
+    parties: Vec<Party>,
+    columns: Vec<Column>,
+    locale: Locale,
+}
+
+impl Report {
+    /// Create an empty report for the given parties.
+    pub fn new(parties: Vec<Party>) -> Report {
+        Report {
+            parties,
+            columns: vec![],
+            locale: Locale::English,
+        }
+    }
+
+    /// Set the language of the headings, English by default.
+    pub fn set_locale(&mut self, locale: &Locale) {
+        self.locale = *locale;
+    }
+
+    /// Add a column with the given title, containing the `seats` calculated for `votes`. Both contain one entry per party, in the order the parties were passed to [`Report::new`]. Returns `false` and leaves the report unchanged if either has a different length.
+    pub fn add(&mut self, title: &str, votes: Vec<f64>, seats: Vec<usize>) -> bool {
+        if votes.len() != self.parties.len() || seats.len() != self.parties.len() {
+            return false;
+        }
+        self.columns.push(Column {
+            title: title.to_string(),
+            votes,
+            seats,
+        });
+        true
+    }
+
+    fn rows(&self) -> Vec<(String, Vec<String>)> {
+        let mut rows: Vec<(String, Vec<String>)> = self
+            .parties
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let cells = self.columns.iter().map(|c| c.seats[i].to_string());
+                (p.name.clone(), cells.collect())
+            })
+            .collect();
+        rows.push((
+            text(&self.locale, Text::Total).to_string(),
+            self.columns
+                .iter()
+                .map(|c| c.seats.iter().sum::<usize>().to_string())
+                .collect(),
+        ));
+        rows.push((
+            text(&self.locale, Text::GallagherIndex).to_string(),
+            self.columns
+                .iter()
+                .map(|c| match metrics::gallagher(&c.votes, &c.seats) {
+                    Some(g) => format!("{:.2}", g),
+                    None => "-".to_string(),
+                })
+                .collect(),
+        ));
+        rows
+    }
+
+    /// Render the report as a Markdown table.
+    pub fn to_markdown(&self) -> String {
+        let escape = |s: &str| s.replace('|', "\\|");
+        let mut markdown = format!("| {} |", escape(text(&self.locale, Text::Party)));
+        for column in self.columns.iter() {
+            markdown.push_str(&format!(" {} |", escape(&column.title)));
+        }
+        markdown.push_str("\n| --- |");
+        markdown.push_str(&" ---: |".repeat(self.columns.len()));
+        markdown.push('\n');
+        for (label, cells) in self.rows() {
+            markdown.push_str(&format!("| {} |", escape(&label)));
+            for cell in cells {
+                markdown.push_str(&format!(" {} |", cell));
+            }
+            markdown.push('\n');
+        }
+        markdown
+    }
+
+    /// Render the report as an HTML table. Parties with a [color](Party::color) are marked with it.
+    pub fn to_html(&self) -> String {
+        let mut html = format!(
+            "<table>\n<thead>\n<tr><th>{}</th>",
+            escape_html(text(&self.locale, Text::Party))
+        );
+        for column in self.columns.iter() {
+            html.push_str(&format!("<th>{}</th>", escape_html(&column.title)));
+        }
+        html.push_str("</tr>\n</thead>\n<tbody>\n");
+        for (i, (label, cells)) in self.rows().into_iter().enumerate() {
+            let color = self.parties.get(i).and_then(|p| p.color);
+            match color {
+                Some(c) => html.push_str(&format!(
+                    "<tr><th style=\"border-left: 0.5em solid {}\">{}</th>",
+                    c,
+                    escape_html(&label)
+                )),
+                None => html.push_str(&format!("<tr><th>{}</th>", escape_html(&label))),
+            }
+            for cell in cells {
+                html.push_str(&format!("<td>{}</td>", cell));
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</tbody>\n</table>\n");
+        html
+    }
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Report;
+    use crate::locale::Locale;
+    use crate::party::{Color, Party};
+    use crate::{distribute, Method, Scenario};
+
+    #[test]
+    fn html() {
+        let mut green = Party::new("g", "Greens & Friends");
+        green.color = Some(Color::new(0, 128, 0));
+        let mut report = Report::new(vec![green, Party::new("o", "<Others>")]);
+        report.add("Votes | 2021", vec![3.0, 1.0], vec![3, 1]);
+
+        assert_eq!(
+            report.to_html(),
+            "<table>\n<thead>\n<tr><th>Party</th><th>Votes | 2021</th></tr>\n</thead>\n<tbody>\n\
+             <tr><th style=\"border-left: 0.5em solid #008000\">Greens &amp; Friends</th><td>3</td></tr>\n\
+             <tr><th>&lt;Others&gt;</th><td>1</td></tr>\n\
+             <tr><th>Total</th><td>4</td></tr>\n\
+             <tr><th>Gallagher index</th><td>0.00</td></tr>\n\
+             </tbody>\n</table>\n"
+        );
+        assert!(report
+            .to_markdown()
+            .starts_with("| Party | Votes \\| 2021 |\n"));
+    }
+
+    #[test]
+    fn compare_scenarios() {
+        let votes = vec![362.0, 318.0, 126.0, 62.0, 53.0];
+        let parties: Vec<Party> = (0..votes.len())
+            .map(|i| Party::new(&i.to_string(), &format!("Party {}", i)))
+            .collect();
+        let mut report = Report::new(parties);
+        for seats in [50, 101].iter() {
+            let scenario = Scenario {
+                votes: votes.clone(),
+                seat_count: *seats,
+                draw_on_tie: false,
+                method: Method::SainteLague,
+            };
+            let distribution = scenario.evaluate().unwrap().into_seats();
+            assert_eq!(distribution, distribute(&votes, seats, &false).unwrap());
+            report.add(&format!("{} seats", seats), votes.clone(), distribution);
+        }
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("| Party 0 | 20 | 39 |\n"));
+        assert!(markdown.contains("| Total | 50 | 101 |\n"));
+    }
+
+    #[test]
+    fn wrong_length() {
+        let mut report = Report::new(vec![Party::new("a", "A")]);
+        assert!(!report.add("x", vec![1.0, 2.0], vec![1, 1]));
+        assert!(!report.add("x", vec![1.0], vec![]));
+        assert_eq!(report, Report::new(vec![Party::new("a", "A")]));
+    }
+
+    #[test]
+    fn localized() {
+        let mut report = Report::new(vec![Party::new("a", "A")]);
+        report.add("2021", vec![1.0], vec![2]);
+        report.set_locale(&Locale::German);
+        assert_eq!(
+            report.to_markdown(),
+            "| Partei | 2021 |\n| --- | ---: |\n| A | 2 |\n| Gesamt | 2 |\n| Gallagher-Index | 0.00 |\n"
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! Results entered by hand as CSV, and the seats that changed between two versions of them.
+//!
+//! The format is deliberately minimal, so it can be typed into any editor or spreadsheet on election night: one line per party with its name and votes, separated by a comma. Empty lines and lines starting with `#` are ignored, as is a first line whose votes aren't a number, e.g. a header like `party,votes`.
+//!
+//! ```
+//! use sainte_lague::io::results::{self, SeatChange};
+//!
+//! let before = results::parse("party,votes\nA,362\nB,318\nC,126\n").unwrap();
+//! let after = results::parse("A,362\nB,330\n# still counting\nC,115\n").unwrap();
+//! let seats = |results: &[(String, f64)]| {
+//!     let votes: Vec<f64> = results.iter().map(|(_, v)| *v).collect();
+//!     sainte_lague::distribute(&votes, &10, &false).unwrap()
+//! };
+//! let changes = results::changes(&before, &seats(&before), &after, &seats(&after));
+//! assert_eq!(changes, vec![
+//!     SeatChange { party: "A".to_string(), before: 4, after: 5 },
+//!     SeatChange { party: "C".to_string(), before: 2, after: 1 },
+//! ]);
+//! assert_eq!(changes[0].to_string(), "A: 4 → 5 (+1)");
+//! ```
+
+use crate::locale::{text_with, Locale, Localize, Text};
+use std::error;
+use std::fmt;
+
+/// A line that isn't of the form `name,votes`, see [`parse`].
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct InvalidLine(pub usize);
+
+impl fmt::Display for InvalidLine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.localize(&Locale::English))
+    }
+}
+
+impl Localize for InvalidLine {
+    fn localize(&self, locale: &Locale) -> String {
+        text_with(locale, Text::InvalidLine, &self.0)
+    }
+}
+
+impl error::Error for InvalidLine {}
+
+/// Parse the name and votes of every party, in the order of the lines, see the [module documentation](self). Fails with the number of the first invalid line, starting at 1. Names are trimmed, but not checked for duplicates.
+pub fn parse(csv: &str) -> Result<Vec<(String, f64)>, InvalidLine> {
+    let mut results = vec![];
+    for (i, line) in csv.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (name, votes) = line.split_once(',').ok_or(InvalidLine(i + 1))?;
+        match votes.trim().parse::<f64>() {
+            Ok(votes) if votes.is_finite() => results.push((name.trim().to_string(), votes)),
+            _ if i == 0 => continue,
+            _ => return Err(InvalidLine(i + 1)),
+        }
+    }
+    Ok(results)
+}
+
+/// A party whose seats changed, see [`changes`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct SeatChange {
+    /// The name of the party.
+    pub party: String,
+    /// The seats before the change.
+    pub before: usize,
+    /// The seats after the change.
+    pub after: usize,
+}
+
+/// E.g. `A: 4 → 5 (+1)`.
+impl fmt::Display for SeatChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} → {} ({:+})",
+            self.party,
+            self.before,
+            self.after,
+            self.after as i64 - self.before as i64
+        )
+    }
+}
+
+/// The parties whose seats differ between two versions of the results, matched by name. Parties missing from one of the versions have zero seats in it. Parties are listed in the order of the current results, followed by the parties that were removed.
+pub fn changes(
+    previous: &[(String, f64)],
+    previous_seats: &[usize],
+    current: &[(String, f64)],
+    current_seats: &[usize],
+) -> Vec<SeatChange> {
+    let seats_of = |results: &[(String, f64)], seats: &[usize], party: &str| {
+        results
+            .iter()
+            .zip(seats)
+            .filter(|((name, _), _)| name == party)
+            .map(|(_, s)| s)
+            .sum()
+    };
+    let removed = previous
+        .iter()
+        .filter(|(name, _)| current.iter().all(|(n, _)| n != name));
+    current
+        .iter()
+        .chain(removed)
+        .map(|(name, _)| SeatChange {
+            party: name.clone(),
+            before: seats_of(previous, previous_seats, name),
+            after: seats_of(current, current_seats, name),
+        })
+        .filter(|change| change.before != change.after)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{changes, parse, InvalidLine, SeatChange};
+
+    fn results(entries: &[(&str, f64)]) -> Vec<(String, f64)> {
+        entries.iter().map(|(n, v)| (n.to_string(), *v)).collect()
+    }
+
+    #[test]
+    fn parsing() {
+        assert_eq!(
+            parse(" A , 1.5\n\n#B,2\nC,0\n"),
+            Ok(results(&[("A", 1.5), ("C", 0.0)]))
+        );
+        assert_eq!(parse("name,votes\n"), Ok(vec![]));
+        assert_eq!(parse("A,1\nname,votes\n"), Err(InvalidLine(2)));
+        assert_eq!(parse("A,1\nB\n"), Err(InvalidLine(2)));
+        assert_eq!(parse("A,1\nB,inf\n"), Err(InvalidLine(2)));
+        assert_eq!(
+            InvalidLine(3).to_string(),
+            "Invalid input, line 3 is not of the form name,votes."
+        );
+    }
+
+    #[test]
+    fn changes_by_name() {
+        let previous = results(&[("A", 1.0), ("B", 1.0), ("C", 1.0)]);
+        let current = results(&[("D", 1.0), ("B", 1.0), ("A", 1.0)]);
+        let change = |party: &str, before: usize, after: usize| SeatChange {
+            party: party.to_string(),
+            before,
+            after,
+        };
+        assert_eq!(
+            changes(&previous, &[2, 1, 1], &current, &[1, 1, 2]),
+            vec![change("D", 0, 1), change("C", 1, 0)]
+        );
+        assert_eq!(change("C", 1, 0).to_string(), "C: 1 → 0 (-1)");
+    }
+}
@@ -0,0 +1,123 @@
+//! Stable text renderings for snapshot tests.
+//!
+//! The [`Debug`](std::fmt::Debug) output of the types in this crate may change whenever a field is added or renamed, which breaks snapshot tests for reasons unrelated to the distribution. A [`Snapshot`] instead renders one `key: value` line per entry, with keys sorted alphabetically and numbers in a fixed, platform-independent format: integers in decimal notation, floats as the shortest decimal representation that reads back to the same value, without exponent and with `-0` normalized to `0`. This format only changes between major versions of the crate.
+//!
+//! ```
+//! use sainte_lague::io::snapshot::Snapshot;
+//! use sainte_lague::Scenario;
+//!
+//! let scenario = Scenario::new(vec![41.5, 25.7, 8.6, 8.4], 631);
+//! assert_eq!(
+//!     scenario.evaluate().snapshot(),
+//!     "draw: none\n\
+//!      seats: 311, 193, 64, 63\n"
+//! );
+//! ```
+
+use crate::{Draw, Outcome, Scenario};
+use std::fmt;
+
+/// Types with a stable text rendering, see the [module documentation](self).
+pub trait Snapshot {
+    /// The rendering, one `key: value` line per entry.
+    fn snapshot(&self) -> String;
+}
+
+fn list<T: fmt::Display>(values: impl Iterator<Item = T>) -> String {
+    values.map(|v| v.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+pub(crate) fn float(value: f64) -> String {
+    if value == 0.0 {
+        "0".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+impl Snapshot for Scenario {
+    fn snapshot(&self) -> String {
+        format!(
+            "draw_on_tie: {}\nmethod: {}\nseat_count: {}\nvotes: {}\n",
+            self.draw_on_tie,
+            self.method.name(),
+            self.seat_count,
+            list(self.votes.iter().map(|v| float(*v)))
+        )
+    }
+}
+
+impl Snapshot for Draw {
+    fn snapshot(&self) -> String {
+        format!(
+            "candidates: {}\nseats: {}\n",
+            list(self.candidates().iter()),
+            self.seats()
+        )
+    }
+}
+
+impl Snapshot for Outcome {
+    fn snapshot(&self) -> String {
+        let draw = match self.draw() {
+            None => "draw: none\n".to_string(),
+            Some(d) => d
+                .snapshot()
+                .lines()
+                .map(|line| format!("draw.{}\n", line))
+                .collect(),
+        };
+        format!("{}seats: {}\n", draw, list(self.seats().iter()))
+    }
+}
+
+/// Errors are rendered by their [`Debug`](fmt::Debug) representation, i.e. the variant name, which is part of the public API.
+impl<T: Snapshot, E: fmt::Debug> Snapshot for Result<T, E> {
+    fn snapshot(&self) -> String {
+        match self {
+            Ok(value) => value.snapshot(),
+            Err(error) => format!("error: {:?}\n", error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Snapshot;
+    use crate::constraints::ConstraintError;
+    use crate::{DistributionError, Method, Scenario};
+
+    #[test]
+    fn scenario() {
+        let scenario = Scenario {
+            votes: vec![3.0, -0.0, 0.1, 1e-7, 1e21],
+            seat_count: 8,
+            draw_on_tie: true,
+            method: Method::SainteLague,
+        };
+        assert_eq!(
+            scenario.snapshot(),
+            "draw_on_tie: true\n\
+             method: sainte-lague\n\
+             seat_count: 8\n\
+             votes: 3, 0, 0.1, 0.0000001, 1000000000000000000000\n"
+        );
+    }
+
+    #[test]
+    fn draws() {
+        let mut scenario = Scenario::new(vec![3.0, 3.0, 1.0], 8);
+        scenario.draw_on_tie = true;
+        let snapshot = scenario.evaluate().snapshot();
+        assert!(snapshot.starts_with("draw.candidates: 0, 1\ndraw.seats: 1\nseats: "));
+    }
+
+    #[test]
+    fn errors() {
+        let scenario = Scenario::new(vec![3.0, 3.0, 1.0], 8);
+        assert_eq!(scenario.evaluate().snapshot(), "error: Tied\n");
+        let error: Result<crate::Outcome, _> =
+            Err(ConstraintError::Distribution(DistributionError::NoVotes));
+        assert_eq!(error.snapshot(), "error: Distribution(NoVotes)\n");
+    }
+}
@@ -0,0 +1,485 @@
+//! Validation of scenario files without distributing any seats, with machine-readable diagnostics.
+//!
+//! Publishers of election data can run [`validate`] on every file before releasing it, e.g. in a CI pipeline through `sainte-lague validate`, to catch mistakes that would only surface once someone distributes the seats. A scenario file is a JSON object with the parties, the number of seats and optionally the method and constraints:
+//!
+//! ```json
+//! {
+//!   "parties": [{"name": "A", "votes": 362}, {"name": "B", "votes": 318}, {"name": "C", "votes": 126}],
+//!   "seatCount": 10,
+//!   "method": "sainte-lague",
+//!   "threshold": 0.05,
+//!   "exemptions": [2],
+//!   "minSeats": [[2, 1]],
+//!   "maxSeats": [[0, 5]]
+//! }
+//! ```
+//!
+//! `method` is one of the [method names](crate::Method::name) and defaults to Sainte-Laguë. Parties in `exemptions`, `minSeats` and `maxSeats` are referred to by their index, bounds are pairs of a party index and a number of seats.
+//!
+//! Every problem found is reported as a [`Diagnostic`], with a stable code and the path of the offending value. Errors make the file unusable, warnings point to values that are probably wrong but can be distributed:
+//!
+//! | Code | Severity | Meaning |
+//! |---|---|---|
+//! | `syntax` | error | The file isn't valid JSON. |
+//! | `missing` | error | A required field is missing. |
+//! | `type` | error | A value has the wrong type. |
+//! | `unknown-field` | warning | A field isn't part of the format, e.g. because of a typo. |
+//! | `duplicate-party` | error | Two parties have the same name. |
+//! | `votes` | error | Votes are negative or out of range, or no party has any. |
+//! | `precision` | warning | Votes above 2^53 can't be represented exactly, so they may already have been rounded. |
+//! | `seat-count` | error | The number of seats isn't a positive integer. |
+//! | `method` | error | The method is unknown. |
+//! | `constraint` | error | The constraints contradict each other or refer to unknown parties. |
+//! | `infeasible` | error | The seat bounds can't be satisfied. |
+//!
+//! ```
+//! use sainte_lague::io::validation::{validate, Severity};
+//!
+//! let diagnostics = validate(r#"{
+//!     "parties": [{"name": "A", "votes": 362}, {"name": "A", "votes": -1}],
+//!     "seatCount": 10,
+//!     "treshold": 0.05
+//! }"#);
+//! let codes: Vec<(&str, &str, Severity)> = diagnostics.iter().map(|d| (d.code, d.path.as_str(), d.severity)).collect();
+//! assert_eq!(codes, vec![
+//!     ("unknown-field", "treshold", Severity::Warning),
+//!     ("duplicate-party", "parties[1].name", Severity::Error),
+//!     ("votes", "parties[1].votes", Severity::Error),
+//! ]);
+//! ```
+
+use super::json::{self, Value};
+use super::vectors::string;
+use crate::constraints::{Constraint, Rules};
+use crate::Method;
+
+const FIELDS: [&str; 7] = [
+    "parties",
+    "seatCount",
+    "method",
+    "threshold",
+    "exemptions",
+    "minSeats",
+    "maxSeats",
+];
+
+const METHODS: [Method; 7] = [
+    Method::SainteLague,
+    Method::DHondt,
+    Method::HuntingtonHill,
+    Method::Adams,
+    Method::Dean,
+    Method::Danish,
+    Method::Imperiali,
+];
+
+/// The largest integer up to which all integers can be represented exactly as floats.
+const EXACT_INTEGERS: f64 = 9007199254740992.0;
+
+/// Whether a [`Diagnostic`] makes the scenario unusable.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Severity {
+    /// The scenario can't be distributed.
+    Error,
+    /// The scenario can be distributed, but probably contains a mistake.
+    Warning,
+}
+
+impl Severity {
+    /// `"error"` or `"warning"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+/// A problem found in a scenario file, see the [module documentation](self) for the codes.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Diagnostic {
+    /// Whether the problem makes the scenario unusable.
+    pub severity: Severity,
+    /// A stable identifier of the kind of problem.
+    pub code: &'static str,
+    /// The path of the offending value, e.g. `parties[1].votes`, or empty for the whole file.
+    pub path: String,
+    /// A description in English.
+    pub message: String,
+}
+
+/// Check the scenario file for all problems, see the [module documentation](self). Checks that depend on invalid values are skipped, e.g. the feasibility of the seat bounds isn't checked if the votes are invalid. An empty result means the scenario can be distributed, although it may still be tied.
+pub fn validate(scenario: &str) -> Vec<Diagnostic> {
+    let mut check = Check {
+        diagnostics: vec![],
+    };
+    let root = match json::parse(scenario) {
+        Ok(root) => root,
+        Err(offset) => {
+            check.error("syntax", "", &format!("Invalid JSON at byte {}.", offset));
+            return check.diagnostics;
+        }
+    };
+    let members = match &root {
+        Value::Object(members) => members,
+        _ => {
+            check.error("type", "", "The scenario must be an object.");
+            return check.diagnostics;
+        }
+    };
+    for (key, _) in members {
+        if !FIELDS.contains(&key.as_str()) {
+            check.warning("unknown-field", key, &format!("Unknown field {}.", key));
+        }
+    }
+
+    let votes = check.parties(root.get("parties"));
+    let seat_count = check.seat_count(root.get("seatCount"));
+    let rules = check.rules(&root);
+    if let (Some(votes), Some(seat_count), Some(rules)) = (votes, seat_count, rules) {
+        if let Err(e) = rules.validate(votes.len()) {
+            check.error("constraint", "", &e.to_string());
+        } else if let Ok(Some(infeasibility)) = rules.check_feasibility(&votes, &seat_count) {
+            check.error("infeasible", "", &infeasibility.to_string());
+        }
+    }
+    check.diagnostics
+}
+
+/// Write the diagnostics as a JSON object with a `valid` flag, which is false if there are any errors, and the `diagnostics` as an array of objects with the fields `severity`, `code`, `path` and `message`.
+pub fn to_json(diagnostics: &[Diagnostic]) -> String {
+    let valid = diagnostics.iter().all(|d| d.severity != Severity::Error);
+    let mut json = format!("{{\n  \"valid\": {},\n  \"diagnostics\": [", valid);
+    for (i, d) in diagnostics.iter().enumerate() {
+        json.push_str(&format!(
+            "{}\n    {{\"severity\": {}, \"code\": {}, \"path\": {}, \"message\": {}}}",
+            if i == 0 { "" } else { "," },
+            string(d.severity.name()),
+            string(d.code),
+            string(&d.path),
+            string(&d.message)
+        ));
+    }
+    if !diagnostics.is_empty() {
+        json.push_str("\n  ");
+    }
+    json.push_str("]\n}\n");
+    json
+}
+
+/// Write a scenario with the votes of parties named `1`, `2` and so on, and no method or constraints, on a single line, e.g. for one scenario per line in a larger file. The votes must be finite, and are written in their shortest exact form, so reading the scenario back yields the same floats.
+pub fn write_scenario(votes: &[f64], seat_count: usize) -> String {
+    let parties: Vec<String> = votes
+        .iter()
+        .enumerate()
+        .map(|(p, v)| {
+            format!(
+                "{{\"name\": {}, \"votes\": {}}}",
+                string(&(p + 1).to_string()),
+                v
+            )
+        })
+        .collect();
+    format!(
+        "{{\"parties\": [{}], \"seatCount\": {}}}",
+        parties.join(", "),
+        seat_count
+    )
+}
+
+struct Check {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Check {
+    fn push(&mut self, severity: Severity, code: &'static str, path: &str, message: &str) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            code,
+            path: path.to_string(),
+            message: message.to_string(),
+        });
+    }
+
+    fn error(&mut self, code: &'static str, path: &str, message: &str) {
+        self.push(Severity::Error, code, path, message)
+    }
+
+    fn warning(&mut self, code: &'static str, path: &str, message: &str) {
+        self.push(Severity::Warning, code, path, message)
+    }
+
+    fn missing(&mut self, path: &str) {
+        self.error("missing", path, &format!("Missing field {}.", path));
+    }
+
+    fn wrong_type(&mut self, path: &str, expected: &str) {
+        self.error("type", path, &format!("{} must be {}.", path, expected));
+    }
+
+    /// The votes of all parties, or [`None`] if any of them is invalid.
+    fn parties(&mut self, parties: Option<&Value>) -> Option<Vec<f64>> {
+        let parties = match parties {
+            Some(Value::Array(parties)) => parties,
+            None => {
+                self.missing("parties");
+                return None;
+            }
+            Some(_) => {
+                self.wrong_type("parties", "an array");
+                return None;
+            }
+        };
+        let errors = self.diagnostics.len();
+        let mut names: Vec<&str> = vec![];
+        let mut votes = vec![];
+        for (i, party) in parties.iter().enumerate() {
+            let path = |field: &str| format!("parties[{}].{}", i, field);
+            if !matches!(party, Value::Object(_)) {
+                self.wrong_type(&format!("parties[{}]", i), "an object");
+                continue;
+            }
+            match party.get("name") {
+                None => self.missing(&path("name")),
+                Some(Value::String(name)) if names.contains(&name.as_str()) => {
+                    let message = format!("The name {} is used by multiple parties.", name);
+                    self.error("duplicate-party", &path("name"), &message);
+                }
+                Some(Value::String(name)) => names.push(name),
+                Some(_) => self.wrong_type(&path("name"), "a string"),
+            }
+            match party.get("votes") {
+                None => self.missing(&path("votes")),
+                Some(Value::Number(v)) if *v < 0.0 => {
+                    let message = "Votes must not be negative.";
+                    self.error("votes", &path("votes"), message);
+                }
+                Some(Value::Number(v)) if v.is_infinite() => {
+                    let message = "Votes are too large to be represented.";
+                    self.error("votes", &path("votes"), message);
+                }
+                Some(Value::Number(v)) => {
+                    if *v > EXACT_INTEGERS {
+                        let message = "Votes above 2^53 can't be represented exactly.";
+                        self.warning("precision", &path("votes"), message);
+                    }
+                    votes.push(*v);
+                }
+                Some(_) => self.wrong_type(&path("votes"), "a number"),
+            }
+        }
+        let valid = self.diagnostics[errors..]
+            .iter()
+            .all(|d| d.severity != Severity::Error);
+        if valid && votes.iter().all(|v| *v == 0.0) {
+            self.error("votes", "parties", "At least one party must have votes.");
+            return None;
+        }
+        match valid {
+            true => Some(votes),
+            false => None,
+        }
+    }
+
+    fn seat_count(&mut self, seat_count: Option<&Value>) -> Option<usize> {
+        match seat_count {
+            None => {
+                self.missing("seatCount");
+                None
+            }
+            Some(Value::Number(s)) if *s >= 1.0 && s.fract() == 0.0 && *s <= EXACT_INTEGERS => {
+                Some(*s as usize)
+            }
+            Some(Value::Number(_)) => {
+                let message = "The number of seats must be a positive integer.";
+                self.error("seat-count", "seatCount", message);
+                None
+            }
+            Some(_) => {
+                self.wrong_type("seatCount", "a number");
+                None
+            }
+        }
+    }
+
+    /// The method and constraints, or [`None`] if any of them is invalid.
+    fn rules(&mut self, root: &Value) -> Option<Rules> {
+        let errors = self.diagnostics.len();
+        let method = match root.get("method") {
+            None => Method::SainteLague,
+            Some(Value::String(name)) => match METHODS.iter().find(|m| m.name() == name) {
+                Some(method) => *method,
+                None => {
+                    let names: Vec<&str> = METHODS.iter().map(Method::name).collect();
+                    let message = format!("Unknown method, must be one of {}.", names.join(", "));
+                    self.error("method", "method", &message);
+                    Method::SainteLague
+                }
+            },
+            Some(_) => {
+                self.wrong_type("method", "a string");
+                Method::SainteLague
+            }
+        };
+        let mut rules = Rules::new(method);
+        match root.get("threshold") {
+            None => {}
+            Some(Value::Number(threshold)) => rules = rules.with(Constraint::Threshold(*threshold)),
+            Some(_) => self.wrong_type("threshold", "a number"),
+        }
+        for party in self.integers(root, "exemptions", |v| vec![v]) {
+            rules = rules.with(Constraint::Exemption(party[0]));
+        }
+        for bound in self.integers(root, "minSeats", |v| match v {
+            Value::Array(pair) if pair.len() == 2 => pair.iter().collect(),
+            _ => vec![],
+        }) {
+            rules = rules.with(Constraint::MinSeats(bound[0], bound[1]));
+        }
+        for bound in self.integers(root, "maxSeats", |v| match v {
+            Value::Array(pair) if pair.len() == 2 => pair.iter().collect(),
+            _ => vec![],
+        }) {
+            rules = rules.with(Constraint::MaxSeats(bound[0], bound[1]));
+        }
+        match self.diagnostics.len() == errors {
+            true => Some(rules),
+            false => None,
+        }
+    }
+
+    /// The entries of the array `field`, each turned into a list of values by `entry` and read as non-negative integers. Entries that aren't are reported and skipped.
+    fn integers<'v, E: Fn(&'v Value) -> Vec<&'v Value>>(
+        &mut self,
+        root: &'v Value,
+        field: &str,
+        entry: E,
+    ) -> Vec<Vec<usize>> {
+        let entries = match root.get(field) {
+            None => return vec![],
+            Some(Value::Array(entries)) => entries,
+            Some(_) => {
+                self.wrong_type(field, "an array");
+                return vec![];
+            }
+        };
+        let mut integers = vec![];
+        for (i, value) in entries.iter().enumerate() {
+            let values: Option<Vec<usize>> = entry(value)
+                .into_iter()
+                .map(|v| match v {
+                    Value::Number(n) if *n >= 0.0 && n.fract() == 0.0 && *n <= EXACT_INTEGERS => {
+                        Some(*n as usize)
+                    }
+                    _ => None,
+                })
+                .collect();
+            match values {
+                Some(values) if !values.is_empty() => integers.push(values),
+                _ => {
+                    let expected = match field {
+                        "exemptions" => "a party index",
+                        _ => "a pair of a party index and a number of seats",
+                    };
+                    self.wrong_type(&format!("{}[{}]", field, i), expected);
+                }
+            }
+        }
+        integers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_json, validate, write_scenario, Severity};
+
+    fn codes(scenario: &str) -> Vec<(&'static str, String)> {
+        validate(scenario)
+            .into_iter()
+            .map(|d| (d.code, d.path))
+            .collect()
+    }
+
+    fn code(code: &'static str, path: &str) -> (&'static str, String) {
+        (code, path.to_string())
+    }
+
+    #[test]
+    fn valid_scenarios() {
+        let scenario = r#"{
+            "parties": [{"name": "A", "votes": 362}, {"name": "B", "votes": 318}, {"name": "C", "votes": 126}],
+            "seatCount": 10,
+            "method": "dhondt",
+            "threshold": 0.05,
+            "exemptions": [2],
+            "minSeats": [[2, 1]],
+            "maxSeats": [[0, 5]]
+        }"#;
+        assert_eq!(validate(scenario), vec![]);
+        assert_eq!(
+            to_json(&validate(scenario)),
+            "{\n  \"valid\": true,\n  \"diagnostics\": []\n}\n"
+        );
+
+        let written = write_scenario(&[362.0, 0.5, 1e20], 10);
+        assert_eq!(
+            written,
+            r#"{"parties": [{"name": "1", "votes": 362}, {"name": "2", "votes": 0.5}, {"name": "3", "votes": 100000000000000000000}], "seatCount": 10}"#
+        );
+        assert_eq!(codes(&written), vec![code("precision", "parties[2].votes")]);
+    }
+
+    #[test]
+    fn structural_errors() {
+        assert_eq!(codes("{"), vec![code("syntax", "")]);
+        assert_eq!(codes("[]"), vec![code("type", "")]);
+        assert_eq!(
+            codes(r#"{"parties": [1, {"votes": "1"}]}"#),
+            vec![
+                code("type", "parties[0]"),
+                code("missing", "parties[1].name"),
+                code("type", "parties[1].votes"),
+                code("missing", "seatCount"),
+            ]
+        );
+        assert_eq!(
+            codes(
+                r#"{"parties": [{"name": "A", "votes": 1}], "seatCount": 1.5, "method": "x", "exemptions": [-1], "minSeats": [[0]]}"#
+            ),
+            vec![
+                code("seat-count", "seatCount"),
+                code("method", "method"),
+                code("type", "exemptions[0]"),
+                code("type", "minSeats[0]"),
+            ]
+        );
+    }
+
+    #[test]
+    fn numeric_errors() {
+        let parties = |votes: &str| {
+            format!(
+                r#"{{"parties": [{{"name": "A", "votes": {}}}, {{"name": "B", "votes": 0}}], "seatCount": 2, "minSeats": [[1, 1]]}}"#,
+                votes
+            )
+        };
+        assert_eq!(codes(&parties("0")), vec![code("votes", "parties")]);
+        assert_eq!(
+            codes(&parties("1e400")),
+            vec![code("votes", "parties[0].votes")]
+        );
+        assert_eq!(
+            codes(&parties("1e17")),
+            vec![code("precision", "parties[0].votes")]
+        );
+        let infeasible =
+            r#"{"parties": [{"name": "A", "votes": 1}], "seatCount": 2, "maxSeats": [[0, 1]]}"#;
+        assert_eq!(codes(infeasible), vec![code("infeasible", "")]);
+        let unknown =
+            r#"{"parties": [{"name": "A", "votes": 1}], "seatCount": 2, "exemptions": [3]}"#;
+        let diagnostics = validate(unknown);
+        assert_eq!(diagnostics[0].code, "constraint");
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert!(to_json(&diagnostics).starts_with("{\n  \"valid\": false,\n  \"diagnostics\": [\n    {\"severity\": \"error\", \"code\": \"constraint\", \"path\": \"\", \"message\": "));
+    }
+}
@@ -0,0 +1,391 @@
+//! Language-agnostic test vectors for validating other implementations against this crate.
+//!
+//! A [`TestVector`] pairs the input of a distribution with the result this crate calculates for it. [`standard`] covers the cases implementations most often get wrong: ties for the last seat, parties failing a threshold, parties without votes, invalid input and vote counts too large for 32-bit integers. [`to_json`] writes them as a JSON document that ports to other languages, such as the JavaScript `sainte-lague` package, can check themselves against:
+//!
+//! ```json
+//! {
+//!   "method": "sainte-lague",
+//!   "version": "0.1.2",
+//!   "vectors": [
+//!     {"name": "tie", "votes": [3, 3, 1], "seatCount": 8, "threshold": null, "seats": null, "error": "Tied"}
+//!   ]
+//! }
+//! ```
+//!
+//! Votes and thresholds are written in the format of [snapshots](super::snapshot), which parses back to the exact same floats. `threshold` is a share between `0` and `1`, applied to the sum of all votes as by [`Constraint::Threshold`]. Ties are never resolved by drawing lots, so every vector has exactly one expected result: either `seats` or the name of the `error`, the other one being `null`. Errors of the distribution itself are named like the variants of [`DistributionError`](crate::DistributionError), errors of the threshold like the variants of [`ConstraintError`].
+//!
+//! ```
+//! use sainte_lague::io::vectors::{standard, to_json, TestVector};
+//!
+//! let vectors = standard();
+//! assert!(vectors.iter().any(|v| v.name == "tie for the last seat"));
+//!
+//! let json = to_json(&[TestVector::new("example", vec![362.0, 318.0, 126.0], 10, None)]);
+//! assert!(json.contains(r#"{"name": "example", "votes": [362, 318, 126], "seatCount": 10, "threshold": null, "seats": [4, 4, 2], "error": null}"#));
+//! ```
+//!
+//! [`random`] generates further vectors, and [`compare`] checks the results of another implementation for them, given as one line per vector: the seats of each party separated by commas, or `error` followed by an optional message. The `divergence` binary, built with the feature of the same name, runs both against each other.
+//!
+//! ```
+//! use sainte_lague::io::vectors::{compare, TestVector};
+//!
+//! let vectors = [TestVector::new("a", vec![3.0, 3.0, 1.0], 8, None), TestVector::new("b", vec![2.0, 1.0], 3, None)];
+//! let divergences = compare(&vectors, "error: tie\n3,0\n");
+//! assert_eq!(divergences.len(), 1);
+//! assert_eq!(divergences[0].to_string(), "b: votes 2, 1 with 3 seats: expected 2, 1, got 3,0");
+//! ```
+
+use super::snapshot::float;
+use crate::constraints::{Constraint, ConstraintError, Rules};
+use crate::Method;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fmt;
+
+/// The input of a distribution and the result this crate calculates for it, see the [module documentation](self).
+#[derive(Clone, PartialEq, Debug)]
+pub struct TestVector {
+    /// A short description of the case, unique among the vectors of a document.
+    pub name: String,
+    /// The votes of each party.
+    pub votes: Vec<f64>,
+    /// The number of seats.
+    pub seat_count: usize,
+    /// The threshold as a share of all votes, if any.
+    pub threshold: Option<f64>,
+    /// The seats of each party, or why the distribution failed.
+    pub expected: Result<Vec<usize>, ConstraintError>,
+}
+
+impl TestVector {
+    /// Create a test vector, calculating the expected result with the Sainte-Laguë method without drawing lots.
+    pub fn new(
+        name: &str,
+        votes: Vec<f64>,
+        seat_count: usize,
+        threshold: Option<f64>,
+    ) -> TestVector {
+        let rules = Rules::new(Method::SainteLague);
+        let rules = match threshold {
+            Some(t) => rules.with(Constraint::Threshold(t)),
+            None => rules,
+        };
+        let expected = rules.distribute(&votes, &seat_count, &false);
+        TestVector {
+            name: name.to_string(),
+            votes,
+            seat_count,
+            threshold,
+            expected,
+        }
+    }
+
+    /// The name of the error, or [`None`] if the distribution succeeded.
+    fn error(&self) -> Option<String> {
+        match &self.expected {
+            Ok(_) => None,
+            Err(ConstraintError::Distribution(e)) => Some(format!("{:?}", e)),
+            Err(e) => Some(format!("{:?}", e)),
+        }
+    }
+}
+
+/// The standard set of test vectors, see the [module documentation](self). New vectors may be added in any release, but existing ones are only changed if their result was wrong.
+pub fn standard() -> Vec<TestVector> {
+    let mut vectors = vec![
+        TestVector::new("simple", vec![362.0, 318.0, 126.0], 10, None),
+        TestVector::new("single party", vec![42.0], 5, None),
+        TestVector::new("single seat", vec![3.0, 5.0, 4.0], 1, None),
+        TestVector::new("more seats than votes", vec![1.0, 2.0], 10, None),
+        TestVector::new("tie for the last seat", vec![3.0, 3.0, 1.0], 8, None),
+        TestVector::new("tie for the first seat", vec![1.0, 1.0], 1, None),
+        TestVector::new("tie resolved by seat count", vec![3.0, 3.0, 1.0], 7, None),
+        TestVector::new("tie between quotients", vec![3.0, 1.0], 2, None),
+        TestVector::new("party without votes", vec![5.0, 0.0, 5.0], 4, None),
+        TestVector::new("only one party with votes", vec![0.0, 7.0, 0.0], 3, None),
+        TestVector::new("no parties", vec![], 5, None),
+        TestVector::new("zero votes", vec![0.0, 0.0], 5, None),
+        TestVector::new("zero seats", vec![1.0, 2.0], 0, None),
+        TestVector::new("negative votes", vec![3.0, -1.0], 5, None),
+        TestVector::new("fractional votes", vec![0.45, 0.35, 0.2], 12, None),
+        TestVector::new("tiny votes", vec![1e-9, 3e-9, 2e-9], 6, None),
+        TestVector::new("huge votes", vec![4.1e15, 2.9e15, 3e14], 598, None),
+        TestVector::new(
+            "votes beyond 32-bit integers",
+            vec![4_294_967_296.0, 4_294_967_295.0, 17.0],
+            3,
+            None,
+        ),
+        TestVector::new(
+            "largest exact integers",
+            vec![9_007_199_254_740_992.0, 3_002_399_751_580_330.0],
+            4,
+            None,
+        ),
+        TestVector::new(
+            "six large parties",
+            vec![
+                12_447_656.0,
+                9_539_381.0,
+                5_878_115.0,
+                4_999_449.0,
+                4_297_270.0,
+                4_158_400.0,
+            ],
+            598,
+            None,
+        ),
+        TestVector::new("threshold", vec![600.0, 355.0, 45.0], 10, Some(0.05)),
+        TestVector::new("exactly at the threshold", vec![95.0, 5.0], 20, Some(0.05)),
+        TestVector::new(
+            "nobody passes the threshold",
+            vec![1.0, 1.0, 1.0],
+            3,
+            Some(0.5),
+        ),
+        TestVector::new("invalid threshold", vec![1.0, 2.0], 3, Some(1.5)),
+        TestVector::new(
+            "tie after threshold",
+            vec![40.0, 40.0, 15.0, 5.0],
+            4,
+            Some(0.1),
+        ),
+    ];
+    // every seat count of a parliament, including all ties on the way
+    let votes = [5.0, 3.0, 2.0, 1.0];
+    for seat_count in 1..=20 {
+        let name = format!("seat count {}", seat_count);
+        vectors.push(TestVector::new(&name, votes.to_vec(), seat_count, None));
+    }
+    vectors
+}
+
+/// `count` pseudo-random test vectors without thresholds, generated from `seed`. Small vote counts make ties likely, and some vectors have no votes or seats at all. The vectors for a seed only change with the version of the `rand` crate.
+pub fn random(count: usize, seed: u64) -> Vec<TestVector> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..count)
+        .map(|i| {
+            let party_count = rng.gen_range(1..10);
+            let max_votes = match rng.gen_range(0..3) {
+                0 => 10,
+                1 => 10_000,
+                _ => 100_000_000,
+            };
+            let votes = (0..party_count)
+                .map(|_| rng.gen_range(0..=max_votes) as f64)
+                .collect();
+            let seat_count = rng.gen_range(0..50);
+            TestVector::new(&format!("random {}", i), votes, seat_count, None)
+        })
+        .collect()
+}
+
+/// A test vector for which another implementation returned a different result, see [`compare`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct Divergence {
+    /// The test vector.
+    pub vector: TestVector,
+    /// The line returned by the other implementation, or [`None`] if it returned no line for this vector.
+    pub actual: Option<String>,
+}
+
+/// E.g. `random 3: votes 2, 1 with 3 seats: expected 2, 1, got 3,0`.
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let votes: Vec<String> = self.vector.votes.iter().map(|v| float(*v)).collect();
+        let expected = match &self.vector.expected {
+            Ok(seats) => {
+                let seats: Vec<String> = seats.iter().map(|s| s.to_string()).collect();
+                seats.join(", ")
+            }
+            Err(_) => format!("error {}", self.vector.error().unwrap_or_default()),
+        };
+        write!(
+            f,
+            "{}: votes {} with {} seats: expected {}, got {}",
+            self.vector.name,
+            votes.join(", "),
+            self.vector.seat_count,
+            expected,
+            self.actual.as_deref().unwrap_or("nothing")
+        )
+    }
+}
+
+/// Compare the `results` of another implementation with the expected results of the `vectors`, see the [module documentation](self). The results contain one line per vector, in the same order. Errors agree if both implementations fail, regardless of the message, since implementations describe errors differently. Missing and malformed lines are divergences.
+pub fn compare(vectors: &[TestVector], results: &str) -> Vec<Divergence> {
+    let mut lines = results.lines().map(str::trim);
+    let mut divergences = vec![];
+    for vector in vectors {
+        let actual = lines.next();
+        let agrees = match (actual, &vector.expected) {
+            (Some(line), Err(_)) => line.starts_with("error"),
+            (Some(line), Ok(seats)) => {
+                let parsed: Option<Vec<usize>> =
+                    line.split(',').map(|s| s.trim().parse().ok()).collect();
+                parsed.as_ref() == Some(seats)
+            }
+            (None, _) => false,
+        };
+        if !agrees {
+            divergences.push(Divergence {
+                vector: vector.clone(),
+                actual: actual.map(str::to_string),
+            });
+        }
+    }
+    divergences
+}
+
+/// Write the test vectors as a JSON document, see the [module documentation](self).
+pub fn to_json(vectors: &[TestVector]) -> String {
+    let mut json = format!(
+        "{{\n  \"method\": \"{}\",\n  \"version\": \"{}\",\n  \"vectors\": [\n",
+        Method::SainteLague.name(),
+        env!("CARGO_PKG_VERSION")
+    );
+    for (i, vector) in vectors.iter().enumerate() {
+        let votes: Vec<String> = vector.votes.iter().map(|v| float(*v)).collect();
+        let seats = match &vector.expected {
+            Ok(seats) => {
+                let seats: Vec<String> = seats.iter().map(|s| s.to_string()).collect();
+                format!("[{}]", seats.join(", "))
+            }
+            Err(_) => "null".to_string(),
+        };
+        json.push_str(&format!(
+            "    {{\"name\": {}, \"votes\": [{}], \"seatCount\": {}, \"threshold\": {}, \"seats\": {}, \"error\": {}}}{}\n",
+            string(&vector.name),
+            votes.join(", "),
+            vector.seat_count,
+            vector.threshold.map_or("null".to_string(), float),
+            seats,
+            vector.error().map_or("null".to_string(), |e| string(&e)),
+            if i + 1 < vectors.len() { "," } else { "" }
+        ));
+    }
+    json.push_str("  ]\n}\n");
+    json
+}
+
+/// A JSON string literal.
+pub(crate) fn string(value: &str) -> String {
+    let mut literal = "\"".to_string();
+    for c in value.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            c if (c as u32) < 0x20 => literal.push_str(&format!("\\u{:04x}", c as u32)),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, random, standard, string, to_json, TestVector};
+    use crate::constraints::ConstraintError;
+    use crate::DistributionError;
+    use std::collections::HashSet;
+
+    #[test]
+    fn coverage() {
+        let vectors = standard();
+        let names: HashSet<&str> = vectors.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names.len(), vectors.len());
+        let errors: HashSet<String> = vectors.iter().filter_map(TestVector::error).collect();
+        let expected: HashSet<String> = [
+            "Tied",
+            "NoVotes",
+            "InvalidSeatCount",
+            "NegativeVotes",
+            "InvalidThreshold",
+            "Infeasible",
+        ]
+        .iter()
+        .map(|e| e.to_string())
+        .collect();
+        assert_eq!(errors, expected);
+
+        let at_threshold = vectors
+            .iter()
+            .find(|v| v.name == "exactly at the threshold");
+        assert_eq!(at_threshold.unwrap().expected, Ok(vec![19, 1]));
+        let sweep = vectors.iter().filter(|v| v.name.starts_with("seat count"));
+        assert!(sweep.clone().any(|v| v.expected.is_err()));
+        assert!(sweep.clone().any(|v| v.expected.is_ok()));
+    }
+
+    #[test]
+    fn json() {
+        let vectors = [
+            TestVector::new("threshold", vec![600.0, 355.0, 45.0], 10, Some(0.05)),
+            TestVector {
+                name: "tie \"quoted\"".to_string(),
+                votes: vec![1.0, 1.0],
+                seat_count: 1,
+                threshold: None,
+                expected: Err(ConstraintError::Distribution(DistributionError::Tied)),
+            },
+        ];
+        let json = to_json(&vectors);
+        let lines: Vec<&str> = json.lines().collect();
+        assert_eq!(lines[1], "  \"method\": \"sainte-lague\",");
+        assert_eq!(
+            lines[4],
+            r#"    {"name": "threshold", "votes": [600, 355, 45], "seatCount": 10, "threshold": 0.05, "seats": [6, 4, 0], "error": null},"#
+        );
+        assert_eq!(
+            lines[5],
+            r#"    {"name": "tie \"quoted\"", "votes": [1, 1], "seatCount": 1, "threshold": null, "seats": null, "error": "Tied"}"#
+        );
+        assert_eq!(&lines[6..], &["  ]", "}"]);
+        assert_eq!(to_json(&[]).lines().nth(4), Some("  ]"));
+        assert_eq!(string("a\\b\n\t"), r#""a\\b\n\u0009""#);
+    }
+
+    #[test]
+    fn divergences() {
+        let vectors = random(200, 485);
+        assert_eq!(vectors, random(200, 485));
+        assert!(vectors.iter().any(|v| v.expected.is_ok()));
+        assert!(vectors.iter().any(|v| v.expected.is_err()));
+
+        // this crate agrees with itself
+        let results: Vec<String> = vectors
+            .iter()
+            .map(|v| match &v.expected {
+                Ok(seats) => {
+                    let seats: Vec<String> = seats.iter().map(|s| s.to_string()).collect();
+                    seats.join(",")
+                }
+                Err(e) => format!("error: {:?}", e),
+            })
+            .collect();
+        assert_eq!(compare(&vectors, &results.join("\n")), vec![]);
+
+        let vectors = [
+            TestVector::new("tie", vec![1.0, 1.0], 1, None),
+            TestVector::new("ok", vec![1.0, 1.0], 2, None),
+            TestVector::new("missing", vec![1.0], 1, None),
+        ];
+        let divergences = compare(&vectors, "1,0\n1, 1 , x\n");
+        let actual: Vec<Option<String>> = divergences.iter().map(|d| d.actual.clone()).collect();
+        assert_eq!(
+            actual,
+            vec![Some("1,0".to_string()), Some("1, 1 , x".to_string()), None]
+        );
+        assert_eq!(
+            divergences[0].to_string(),
+            "tie: votes 1, 1 with 1 seats: expected error Tied, got 1,0"
+        );
+        assert_eq!(
+            divergences[2].to_string(),
+            "missing: votes 1 with 1 seats: expected 1, got nothing"
+        );
+        assert_eq!(compare(&vectors[1..2], " 1 , 1 "), vec![]);
+    }
+}
@@ -0,0 +1,460 @@
+//! A compact, versioned binary encoding for [`Scenario`]s and [`Outcome`]s.
+//!
+//! Every encoded value starts with a short header: the magic bytes `SL`, the [`FORMAT_VERSION`] it was written with and a tag describing the kind of value. Integers are stored as LEB128 varints, floats as their little-endian IEEE 754 bit pattern, so values round-trip exactly. The version is raised whenever the layout or the set of tags changes, e.g. for a new [`Method`], so older decoders reject such input by its version instead of failing in the middle of a value. Decoders of later crate versions keep accepting every format version that was ever released.
+//!
+//! ```
+//! use sainte_lague::io::wire;
+//! use sainte_lague::Scenario;
+//!
+//! let scenario = Scenario::new(vec![362.0, 318.0, 126.0, 62.0, 53.0], 101);
+//! let bytes = wire::encode_scenario(&scenario);
+//! assert_eq!(wire::decode_scenario(&bytes), Ok(scenario));
+//! ```
+
+use crate::locale::{text, text_with, Locale, Localize, Text};
+#[cfg(feature = "experimental")]
+use crate::simulation::{Checkpoint, PollAverage};
+use crate::{Draw, Method, Outcome, Scenario};
+use std::error;
+use std::fmt;
+
+/// The format version written by this version of the crate.
+pub const FORMAT_VERSION: u8 = 1;
+
+const MAGIC: &[u8; 2] = b"SL";
+const KIND_SCENARIO: u8 = 1;
+const KIND_OUTCOME: u8 = 2;
+#[cfg(feature = "experimental")]
+const KIND_CHECKPOINT: u8 = 3;
+
+/// Possible error cases when decoding.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum DecodeError {
+    /// The input doesn't start with the expected magic bytes.
+    InvalidMagic,
+
+    /// The input was written with a format version this crate doesn't know.
+    UnsupportedVersion(u8),
+
+    /// The input contains a different kind of value than requested, e.g. an outcome instead of a scenario.
+    UnexpectedKind,
+
+    /// The input ended in the middle of a value.
+    UnexpectedEnd,
+
+    /// The input contains a malformed or out of range value.
+    InvalidValue,
+
+    /// The input contains additional bytes after the decoded value.
+    TrailingBytes,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.localize(&Locale::English))
+    }
+}
+
+impl Localize for DecodeError {
+    fn localize(&self, locale: &Locale) -> String {
+        let key = match self {
+            DecodeError::InvalidMagic => Text::InvalidMagic,
+            DecodeError::UnsupportedVersion(v) => {
+                return text_with(locale, Text::UnsupportedVersion, v)
+            }
+            DecodeError::UnexpectedKind => Text::UnexpectedKind,
+            DecodeError::UnexpectedEnd => Text::UnexpectedEnd,
+            DecodeError::InvalidValue => Text::InvalidValue,
+            DecodeError::TrailingBytes => Text::TrailingBytes,
+        };
+        text(locale, key).to_string()
+    }
+}
+
+impl error::Error for DecodeError {}
+
+/// Encode a scenario.
+pub fn encode_scenario(scenario: &Scenario) -> Vec<u8> {
+    let mut writer = Writer::new(KIND_SCENARIO);
+    writer.floats(&scenario.votes);
+    writer.usize(scenario.seat_count);
+    writer.bool(scenario.draw_on_tie);
+    writer.method(&scenario.method);
+    writer.finish()
+}
+
+/// Decode a scenario previously encoded with [`encode_scenario`].
+pub fn decode_scenario(bytes: &[u8]) -> Result<Scenario, DecodeError> {
+    let mut reader = Reader::new(bytes, KIND_SCENARIO)?;
+    let scenario = Scenario {
+        votes: reader.floats()?,
+        seat_count: reader.usize()?,
+        draw_on_tie: reader.bool()?,
+        method: reader.method()?,
+    };
+    reader.finish()?;
+    Ok(scenario)
+}
+
+/// Encode an outcome.
+pub fn encode_outcome(outcome: &Outcome) -> Vec<u8> {
+    let mut writer = Writer::new(KIND_OUTCOME);
+    writer.usizes(outcome.seats());
+    match outcome.draw() {
+        None => writer.bool(false),
+        Some(draw) => {
+            writer.bool(true);
+            writer.usizes(draw.candidates());
+            writer.usize(draw.seats());
+        }
+    }
+    writer.finish()
+}
+
+/// Decode an outcome previously encoded with [`encode_outcome`].
+pub fn decode_outcome(bytes: &[u8]) -> Result<Outcome, DecodeError> {
+    let mut reader = Reader::new(bytes, KIND_OUTCOME)?;
+    let seats = reader.usizes()?;
+    let draw = if reader.bool()? {
+        let candidates = reader.usizes()?;
+        Some(Draw::new(candidates, reader.usize()?))
+    } else {
+        None
+    };
+    reader.finish()?;
+    Ok(Outcome::new(seats, draw))
+}
+
+/// Encode the state of an interrupted simulation run.
+#[cfg(feature = "experimental")]
+pub fn encode_checkpoint(checkpoint: &Checkpoint) -> Vec<u8> {
+    let mut writer = Writer::new(KIND_CHECKPOINT);
+    writer.floats(&checkpoint.average.shares);
+    writer.float(checkpoint.average.sample_size);
+    writer.usize(checkpoint.seat_count);
+    writer.usize(checkpoint.trials);
+    writer.u64(checkpoint.seed);
+    writer.usize(checkpoint.done);
+    writer.usize(checkpoint.skipped);
+    writer.usize(checkpoint.samples.len());
+    for sample in checkpoint.samples.iter() {
+        writer.usizes(sample);
+    }
+    writer.finish()
+}
+
+/// Decode the state of a simulation run previously encoded with [`encode_checkpoint`].
+#[cfg(feature = "experimental")]
+pub fn decode_checkpoint(bytes: &[u8]) -> Result<Checkpoint, DecodeError> {
+    let mut reader = Reader::new(bytes, KIND_CHECKPOINT)?;
+    let shares = reader.floats()?;
+    let sample_size = reader.float()?;
+    let mut checkpoint = Checkpoint {
+        average: PollAverage {
+            shares,
+            sample_size,
+        },
+        seat_count: reader.usize()?,
+        trials: reader.usize()?,
+        seed: reader.u64()?,
+        done: reader.usize()?,
+        skipped: reader.usize()?,
+        samples: vec![],
+    };
+    let sample_count = reader.len(1)?;
+    for _ in 0..sample_count {
+        checkpoint.samples.push(reader.usizes()?);
+    }
+    reader.finish()?;
+    let consistent = checkpoint.done <= checkpoint.trials
+        && checkpoint.samples.len() == checkpoint.average.shares.len()
+        && checkpoint
+            .samples
+            .iter()
+            .all(|s| s.len() + checkpoint.skipped == checkpoint.done);
+    if !consistent {
+        return Err(DecodeError::InvalidValue);
+    }
+    Ok(checkpoint)
+}
+
+struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    fn new(kind: u8) -> Writer {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(FORMAT_VERSION);
+        bytes.push(kind);
+        Writer { bytes }
+    }
+
+    fn u64(&mut self, mut value: u64) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                self.bytes.push(byte);
+                return;
+            }
+            self.bytes.push(byte | 0x80);
+        }
+    }
+
+    fn usize(&mut self, value: usize) {
+        self.u64(value as u64)
+    }
+
+    fn usizes(&mut self, values: &[usize]) {
+        self.usize(values.len());
+        for v in values {
+            self.usize(*v);
+        }
+    }
+
+    fn float(&mut self, value: f64) {
+        self.bytes.extend_from_slice(&value.to_bits().to_le_bytes());
+    }
+
+    fn floats(&mut self, values: &[f64]) {
+        self.usize(values.len());
+        for v in values {
+            self.float(*v);
+        }
+    }
+
+    fn bool(&mut self, value: bool) {
+        self.bytes.push(value as u8)
+    }
+
+    fn method(&mut self, method: &Method) {
+        match method {
+            Method::SainteLague => self.bytes.push(0),
+            Method::DHondt => self.bytes.push(1),
+            Method::HuntingtonHill => self.bytes.push(2),
+            Method::Adams => self.bytes.push(3),
+            Method::Dean => self.bytes.push(4),
+            Method::Danish => self.bytes.push(5),
+            Method::Imperiali => self.bytes.push(6),
+        }
+    }
+
+    fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8], kind: u8) -> Result<Reader<'a>, DecodeError> {
+        if bytes.len() < MAGIC.len() || &bytes[..MAGIC.len()] != MAGIC {
+            return Err(DecodeError::InvalidMagic);
+        }
+        let mut reader = Reader {
+            bytes: &bytes[MAGIC.len()..],
+        };
+        let version = reader.byte()?;
+        if version != FORMAT_VERSION {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+        if reader.byte()? != kind {
+            return Err(DecodeError::UnexpectedKind);
+        }
+        Ok(reader)
+    }
+
+    fn byte(&mut self) -> Result<u8, DecodeError> {
+        let (first, rest) = self.bytes.split_first().ok_or(DecodeError::UnexpectedEnd)?;
+        self.bytes = rest;
+        Ok(*first)
+    }
+
+    fn u64(&mut self) -> Result<u64, DecodeError> {
+        let mut value: u64 = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            let bits = u64::from(byte & 0x7f);
+            if shift == 63 && bits > 1 {
+                return Err(DecodeError::InvalidValue);
+            }
+            value |= bits << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(DecodeError::InvalidValue)
+    }
+
+    fn usize(&mut self) -> Result<usize, DecodeError> {
+        let value = self.u64()?;
+        if value > usize::MAX as u64 {
+            return Err(DecodeError::InvalidValue);
+        }
+        Ok(value as usize)
+    }
+
+    fn len(&mut self, item_size: usize) -> Result<usize, DecodeError> {
+        let len = self.usize()?;
+        // every item takes up at least `item_size` bytes, so this rejects absurd lengths
+        // before allocating memory for them
+        if len > self.bytes.len() / item_size {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+        Ok(len)
+    }
+
+    fn usizes(&mut self) -> Result<Vec<usize>, DecodeError> {
+        let len = self.len(1)?;
+        (0..len).map(|_| self.usize()).collect()
+    }
+
+    fn float(&mut self) -> Result<f64, DecodeError> {
+        if self.bytes.len() < 8 {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+        let (float, rest) = self.bytes.split_at(8);
+        self.bytes = rest;
+        let mut bits = [0; 8];
+        bits.copy_from_slice(float);
+        Ok(f64::from_bits(u64::from_le_bytes(bits)))
+    }
+
+    fn floats(&mut self) -> Result<Vec<f64>, DecodeError> {
+        let len = self.len(8)?;
+        (0..len).map(|_| self.float()).collect()
+    }
+
+    fn bool(&mut self) -> Result<bool, DecodeError> {
+        match self.byte()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(DecodeError::InvalidValue),
+        }
+    }
+
+    fn method(&mut self) -> Result<Method, DecodeError> {
+        match self.byte()? {
+            0 => Ok(Method::SainteLague),
+            1 => Ok(Method::DHondt),
+            2 => Ok(Method::HuntingtonHill),
+            3 => Ok(Method::Adams),
+            4 => Ok(Method::Dean),
+            5 => Ok(Method::Danish),
+            6 => Ok(Method::Imperiali),
+            _ => Err(DecodeError::InvalidValue),
+        }
+    }
+
+    fn finish(self) -> Result<(), DecodeError> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(DecodeError::TrailingBytes)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_outcome, decode_scenario, encode_outcome, encode_scenario, DecodeError};
+    use crate::Scenario;
+
+    #[test]
+    fn scenario_round_trip() {
+        let mut scenario = Scenario::new(vec![0.1, 1e300, 0.0, 5e-324], 300);
+        scenario.draw_on_tie = true;
+
+        let bytes = encode_scenario(&scenario);
+        assert_eq!(decode_scenario(&bytes), Ok(scenario));
+    }
+
+    #[test]
+    fn outcome_round_trip() {
+        let mut scenario = Scenario::new(vec![3.0, 3.0, 1.0], 8);
+        let outcome = Scenario::new(vec![41.5, 25.7, 8.6, 8.4], 631)
+            .evaluate()
+            .unwrap();
+        assert_eq!(decode_outcome(&encode_outcome(&outcome)), Ok(outcome));
+
+        scenario.draw_on_tie = true;
+        let outcome_with_draw = scenario.evaluate().unwrap();
+        assert_eq!(
+            decode_outcome(&encode_outcome(&outcome_with_draw)),
+            Ok(outcome_with_draw)
+        );
+    }
+
+    #[cfg(feature = "experimental")]
+    #[test]
+    fn checkpoint_round_trip() {
+        use super::{decode_checkpoint, encode_checkpoint};
+        use crate::simulation::{Checkpoint, PollAverage};
+
+        let average = PollAverage {
+            shares: vec![0.6, 0.4],
+            sample_size: 1_000.0,
+        };
+        let checkpoint = Checkpoint::new(average, 10, 100, 7);
+        let bytes = encode_checkpoint(&checkpoint);
+        assert_eq!(decode_checkpoint(&bytes), Ok(checkpoint));
+        // header, two shares and half of the sample size
+        assert_eq!(
+            decode_checkpoint(&bytes[..4 + 1 + 16 + 4]),
+            Err(DecodeError::UnexpectedEnd)
+        );
+    }
+
+    #[test]
+    fn stable_layout() {
+        let scenario = Scenario::new(vec![1.0], 130);
+        assert_eq!(
+            encode_scenario(&scenario),
+            vec![b'S', b'L', 1, 1, 1, 0, 0, 0, 0, 0, 0, 0xf0, 0x3f, 0x82, 0x01, 0, 0]
+        );
+    }
+
+    #[test]
+    fn invalid_input() {
+        let bytes = encode_scenario(&Scenario::new(vec![1.0, 2.0], 3));
+
+        assert_eq!(decode_scenario(&[]), Err(DecodeError::InvalidMagic));
+        assert_eq!(
+            decode_scenario(b"XY\x01\x01"),
+            Err(DecodeError::InvalidMagic)
+        );
+        assert_eq!(
+            decode_scenario(b"SL\x07\x01"),
+            Err(DecodeError::UnsupportedVersion(7))
+        );
+        assert_eq!(
+            decode_scenario(b"SL\x00\x01"),
+            Err(DecodeError::UnsupportedVersion(0))
+        );
+        assert_eq!(decode_outcome(&bytes), Err(DecodeError::UnexpectedKind));
+        assert_eq!(
+            decode_scenario(&bytes[..bytes.len() - 1]),
+            Err(DecodeError::UnexpectedEnd)
+        );
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(decode_scenario(&trailing), Err(DecodeError::TrailingBytes));
+
+        let mut invalid_method = bytes;
+        *invalid_method.last_mut().unwrap() = 255;
+        assert_eq!(
+            decode_scenario(&invalid_method),
+            Err(DecodeError::InvalidValue)
+        );
+
+        // a length prefix claiming far more floats than there are bytes
+        assert_eq!(
+            decode_scenario(b"SL\x01\x01\xff\xff\xff\xff\x0f"),
+            Err(DecodeError::UnexpectedEnd)
+        );
+    }
+}
@@ -9,11 +9,74 @@
 //! A rust implementation of the **[Sainte-Laguë](https://en.wikipedia.org/wiki/Webster/Sainte-Lagu%C3%AB_method)** (also known as **Webster** or **Schepers**) method. Parliament seat allocation algorithm used in multiple countries such as Germany, Latvia, New Zealand etc…
 //!
 //! *Attention: Since some countries (like Latvia or Norway) use a modification of the algorithm instead of this vanilla version, you should check your country's electoral legislature. Furthermore, I don't take any responsibility for the accuracy of the calculated numbers, even though I'm pretty confident with my implementation.*
+//!
+//! # Organization
+//!
+//! [`distribute`] and the types describing a single distribution ([`Scenario`], [`Outcome`], [`DistributionError`]) live at the root of the crate. Everything else is grouped by topic:
+//!
+//! - [`methods`]: the allocation methods, including [`Stationary`](methods::Stationary) divisor methods and [`LargestRemainder`](methods::LargestRemainder) methods, and the [`Allocator`] trait, with [`sparse`] votes for universes of mostly empty parties.
+//! - [`constraints`], [`election`] and [`party`]: electoral rules, ballots and named parties on top of the methods, with [`cumulative`] voting for ballots with several votes and [`incumbency`] protection for reshuffles.
+//! - [`grid`]: the same votes under every combination of thresholds and seat counts.
+//! - [`parallel`] and [`plurality`]: electoral systems combining district and list seats, and [`bonus`] for majority bonuses.
+//! - [`groups`]: compositions of supranational bodies aggregated from national elections.
+//! - [`system`]: declarative descriptions of whole electoral systems and their interpreter, with [`presets`] for the electoral rules of specific parliaments.
+//! - [`fairness`], [`metrics`], [`paradox`] and [`targeting`]: analysis of distributions, [`alerts`] and explanations when it changes, [`counterfactual`] results for mergers and splits of parties, and [`disqualification`]s of parties after the allocation.
+//! - [`certificate`]: certificates proving single distributions correct, and their verifier.
+//! - [`io`]: serialization, fingerprints, snapshots and reports.
+//! - [`progress`]: progress reporting and cancellation of long-running computations.
+//! - [`prelude`]: the most commonly used items, for glob imports.
+//!
+//! Items behind the `experimental` feature (magnitude optimization, simulations, differential privacy and synthetic elections) may change in any release. Everything else follows semantic versioning.
 
-use rand::seq::SliceRandom;
+use crate::locale::{Locale, Localize, Text};
 use std::error;
 use std::fmt;
 
+pub mod alerts;
+#[cfg(feature = "quickcheck")]
+pub mod arbitrary;
+pub mod batch;
+pub mod bonus;
+pub mod cache;
+pub mod certificate;
+pub mod constraints;
+pub mod counterfactual;
+pub mod cumulative;
+pub mod disqualification;
+pub mod election;
+pub mod fairness;
+pub mod grid;
+pub mod groups;
+pub mod incumbency;
+pub mod io;
+pub mod locale;
+#[cfg(feature = "experimental")]
+pub mod magnitude;
+pub mod methods;
+pub mod metrics;
+pub mod normalize;
+pub mod paradox;
+pub mod parallel;
+pub mod party;
+pub mod pipeline;
+pub mod plurality;
+pub mod prelude;
+pub mod presets;
+#[cfg(feature = "experimental")]
+pub mod privacy;
+pub mod progress;
+mod scenario;
+#[cfg(feature = "experimental")]
+pub mod simulation;
+pub mod sparse;
+#[cfg(feature = "experimental")]
+pub mod synthetic;
+pub mod system;
+pub mod targeting;
+
+pub use methods::{Allocator, Method};
+pub use scenario::{Draw, Outcome, Scenario};
+
 /// Possible error cases of [`distribute`].
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub enum DistributionError {
@@ -28,43 +91,44 @@ pub enum DistributionError {
 
     /// The given list of votes contained no values or the sum of all values was zero.
     NoVotes,
+
+    /// The sum of all votes was larger than zero, but not larger than the configured [tolerance](constraints::Rules::with_tolerance), e.g. because all vote shares were rounded to tiny values. [`distribute`] itself uses no tolerance, so it never returns this error.
+    EffectivelyZeroVotes,
+
+    /// The votes were too few to fill all seats with the method, e.g. with a [largest remainder method](methods::LargestRemainder) and the Droop quota for fewer votes than seats.
+    TooFewVotes,
 }
 
 impl fmt::Display for DistributionError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            &DistributionError::Tied => write!(
-                f,
-                "Tie detected, could only be resolved by randomly awarding a seat to one party."
-            ),
-            &DistributionError::InvalidSeatCount => {
-                write!(f, "Invalid seat count, must be an integer larger than 0.")
-            }
-            &DistributionError::NegativeVotes => write!(
-                f,
-                "Invalid votes, all parties must have at least zero votes."
-            ),
-            &DistributionError::NoVotes => {
-                write!(f, "Invalid votes, one party must have at least one vote.")
-            }
-        }
+        write!(f, "{}", self.localize(&Locale::English))
     }
 }
 
-impl error::Error for DistributionError {}
-
-#[derive(Clone)]
-struct PartyQuotient {
-    party: usize,
-    quotient: f64,
+impl Localize for DistributionError {
+    fn localize(&self, locale: &Locale) -> String {
+        let key = match self {
+            DistributionError::Tied => Text::Tied,
+            DistributionError::InvalidSeatCount => Text::InvalidSeatCount,
+            DistributionError::NegativeVotes => Text::NegativeVotes,
+            DistributionError::NoVotes => Text::NoVotes,
+            DistributionError::EffectivelyZeroVotes => Text::EffectivelyZeroVotes,
+            DistributionError::TooFewVotes => Text::TooFewVotes,
+        };
+        locale::text(locale, key).to_string()
+    }
 }
 
+impl error::Error for DistributionError {}
+
 /// Calculate the **[Sainte-Laguë](https://en.wikipedia.org/wiki/Webster/Sainte-Lagu%C3%AB_method)** distribution for the given `votes` and a parliament of size `seat_count`. Note that while votes are usually restricted to integers in normal elections, this function expects floating point numbers, allowing additional use cases.
 ///
 /// The `draw_on_tie` flag should be used to indicate if the method should randomly assign seats in case of a draw or return an error instead.
 ///
 /// Check [`DistributionError`] for a list of all possible error cases.
 ///
+/// Votes may span the whole range of positive floating point numbers, e.g. `1e-9` and `1e12` in the same vector, or subnormal numbers and values close to [`f64::MAX`]. Before the quotients are calculated, votes that are extremely large or small are rescaled by a power of two, which is exact, so quotients never overflow to infinity or lose precision as subnormal numbers. Every quotient is then correctly rounded, so their relative order is the exact one, except that quotients differing by less than their rounding error are reported as a tie.
+///
 /// # Examples
 ///
 /// Note that, while you would usually use the absolute vote counts to calculate a distribution, as explained before, the method is not restricted to integer input, so you can also use relative vote shares, as in in this example:
@@ -107,10 +171,20 @@ pub fn distribute(
     seat_count: &usize,
     draw_on_tie: &bool,
 ) -> Result<Vec<usize>, DistributionError> {
-    // @todo this is certainly far from an optimal implementation, it is just a copy of
-    // https://github.com/juliuste/sainte-lague for now, which should at least work correctly
+    methods::allocate(votes, seat_count, draw_on_tie).map(Outcome::into_seats)
+}
+
+/// Validate the prerequisites shared by all methods.
+pub(crate) fn validate(votes: &[f64], seat_count: &usize) -> Result<(), DistributionError> {
+    validate_with_tolerance(votes, seat_count, 0.0)
+}
 
-    // validate prerequisites
+/// Same as [`validate`], but vote totals up to `tolerance` are treated as effectively zero.
+pub(crate) fn validate_with_tolerance(
+    votes: &[f64],
+    seat_count: &usize,
+    tolerance: f64,
+) -> Result<(), DistributionError> {
     if seat_count < &1 {
         return Err(DistributionError::InvalidSeatCount);
     }
@@ -122,71 +196,16 @@ pub fn distribute(
     if total_votes == 0.0 {
         return Err(DistributionError::NoVotes);
     }
-
-    let mut party_quotients: Vec<PartyQuotient> = votes
-        .iter()
-        .enumerate()
-        .flat_map(|(i, v)| {
-            let divisors = (1..=(seat_count.clone() as i64)).map(|d| (d as f64) - 0.5);
-            return divisors.map(move |d| PartyQuotient {
-                party: i,
-                quotient: v / d,
-            });
-        })
-        .collect();
-
-    party_quotients.sort_by(|a, b| {
-        b.quotient
-            .partial_cmp(&a.quotient)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
-
-    let last_winning_quotient = party_quotients
-        .get(seat_count.clone() - 1)
-        .map(|pq| pq.quotient)
-        .unwrap_or(0.0);
-    let mut winners: Vec<PartyQuotient> = party_quotients
-        .iter()
-        .filter(|pq| pq.quotient > last_winning_quotient)
-        .cloned()
-        .collect();
-    let mut possible_winners: Vec<PartyQuotient> = party_quotients
-        .iter()
-        .filter(|pq| pq.quotient == last_winning_quotient)
-        .cloned()
-        .collect();
-
-    // check if the "last" winner had the same quotient as the "first" loser, if so we need
-    // to make a draw to resolve the tie or return an error
-    let seats_too_many =
-        (winners.len() as i64) + (possible_winners.len() as i64) - (seat_count.clone() as i64);
-
-    if seats_too_many > 0 {
-        if !draw_on_tie {
-            return Err(DistributionError::Tied);
-        }
-        let number_of_draws = (possible_winners.len() as i64) - seats_too_many;
-        let mut drawn_winners: Vec<PartyQuotient> = (&possible_winners)
-            .choose_multiple(&mut rand::thread_rng(), number_of_draws.max(0) as usize)
-            .cloned()
-            .collect();
-        winners.append(&mut drawn_winners);
-    } else {
-        winners.append(&mut possible_winners);
-    }
-
-    let mut distribution: Vec<usize> = vec![0; votes.len()];
-    for pq in winners.iter() {
-        distribution[pq.party] += 1 // @todo
+    if total_votes <= tolerance {
+        return Err(DistributionError::EffectivelyZeroVotes);
     }
-
-    return Ok(distribution);
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::distribute;
     use super::DistributionError;
+    use super::{distribute, validate_with_tolerance};
 
     #[test]
     fn german_bundestag_2013() {
@@ -239,12 +258,9 @@ mod tests {
         let distribution_with_draw = distribute(&votes, &seats, &true);
         let parliament_draw_a: Vec<usize> = vec![4, 3, 1];
         let parliament_draw_b: Vec<usize> = vec![3, 4, 1];
-        assert_eq!(
-            [Ok(parliament_draw_a), Ok(parliament_draw_b)]
-                .iter()
-                .any(|x| x == &distribution_with_draw),
-            true
-        );
+        assert!([Ok(parliament_draw_a), Ok(parliament_draw_b)]
+            .iter()
+            .any(|x| x == &distribution_with_draw));
     }
 
     #[test]
@@ -292,6 +308,60 @@ mod tests {
         assert_eq!(distribution_valid_votes, Ok(vec![0, seats]));
     }
 
+    #[test]
+    fn effectively_zero_votes() {
+        let tiny = [5e-324, 1e-300];
+        assert_eq!(validate_with_tolerance(&tiny, &1, 0.0), Ok(()));
+        assert_eq!(
+            validate_with_tolerance(&tiny, &1, 1e-12),
+            Err(DistributionError::EffectivelyZeroVotes)
+        );
+        assert_eq!(
+            validate_with_tolerance(&[0.0], &1, 1e-12),
+            Err(DistributionError::NoVotes)
+        );
+        assert_eq!(validate_with_tolerance(&[1.0], &1, 1e-12), Ok(()));
+    }
+
+    #[test]
+    fn zero_votes_never_receive_seats() {
+        let votes = [0.0, 3.0, 0.0, 1.0];
+        for seats in 1..50 {
+            for draw_on_tie in [false, true].iter() {
+                if let Ok(distribution) = distribute(&votes, &seats, draw_on_tie) {
+                    assert_eq!(distribution[0], 0);
+                    assert_eq!(distribution[2], 0);
+                }
+            }
+        }
+
+        // all quotients of the first party but the first few underflow to zero
+        let distribution = distribute(&[5e-324, 0.0], &10, &true);
+        assert_eq!(distribution, Ok(vec![10, 0]));
+    }
+
+    #[test]
+    fn extreme_ratios() {
+        assert_eq!(distribute(&[1e-9, 1e12], &1000, &false), Ok(vec![0, 1000]));
+        assert_eq!(
+            distribute(&[1e-9, 1e12, 1e12], &3, &false),
+            Err(DistributionError::Tied)
+        );
+        // quotients would overflow to infinity
+        assert_eq!(distribute(&[1.7e308, 1e308], &1, &false), Ok(vec![1, 0]));
+        assert_eq!(
+            distribute(&[f64::MAX, f64::MAX / 3.0], &4, &false),
+            Ok(vec![3, 1])
+        );
+        // quotients would be subnormal, with too little precision to be told apart
+        assert_eq!(distribute(&[5e-324, 1e-323], &3, &false), Ok(vec![1, 2]));
+        let tiny = [3.0, 2.0, 1.0].map(|v| v * f64::MIN_POSITIVE / 1e10);
+        assert_eq!(
+            distribute(&tiny, &12, &false),
+            distribute(&[3.0, 2.0, 1.0], &12, &false)
+        );
+    }
+
     #[test]
     fn negative_votes() {
         let seats = 50;
@@ -0,0 +1,314 @@
+//! Translations of error messages and report strings.
+//!
+//! All user-facing strings of this crate are kept in one catalog. The [`Display`](std::fmt::Display) implementations of the error and warning types use its English entries, [`Localize`] gives access to all other languages. [`Localize::in_locale`] wraps a value for format strings and loggers, so applications can show translated messages without keeping their own tables.
+//!
+//! ```
+//! use sainte_lague::locale::{Locale, Localize};
+//! use sainte_lague::DistributionError;
+//!
+//! let error = DistributionError::InvalidSeatCount;
+//! assert_eq!(
+//!     error.localize(&Locale::German),
+//!     "Ungültige Sitzzahl, muss eine ganze Zahl größer als 0 sein."
+//! );
+//! assert_eq!(error.localize(&Locale::English), error.to_string());
+//! assert_eq!(
+//!     format!("Fehler: {}", error.in_locale(Locale::German)),
+//!     "Fehler: Ungültige Sitzzahl, muss eine ganze Zahl größer als 0 sein."
+//! );
+//! ```
+
+use std::fmt;
+
+/// The languages supported by the catalog.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub enum Locale {
+    /// English, used by [`Display`](std::fmt::Display).
+    #[default]
+    English,
+
+    /// German.
+    German,
+}
+
+/// Types with a translatable, user-facing description.
+pub trait Localize {
+    /// The description in the given language.
+    fn localize(&self, locale: &Locale) -> String;
+
+    /// A wrapper displaying the description in the given language, for format strings.
+    fn in_locale(&self, locale: Locale) -> Localized<'_, Self> {
+        Localized {
+            value: self,
+            locale,
+        }
+    }
+}
+
+/// A value displayed in a given language, see [`Localize::in_locale`].
+#[derive(Copy, Clone, Debug)]
+pub struct Localized<'a, T: ?Sized> {
+    value: &'a T,
+    locale: Locale,
+}
+
+impl<'a, T: Localize + ?Sized> fmt::Display for Localized<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(&self.value.localize(&self.locale))
+    }
+}
+
+/// Keys of the catalog.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum Text {
+    Tied,
+    InvalidSeatCount,
+    NegativeVotes,
+    NoVotes,
+    EffectivelyZeroVotes,
+    TooFewVotes,
+    UnknownParty,
+    InvalidThreshold,
+    ConflictingThresholds,
+    ConflictingBounds,
+    OverlappingAlliances,
+    ExcludedWithMinimum,
+    ConflictingOthers,
+    InvalidWeight,
+    Infeasible,
+    UnknownDistrict,
+    TooManyMinimums,
+    TooFewSeats,
+    StepFailed,
+    DuplicateParty,
+    InvalidMagic,
+    UnsupportedVersion,
+    UnexpectedKind,
+    UnexpectedEnd,
+    InvalidValue,
+    TrailingBytes,
+    Party,
+    Total,
+    GallagherIndex,
+    SharesMismatch,
+    InvalidLine,
+    InvalidCertificateInput,
+    EmptyInterval,
+    TooFewSeatsFor,
+    TooManySeatsFor,
+    InvalidBallotWeight,
+    UnknownList,
+    UnknownCandidate,
+    OverLimit,
+    OverBudget,
+}
+
+/// Look up a catalog entry. Entries may contain a `{}` placeholder, see [`text_with`].
+pub(crate) fn text(locale: &Locale, text: Text) -> &'static str {
+    match locale {
+        Locale::English => match text {
+            Text::Tied => {
+                "Tie detected, could only be resolved by randomly awarding a seat to one party."
+            }
+            Text::InvalidSeatCount => "Invalid seat count, must be an integer larger than 0.",
+            Text::NegativeVotes => "Invalid votes, all parties must have at least zero votes.",
+            Text::NoVotes => "Invalid votes, one party must have at least one vote.",
+            Text::EffectivelyZeroVotes => {
+                "Invalid votes, the total is too small to be distinguished from zero."
+            }
+            Text::TooFewVotes => "Invalid votes, there are too few to fill all seats.",
+            Text::UnknownParty => "Invalid constraint, there is no party {}.",
+            Text::InvalidThreshold => "Invalid threshold, must be between 0 and 1.",
+            Text::ConflictingThresholds => "Conflicting constraints, multiple thresholds given.",
+            Text::ConflictingBounds => "Conflicting constraints, seat bounds of party {}.",
+            Text::OverlappingAlliances => {
+                "Conflicting constraints, party {} is in multiple alliances."
+            }
+            Text::ExcludedWithMinimum => {
+                "Conflicting constraints, party {} has a minimum number of seats but failed the threshold."
+            }
+            Text::ConflictingOthers => {
+                "Conflicting constraints, party {} represents others but may receive seats."
+            }
+            Text::InvalidWeight => {
+                "Invalid weight, the weight of party {} is not positive or given more than once."
+            }
+            Text::Infeasible => "Infeasible constraints, seat bounds can't be satisfied.",
+            Text::UnknownDistrict => {
+                "Invalid list votes, they are missing for district {} or it doesn't exist."
+            }
+            Text::TooManyMinimums => {
+                "Infeasible constraints, the minimums add up to {} seats, but there are only {}."
+            }
+            Text::TooFewSeats => {
+                "Infeasible constraints, at most {} seats can be distributed, but there are {}."
+            }
+            Text::StepFailed => "Step {} failed: {}.",
+            Text::DuplicateParty => "Invalid parties, party {} has the same identifier as party {}.",
+            Text::InvalidMagic => "Invalid input, missing magic bytes.",
+            Text::UnsupportedVersion => "Unsupported format version {}.",
+            Text::UnexpectedKind => "Invalid input, unexpected kind of value.",
+            Text::UnexpectedEnd => "Invalid input, unexpected end of data.",
+            Text::InvalidValue => "Invalid input, malformed value.",
+            Text::TrailingBytes => "Invalid input, trailing bytes.",
+            Text::Party => "Party",
+            Text::Total => "Total",
+            Text::GallagherIndex => "Gallagher index",
+            Text::SharesMismatch => "Invalid shares, they add up to {} instead of {}.",
+            Text::InvalidLine => "Invalid input, line {} is not of the form name,votes.",
+            Text::InvalidCertificateInput => {
+                "Invalid input, the votes and seats differ in length or contain negative votes."
+            }
+            Text::EmptyInterval => "Invalid certificate, the lower bound exceeds the upper bound.",
+            Text::TooFewSeatsFor => "Incorrect distribution, party {} should receive more seats.",
+            Text::TooManySeatsFor => "Incorrect distribution, party {} should receive fewer seats.",
+            Text::InvalidBallotWeight => "Ballot rejected, its weight is not positive and finite.",
+            Text::UnknownList => "Ballot rejected, there is no list {}.",
+            Text::UnknownCandidate => "Line struck, there is no candidate {}.",
+            Text::OverLimit => {
+                "Line struck, candidate {} already received the maximum number of votes."
+            }
+            Text::OverBudget => "Line struck, all votes of the ballot have already been used.",
+        },
+        Locale::German => match text {
+            Text::Tied => {
+                "Gleichstand festgestellt, der nur durch zufällige Vergabe eines Sitzes an eine Partei aufgelöst werden könnte."
+            }
+            Text::InvalidSeatCount => "Ungültige Sitzzahl, muss eine ganze Zahl größer als 0 sein.",
+            Text::NegativeVotes => {
+                "Ungültige Stimmen, alle Parteien müssen mindestens null Stimmen haben."
+            }
+            Text::NoVotes => "Ungültige Stimmen, eine Partei muss mindestens eine Stimme haben.",
+            Text::EffectivelyZeroVotes => {
+                "Ungültige Stimmen, die Summe ist zu klein, um sie von null zu unterscheiden."
+            }
+            Text::TooFewVotes => {
+                "Ungültige Stimmen, es sind zu wenige, um alle Sitze zu vergeben."
+            }
+            Text::UnknownParty => "Ungültige Bedingung, es gibt keine Partei {}.",
+            Text::InvalidThreshold => "Ungültige Sperrklausel, muss zwischen 0 und 1 liegen.",
+            Text::ConflictingThresholds => {
+                "Widersprüchliche Bedingungen, mehrere Sperrklauseln angegeben."
+            }
+            Text::ConflictingBounds => "Widersprüchliche Bedingungen, Sitzgrenzen der Partei {}.",
+            Text::OverlappingAlliances => {
+                "Widersprüchliche Bedingungen, Partei {} ist Teil mehrerer Bündnisse."
+            }
+            Text::ExcludedWithMinimum => {
+                "Widersprüchliche Bedingungen, Partei {} hat eine Mindestsitzzahl, ist aber an der Sperrklausel gescheitert."
+            }
+            Text::ConflictingOthers => {
+                "Widersprüchliche Bedingungen, Partei {} steht für Sonstige, könnte aber Sitze erhalten."
+            }
+            Text::InvalidWeight => {
+                "Ungültige Gewichtung, die Gewichtung von Partei {} ist nicht positiv oder mehrfach angegeben."
+            }
+            Text::Infeasible => {
+                "Unerfüllbare Bedingungen, die Sitzgrenzen können nicht eingehalten werden."
+            }
+            Text::UnknownDistrict => {
+                "Ungültige Listenstimmen, sie fehlen für Wahlkreis {} oder es gibt ihn nicht."
+            }
+            Text::TooManyMinimums => {
+                "Unerfüllbare Bedingungen, die Mindestsitzzahlen ergeben zusammen {} Sitze, es gibt aber nur {}."
+            }
+            Text::TooFewSeats => {
+                "Unerfüllbare Bedingungen, höchstens {} Sitze können vergeben werden, es gibt aber {}."
+            }
+            Text::StepFailed => "Schritt {} fehlgeschlagen: {}.",
+            Text::DuplicateParty => "Ungültige Parteien, Partei {} hat dieselbe Kennung wie Partei {}.",
+            Text::InvalidMagic => "Ungültige Eingabe, Kennung fehlt.",
+            Text::UnsupportedVersion => "Nicht unterstützte Formatversion {}.",
+            Text::UnexpectedKind => "Ungültige Eingabe, unerwartete Art von Wert.",
+            Text::UnexpectedEnd => "Ungültige Eingabe, unerwartetes Ende der Daten.",
+            Text::InvalidValue => "Ungültige Eingabe, fehlerhafter Wert.",
+            Text::TrailingBytes => "Ungültige Eingabe, überzählige Bytes.",
+            Text::Party => "Partei",
+            Text::Total => "Gesamt",
+            Text::GallagherIndex => "Gallagher-Index",
+            Text::SharesMismatch => "Ungültige Anteile, ihre Summe ist {} statt {}.",
+            Text::InvalidLine => "Ungültige Eingabe, Zeile {} hat nicht die Form Name,Stimmen.",
+            Text::InvalidCertificateInput => {
+                "Ungültige Eingabe, Stimmen und Sitze sind unterschiedlich lang oder enthalten negative Stimmen."
+            }
+            Text::EmptyInterval => {
+                "Ungültiges Zertifikat, die untere Grenze übersteigt die obere Grenze."
+            }
+            Text::TooFewSeatsFor => "Falsche Verteilung, Partei {} müsste mehr Sitze erhalten.",
+            Text::TooManySeatsFor => "Falsche Verteilung, Partei {} müsste weniger Sitze erhalten.",
+            Text::InvalidBallotWeight => {
+                "Stimmzettel ungültig, seine Gewichtung ist nicht positiv und endlich."
+            }
+            Text::UnknownList => "Stimmzettel ungültig, es gibt keine Liste {}.",
+            Text::UnknownCandidate => "Zeile gestrichen, es gibt keinen Kandidaten {}.",
+            Text::OverLimit => {
+                "Zeile gestrichen, Kandidat {} hat bereits die Höchstzahl an Stimmen erhalten."
+            }
+            Text::OverBudget => {
+                "Zeile gestrichen, alle Stimmen des Stimmzettels wurden bereits vergeben."
+            }
+        },
+    }
+}
+
+/// Look up a catalog entry and replace its placeholder with `value`.
+pub(crate) fn text_with(locale: &Locale, text: Text, value: &dyn std::fmt::Display) -> String {
+    text_with_all(locale, text, &[value])
+}
+
+/// Look up a catalog entry and replace its placeholders with `values`, in order.
+pub(crate) fn text_with_all(
+    locale: &Locale,
+    text: Text,
+    values: &[&dyn std::fmt::Display],
+) -> String {
+    let mut parts = self::text(locale, text).split("{}");
+    let mut result = parts.next().unwrap_or_default().to_string();
+    for (i, part) in parts.enumerate() {
+        if let Some(value) = values.get(i) {
+            result.push_str(&value.to_string());
+        }
+        result.push_str(part);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{text, text_with, text_with_all, Locale, Localize, Text};
+    use crate::certificate::Violation;
+    use crate::cumulative::Reason;
+
+    #[test]
+    fn placeholders() {
+        assert_eq!(
+            text_with(&Locale::German, Text::UnknownParty, &7),
+            "Ungültige Bedingung, es gibt keine Partei 7."
+        );
+        assert_eq!(text(&Locale::English, Text::Total), "Total");
+        assert_eq!(
+            text_with_all(&Locale::English, Text::StepFailed, &[&3, &"x"]),
+            "Step 3 failed: x."
+        );
+    }
+
+    #[test]
+    fn in_locale() {
+        let violation = Violation::TooFewSeats(2);
+        assert_eq!(
+            violation.in_locale(Locale::English).to_string(),
+            violation.to_string()
+        );
+        assert_eq!(
+            format!("{}", violation.in_locale(Locale::German)),
+            "Falsche Verteilung, Partei 2 müsste mehr Sitze erhalten."
+        );
+        assert_eq!(
+            format!(
+                "[{:>40}]",
+                Reason::UnknownList(3).in_locale(Locale::English)
+            ),
+            "[    Ballot rejected, there is no list 3.]"
+        );
+    }
+}
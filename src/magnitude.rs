@@ -0,0 +1,232 @@
+//! Choosing district magnitudes that minimize disproportionality.
+//!
+//! If seats are distributed separately in multiple districts, the overall result depends on how many seats each district gets. [`optimize`] searches for the magnitudes that minimize a disproportionality index, such as the [Gallagher index](crate::metrics::gallagher), of the combined result.
+//!
+//! ```
+//! use sainte_lague::magnitude::optimize;
+//! use sainte_lague::{metrics, Method};
+//!
+//! let districts = [vec![700.0, 300.0], vec![400.0, 600.0], vec![100.0, 900.0]];
+//! let bounds = [(1, 5), (1, 5), (1, 5)];
+//! let optimum = optimize(&Method::SainteLague, metrics::gallagher, &districts, &bounds, &9).unwrap();
+//! assert_eq!(optimum.magnitudes.iter().sum::<usize>(), 9);
+//! assert_eq!(optimum.seats, vec![4, 5]);
+//! ```
+
+use crate::Method;
+
+/// The combined seats of a set of magnitudes and their index.
+type Evaluation = (Vec<usize>, f64);
+
+/// The result of [`optimize`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct Optimum {
+    /// The number of seats of each district.
+    pub magnitudes: Vec<usize>,
+    /// The combined seats of each party over all districts.
+    pub seats: Vec<usize>,
+    /// The value of the index for the combined result.
+    pub index: f64,
+}
+
+/// Choose the number of seats of each district, within the inclusive `bounds`, such that the districts have `seat_count` seats in total and `index` is minimal for the combined votes and seats of all districts. Districts are given by their votes, with the same parties in the same order in every district.
+///
+/// The search starts by distributing the seats among the districts proportionally to their total votes and then keeps moving single seats from one district to another as long as this improves the index. The result is therefore a local optimum, which isn't necessarily the global one. Magnitudes that lead to a tie in any district are skipped.
+///
+/// Returns [`None`] if the bounds can't be satisfied or no magnitudes without ties could be found. Panics if the number of bounds or parties doesn't match.
+pub fn optimize(
+    method: &Method,
+    index: fn(&[f64], &[usize]) -> Option<f64>,
+    districts: &[Vec<f64>],
+    bounds: &[(usize, usize)],
+    seat_count: &usize,
+) -> Option<Optimum> {
+    assert_eq!(bounds.len(), districts.len(), "one bound per district");
+    let party_count = districts.first().map_or(0, |d| d.len());
+    assert!(
+        districts.iter().all(|d| d.len() == party_count),
+        "same number of parties in every district"
+    );
+    let bounds: Vec<(usize, usize)> = bounds
+        .iter()
+        .map(|(min, max)| (*min, (*max).min(*seat_count)))
+        .collect();
+    if bounds.iter().any(|(min, max)| min > max)
+        || bounds.iter().map(|(min, _)| min).sum::<usize>() > *seat_count
+        || bounds.iter().map(|(_, max)| max).sum::<usize>() < *seat_count
+    {
+        return None;
+    }
+
+    // seats of each district for every possible magnitude, None on ties and other errors
+    let table: Vec<Vec<Option<Vec<usize>>>> = districts
+        .iter()
+        .zip(bounds.iter())
+        .map(|(votes, (min, max))| {
+            (*min..=*max)
+                .map(|m| match m {
+                    0 => Some(vec![0; party_count]),
+                    _ => method.distribute(votes, &m, &false).ok(),
+                })
+                .collect()
+        })
+        .collect();
+    let national_votes: Vec<f64> = (0..party_count)
+        .map(|p| districts.iter().map(|d| d[p]).sum())
+        .collect();
+    let evaluate = |magnitudes: &[usize]| -> Option<Evaluation> {
+        let mut seats = vec![0; party_count];
+        for (d, m) in magnitudes.iter().enumerate() {
+            let district_seats = table[d][m - bounds[d].0].as_ref()?;
+            for (s, ds) in seats.iter_mut().zip(district_seats) {
+                *s += ds;
+            }
+        }
+        let value = index(&national_votes, &seats)?;
+        Some((seats, value))
+    };
+
+    // start proportionally to the size of the districts
+    let sizes: Vec<f64> = districts.iter().map(|d| d.iter().sum()).collect();
+    let mut magnitudes: Vec<usize> = bounds.iter().map(|(min, _)| *min).collect();
+    for _ in magnitudes.iter().sum::<usize>()..*seat_count {
+        let next = (0..districts.len())
+            .filter(|d| magnitudes[*d] < bounds[*d].1)
+            .max_by(|a, b| {
+                let quotient = |d: &usize| sizes[*d] / method.divisor(magnitudes[*d]);
+                quotient(a)
+                    .partial_cmp(&quotient(b))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then(b.cmp(a))
+            })?;
+        magnitudes[next] += 1;
+    }
+
+    let mut best = evaluate(&magnitudes);
+    loop {
+        let mut step: Option<(Vec<usize>, Evaluation)> = None;
+        for from in 0..districts.len() {
+            for to in 0..districts.len() {
+                if from == to
+                    || magnitudes[from] == bounds[from].0
+                    || magnitudes[to] == bounds[to].1
+                {
+                    continue;
+                }
+                let mut candidate = magnitudes.clone();
+                candidate[from] -= 1;
+                candidate[to] += 1;
+                if let Some(result) = evaluate(&candidate) {
+                    let current = step.as_ref().map(|(_, r)| r).or(best.as_ref());
+                    if !matches!(current, Some((_, value)) if result.1 >= *value) {
+                        step = Some((candidate, result));
+                    }
+                }
+            }
+        }
+        match step {
+            Some((candidate, result)) => {
+                magnitudes = candidate;
+                best = Some(result);
+            }
+            None => break,
+        }
+    }
+
+    let (seats, index) = best?;
+    Some(Optimum {
+        magnitudes,
+        seats,
+        index,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::optimize;
+    use crate::{metrics, Method};
+
+    fn districts() -> Vec<Vec<f64>> {
+        vec![
+            vec![700.0, 300.0, 0.0],
+            vec![400.0, 500.0, 100.0],
+            vec![100.0, 800.0, 100.0],
+            vec![300.0, 300.0, 400.0],
+        ]
+    }
+
+    #[test]
+    fn local_optimum() {
+        let bounds = [(1, 6), (2, 6), (0, 6), (1, 3)];
+        let optimum = optimize(
+            &Method::SainteLague,
+            metrics::gallagher,
+            &districts(),
+            &bounds,
+            &12,
+        )
+        .unwrap();
+        assert_eq!(optimum.magnitudes.iter().sum::<usize>(), 12);
+        assert_eq!(optimum.seats.iter().sum::<usize>(), 12);
+        for (m, (min, max)) in optimum.magnitudes.iter().zip(bounds.iter()) {
+            assert!(min <= m && m <= max);
+        }
+
+        // no single move improves the index
+        for from in 0..4 {
+            for to in 0..4 {
+                let mut fixed = bounds;
+                for (d, m) in optimum.magnitudes.iter().enumerate() {
+                    fixed[d] = (*m, *m);
+                }
+                if from == to
+                    || optimum.magnitudes[from] == bounds[from].0
+                    || optimum.magnitudes[to] == bounds[to].1
+                {
+                    continue;
+                }
+                fixed[from] = (fixed[from].0 - 1, fixed[from].0 - 1);
+                fixed[to] = (fixed[to].0 + 1, fixed[to].0 + 1);
+                if let Some(other) = optimize(
+                    &Method::SainteLague,
+                    metrics::gallagher,
+                    &districts(),
+                    &fixed,
+                    &12,
+                ) {
+                    assert!(other.index >= optimum.index);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn infeasible_bounds() {
+        let optimize = |bounds: &[(usize, usize)]| {
+            optimize(
+                &Method::SainteLague,
+                metrics::gallagher,
+                &districts(),
+                bounds,
+                &12,
+            )
+        };
+        assert_eq!(optimize(&[(4, 4), (4, 4), (4, 4), (4, 4)]), None);
+        assert_eq!(optimize(&[(0, 2), (0, 2), (0, 2), (0, 2)]), None);
+        assert_eq!(optimize(&[(3, 2), (0, 12), (0, 12), (0, 12)]), None);
+    }
+
+    #[test]
+    fn fixed_magnitudes() {
+        let optimum = optimize(
+            &Method::SainteLague,
+            metrics::gallagher,
+            &districts(),
+            &[(3, 3), (3, 3), (3, 3), (3, 3)],
+            &12,
+        )
+        .unwrap();
+        assert_eq!(optimum.magnitudes, vec![3, 3, 3, 3]);
+        assert_eq!(optimum.seats, vec![4, 7, 1]);
+    }
+}
@@ -0,0 +1,86 @@
+//! The divisor sequences of common highest averages methods.
+//!
+//! Each function returns the first `n` divisors of a method: the divisor at index `s` is applied to a party's votes when it competes for its seat number `s + 1`. Divisors are given in the form used by this crate, e.g. `0.5, 1.5, 2.5, …` for Sainte-Laguë. Sequences that only differ by a constant factor, such as `1, 3, 5, …`, produce the same distributions.
+//!
+//! ```
+//! use sainte_lague::methods::divisors;
+//!
+//! assert_eq!(divisors::sainte_lague(4), vec![0.5, 1.5, 2.5, 3.5]);
+//! assert_eq!(divisors::dhondt(3), vec![1.0, 2.0, 3.0]);
+//! assert_eq!(divisors::modified_sainte_lague(3), vec![0.7, 1.5, 2.5]);
+//! ```
+
+use super::Stationary;
+
+fn stationary(method: Stationary, n: usize) -> Vec<f64> {
+    (0..n).map(|s| method.divisor(s)).collect()
+}
+
+/// The Sainte-Laguë (Webster) divisors `0.5, 1.5, 2.5, …`, equivalent to `1, 3, 5, …`.
+pub fn sainte_lague(n: usize) -> Vec<f64> {
+    stationary(Stationary::new(0.5).expect("0.5 is a valid offset"), n)
+}
+
+/// The modified Sainte-Laguë divisors `0.7, 1.5, 2.5, …` used in Norway and Sweden, equivalent to `1.4, 3, 5, …`, see [`Stationary::modified_sainte_lague`].
+pub fn modified_sainte_lague(n: usize) -> Vec<f64> {
+    stationary(Stationary::modified_sainte_lague(), n)
+}
+
+/// The D'Hondt (Jefferson) divisors `1, 2, 3, …`.
+pub fn dhondt(n: usize) -> Vec<f64> {
+    stationary(Stationary::new(1.0).expect("1.0 is a valid offset"), n)
+}
+
+/// The Adams divisors `0, 1, 2, …`. The first divisor of zero gives every party with votes a seat before any party receives a second one.
+pub fn adams(n: usize) -> Vec<f64> {
+    stationary(Stationary::new(0.0).expect("0.0 is a valid offset"), n)
+}
+
+/// The Danish divisors `1/3, 4/3, 7/3, …`, equivalent to `1, 4, 7, …`.
+pub fn danish(n: usize) -> Vec<f64> {
+    stationary(
+        Stationary::new(1.0 / 3.0).expect("1/3 is a valid offset"),
+        n,
+    )
+}
+
+/// The Imperiali divisors `2, 3, 4, …`, the D'Hondt divisors without the first one.
+pub fn imperiali(n: usize) -> Vec<f64> {
+    (0..n).map(|s| s as f64 + 2.0).collect()
+}
+
+/// The Huntington-Hill divisors `0, √2, √6, …`, the geometric means `√(s (s + 1))` of consecutive seat counts. Not a stationary method, and like Adams it gives every party with votes a seat first.
+pub fn huntington_hill(n: usize) -> Vec<f64> {
+    (0..n).map(|s| ((s * (s + 1)) as f64).sqrt()).collect()
+}
+
+/// The Dean divisors `0, 4/3, 12/5, …`, the harmonic means `2s (s + 1) / (2s + 1)` of consecutive seat counts. Not a stationary method, and like Adams it gives every party with votes a seat first.
+pub fn dean(n: usize) -> Vec<f64> {
+    (0..n)
+        .map(|s| (2 * s * (s + 1)) as f64 / (2 * s + 1) as f64)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        adams, danish, dean, dhondt, huntington_hill, imperiali, modified_sainte_lague,
+        sainte_lague,
+    };
+
+    #[test]
+    fn tables() {
+        assert_eq!(sainte_lague(0), vec![]);
+        assert_eq!(adams(3), vec![0.0, 1.0, 2.0]);
+        let danish: Vec<f64> = danish(3).iter().map(|d| d * 3.0).collect();
+        assert_eq!(danish, vec![1.0, 4.0, 7.0]);
+        assert_eq!(huntington_hill(3), vec![0.0, 2f64.sqrt(), 6f64.sqrt()]);
+        assert_eq!(dean(3), vec![0.0, 4.0 / 3.0, 2.4]);
+        // the i-th divisor of the classic integer form is twice the one of this crate
+        let classic: Vec<f64> = sainte_lague(5).iter().map(|d| d * 2.0).collect();
+        assert_eq!(classic, vec![1.0, 3.0, 5.0, 7.0, 9.0]);
+        assert_eq!(modified_sainte_lague(1), vec![0.7]);
+        assert_eq!(dhondt(2), vec![1.0, 2.0]);
+        assert_eq!(imperiali(3), vec![2.0, 3.0, 4.0]);
+    }
+}
@@ -0,0 +1,67 @@
+use std::cmp::Ordering;
+
+/// Split a finite non-negative float into an integer mantissa and a binary exponent.
+fn decompose(x: f64) -> (u64, i32) {
+    let bits = x.to_bits();
+    let exponent = ((bits >> 52) & 0x7ff) as i32;
+    let mantissa = bits & ((1 << 52) - 1);
+    match exponent {
+        0 => (mantissa, -1074),
+        _ => (mantissa | (1 << 52), exponent - 1075),
+    }
+}
+
+/// The exact product of two finite non-negative floats, normalized so that equal values have equal representations: the mantissa has its highest bit set, unless it is zero.
+fn product(a: f64, b: f64) -> (u128, i32) {
+    let (ma, ea) = decompose(a);
+    let (mb, eb) = decompose(b);
+    let mantissa = ma as u128 * mb as u128;
+    match mantissa {
+        0 => (0, 0),
+        _ => {
+            let shift = mantissa.leading_zeros();
+            (mantissa << shift, ea + eb - shift as i32)
+        }
+    }
+}
+
+/// Compare `a * b` with `c * d` without rounding, for finite non-negative floats. Products of floats have at most 106 significant bits, so they are compared exactly as integers.
+pub(crate) fn compare_products(a: f64, b: f64, c: f64, d: f64) -> Ordering {
+    let (m1, e1) = product(a, b);
+    let (m2, e2) = product(c, d);
+    match (m1, m2) {
+        (0, 0) => Ordering::Equal,
+        (0, _) => Ordering::Less,
+        (_, 0) => Ordering::Greater,
+        _ => e1.cmp(&e2).then(m1.cmp(&m2)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compare_products;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn exact_products() {
+        let epsilon = f64::EPSILON;
+        // (1 + ε)² = 1 + 2ε + ε² rounds to 1 + 2ε
+        assert_eq!((1.0 + epsilon) * (1.0 + epsilon), 1.0 + 2.0 * epsilon);
+        assert_eq!(
+            compare_products(1.0 + epsilon, 1.0 + epsilon, 1.0 + 2.0 * epsilon, 1.0),
+            Ordering::Greater
+        );
+        assert_eq!(compare_products(3.0, 0.5, 1.5, 1.0), Ordering::Equal);
+        assert_eq!(compare_products(2.0, 0.25, 0.125, 4.0), Ordering::Equal);
+        assert_eq!(compare_products(0.0, 7.0, 1.0, 0.0), Ordering::Equal);
+        assert_eq!(compare_products(0.0, 7.0, 1.0, 1e-300), Ordering::Less);
+        // subnormal numbers
+        let tiny = f64::from_bits(1);
+        assert_eq!(
+            compare_products(tiny, 2.0, tiny * 2.0, 1.0),
+            Ordering::Equal
+        );
+        assert_eq!(compare_products(tiny, 1e300, 1.0, 1e-20), Ordering::Less);
+        assert_eq!(compare_products(1e300, 1e300, 1.0, 1.0), Ordering::Greater);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{validate, DistributionError, Draw, Outcome};
+use rand::seq::SliceRandom;
+use std::borrow::Cow;
+
+/// How [`allocate_with`] resolves ties for the last seats.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum Ties {
+    /// Fail with [`DistributionError::Tied`].
+    Error,
+    /// Draw lots among the tied parties.
+    Draw,
+    /// Award the seats to the tied parties with the lowest indices.
+    LowestIndex,
+}
+
+impl From<&bool> for Ties {
+    fn from(draw_on_tie: &bool) -> Ties {
+        match draw_on_tie {
+            true => Ties::Draw,
+            false => Ties::Error,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct PartyQuotient {
+    party: usize,
+    quotient: f64,
+}
+
+/// Same as [`distribute`](crate::distribute), but returns the full [`Outcome`] including information about draws.
+pub(crate) fn allocate(
+    votes: &[f64],
+    seat_count: &usize,
+    draw_on_tie: &bool,
+) -> Result<Outcome, DistributionError> {
+    allocate_bounded(votes, seat_count, draw_on_tie, &[])
+}
+
+/// Same as [`allocate`], but every party receives at least the minimum and at most the maximum number of seats given in `bounds` (or is unbounded if `bounds` is empty).
+///
+/// The caller must make sure the bounds are feasible: the minimums must not add up to more than `seat_count`, and the maximums of all parties with votes must leave room for the remaining seats.
+pub(crate) fn allocate_bounded(
+    votes: &[f64],
+    seat_count: &usize,
+    draw_on_tie: &bool,
+    bounds: &[(usize, usize)],
+) -> Result<Outcome, DistributionError> {
+    allocate_with(votes, seat_count, draw_on_tie.into(), bounds, |seats| {
+        seats as f64 + 0.5
+    })
+}
+
+/// Same as [`allocate_bounded`], but for any divisor method: `divisor(s)` is the divisor of a party's votes when competing for its seat number `s + 1`.
+pub(crate) fn allocate_with<D: Fn(usize) -> f64>(
+    votes: &[f64],
+    seat_count: &usize,
+    ties: Ties,
+    bounds: &[(usize, usize)],
+    divisor: D,
+) -> Result<Outcome, DistributionError> {
+    // @todo this is certainly far from an optimal implementation, it is just a copy of
+    // https://github.com/juliuste/sainte-lague for now, which should at least work correctly
+
+    validate(votes, seat_count)?;
+    let votes = rescale(votes);
+
+    let bound = |party: usize| bounds.get(party).cloned().unwrap_or((0, *seat_count));
+    let mut distribution: Vec<usize> = (0..votes.len()).map(|i| bound(i).0).collect();
+    let guaranteed_seats: usize = distribution.iter().sum();
+    debug_assert!(guaranteed_seats <= *seat_count);
+    let remaining_seats = seat_count - guaranteed_seats;
+    if remaining_seats == 0 {
+        return Ok(Outcome::new(distribution, None));
+    }
+
+    // parties without votes never receive seats, not even when a tie is resolved by drawing
+    // lots: tiny vote counts can produce quotients that underflow to zero, which must not
+    // be considered equal to the quotients of parties without any votes
+    let mut party_quotients: Vec<PartyQuotient> = votes
+        .iter()
+        .enumerate()
+        .filter(|(_, v)| *v > &0.0)
+        .flat_map(|(i, v)| {
+            let (min, max) = bound(i);
+            let divisors = (min..max.min(min + remaining_seats)).map(&divisor);
+            divisors.map(move |d| PartyQuotient {
+                party: i,
+                quotient: v / d,
+            })
+        })
+        .collect();
+
+    party_quotients.sort_by(|a, b| {
+        b.quotient
+            .partial_cmp(&a.quotient)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let last_winning_quotient = party_quotients
+        .get(remaining_seats - 1)
+        .map(|pq| pq.quotient)
+        .unwrap_or(0.0);
+    let mut winners: Vec<PartyQuotient> = party_quotients
+        .iter()
+        .filter(|pq| pq.quotient > last_winning_quotient)
+        .cloned()
+        .collect();
+    let mut possible_winners: Vec<PartyQuotient> = party_quotients
+        .iter()
+        .filter(|pq| pq.quotient == last_winning_quotient)
+        .cloned()
+        .collect();
+
+    // check if the "last" winner had the same quotient as the "first" loser, if so we need
+    // to make a draw to resolve the tie or return an error
+    let seats_too_many =
+        (winners.len() as i64) + (possible_winners.len() as i64) - (remaining_seats as i64);
+
+    let mut draw = None;
+    if seats_too_many > 0 {
+        if ties == Ties::Error {
+            return Err(DistributionError::Tied);
+        }
+        let number_of_draws = (possible_winners.len() as i64) - seats_too_many;
+        let mut candidates: Vec<usize> = possible_winners.iter().map(|pq| pq.party).collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+        draw = Some(Draw::new(candidates, number_of_draws.max(0) as usize));
+        let mut drawn_winners: Vec<PartyQuotient> = match ties {
+            // the stable sort kept the tied quotients in the order of their parties
+            Ties::LowestIndex => possible_winners
+                .into_iter()
+                .take(number_of_draws.max(0) as usize)
+                .collect(),
+            _ => possible_winners
+                .choose_multiple(&mut rand::thread_rng(), number_of_draws.max(0) as usize)
+                .cloned()
+                .collect(),
+        };
+        winners.append(&mut drawn_winners);
+    } else {
+        winners.append(&mut possible_winners);
+    }
+
+    for pq in winners.iter() {
+        distribution[pq.party] += 1 // @todo
+    }
+
+    debug_assert_distribution(&votes, seat_count, bounds, &distribution);
+    Ok(Outcome::new(distribution, draw))
+}
+
+/// Check the invariants of every distribution returned by the methods in debug builds, so bugs in optimized code paths fail fast instead of producing plausible but wrong parliaments: the seats add up to `seat_count`, every party is within its `bounds`, and parties without votes receive no seats beyond their minimum. Seats can't be negative by their type.
+pub(crate) fn debug_assert_distribution(
+    votes: &[f64],
+    seat_count: &usize,
+    bounds: &[(usize, usize)],
+    seats: &[usize],
+) {
+    debug_assert_eq!(seats.len(), votes.len(), "one entry per party");
+    debug_assert_eq!(
+        seats.iter().sum::<usize>(),
+        *seat_count,
+        "all seats awarded"
+    );
+    for (party, (v, s)) in votes.iter().zip(seats.iter()).enumerate() {
+        let (min, max) = bounds.get(party).cloned().unwrap_or((0, *seat_count));
+        debug_assert!((min..=max).contains(s), "party {} within its bounds", party);
+        debug_assert!(*v > 0.0 || *s == min, "party {} without votes", party);
+    }
+}
+
+/// Rescale `votes` by a power of two if their largest value is so large that quotients could overflow to infinity, or so small that they would be subnormal numbers with reduced precision. Multiplying by a power of two is exact, so the ratios between the votes and thus the distribution don't change.
+///
+/// Votes that become subnormal or zero compared to the largest one could only win a seat in a parliament with more than 2^900 seats, so their loss of precision doesn't matter.
+pub(crate) fn rescale(votes: &[f64]) -> Cow<'_, [f64]> {
+    let largest = votes.iter().cloned().fold(0.0, f64::max);
+    let moderate = 2f64.powi(-512)..=2f64.powi(512);
+    if largest == 0.0 || !largest.is_finite() || moderate.contains(&largest) {
+        return Cow::Borrowed(votes);
+    }
+    // scale the largest vote to about 1, in two steps because 2^k itself may not be representable
+    let k = -(largest.log2().floor() as i32);
+    let (first, second) = (2f64.powi(k / 2), 2f64.powi(k - k / 2));
+    Cow::Owned(votes.iter().map(|v| v * first * second).collect())
+}
@@ -0,0 +1,337 @@
+use super::highest_averages::{debug_assert_distribution, rescale, Ties};
+use super::{quotas, Allocator, LowestIndex};
+use crate::{validate, DistributionError, Draw, Outcome};
+use rand::seq::SliceRandom;
+use std::borrow::Cow;
+
+/// The quota of a [`LargestRemainder`] method, see [`quotas`](super::quotas) for the formulas.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Quota {
+    /// The Hare quota `votes / seats` of the Hare-Niemeyer (Hamilton) method.
+    Hare,
+
+    /// The Droop quota `⌊votes / (seats + 1)⌋ + 1`, as formerly used for the National Assembly of South Africa. It is smaller than the Hare quota, so more seats are awarded for full quotas, which favors larger parties.
+    ///
+    /// Rounding down only makes sense for whole votes, for vote shares the result would depend on their unit. Votes with a fractional part use the exact [Hagenbach-Bischoff](super::quotas::hagenbach_bischoff) quota `votes / (seats + 1)` instead, which doesn't. For electorates much larger than the number of seats, both quotas give the same seats.
+    Droop,
+}
+
+/// A largest remainder method: every party receives one seat for each full quota of its votes, and the remaining seats go to the parties with the largest remainders, one each.
+///
+/// Unlike divisor methods, largest remainder methods can give a party fewer seats when the number of seats grows (the Alabama paradox), see [`paradox`](crate::paradox). Parties with equal remainders competing for the last of the remaining seats are tied. Remainders are compared as fractions of the quota with a common denominator, so equal remainders of whole-numbered votes are detected exactly.
+///
+/// ```
+/// use sainte_lague::methods::LargestRemainder;
+/// use sainte_lague::{DistributionError, Method};
+///
+/// // the quota is 1000 / 10 = 100 votes, leaving remainders of 0.2, 0.5 and 0.3 quotas for the last seat
+/// let votes = [720.0, 150.0, 130.0];
+/// let hare_niemeyer = LargestRemainder::hare();
+/// assert_eq!(hare_niemeyer.distribute(&votes, &10, &false), Ok(vec![7, 2, 1]));
+/// assert_eq!(Method::DHondt.distribute(&votes, &10, &false), Ok(vec![8, 1, 1]));
+///
+/// // the Droop quota is ⌊1000 / 11⌋ + 1 = 91 votes, leaving remainders of 83, 59 and 39 votes
+/// assert_eq!(LargestRemainder::droop().distribute(&votes, &10, &false), Ok(vec![8, 1, 1]));
+///
+/// // remainders of half a quota each for two seats
+/// assert_eq!(hare_niemeyer.distribute(&[3.0, 1.0, 1.0, 1.0], &3, &false), Err(DistributionError::Tied));
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct LargestRemainder {
+    quota: Quota,
+}
+
+impl LargestRemainder {
+    /// Create a largest remainder method with the given quota.
+    pub fn new(quota: Quota) -> LargestRemainder {
+        LargestRemainder { quota }
+    }
+
+    /// The Hare-Niemeyer (Hamilton) method, with the [`Hare`](Quota::Hare) quota.
+    pub fn hare() -> LargestRemainder {
+        LargestRemainder::new(Quota::Hare)
+    }
+
+    /// The largest remainder method with the [`Droop`](Quota::Droop) quota.
+    pub fn droop() -> LargestRemainder {
+        LargestRemainder::new(Quota::Droop)
+    }
+
+    /// The quota.
+    pub fn quota(&self) -> Quota {
+        self.quota
+    }
+
+    /// Calculate the distribution for the given `votes` and `seat_count` using this method. Arguments and error cases are the same as for [`distribute`](crate::distribute): parties with equal remainders competing for the last remaining seats are tied, and lots are drawn among them if `draw_on_tie` is set.
+    ///
+    /// With the Droop quota, a very small number of votes compared to the seats can leave more seats than parties with votes. Each of them can only receive one remaining seat, so this fails with [`DistributionError::TooFewVotes`].
+    pub fn distribute(
+        &self,
+        votes: &[f64],
+        seat_count: &usize,
+        draw_on_tie: &bool,
+    ) -> Result<Vec<usize>, DistributionError> {
+        self.evaluate(votes, seat_count, draw_on_tie)
+            .map(Outcome::into_seats)
+    }
+
+    /// Same as [`LargestRemainder::distribute`], but returns the full [`Outcome`] including information about draws among tied remainders.
+    pub fn evaluate(
+        &self,
+        votes: &[f64],
+        seat_count: &usize,
+        draw_on_tie: &bool,
+    ) -> Result<Outcome, DistributionError> {
+        self.allocate_with(votes, seat_count, draw_on_tie.into())
+    }
+
+    fn allocate_with(
+        &self,
+        votes: &[f64],
+        seat_count: &usize,
+        ties: Ties,
+    ) -> Result<Outcome, DistributionError> {
+        validate(votes, seat_count)?;
+        // the quota as a fraction, so the remainders share its numerator as their denominator
+        let (votes, numerator, denominator) = match self.quota {
+            Quota::Hare => {
+                let votes = rescale(votes);
+                let total = votes.iter().sum();
+                (votes, total, *seat_count as f64)
+            }
+            // rounding down depends on the magnitude of the votes, so they are kept as they are
+            Quota::Droop if votes.iter().all(|v| v.fract() == 0.0) => {
+                let quota = quotas::droop(votes.iter().sum(), *seat_count);
+                (Cow::Borrowed(votes), quota, 1.0)
+            }
+            Quota::Droop => {
+                let votes = rescale(votes);
+                let total = votes.iter().sum();
+                (votes, total, (seat_count + 1) as f64)
+            }
+        };
+
+        let mut seats = vec![0; votes.len()];
+        let mut remainders = vec![0.0; votes.len()];
+        for (p, v) in votes.iter().enumerate() {
+            let scaled = v * denominator;
+            let mut full = (scaled / numerator).floor();
+            // the division may round up to the next whole quota
+            if full * numerator > scaled {
+                full -= 1.0;
+            }
+            seats[p] = full as usize;
+            remainders[p] = scaled - full * numerator;
+        }
+
+        let awarded: usize = seats.iter().sum();
+        let mut draw = None;
+        if awarded > *seat_count {
+            // only the Hagenbach-Bischoff quota can be reached once more than there are seats,
+            // if the votes of every party are a multiple of it, so all of them are tied for the
+            // seat of their last full quota
+            let tied: Vec<usize> = (0..votes.len()).filter(|p| votes[*p] > 0.0).collect();
+            let kept = tied.len() - (awarded - seat_count);
+            for p in tied.iter() {
+                seats[*p] -= 1;
+            }
+            for p in choose(&tied, kept, ties)? {
+                seats[p] += 1;
+            }
+            draw = Some(Draw::new(tied, kept));
+        } else {
+            let remaining = seat_count - awarded;
+            let mut order: Vec<usize> = (0..votes.len()).filter(|p| votes[*p] > 0.0).collect();
+            // the sort is stable, so equal remainders stay in the order of their parties
+            order.sort_by(|a, b| {
+                remainders[*b]
+                    .partial_cmp(&remainders[*a])
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+            if remaining > order.len() {
+                return Err(DistributionError::TooFewVotes);
+            }
+            if remaining > 0 {
+                let last = remainders[order[remaining - 1]];
+                let winners = order.iter().filter(|p| remainders[**p] > last).count();
+                for p in order.iter().take(winners) {
+                    seats[*p] += 1;
+                }
+                // ascending, as equal remainders are in the order of their parties
+                let tied: Vec<usize> = order
+                    .iter()
+                    .cloned()
+                    .filter(|p| remainders[*p] == last)
+                    .collect();
+                let drawn = remaining - winners;
+                for p in choose(&tied, drawn, ties)? {
+                    seats[p] += 1;
+                }
+                if tied.len() > drawn {
+                    draw = Some(Draw::new(tied, drawn));
+                }
+            }
+        }
+
+        debug_assert_distribution(&votes, seat_count, &[], &seats);
+        Ok(Outcome::new(seats, draw))
+    }
+}
+
+/// The `count` parties among the `tied` ones that receive a seat according to `ties`.
+fn choose(tied: &[usize], count: usize, ties: Ties) -> Result<Vec<usize>, DistributionError> {
+    if count == tied.len() {
+        return Ok(tied.to_vec());
+    }
+    match ties {
+        Ties::Error => Err(DistributionError::Tied),
+        Ties::LowestIndex => Ok(tied[..count].to_vec()),
+        Ties::Draw => Ok(tied
+            .choose_multiple(&mut rand::thread_rng(), count)
+            .cloned()
+            .collect()),
+    }
+}
+
+impl Allocator for LargestRemainder {
+    type Error = DistributionError;
+
+    fn allocate(&self, votes: &[f64], seat_count: &usize) -> Result<Vec<usize>, DistributionError> {
+        self.distribute(votes, seat_count, &false)
+    }
+}
+
+impl Allocator for LowestIndex<LargestRemainder> {
+    type Error = DistributionError;
+
+    fn allocate(&self, votes: &[f64], seat_count: &usize) -> Result<Vec<usize>, DistributionError> {
+        self.0
+            .allocate_with(votes, seat_count, Ties::LowestIndex)
+            .map(Outcome::into_seats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LargestRemainder, Quota};
+    use crate::methods::LowestIndex;
+    use crate::{Allocator, DistributionError};
+
+    #[test]
+    fn hare_niemeyer() {
+        let method = LargestRemainder::hare();
+        assert_eq!(method.quota(), Quota::Hare);
+        // the quota is 100 / 11 votes, leaving remainders of 0.49, 0.86, 0.1 and 0.55 quotas
+        let votes = [59.0, 26.0, 10.0, 5.0];
+        assert_eq!(method.distribute(&votes, &11, &false), Ok(vec![6, 3, 1, 1]));
+        assert_eq!(method.distribute(&[1.0, 0.0], &3, &false), Ok(vec![3, 0]));
+
+        let drawn = method.distribute(&[1.0, 1.0, 1.0], &2, &true).unwrap();
+        assert_eq!(drawn.iter().sum::<usize>(), 2);
+        assert!(drawn.iter().all(|s| *s <= 1));
+        assert_eq!(
+            method.distribute(&[1.0, 1.0, 1.0], &2, &false),
+            Err(DistributionError::Tied)
+        );
+        assert_eq!(
+            method.distribute(&[1.0, 1.0], &0, &false),
+            Err(DistributionError::InvalidSeatCount)
+        );
+    }
+
+    #[test]
+    fn droop() {
+        let method = LargestRemainder::droop();
+        assert_eq!(method.quota(), Quota::Droop);
+        // the quota is ⌊158100 / 11⌋ + 1 = 14373 votes, leaving remainders of 13381, 10381, 9554 and
+        // 9800 votes, while the Hare quota of 15810 votes leaves 9070, 6070, 6680 and 9800 votes
+        let votes = [56500.0, 53500.0, 38300.0, 9800.0];
+        assert_eq!(method.distribute(&votes, &10, &false), Ok(vec![4, 4, 2, 0]));
+        let hare = LargestRemainder::hare();
+        assert_eq!(hare.distribute(&votes, &10, &false), Ok(vec![4, 3, 2, 1]));
+
+        // the quota is ⌊100 / 12⌋ + 1 = 9 votes, leaving remainders of 5, 8, 1 and 5 votes
+        let votes = [59.0, 26.0, 10.0, 5.0];
+        assert_eq!(
+            method.distribute(&votes, &11, &false),
+            Err(DistributionError::Tied)
+        );
+
+        // a quota of 1 vote leaves more seats than parties
+        assert_eq!(
+            method.distribute(&[2.0, 1.0, 0.0], &7, &false),
+            Err(DistributionError::TooFewVotes)
+        );
+        assert_eq!(
+            method.distribute(&[-1.0, 2.0], &1, &false),
+            Err(DistributionError::NegativeVotes)
+        );
+    }
+
+    #[test]
+    fn droop_shares() {
+        let method = LargestRemainder::droop();
+        let shares = [41.5, 25.7, 8.6, 8.4];
+        let votes = [415000.0, 257000.0, 86000.0, 84000.0];
+        assert_eq!(
+            method.distribute(&votes, &631, &false),
+            Ok(vec![311, 193, 64, 63])
+        );
+        assert_eq!(
+            method.distribute(&shares, &631, &false),
+            Ok(vec![311, 193, 64, 63])
+        );
+        let scaled: Vec<f64> = shares.iter().map(|s| s * 1e-3).collect();
+        assert_eq!(
+            method.distribute(&scaled, &631, &false),
+            Ok(vec![311, 193, 64, 63])
+        );
+
+        // every share is a multiple of the quota of 1 / 4, which is reached 4 times for 3 seats
+        let shares = [0.5, 0.25, 0.25];
+        assert_eq!(
+            method.distribute(&shares, &3, &false),
+            Err(DistributionError::Tied)
+        );
+        let drawn = method.distribute(&shares, &3, &true).unwrap();
+        assert_eq!(drawn.iter().sum::<usize>(), 3);
+        assert!(drawn
+            .iter()
+            .zip([2, 1, 1].iter())
+            .all(|(s, max)| s <= max && s + 1 >= *max));
+    }
+
+    #[test]
+    fn tied_remainders() {
+        let method = LargestRemainder::hare();
+        // the quota is 60 / 5 = 12 votes, leaving remainders of 0, 6, 6 and 0 votes for 1 seat
+        let votes = [24.0, 18.0, 6.0, 12.0];
+        assert_eq!(
+            method.distribute(&votes, &5, &false),
+            Err(DistributionError::Tied)
+        );
+        let outcome = method.evaluate(&votes, &5, &true).unwrap();
+        let draw = outcome.draw().unwrap();
+        assert_eq!((draw.candidates(), draw.seats()), (&[1, 2][..], 1));
+        assert_eq!((outcome.seats()[0], outcome.seats()[3]), (2, 1));
+        assert_eq!(outcome.seats()[1] + outcome.seats()[2], 2);
+        assert_eq!(
+            LowestIndex(method).allocate(&votes, &5),
+            Ok(vec![2, 2, 0, 1])
+        );
+
+        // no draw is needed if all tied parties receive a seat
+        let outcome = method.evaluate(&[18.0, 18.0, 24.0], &6, &true).unwrap();
+        assert_eq!((outcome.seats(), outcome.draw()), (&[2, 2, 2][..], None));
+
+        // every share is a multiple of the Hagenbach-Bischoff quota of 1 / 4, reached 4 times for 3 seats
+        let droop = LargestRemainder::droop();
+        let outcome = droop.evaluate(&[0.5, 0.25, 0.25], &3, &true).unwrap();
+        let draw = outcome.draw().unwrap();
+        assert_eq!((draw.candidates(), draw.seats()), (&[0, 1, 2][..], 2));
+        assert_eq!(
+            LowestIndex(droop).allocate(&[0.5, 0.25, 0.25], &3),
+            Ok(vec![2, 1, 0])
+        );
+    }
+}
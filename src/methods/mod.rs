@@ -0,0 +1,575 @@
+//! The seat allocation methods and the [`Allocator`] abstraction over them, with the [`divisors`] and [`quotas`] of common methods for custom pipelines.
+
+use crate::progress::{Control, Progress, Tracker};
+use crate::{validate, DistributionError, Outcome};
+
+pub mod divisors;
+mod exact;
+mod highest_averages;
+mod largest_remainder;
+pub mod quotas;
+mod search;
+mod stationary;
+
+pub(crate) use highest_averages::{allocate, allocate_bounded};
+use highest_averages::{allocate_with, Ties};
+pub use largest_remainder::{LargestRemainder, Quota};
+pub use search::{DivisorSearch, Rounding};
+pub use stationary::Stationary;
+
+/// The seat allocation methods implemented by this crate.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum Method {
+    /// The Sainte-Laguë method, see [`distribute`](crate::distribute).
+    #[default]
+    SainteLague,
+
+    /// The D'Hondt (or Jefferson) method, whose divisors 1, 2, 3, … favour larger parties more than those of Sainte-Laguë. Validation and ties are the same as for [`distribute`](crate::distribute).
+    ///
+    /// ```
+    /// use sainte_lague::Method;
+    ///
+    /// let votes = [620.0, 240.0, 140.0];
+    /// assert_eq!(Method::SainteLague.distribute(&votes, &10, &false), Ok(vec![6, 3, 1]));
+    /// assert_eq!(Method::DHondt.distribute(&votes, &10, &false), Ok(vec![7, 2, 1]));
+    /// ```
+    DHondt,
+
+    /// The Huntington-Hill method used to apportion the seats of the US House of Representatives, whose divisor for seat `s + 1` is the geometric mean `√(s(s + 1))` of `s` and `s + 1`. As the first divisor is zero, every party with votes receives a seat before any party receives a second one. With fewer seats than parties with votes, all of them are tied for these seats. Validation and ties are otherwise the same as for [`distribute`](crate::distribute).
+    ///
+    /// ```
+    /// use sainte_lague::{DistributionError, Method};
+    ///
+    /// let votes = [900.0, 90.0, 10.0];
+    /// assert_eq!(Method::SainteLague.distribute(&votes, &10, &false), Ok(vec![9, 1, 0]));
+    /// assert_eq!(Method::HuntingtonHill.distribute(&votes, &10, &false), Ok(vec![8, 1, 1]));
+    /// assert_eq!(
+    ///     Method::HuntingtonHill.distribute(&votes, &2, &false),
+    ///     Err(DistributionError::Tied)
+    /// );
+    /// ```
+    HuntingtonHill,
+
+    /// The Adams method of smallest divisors 0, 1, 2, …, which rounds every quotient up and thus favours smaller parties more than Sainte-Laguë. Like [`HuntingtonHill`](Method::HuntingtonHill), it gives every party with votes a seat first, and with fewer seats than parties with votes, all of them are tied for these seats. Validation and ties are otherwise the same as for [`distribute`](crate::distribute).
+    ///
+    /// ```
+    /// use sainte_lague::Method;
+    ///
+    /// let votes = [620.0, 240.0, 140.0];
+    /// assert_eq!(Method::SainteLague.distribute(&votes, &10, &false), Ok(vec![6, 3, 1]));
+    /// assert_eq!(Method::Adams.distribute(&votes, &10, &false), Ok(vec![6, 2, 2]));
+    /// ```
+    Adams,
+
+    /// The Dean method, whose divisor for seat `s + 1` is the harmonic mean `2s(s + 1) / (2s + 1)` of `s` and `s + 1`. Its bias lies between those of Adams and Huntington-Hill, and like them it gives every party with votes a seat first. With fewer seats than parties with votes, all of them are tied for these seats. Validation and ties are otherwise the same as for [`distribute`](crate::distribute).
+    ///
+    /// ```
+    /// use sainte_lague::Method;
+    ///
+    /// let votes = [2000.0, 780.0, 220.0];
+    /// assert_eq!(Method::Dean.distribute(&votes, &10, &false), Ok(vec![6, 3, 1]));
+    /// assert_eq!(Method::DHondt.distribute(&votes, &10, &false), Ok(vec![7, 3, 0]));
+    /// ```
+    Dean,
+
+    /// The Danish method with the divisors 1/3, 4/3, 7/3, …, i.e. `s + 1/3` for seat `s + 1`, a third of the common `1, 4, 7, …` convention, which Denmark uses to distribute the seats of a party among its lists. It favours smaller parties more than Sainte-Laguë, but less than Adams, and doesn't give every party with votes a seat first. Validation and ties are the same as for [`distribute`](crate::distribute).
+    ///
+    /// ```
+    /// use sainte_lague::Method;
+    ///
+    /// let votes = [2000.0, 780.0, 220.0];
+    /// assert_eq!(Method::SainteLague.distribute(&votes, &5, &false), Ok(vec![4, 1, 0]));
+    /// assert_eq!(Method::Danish.distribute(&votes, &5, &false), Ok(vec![3, 1, 1]));
+    /// ```
+    Danish,
+
+    /// The Imperiali method with the divisors 2, 3, 4, …, used for Belgian municipal councils and formerly in Italy. It is D'Hondt without its first divisor, so it favours larger parties even more. Validation and ties are the same as for [`distribute`](crate::distribute).
+    ///
+    /// ```
+    /// use sainte_lague::Method;
+    ///
+    /// let votes = [2000.0, 780.0, 220.0];
+    /// assert_eq!(Method::DHondt.distribute(&votes, &10, &false), Ok(vec![7, 3, 0]));
+    /// assert_eq!(Method::Imperiali.distribute(&votes, &10, &false), Ok(vec![8, 2, 0]));
+    /// ```
+    Imperiali,
+}
+
+impl Method {
+    /// Calculate the distribution for the given `votes` and `seat_count` using this method. Arguments and error cases are the same as for [`distribute`](crate::distribute).
+    ///
+    /// ```
+    /// use sainte_lague::Method;
+    ///
+    /// let distribution = Method::SainteLague.distribute(&[362.0, 318.0, 126.0], &10, &false);
+    /// assert_eq!(distribution, Ok(vec![4, 4, 2]));
+    /// ```
+    pub fn distribute(
+        &self,
+        votes: &[f64],
+        seat_count: &usize,
+        draw_on_tie: &bool,
+    ) -> Result<Vec<usize>, DistributionError> {
+        self.evaluate(votes, seat_count, draw_on_tie)
+            .map(Outcome::into_seats)
+    }
+
+    pub(crate) fn evaluate(
+        &self,
+        votes: &[f64],
+        seat_count: &usize,
+        draw_on_tie: &bool,
+    ) -> Result<Outcome, DistributionError> {
+        self.evaluate_bounded(votes, seat_count, draw_on_tie, &[])
+    }
+
+    /// See [`allocate_bounded`] for the meaning of `bounds`.
+    pub(crate) fn evaluate_bounded(
+        &self,
+        votes: &[f64],
+        seat_count: &usize,
+        draw_on_tie: &bool,
+        bounds: &[(usize, usize)],
+    ) -> Result<Outcome, DistributionError> {
+        match self {
+            Method::SainteLague => allocate_bounded(votes, seat_count, draw_on_tie, bounds),
+            Method::DHondt
+            | Method::HuntingtonHill
+            | Method::Adams
+            | Method::Dean
+            | Method::Danish
+            | Method::Imperiali => {
+                allocate_with(votes, seat_count, draw_on_tie.into(), bounds, |s| {
+                    self.divisor(s)
+                })
+            }
+        }
+    }
+
+    /// The distributions for every seat count from 1 to `max_seat_count`, in this order. Equivalent to calling [`Method::distribute`] without drawing lots for each seat count, but much faster: divisor methods never take a seat away from a party when the parliament grows, so each distribution starts from the previous one and only the additional seat has to be awarded.
+    ///
+    /// ```
+    /// use sainte_lague::{DistributionError, Method};
+    ///
+    /// let sweep = Method::SainteLague.sweep_seats(&[2.0, 1.0, 1.0], &4);
+    /// assert_eq!(sweep[0], Ok(vec![1, 0, 0]));
+    /// assert_eq!(sweep[1], Err(DistributionError::Tied));
+    /// assert_eq!(sweep[2], Ok(vec![1, 1, 1]));
+    /// assert_eq!(sweep[3], Ok(vec![2, 1, 1]));
+    /// ```
+    pub fn sweep_seats(
+        &self,
+        votes: &[f64],
+        max_seat_count: &usize,
+    ) -> Vec<Result<Vec<usize>, DistributionError>> {
+        self.sweep_seats_with_progress(votes, max_seat_count, |_| Control::Continue)
+    }
+
+    /// Same as [`Method::sweep_seats`], but reports the [`Progress`] to the given callback after every distribution. If the callback cancels the sweep, only the distributions computed so far are returned.
+    pub fn sweep_seats_with_progress<P: FnMut(Progress) -> Control>(
+        &self,
+        votes: &[f64],
+        max_seat_count: &usize,
+        progress: P,
+    ) -> Vec<Result<Vec<usize>, DistributionError>> {
+        if let Err(e) = validate(votes, &1) {
+            return vec![Err(e); *max_seat_count];
+        }
+        let votes = highest_averages::rescale(votes);
+        let mut tracker = Tracker::new(*max_seat_count, progress);
+        let next_quotient = |party: usize, seats: usize| votes[party] / self.divisor(seats);
+        let mut seats = vec![0; votes.len()];
+        let mut sweep = Vec::with_capacity(*max_seat_count);
+        while sweep.len() < *max_seat_count {
+            let before = sweep.len();
+            // parties without votes never receive seats
+            let highest = (0..votes.len())
+                .filter(|p| votes[*p] > 0.0)
+                .map(|p| next_quotient(p, seats[p]))
+                .fold(0.0, f64::max);
+            let tied: Vec<usize> = (0..votes.len())
+                .filter(|p| votes[*p] > 0.0 && next_quotient(*p, seats[*p]) == highest)
+                .collect();
+            // all tied parties have to receive a seat before the next distribution is unambiguous
+            for _ in 1..tied.len() {
+                sweep.push(Err(DistributionError::Tied));
+            }
+            for p in tied.iter() {
+                seats[*p] += 1;
+            }
+            highest_averages::debug_assert_distribution(&votes, &(sweep.len() + 1), &[], &seats);
+            sweep.push(Ok(seats.clone()));
+            sweep.truncate(*max_seat_count);
+            if !tracker.advance(sweep.len() - before) {
+                break;
+            }
+        }
+        sweep
+    }
+
+    /// The divisor of a party's votes when competing for its seat number `seats + 1`.
+    pub(crate) fn divisor(&self, seats: usize) -> f64 {
+        match self {
+            Method::SainteLague => seats as f64 + 0.5,
+            Method::DHondt => seats as f64 + 1.0,
+            Method::HuntingtonHill => (seats as f64 * (seats as f64 + 1.0)).sqrt(),
+            Method::Adams => seats as f64,
+            Method::Dean => {
+                let s = seats as f64;
+                2.0 * s * (s + 1.0) / (2.0 * s + 1.0)
+            }
+            Method::Danish => seats as f64 + 1.0 / 3.0,
+            Method::Imperiali => seats as f64 + 2.0,
+        }
+    }
+
+    /// A stable, human-readable identifier of the method, e.g. `"sainte-lague"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Method::SainteLague => "sainte-lague",
+            Method::DHondt => "dhondt",
+            Method::HuntingtonHill => "huntington-hill",
+            Method::Adams => "adams",
+            Method::Dean => "dean",
+            Method::Danish => "danish",
+            Method::Imperiali => "imperiali",
+        }
+    }
+}
+
+/// Anything that deterministically distributes seats for given votes, such as a [`Method`], constrained [`Rules`](crate::constraints::Rules) or a closure wrapping a more elaborate configuration.
+///
+/// Since the result must be reproducible, allocators never resolve ties by drawing lots: [`Method`] and [`Rules`](crate::constraints::Rules) report ties as [`DistributionError::Tied`].
+pub trait Allocator {
+    /// The error type, which must be able to represent a [`DistributionError`].
+    type Error: From<DistributionError> + PartialEq;
+
+    /// Distribute `seat_count` seats according to `votes`.
+    fn allocate(&self, votes: &[f64], seat_count: &usize) -> Result<Vec<usize>, Self::Error>;
+}
+
+impl Allocator for Method {
+    type Error = DistributionError;
+
+    fn allocate(&self, votes: &[f64], seat_count: &usize) -> Result<Vec<usize>, DistributionError> {
+        self.distribute(votes, seat_count, &false)
+    }
+}
+
+/// Resolves ties for the last seats by awarding them to the tied parties with the lowest indices, instead of failing like the wrapped method. No electoral law resolves ties this way, so the results must never be presented as legal distributions, but they are reproducible and skip the random number generator. This is meant for simulations and benchmarks that distribute many scenarios, where exact ties are rare and their resolution doesn't matter, but failing or drawing lots does.
+///
+/// ```
+/// use sainte_lague::batch::{Batch, Kernel};
+/// use sainte_lague::methods::LowestIndex;
+/// use sainte_lague::{Allocator, Method};
+///
+/// assert_eq!(LowestIndex(Method::SainteLague).allocate(&[1.0, 3.0, 1.0], &2), Ok(vec![1, 1, 0]));
+///
+/// let mut batch = Batch::new();
+/// batch.push(&[1.0, 1.0], 1);
+/// assert_eq!(LowestIndex(Method::SainteLague).run(&batch).seats(0), Ok(&[1, 0][..]));
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct LowestIndex<M>(pub M);
+
+impl Allocator for LowestIndex<Method> {
+    type Error = DistributionError;
+
+    fn allocate(&self, votes: &[f64], seat_count: &usize) -> Result<Vec<usize>, DistributionError> {
+        allocate_with(votes, seat_count, Ties::LowestIndex, &[], |s| {
+            self.0.divisor(s)
+        })
+        .map(Outcome::into_seats)
+    }
+}
+
+impl<F, E> Allocator for F
+where
+    F: Fn(&[f64], &usize) -> Result<Vec<usize>, E>,
+    E: From<DistributionError> + PartialEq,
+{
+    type Error = E;
+
+    fn allocate(&self, votes: &[f64], seat_count: &usize) -> Result<Vec<usize>, E> {
+        self(votes, seat_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{divisors, LowestIndex, Method, Stationary};
+    use crate::progress::Control;
+    use crate::Allocator;
+    use crate::DistributionError;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn sweep_matches_single_distributions() {
+        let mut rng = StdRng::seed_from_u64(462);
+        for _ in 0..200 {
+            let party_count = rng.gen_range(0..8);
+            // small vote counts produce many ties
+            let votes: Vec<f64> = (0..party_count)
+                .map(|_| rng.gen_range(0..20) as f64)
+                .collect();
+            let max_seat_count = rng.gen_range(0..60);
+            let sweep = Method::SainteLague.sweep_seats(&votes, &max_seat_count);
+            assert_eq!(sweep.len(), max_seat_count);
+            for (i, distribution) in sweep.into_iter().enumerate() {
+                let expected = Method::SainteLague.distribute(&votes, &(i + 1), &false);
+                assert_eq!(distribution, expected, "{:?} {}", votes, i + 1);
+            }
+        }
+        assert_eq!(
+            Method::SainteLague.sweep_seats(&[1.7e308, 1e308], &2),
+            vec![Ok(vec![1, 0]), Ok(vec![1, 1])]
+        );
+        assert_eq!(
+            Method::SainteLague.sweep_seats(&[1.0, -1.0], &2),
+            vec![Err(DistributionError::NegativeVotes); 2]
+        );
+    }
+
+    #[test]
+    fn dhondt() {
+        let stationary = Stationary::new(1.0).unwrap();
+        let mut rng = StdRng::seed_from_u64(501);
+        for _ in 0..200 {
+            let votes: Vec<f64> = (0..rng.gen_range(0..8))
+                .map(|_| rng.gen_range(0..20) as f64)
+                .collect();
+            let seat_count = rng.gen_range(0..40);
+            let expected = stationary.distribute(&votes, &seat_count, &false);
+            assert_eq!(
+                Method::DHondt.distribute(&votes, &seat_count, &false),
+                expected
+            );
+            if seat_count > 0 {
+                let sweep = Method::DHondt.sweep_seats(&votes, &seat_count);
+                assert_eq!(sweep[seat_count - 1], expected);
+            }
+        }
+        assert_eq!(
+            Method::DHondt.distribute(&[2.0, 1.0], &2, &false),
+            Err(DistributionError::Tied)
+        );
+        assert_eq!(
+            Method::DHondt.distribute(&[1.0, -1.0], &2, &false),
+            Err(DistributionError::NegativeVotes)
+        );
+        assert_eq!(Method::DHondt.name(), "dhondt");
+    }
+
+    #[test]
+    fn huntington_hill() {
+        let method = Method::HuntingtonHill;
+        let mut rng = StdRng::seed_from_u64(502);
+        for _ in 0..200 {
+            let votes: Vec<f64> = (0..rng.gen_range(1..8))
+                .map(|_| rng.gen_range(0..1000) as f64)
+                .collect();
+            let seat_count = rng.gen_range(1..40);
+            let with_votes = votes.iter().filter(|v| **v > 0.0).count();
+            match method.distribute(&votes, &seat_count, &true) {
+                Ok(seats) => {
+                    for (v, s) in votes.iter().zip(seats.iter()) {
+                        match seat_count >= with_votes {
+                            true => assert_eq!(*s > 0, *v > 0.0),
+                            // drawn among the parties with votes
+                            false => assert!(*s <= 1 && (*s == 0 || *v > 0.0)),
+                        }
+                    }
+                    let sweep = method.sweep_seats(&votes, &seat_count);
+                    if let Ok(swept) = &sweep[seat_count - 1] {
+                        assert_eq!(swept, &seats);
+                    }
+                }
+                Err(e) => assert_eq!(e, DistributionError::NoVotes),
+            }
+        }
+        assert_eq!(method.divisor(0), 0.0);
+        assert_eq!(method.divisor(2), 6f64.sqrt());
+    }
+
+    #[test]
+    fn adams() {
+        let stationary = Stationary::new(0.0).unwrap();
+        let mut rng = StdRng::seed_from_u64(503);
+        for _ in 0..200 {
+            let votes: Vec<f64> = (0..rng.gen_range(0..8))
+                .map(|_| rng.gen_range(0..20) as f64)
+                .collect();
+            let seat_count = rng.gen_range(0..40);
+            assert_eq!(
+                Method::Adams.distribute(&votes, &seat_count, &false),
+                stationary.distribute(&votes, &seat_count, &false)
+            );
+        }
+        assert_eq!(
+            Method::Adams.distribute(&[100.0, 1.0, 0.0], &2, &false),
+            Ok(vec![1, 1, 0])
+        );
+        assert_eq!(
+            Method::Adams.distribute(&[100.0, 1.0, 1.0], &2, &false),
+            Err(DistributionError::Tied)
+        );
+        assert_eq!(
+            LowestIndex(Method::Adams).allocate(&[100.0, 1.0, 1.0], &2),
+            Ok(vec![1, 1, 0])
+        );
+    }
+
+    #[test]
+    fn divisors_are_ordered() {
+        // the signposts of the classical methods lie between s and s + 1, in the order of their bias
+        let methods = [
+            Method::Adams,
+            Method::Dean,
+            Method::HuntingtonHill,
+            Method::SainteLague,
+            Method::DHondt,
+        ];
+        for s in 0..50 {
+            let divisors: Vec<f64> = methods.iter().map(|m| m.divisor(s)).collect();
+            assert_eq!(divisors[0], s as f64);
+            assert_eq!(divisors[4], s as f64 + 1.0);
+            assert!(divisors.windows(2).all(|d| d[0] <= d[1]), "{:?}", divisors);
+        }
+        assert_eq!(Method::Dean.divisor(1), 4.0 / 3.0);
+        assert_eq!(
+            Method::Dean.distribute(&[5.0, 1.0, 0.0], &2, &false),
+            Ok(vec![1, 1, 0])
+        );
+    }
+
+    #[test]
+    fn danish() {
+        let divisors: Vec<f64> = (0..4).map(|s| Method::Danish.divisor(s)).collect();
+        assert_eq!(divisors, divisors::danish(4));
+        let classic: Vec<f64> = divisors.iter().map(|d| d * 3.0).collect();
+        assert_eq!(classic, vec![1.0, 4.0, 7.0, 10.0]);
+        // 4 / 4 ties with 1 / 1 for the second seat
+        assert_eq!(
+            Method::Danish.distribute(&[4.0, 1.0], &2, &false),
+            Err(DistributionError::Tied)
+        );
+        assert_eq!(
+            Method::Danish.distribute(&[5.0, 1.0], &2, &false),
+            Ok(vec![2, 0])
+        );
+        assert_eq!(
+            Method::Danish.distribute(&[1.0, -1.0], &2, &false),
+            Err(DistributionError::NegativeVotes)
+        );
+        let drawn = Method::Danish.distribute(&[4.0, 1.0], &2, &true).unwrap();
+        assert!(drawn == vec![2, 0] || drawn == vec![1, 1]);
+    }
+
+    #[test]
+    fn imperiali() {
+        // the second seat of the larger party ties with the first seat of the smaller one under D'Hondt
+        assert_eq!(
+            Method::DHondt.distribute(&[2.0, 1.0], &2, &false),
+            Err(DistributionError::Tied)
+        );
+        assert_eq!(
+            Method::Imperiali.distribute(&[2.0, 1.0], &2, &false),
+            Ok(vec![2, 0])
+        );
+        assert_eq!(
+            Method::Imperiali.distribute(&[3.0, 2.0], &2, &false),
+            Err(DistributionError::Tied)
+        );
+        assert_eq!(
+            Method::Imperiali.distribute(&[1.0, 1.0], &0, &false),
+            Err(DistributionError::InvalidSeatCount)
+        );
+    }
+
+    #[test]
+    fn sweep_progress() {
+        let votes = [2.0, 1.0, 1.0];
+        let mut reports = vec![];
+        let sweep = Method::SainteLague.sweep_seats_with_progress(&votes, &4, |p| {
+            reports.push((p.done, p.total));
+            Control::Continue
+        });
+        assert_eq!(sweep, Method::SainteLague.sweep_seats(&votes, &4));
+        // the tied seats are reported at once
+        assert_eq!(reports, vec![(1, 4), (3, 4), (4, 4)]);
+
+        let cancelled =
+            Method::SainteLague.sweep_seats_with_progress(&votes, &4, |_| Control::Cancel);
+        assert_eq!(cancelled, vec![Ok(vec![1, 0, 0])]);
+    }
+
+    #[test]
+    fn lowest_index_ties() {
+        let modified = Stationary::modified_sainte_lague();
+        let mut rng = StdRng::seed_from_u64(496);
+        for _ in 0..200 {
+            let votes: Vec<f64> = (0..rng.gen_range(1..6))
+                .map(|_| rng.gen_range(0..10) as f64)
+                .collect();
+            let seat_count = rng.gen_range(1..20);
+            let fast = LowestIndex(Method::SainteLague).allocate(&votes, &seat_count);
+            match Method::SainteLague.distribute(&votes, &seat_count, &false) {
+                Err(DistributionError::Tied) => {
+                    // only the tied parties can end up with different seats than after a draw
+                    let fast = fast.unwrap();
+                    let drawn = Method::SainteLague
+                        .evaluate(&votes, &seat_count, &true)
+                        .unwrap();
+                    let candidates = drawn.draw().unwrap().candidates();
+                    for (p, (f, d)) in fast.iter().zip(drawn.seats()).enumerate() {
+                        assert!(
+                            f == d || candidates.contains(&p),
+                            "{:?} {}",
+                            votes,
+                            seat_count
+                        );
+                    }
+                }
+                expected => assert_eq!(fast, expected),
+            }
+            assert_eq!(
+                LowestIndex(modified).allocate(&votes, &seat_count).is_ok(),
+                votes.iter().any(|v| *v > 0.0)
+            );
+        }
+        assert_eq!(
+            LowestIndex(Method::SainteLague).allocate(&[3.0, 1.0, 1.0, 1.0], &3),
+            Ok(vec![2, 1, 0, 0])
+        );
+        assert_eq!(
+            LowestIndex(modified).allocate(&[1.0, 1.0, 1.0], &4),
+            Ok(vec![2, 1, 1])
+        );
+        assert_eq!(
+            LowestIndex(Method::SainteLague).allocate(&[0.0, 0.0], &1),
+            Err(DistributionError::NoVotes)
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn invariants() {
+        use super::highest_averages::debug_assert_distribution;
+
+        debug_assert_distribution(&[2.0, 0.0, 1.0], &3, &[], &[2, 0, 1]);
+        debug_assert_distribution(&[2.0, 0.0], &3, &[(0, 3), (1, 1)], &[2, 1]);
+        let violations: [(&[f64], usize, &[usize]); 4] = [
+            (&[2.0, 1.0], 3, &[2, 0]),
+            (&[2.0, 0.0], 2, &[1, 1]),
+            (&[2.0, 1.0], 2, &[2]),
+            (&[2.0, 1.0], 2, &[3, 0]),
+        ];
+        for (votes, seat_count, seats) in violations.iter() {
+            let result = std::panic::catch_unwind(|| {
+                debug_assert_distribution(votes, seat_count, &[], seats)
+            });
+            assert!(result.is_err(), "{:?} {} {:?}", votes, seat_count, seats);
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! The quotas of common largest remainder and single transferable vote systems.
+//!
+//! A quota is the number of votes a party (or candidate) needs for one seat, given the `votes` cast and the number of `seats`. All functions take the votes as floating point numbers like the rest of the crate, and return an infinite quota if there is nothing to divide by.
+//!
+//! ```
+//! use sainte_lague::methods::quotas;
+//!
+//! assert_eq!(quotas::hare(1_000.0, 4), 250.0);
+//! assert_eq!(quotas::droop(1_000.0, 4), 201.0);
+//! assert_eq!(quotas::hagenbach_bischoff(1_000.0, 4), 200.0);
+//! assert_eq!(quotas::imperiali(1_000.0, 4), 1_000.0 / 6.0);
+//! ```
+
+/// The Hare quota `votes / seats`, used by the largest remainder method of Hare-Niemeyer (Hamilton).
+pub fn hare(votes: f64, seats: usize) -> f64 {
+    votes / seats as f64
+}
+
+/// The Droop quota `⌊votes / (seats + 1)⌋ + 1`, the smallest whole number of votes that no more than `seats` candidates can reach at the same time, as used by most STV elections. Note the `+ 1` after rounding down, not before. Rounding depends on the unit of the votes, so the formula is only meaningful for whole numbers of votes.
+pub fn droop(votes: f64, seats: usize) -> f64 {
+    (votes / (seats + 1) as f64).floor() + 1.0
+}
+
+/// The Hagenbach-Bischoff quota `votes / (seats + 1)`, also known as the exact Droop quota, used with fractional transfers and by some largest remainder systems.
+pub fn hagenbach_bischoff(votes: f64, seats: usize) -> f64 {
+    votes / (seats + 1) as f64
+}
+
+/// The Imperiali quota `votes / (seats + 2)`. It can award more seats than available in a largest remainder system, which then have to be taken away again.
+pub fn imperiali(votes: f64, seats: usize) -> f64 {
+    votes / (seats + 2) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{droop, hagenbach_bischoff, hare, imperiali};
+
+    #[test]
+    fn formulas() {
+        // a single seat needs a majority
+        assert_eq!(droop(100.0, 1), 51.0);
+        assert_eq!(droop(101.0, 1), 51.0);
+        assert_eq!(droop(99.0, 2), 34.0);
+        assert_eq!(hagenbach_bischoff(99.0, 2), 33.0);
+        assert_eq!(hare(99.0, 0), f64::INFINITY);
+        assert_eq!(imperiali(0.0, 0), 0.0);
+    }
+}
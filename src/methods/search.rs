@@ -0,0 +1,361 @@
+use super::exact::compare_products;
+use super::highest_averages::{debug_assert_distribution, rescale};
+use super::Allocator;
+use crate::{validate, DistributionError};
+use rand::seq::SliceRandom;
+use std::cmp::Ordering;
+
+/// How [`DivisorSearch`] rounds the quotient of a party's votes and the divisor to its number of seats.
+///
+/// Between `n` and `n + 1` seats lies the signpost `n + offset`: quotients above it are rounded up, quotients below it down. The rules only differ for quotients exactly at a signpost, which many electoral laws define explicitly instead of leaving them to a draw.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Rounding {
+    /// Signposts with the given offset between `0.0` (Adams, rounding up) and `1.0` (D'Hondt, rounding down), which serves every stationary divisor method. Quotients exactly at a signpost are rounded down, so they are tied if their seat is needed.
+    Signpost(f64),
+
+    /// Standard rounding for Sainte-Laguë, with halves rounded up.
+    HalfUp,
+
+    /// Standard rounding for Sainte-Laguë, with halves rounded to the even number of seats.
+    HalfEven,
+}
+
+impl Rounding {
+    /// The offset of the signposts.
+    pub fn offset(&self) -> f64 {
+        match self {
+            Rounding::Signpost(offset) => *offset,
+            Rounding::HalfUp | Rounding::HalfEven => 0.5,
+        }
+    }
+
+    /// Whether a quotient exactly at the signpost between `n` and `n + 1` seats is rounded up.
+    fn rounds_up_at(&self, n: usize) -> bool {
+        match self {
+            Rounding::Signpost(_) => false,
+            Rounding::HalfUp => true,
+            Rounding::HalfEven => n % 2 == 1,
+        }
+    }
+
+    /// Round a non-negative quotient to a number of seats.
+    fn round(&self, quotient: f64) -> usize {
+        let n = quotient.floor();
+        let signpost = n + self.offset();
+        if quotient > signpost || (quotient == signpost && self.rounds_up_at(n as usize)) {
+            n as usize + 1
+        } else {
+            n as usize
+        }
+    }
+}
+
+/// A divisor method computed by searching for a divisor for which the rounded quotients of all parties add up to the number of seats, as electoral laws usually define it, instead of awarding the seats one by one. The search starts from the total votes per seat, so the number of steps depends on the number of parties, but not on the number of seats.
+///
+/// The [`Rounding`] rule decides quotients exactly at a signpost, so the same search serves every stationary method, and follows the laws that specify the direction at exact halves. Whether a quotient is exactly at a signpost is decided without rounding errors, by comparing the products of votes and signposts exactly instead of dividing. With [`Rounding::Signpost`], the results are the same as for [`Stationary`](super::Stationary) with the same offset, except that `Stationary` may consider quotients tied that differ by less than the precision of a float.
+///
+/// ```
+/// use sainte_lague::methods::{DivisorSearch, Rounding};
+/// use sainte_lague::DistributionError;
+///
+/// let dhondt = DivisorSearch::new(Rounding::Signpost(1.0)).unwrap();
+/// assert_eq!(dhondt.distribute(&[620.0, 240.0, 140.0], &10, &false), Ok(vec![7, 2, 1]));
+///
+/// // with a divisor of 2, the quotients 1.5 and 0.5 are both exactly at a signpost
+/// let votes = [3.0, 1.0];
+/// let half_up = DivisorSearch::new(Rounding::HalfUp).unwrap();
+/// assert_eq!(half_up.distribute(&votes, &2, &false), Err(DistributionError::Tied));
+/// let half_even = DivisorSearch::new(Rounding::HalfEven).unwrap();
+/// assert_eq!(half_even.distribute(&votes, &2, &false), Ok(vec![2, 0]));
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct DivisorSearch {
+    rounding: Rounding,
+}
+
+impl DivisorSearch {
+    /// Create a divisor search with the given rounding rule. Returns [`None`] if the offset of the signposts is not between `0.0` and `1.0`.
+    pub fn new(rounding: Rounding) -> Option<DivisorSearch> {
+        if !(0.0..=1.0).contains(&rounding.offset()) {
+            return None;
+        }
+        Some(DivisorSearch { rounding })
+    }
+
+    /// The rounding rule.
+    pub fn rounding(&self) -> Rounding {
+        self.rounding
+    }
+
+    /// Calculate the distribution for the given `votes` and `seat_count`. Arguments and error cases are the same as for [`distribute`](crate::distribute). The result is tied if no divisor yields exactly `seat_count` seats under the rounding rule.
+    pub fn distribute(
+        &self,
+        votes: &[f64],
+        seat_count: &usize,
+        draw_on_tie: &bool,
+    ) -> Result<Vec<usize>, DistributionError> {
+        self.distribute_bounded(votes, seat_count, draw_on_tie, &[])
+    }
+
+    /// Same as [`DivisorSearch::distribute`], but every party receives between the minimum and maximum number of seats given in `bounds` (or is unbounded if `bounds` is empty): the rounded quotients are clamped to the bounds. The caller must make sure the bounds are feasible, see [`allocate_bounded`](super::highest_averages::allocate_bounded).
+    pub(crate) fn distribute_bounded(
+        &self,
+        votes: &[f64],
+        seat_count: &usize,
+        draw_on_tie: &bool,
+        bounds: &[(usize, usize)],
+    ) -> Result<Vec<usize>, DistributionError> {
+        validate(votes, seat_count)?;
+        let votes = rescale(votes);
+        let bound = |party: usize| bounds.get(party).cloned().unwrap_or((0, usize::MAX));
+        let total: f64 = votes.iter().sum();
+        let divisor = total / *seat_count as f64;
+        let mut seats: Vec<usize> = votes
+            .iter()
+            .enumerate()
+            .map(|(p, v)| {
+                let (min, max) = bound(p);
+                self.rounding.round(v / divisor).clamp(min, max)
+            })
+            .collect();
+
+        // the divisor below which a party with `s` seats receives another one is its votes
+        // divided by the signpost `s + offset`, and the divisor above which it loses one its
+        // votes divided by `s - 1 + offset`; the divisors of all parties must be on the same side
+        let offset = self.rounding.offset();
+        let next = |s: usize| s as f64 + offset;
+        let last = |s: usize| s as f64 - 1.0 + offset;
+        loop {
+            // the first party with the highest divisor to gain and the lowest one to lose a seat
+            let mut gainer: Option<usize> = None;
+            let mut loser: Option<usize> = None;
+            for (p, s) in seats.iter().enumerate() {
+                let (min, max) = bound(p);
+                if votes[p] > 0.0
+                    && *s < max
+                    && gainer.is_none_or(|g| {
+                        compare_divisors(&votes, (p, next(*s)), (g, next(seats[g])))
+                            == Ordering::Greater
+                    })
+                {
+                    gainer = Some(p);
+                }
+                if *s > min
+                    && loser.is_none_or(|l| {
+                        compare_divisors(&votes, (p, last(*s)), (l, last(seats[l])))
+                            == Ordering::Less
+                    })
+                {
+                    loser = Some(p);
+                }
+            }
+            let awarded: usize = seats.iter().sum();
+            if awarded < *seat_count {
+                seats[gainer.expect("feasible bounds")] += 1;
+                continue;
+            }
+            if awarded > *seat_count {
+                seats[loser.expect("feasible bounds")] -= 1;
+                continue;
+            }
+            // all parties may be at their bounds
+            let (gainer, loser) = match (gainer, loser) {
+                (Some(gainer), Some(loser)) => (gainer, loser),
+                _ => break,
+            };
+            let gain = (gainer, next(seats[gainer]));
+            match compare_divisors(&votes, gain, (loser, last(seats[loser]))) {
+                Ordering::Greater => {
+                    seats[loser] -= 1;
+                    seats[gainer] += 1;
+                }
+                Ordering::Less => break,
+                Ordering::Equal => {
+                    self.resolve(&votes, &mut seats, bounds, gain, draw_on_tie)?;
+                    break;
+                }
+            }
+        }
+
+        debug_assert_distribution(&votes, seat_count, bounds, &seats);
+        Ok(seats)
+    }
+
+    /// Round the quotients exactly at a signpost for the divisor at which the seats are about to change, given as a party and its signpost. Fails if the rounding rule doesn't yield the right number of seats, unless lots are drawn among these parties.
+    fn resolve(
+        &self,
+        votes: &[f64],
+        seats: &mut [usize],
+        bounds: &[(usize, usize)],
+        divisor: (usize, f64),
+        draw_on_tie: &bool,
+    ) -> Result<(), DistributionError> {
+        let offset = self.rounding.offset();
+        // the tied parties within their bounds, each at the signpost to its next seat or to its last one
+        let mut next = vec![];
+        let mut last = vec![];
+        for (p, s) in seats.iter().enumerate() {
+            let (min, max) = bounds.get(p).cloned().unwrap_or((0, usize::MAX));
+            let at = |signpost| compare_divisors(votes, (p, signpost), divisor) == Ordering::Equal;
+            if votes[p] > 0.0 && *s < max && at(*s as f64 + offset) {
+                next.push(p);
+            } else if *s > min && at(*s as f64 - 1.0 + offset) {
+                last.push(p);
+            }
+        }
+        let gained: Vec<usize> = next
+            .iter()
+            .cloned()
+            .filter(|p| self.rounding.rounds_up_at(seats[*p]))
+            .collect();
+        let lost: Vec<usize> = last
+            .iter()
+            .cloned()
+            .filter(|p| !self.rounding.rounds_up_at(seats[*p] - 1))
+            .collect();
+        if gained.len() == lost.len() {
+            gained.iter().for_each(|p| seats[*p] += 1);
+            lost.iter().for_each(|p| seats[*p] -= 1);
+            return Ok(());
+        }
+        if !draw_on_tie {
+            return Err(DistributionError::Tied);
+        }
+        last.iter().for_each(|p| seats[*p] -= 1);
+        let mut candidates = [next, last.clone()].concat();
+        candidates.sort_unstable();
+        for p in candidates.choose_multiple(&mut rand::thread_rng(), last.len()) {
+            seats[*p] += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Compare the divisors at which two parties reach their signposts, each given as the index of the party and the signpost, i.e. compare their votes divided by their signposts. The comparison is exact, so quotients exactly at a signpost are detected even if the divisions would round differently, and a signpost of zero is an infinite divisor.
+fn compare_divisors(votes: &[f64], (p, a): (usize, f64), (q, b): (usize, f64)) -> Ordering {
+    compare_products(votes[p], b, votes[q], a)
+}
+
+impl Allocator for DivisorSearch {
+    type Error = DistributionError;
+
+    fn allocate(&self, votes: &[f64], seat_count: &usize) -> Result<Vec<usize>, DistributionError> {
+        self.distribute(votes, seat_count, &false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DivisorSearch, Rounding};
+    use crate::methods::Stationary;
+    use crate::DistributionError;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn same_as_stationary() {
+        let mut rng = StdRng::seed_from_u64(505);
+        for offset in [0.0, 1.0 / 3.0, 0.5, 1.0].iter() {
+            let search = DivisorSearch::new(Rounding::Signpost(*offset)).unwrap();
+            let stationary = Stationary::new(*offset).unwrap();
+            for _ in 0..300 {
+                let votes: Vec<f64> = (0..rng.gen_range(1..8))
+                    .map(|_| rng.gen_range(0..1000) as f64)
+                    .collect();
+                let seat_count = rng.gen_range(0..60);
+                let expected = stationary.distribute(&votes, &seat_count, &false);
+                assert_eq!(
+                    search.distribute(&votes, &seat_count, &false),
+                    expected,
+                    "{:?} {}",
+                    votes,
+                    seat_count
+                );
+                if expected == Err(DistributionError::Tied) {
+                    let drawn = search.distribute(&votes, &seat_count, &true).unwrap();
+                    assert_eq!(drawn.iter().sum::<usize>(), seat_count);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bounded_same_as_stationary() {
+        let mut rng = StdRng::seed_from_u64(483);
+        for offset in [0.0, 0.5, 1.0].iter() {
+            let search = DivisorSearch::new(Rounding::Signpost(*offset)).unwrap();
+            let stationary = Stationary::new(*offset).unwrap();
+            for _ in 0..300 {
+                let party_count = rng.gen_range(1..8);
+                let votes: Vec<f64> = (0..party_count)
+                    .map(|_| rng.gen_range(0..1000) as f64)
+                    .collect();
+                let seat_count = rng.gen_range(1..40);
+                let bounds: Vec<(usize, usize)> = (0..party_count)
+                    .map(|p| {
+                        let min = rng.gen_range(0..3);
+                        // parties without votes can't receive more than their minimum
+                        match votes[p] > 0.0 {
+                            true => (min, min + rng.gen_range(0..20)),
+                            false => (min, min),
+                        }
+                    })
+                    .collect();
+                let minimums: usize = bounds.iter().map(|(min, _)| min).sum();
+                let maximums: usize = bounds.iter().map(|(_, max)| max).sum();
+                if minimums > seat_count || maximums < seat_count {
+                    continue;
+                }
+                assert_eq!(
+                    search.distribute_bounded(&votes, &seat_count, &false, &bounds),
+                    stationary.distribute_bounded(&votes, &seat_count, &false, &bounds),
+                    "{:?} {} {:?}",
+                    votes,
+                    seat_count,
+                    bounds
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn exact_signposts() {
+        // (3 + 2ε) / 1.5 is slightly less than (1 + ε) / 0.5, but both divisions round to 2 + 2ε
+        let votes = [1.0 + f64::EPSILON, 3.0 + 2.0 * f64::EPSILON];
+        assert_eq!(votes[1] / 1.5, votes[0] / 0.5);
+        let sainte_lague = Stationary::new(0.5).unwrap();
+        assert_eq!(
+            sainte_lague.distribute(&votes, &2, &false),
+            Err(DistributionError::Tied)
+        );
+        let search = DivisorSearch::new(Rounding::Signpost(0.5)).unwrap();
+        assert_eq!(search.distribute(&votes, &2, &false), Ok(vec![1, 1]));
+        let half_up = DivisorSearch::new(Rounding::HalfUp).unwrap();
+        assert_eq!(half_up.distribute(&votes, &2, &false), Ok(vec![1, 1]));
+    }
+
+    #[test]
+    fn halves() {
+        let half_up = DivisorSearch::new(Rounding::HalfUp).unwrap();
+        let half_even = DivisorSearch::new(Rounding::HalfEven).unwrap();
+        let votes = [5.0, 3.0, 2.0];
+        assert_eq!(half_up.distribute(&votes, &4, &false), Ok(vec![2, 1, 1]));
+        assert_eq!(half_even.distribute(&votes, &4, &false), Ok(vec![2, 1, 1]));
+        // with a divisor of 2, the quotients are 2.5, 1.5 and 1, and only rounding to even adds up
+        assert_eq!(
+            half_up.distribute(&votes, &5, &false),
+            Err(DistributionError::Tied)
+        );
+        assert_eq!(half_even.distribute(&votes, &5, &false), Ok(vec![2, 2, 1]));
+        let drawn = half_up.distribute(&votes, &5, &true).unwrap();
+        assert!(drawn == vec![3, 1, 1] || drawn == vec![2, 2, 1]);
+
+        assert_eq!(half_up.rounding().offset(), 0.5);
+        assert_eq!(DivisorSearch::new(Rounding::Signpost(1.5)), None);
+        assert_eq!(DivisorSearch::new(Rounding::Signpost(f64::NAN)), None);
+        assert_eq!(
+            half_up.distribute(&[1.0, -1.0], &2, &false),
+            Err(DistributionError::NegativeVotes)
+        );
+    }
+}
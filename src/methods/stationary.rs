@@ -0,0 +1,173 @@
+use super::highest_averages::{allocate_with, Ties};
+use super::{Allocator, LowestIndex};
+use crate::{DistributionError, Outcome};
+
+/// A stationary divisor method: a party competing for its seat number `s + 1` has its votes divided by `s + offset`, with an offset between `0.0` (Adams) and `1.0` (D'Hondt). Sainte-Laguë has an offset of `0.5`.
+///
+/// Some electoral systems raise the hurdle for the first seat by using a larger first divisor, followed by the standard divisors, e.g. the modified Sainte-Laguë method used in Norway and Sweden, whose divisors 1.4, 3, 5, 7, … are equivalent to 0.7, 1.5, 2.5, 3.5, ….
+///
+/// ```
+/// use sainte_lague::methods::Stationary;
+///
+/// let votes = [600.0, 300.0, 60.0];
+/// let sainte_lague = Stationary::new(0.5).unwrap();
+/// assert_eq!(sainte_lague.distribute(&votes, &10, &false), Ok(vec![6, 3, 1]));
+///
+/// let modified = sainte_lague.with_first_divisor(0.7).unwrap();
+/// assert_eq!(modified, Stationary::modified_sainte_lague());
+/// assert_eq!(modified.distribute(&votes, &10, &false), Ok(vec![7, 3, 0]));
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Stationary {
+    offset: f64,
+    first_divisor: f64,
+}
+
+impl Stationary {
+    /// Create a stationary divisor method with the given offset, which is also its first divisor. Returns [`None`] if the offset is not between `0.0` and `1.0`.
+    pub fn new(offset: f64) -> Option<Stationary> {
+        if !(0.0..=1.0).contains(&offset) {
+            return None;
+        }
+        Some(Stationary {
+            offset,
+            first_divisor: offset,
+        })
+    }
+
+    /// The modified Sainte-Laguë method, with a first divisor of `0.7` followed by the Sainte-Laguë divisors `1.5`, `2.5`, ….
+    pub fn modified_sainte_lague() -> Stationary {
+        Stationary {
+            offset: 0.5,
+            first_divisor: 0.7,
+        }
+    }
+
+    /// Use a different divisor for the first seat of every party. Returns [`None`] if it is negative, or larger than the divisor of the second seat, `1.0 + offset`.
+    pub fn with_first_divisor(self, first_divisor: f64) -> Option<Stationary> {
+        if !(0.0..=1.0 + self.offset).contains(&first_divisor) {
+            return None;
+        }
+        Some(Stationary {
+            first_divisor,
+            ..self
+        })
+    }
+
+    /// The offset of all divisors except the first one.
+    pub fn offset(&self) -> f64 {
+        self.offset
+    }
+
+    /// The divisor for the first seat of every party.
+    pub fn first_divisor(&self) -> f64 {
+        self.first_divisor
+    }
+
+    /// The divisor of a party's votes when competing for its seat number `seats + 1`.
+    pub fn divisor(&self, seats: usize) -> f64 {
+        match seats {
+            0 => self.first_divisor,
+            seats => seats as f64 + self.offset,
+        }
+    }
+
+    /// Calculate the distribution for the given `votes` and `seat_count` using this method. Arguments and error cases are the same as for [`distribute`](crate::distribute).
+    pub fn distribute(
+        &self,
+        votes: &[f64],
+        seat_count: &usize,
+        draw_on_tie: &bool,
+    ) -> Result<Vec<usize>, DistributionError> {
+        self.distribute_bounded(votes, seat_count, draw_on_tie, &[])
+    }
+
+    /// Same as [`Stationary::distribute`], but every party receives between the minimum and maximum number of seats given in `bounds`, which the caller must make feasible, see [`allocate_bounded`](super::highest_averages::allocate_bounded).
+    pub(crate) fn distribute_bounded(
+        &self,
+        votes: &[f64],
+        seat_count: &usize,
+        draw_on_tie: &bool,
+        bounds: &[(usize, usize)],
+    ) -> Result<Vec<usize>, DistributionError> {
+        allocate_with(votes, seat_count, draw_on_tie.into(), bounds, |s| {
+            self.divisor(s)
+        })
+        .map(Outcome::into_seats)
+    }
+}
+
+impl Allocator for Stationary {
+    type Error = DistributionError;
+
+    fn allocate(&self, votes: &[f64], seat_count: &usize) -> Result<Vec<usize>, DistributionError> {
+        self.distribute(votes, seat_count, &false)
+    }
+}
+
+impl Allocator for LowestIndex<Stationary> {
+    type Error = DistributionError;
+
+    fn allocate(&self, votes: &[f64], seat_count: &usize) -> Result<Vec<usize>, DistributionError> {
+        allocate_with(votes, seat_count, Ties::LowestIndex, &[], |s| {
+            self.0.divisor(s)
+        })
+        .map(Outcome::into_seats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Stationary;
+    use crate::{distribute, DistributionError};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn same_as_sainte_lague() {
+        let method = Stationary::new(0.5).unwrap();
+        let mut rng = StdRng::seed_from_u64(463);
+        for _ in 0..200 {
+            let votes: Vec<f64> = (0..rng.gen_range(1..8))
+                .map(|_| rng.gen_range(0..1000) as f64)
+                .collect();
+            let seat_count = rng.gen_range(0..50);
+            assert_eq!(
+                method.distribute(&votes, &seat_count, &false),
+                distribute(&votes, &seat_count, &false)
+            );
+        }
+    }
+
+    #[test]
+    fn first_divisor() {
+        let dhondt = Stationary::new(1.0).unwrap();
+        assert_eq!(
+            dhondt.distribute(&[620.0, 240.0, 140.0], &10, &false),
+            Ok(vec![7, 2, 1])
+        );
+        let divisors: Vec<f64> = (0..3)
+            .map(|s| Stationary::modified_sainte_lague().divisor(s))
+            .collect();
+        assert_eq!(divisors, vec![0.7, 1.5, 2.5]);
+
+        // the first seats of all parties are awarded first with a divisor of zero
+        let adams = Stationary::new(0.0).unwrap();
+        assert_eq!(adams.distribute(&[1000.0, 1.0], &2, &false), Ok(vec![1, 1]));
+        assert_eq!(
+            adams.distribute(&[1000.0, 1.0, 1.0], &2, &false),
+            Err(DistributionError::Tied)
+        );
+
+        assert_eq!(Stationary::new(1.1), None);
+        assert_eq!(Stationary::new(-0.1), None);
+        assert_eq!(Stationary::new(f64::NAN), None);
+        assert_eq!(dhondt.with_first_divisor(2.5), None);
+        assert_eq!(dhondt.with_first_divisor(-1.0), None);
+        assert_eq!(
+            dhondt.with_first_divisor(2.0).map(|m| m.first_divisor()),
+            Some(2.0)
+        );
+        assert_eq!(dhondt.offset(), 1.0);
+    }
+}
@@ -0,0 +1,278 @@
+//! Disproportionality metrics comparing a distribution of seats with the underlying votes.
+//!
+//! All functions take the `votes` as passed to [`distribute`](crate::distribute) and the resulting `seats`, in the same order. They return [`None`] if both slices differ in length, the votes are negative or sum up to zero, or no seats were distributed.
+//!
+//! ```
+//! use sainte_lague::{distribute, metrics};
+//!
+//! let votes = [41.5, 25.7, 8.6, 8.4];
+//! let seats = distribute(&votes, &631, &false).unwrap();
+//!
+//! let gallagher = metrics::gallagher(&votes, &seats).unwrap();
+//! assert!(gallagher > 0.0 && gallagher < 0.1);
+//! ```
+//!
+//! [`seat_prices`] reports the quotient each seat was won with instead, the votes a party paid for it at the margin.
+
+use crate::Method;
+
+/// Vote and seat shares in percent, or [`None`] for invalid input.
+fn shares(votes: &[f64], seats: &[usize]) -> Option<Vec<(f64, f64)>> {
+    if votes.len() != seats.len() || votes.iter().any(|v| v < &0.0) {
+        return None;
+    }
+    let total_votes: f64 = votes.iter().sum();
+    let total_seats: usize = seats.iter().sum();
+    if total_votes == 0.0 || total_seats == 0 {
+        return None;
+    }
+    Some(
+        votes
+            .iter()
+            .zip(seats)
+            .map(|(v, s)| {
+                (
+                    100.0 * v / total_votes,
+                    100.0 * (*s as f64) / (total_seats as f64),
+                )
+            })
+            .collect(),
+    )
+}
+
+/// The Gallagher index (least squares index), in percentage points: `sqrt(Σ (v - s)² / 2)`, where `v` and `s` are the vote and seat shares of each party in percent.
+pub fn gallagher(votes: &[f64], seats: &[usize]) -> Option<f64> {
+    let shares = shares(votes, seats)?;
+    let sum: f64 = shares.iter().map(|(v, s)| (v - s).powi(2)).sum();
+    Some((sum / 2.0).sqrt())
+}
+
+/// The Sainte-Laguë index: `Σ (s - v)² / v`, where `v` and `s` are the vote and seat shares of each party in percent. This is the quantity the Sainte-Laguë method minimizes, which is why it's commonly reported alongside it.
+///
+/// Parties without votes and without seats don't contribute to the index. If a party without votes received seats, the index is infinite.
+pub fn sainte_lague_index(votes: &[f64], seats: &[usize]) -> Option<f64> {
+    let shares = shares(votes, seats)?;
+    Some(
+        shares
+            .iter()
+            .map(|(v, s)| weighted_square(*s - *v, *v))
+            .sum(),
+    )
+}
+
+/// Pearson's chi-squared statistic `Σ (S - E)² / E`, comparing the seats `S` of each party with the seats `E` it would receive under perfect proportionality. This equals the [`sainte_lague_index`] scaled by the number of seats divided by 100.
+///
+/// Parties without votes and without seats don't contribute to the statistic. If a party without votes received seats, the statistic is infinite.
+pub fn chi_squared(votes: &[f64], seats: &[usize]) -> Option<f64> {
+    let shares = shares(votes, seats)?;
+    let total_seats: usize = seats.iter().sum();
+    let scale = (total_seats as f64) / 100.0;
+    Some(
+        shares
+            .iter()
+            .map(|(v, s)| weighted_square(scale * (s - v), scale * v))
+            .sum(),
+    )
+}
+
+/// The Samuels–Snyder malapportionment index, in percent: `Σ |s - p| / 2`, where `p` and `s` are the shares of the population and of the seats of each district in percent. This is the share of seats not allocated to the districts that would receive them under perfect apportionment. Most of the literature reports it as a fraction, i.e. divided by 100.
+///
+/// Unlike the other metrics, this compares districts instead of parties: pass the `populations` of the districts in place of the votes and their magnitudes in place of the seats.
+///
+/// ```
+/// use sainte_lague::metrics;
+///
+/// let populations = [500_000.0, 300_000.0, 200_000.0];
+/// let magnitudes = [4, 3, 3];
+/// assert_eq!(metrics::malapportionment(&populations, &magnitudes), Some(10.0));
+/// ```
+pub fn malapportionment(populations: &[f64], magnitudes: &[usize]) -> Option<f64> {
+    let shares = shares(populations, magnitudes)?;
+    let sum: f64 = shares.iter().map(|(p, s)| (s - p).abs()).sum();
+    Some(sum / 2.0)
+}
+
+/// A seat and the quotient it was won with, see [`seat_prices`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct SeatPrice {
+    /// The position of the seat in the order the seats were awarded, starting at 1.
+    pub seat: usize,
+    /// The index of the party that won the seat.
+    pub party: usize,
+    /// The seat number of the party, starting at 1.
+    pub party_seat: usize,
+    /// The winning quotient: the votes of the party divided by the divisor for its seat number.
+    pub quotient: f64,
+}
+
+/// Every awarded seat with its price, from the most expensive to the cheapest, see [`seat_prices`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct SeatPrices {
+    /// The seats in the order they were awarded.
+    pub seats: Vec<SeatPrice>,
+}
+
+impl SeatPrices {
+    /// The seat won with the lowest quotient, i.e. the last seat awarded, or `None` if there are no seats.
+    pub fn cheapest(&self) -> Option<&SeatPrice> {
+        self.seats.last()
+    }
+
+    /// The seat won with the highest quotient, i.e. the first seat awarded, or `None` if there are no seats.
+    pub fn most_expensive(&self) -> Option<&SeatPrice> {
+        self.seats.first()
+    }
+
+    /// The difference between the quotients of the most expensive and the cheapest seat, leaving out seats with an infinite price, or `None` if there are no other seats.
+    pub fn spread(&self) -> Option<f64> {
+        let mut finite = self.seats.iter().filter(|s| s.quotient.is_finite());
+        let most_expensive = finite.next()?.quotient;
+        Some(most_expensive - finite.next_back().map_or(most_expensive, |s| s.quotient))
+    }
+
+    /// The seats won by the party with the given index, from its first to its last seat.
+    pub fn of_party(&self, party: usize) -> Vec<&SeatPrice> {
+        let mut seats: Vec<&SeatPrice> = self.seats.iter().filter(|s| s.party == party).collect();
+        seats.sort_by_key(|s| s.party_seat);
+        seats
+    }
+}
+
+/// The price of every seat: the quotient `v / d(k)` it was won with under `method`, where `v` are the votes of the party and `d(k)` the divisor for its `k`-th seat. Seats are ordered by descending quotient, parties with equal quotients in the order they were passed, which is the order a highest averages procedure awards them in. The quotients use the divisors of the method, i.e. `0.5, 1.5, …` for Sainte-Laguë, twice the quotients of the common `1, 3, …` convention. Methods with a first divisor of 0, like [`Method::Adams`], guarantee every party with votes its first seat, these seats have an infinite price and come first.
+///
+/// ```
+/// use sainte_lague::{metrics, Method};
+///
+/// let prices = metrics::seat_prices(&Method::SainteLague, &[362.0, 318.0, 126.0], &[4, 4, 2]).unwrap();
+/// let most_expensive = prices.most_expensive().unwrap();
+/// assert_eq!((most_expensive.party, most_expensive.quotient), (0, 724.0));
+/// let cheapest = prices.cheapest().unwrap();
+/// assert_eq!((cheapest.party, cheapest.party_seat), (2, 2));
+/// assert_eq!(prices.spread(), Some(640.0));
+///
+/// let prices = metrics::seat_prices(&Method::Adams, &[362.0, 318.0, 126.0], &[4, 4, 2]).unwrap();
+/// assert_eq!(prices.most_expensive().unwrap().quotient, f64::INFINITY);
+/// assert_eq!(prices.spread(), Some(362.0 - 318.0 / 3.0));
+/// ```
+pub fn seat_prices(method: &Method, votes: &[f64], seats: &[usize]) -> Option<SeatPrices> {
+    shares(votes, seats)?;
+    let mut prices = vec![];
+    for (party, (v, s)) in votes.iter().zip(seats).enumerate() {
+        for k in 0..*s {
+            prices.push(SeatPrice {
+                seat: 0,
+                party,
+                party_seat: k + 1,
+                quotient: v / method.divisor(k),
+            });
+        }
+    }
+    // a stable sort keeps the parties of equal quotients in order
+    prices.sort_by(|a, b| b.quotient.total_cmp(&a.quotient));
+    for (i, price) in prices.iter_mut().enumerate() {
+        price.seat = i + 1;
+    }
+    Some(SeatPrices { seats: prices })
+}
+
+fn weighted_square(difference: f64, expected: f64) -> f64 {
+    if difference == 0.0 {
+        0.0
+    } else {
+        difference.powi(2) / expected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        chi_squared, gallagher, malapportionment, sainte_lague_index, seat_prices, SeatPrices,
+    };
+    use crate::Method;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn perfect_proportionality() {
+        let votes = [50.0, 30.0, 20.0, 0.0];
+        let seats = [5, 3, 2, 0];
+        assert_eq!(gallagher(&votes, &seats), Some(0.0));
+        assert_eq!(sainte_lague_index(&votes, &seats), Some(0.0));
+        assert_eq!(chi_squared(&votes, &seats), Some(0.0));
+        assert_eq!(malapportionment(&votes, &seats), Some(0.0));
+    }
+
+    #[test]
+    fn known_values() {
+        let votes = [60.0, 40.0];
+        let seats = [1, 0];
+        // shares 60/40 vs 100/0
+        assert_close(gallagher(&votes, &seats).unwrap(), 40.0);
+        assert_close(
+            sainte_lague_index(&votes, &seats).unwrap(),
+            40.0 * 40.0 / 60.0 + 40.0 * 40.0 / 40.0,
+        );
+        assert_close(
+            chi_squared(&votes, &seats).unwrap(),
+            0.4 * 0.4 / 0.6 + 0.4 * 0.4 / 0.4,
+        );
+        assert_close(malapportionment(&votes, &seats).unwrap(), 40.0);
+    }
+
+    #[test]
+    fn chi_squared_scales_sainte_lague_index() {
+        let votes = [362.0, 318.0, 126.0, 62.0, 53.0];
+        let seats = [39, 35, 14, 7, 6];
+        assert_close(
+            chi_squared(&votes, &seats).unwrap(),
+            sainte_lague_index(&votes, &seats).unwrap() * 101.0 / 100.0,
+        );
+    }
+
+    #[test]
+    fn seats_without_votes() {
+        let votes = [1.0, 0.0];
+        let seats = [1, 1];
+        assert_eq!(sainte_lague_index(&votes, &seats), Some(f64::INFINITY));
+        assert_eq!(chi_squared(&votes, &seats), Some(f64::INFINITY));
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert_eq!(gallagher(&[1.0], &[1, 0]), None);
+        assert_eq!(gallagher(&[0.0, 0.0], &[1, 0]), None);
+        assert_eq!(gallagher(&[1.0, -1.0, 1.0], &[1, 0, 0]), None);
+        assert_eq!(sainte_lague_index(&[1.0, 1.0], &[0, 0]), None);
+        assert_eq!(chi_squared(&[], &[]), None);
+        assert_eq!(malapportionment(&[1.0], &[]), None);
+    }
+
+    #[test]
+    fn prices() {
+        let prices = seat_prices(&Method::SainteLague, &[3.0, 1.0, 0.0], &[2, 1, 0]).unwrap();
+        let order: Vec<(usize, usize, usize, f64)> = prices
+            .seats
+            .iter()
+            .map(|s| (s.seat, s.party, s.party_seat, s.quotient))
+            .collect();
+        // 3 / 1.5 and 1 / 0.5 tie, the parties keep their order
+        assert_eq!(order, vec![(1, 0, 1, 6.0), (2, 0, 2, 2.0), (3, 1, 1, 2.0)]);
+        assert_eq!(prices.cheapest().map(|s| s.party), Some(1));
+        assert_eq!(prices.spread(), Some(4.0));
+        let party_seats: Vec<usize> = prices.of_party(0).iter().map(|s| s.seat).collect();
+        assert_eq!(party_seats, vec![1, 2]);
+
+        assert_eq!(seat_prices(&Method::SainteLague, &[1.0], &[0]), None);
+        assert_eq!(seat_prices(&Method::SainteLague, &[1.0, 1.0], &[1]), None);
+        // the guaranteed first seats of Adams are left out of the spread
+        let prices = seat_prices(&Method::Adams, &[3.0, 1.0], &[1, 1]).unwrap();
+        assert_eq!(prices.seats[1].quotient, f64::INFINITY);
+        assert_eq!(prices.spread(), None);
+        let prices = seat_prices(&Method::Adams, &[3.0, 1.0], &[3, 1]).unwrap();
+        assert_eq!(prices.spread(), Some(1.5));
+        let empty = SeatPrices { seats: vec![] };
+        assert_eq!((empty.cheapest(), empty.spread()), (None, None));
+    }
+}
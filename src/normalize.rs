@@ -0,0 +1,296 @@
+//! Helpers for converting between absolute vote counts and vote shares.
+//!
+//! Divisor methods are scale invariant: multiplying all votes by the same positive factor doesn't change the distribution. The helpers in this module only ever rescale all votes by a common factor (or move votes out of the distribution entirely), so their results can be passed to [`distribute`](crate::distribute) directly. The only caveat are quotients that tie within floating point precision, which can be resolved differently after rescaling.
+//!
+//! ```
+//! use sainte_lague::{distribute, normalize};
+//!
+//! let votes = [362_000.0, 318_000.0, 126_000.0, 62_000.0, 53_000.0];
+//! let shares = normalize::shares(&votes).unwrap();
+//! assert_eq!(distribute(&shares, &101, &false), distribute(&votes, &101, &false));
+//! ```
+//!
+//! Published shares are usually rounded and rarely add up to exactly 100 %. [`reconcile`] makes the treatment of the difference explicit, which matters for parties close to a threshold.
+
+use crate::locale::{text, text_with_all, Locale, Localize, Text};
+use std::error;
+use std::fmt;
+
+/// Convert vote counts to shares that add up to `1.0`. Returns [`None`] if any of the votes is negative or all of them add up to zero.
+pub fn shares(votes: &[f64]) -> Option<Vec<f64>> {
+    rescale(votes, 1.0)
+}
+
+/// Rescale the votes such that they add up to `total`, e.g. to turn shares of all ballots into shares of the valid ballots after removing invalid ones. Returns [`None`] if any of the votes or `total` is negative, or the votes add up to zero.
+///
+/// ```
+/// use sainte_lague::normalize;
+///
+/// // percentages of all ballots, 4 % of which were invalid
+/// let percentages = [48.0, 36.0, 12.0];
+/// let valid = normalize::rescale(&percentages, 100.0).unwrap();
+/// assert_eq!(valid, vec![50.0, 37.5, 12.5]);
+/// ```
+pub fn rescale(votes: &[f64], total: f64) -> Option<Vec<f64>> {
+    if total < 0.0 || votes.iter().any(|v| v < &0.0) {
+        return None;
+    }
+    let sum: f64 = votes.iter().sum();
+    if sum == 0.0 {
+        return None;
+    }
+    Some(votes.iter().map(|v| v / sum * total).collect())
+}
+
+/// Move the votes of all parties whose share is below `cutoff` (between `0.0` and `1.0`) into a common "others" bucket. Returns the votes with those parties set to zero, so indices stay the same, together with the total votes of the others. Returns [`None`] under the same conditions as [`shares`].
+///
+/// Parties set to zero don't receive any seats, and the votes of the other parties are left untouched, so the distribution among them is the same as if the merged parties had never been part of the input.
+///
+/// ```
+/// use sainte_lague::normalize;
+///
+/// let (votes, others) = normalize::merge_others(&[60.0, 35.0, 3.0, 2.0], 0.05).unwrap();
+/// assert_eq!(votes, vec![60.0, 35.0, 0.0, 0.0]);
+/// assert_eq!(others, 5.0);
+/// ```
+pub fn merge_others(votes: &[f64], cutoff: f64) -> Option<(Vec<f64>, f64)> {
+    let shares = shares(votes)?;
+    let mut others = 0.0;
+    let kept = votes
+        .iter()
+        .zip(shares)
+        .map(|(v, s)| {
+            if s < cutoff {
+                others += v;
+                0.0
+            } else {
+                *v
+            }
+        })
+        .collect();
+    Some((kept, others))
+}
+
+/// How [`reconcile`] treats shares that don't add up to the expected total.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Residual {
+    /// Rescale all shares by a common factor so that they add up to the total, see [`rescale`]. Every party keeps its share relative to the other listed parties, thresholds apply to the rescaled shares.
+    Renormalize,
+
+    /// Leave the shares unchanged if their sum differs from the total by at most the given tolerance, and fail with [`ShareError::Mismatch`] otherwise.
+    Reject(f64),
+
+    /// Add the difference to the total in equal parts to all parties with a positive share, which is the expected error of rounding every share to the same number of decimals. Parties whose share would become negative are set to zero and the rest of the difference is spread among the others.
+    Spread,
+}
+
+/// Possible error cases of [`reconcile`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ShareError {
+    /// The given shares contained negative values.
+    NegativeShares,
+
+    /// The given shares contained no values or all of them were zero.
+    NoShares,
+
+    /// The shares added up to `sum`, which differs from `total` by more than the tolerance, or `total` wasn't positive.
+    Mismatch {
+        /// The sum of the shares.
+        sum: f64,
+        /// The expected total.
+        total: f64,
+    },
+}
+
+impl fmt::Display for ShareError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.localize(&Locale::English))
+    }
+}
+
+impl Localize for ShareError {
+    fn localize(&self, locale: &Locale) -> String {
+        match self {
+            ShareError::NegativeShares => text(locale, Text::NegativeVotes).to_string(),
+            ShareError::NoShares => text(locale, Text::NoVotes).to_string(),
+            ShareError::Mismatch { sum, total } => {
+                text_with_all(locale, Text::SharesMismatch, &[sum, total])
+            }
+        }
+    }
+}
+
+impl error::Error for ShareError {}
+
+/// Make the `shares` add up to `total`, e.g. `100.0` for percentages, treating the difference according to `residual`.
+///
+/// ```
+/// use sainte_lague::normalize::{reconcile, Residual, ShareError};
+///
+/// // rounded to whole percent, the poll adds up to 101 %
+/// let poll = [34.0, 31.0, 21.0, 10.0, 5.0];
+/// assert_eq!(
+///     reconcile(&poll, 100.0, Residual::Reject(0.5)),
+///     Err(ShareError::Mismatch { sum: 101.0, total: 100.0 })
+/// );
+/// assert_eq!(
+///     reconcile(&poll, 100.0, Residual::Spread).unwrap(),
+///     vec![33.8, 30.8, 20.8, 9.8, 4.8]
+/// );
+/// // the last party drops below 5 % of the listed parties only after renormalizing
+/// let renormalized = reconcile(&poll, 100.0, Residual::Renormalize).unwrap();
+/// assert!(renormalized[4] < 5.0);
+/// ```
+pub fn reconcile(shares: &[f64], total: f64, residual: Residual) -> Result<Vec<f64>, ShareError> {
+    if shares.iter().any(|s| s < &0.0) {
+        return Err(ShareError::NegativeShares);
+    }
+    let sum: f64 = shares.iter().sum();
+    if sum == 0.0 {
+        return Err(ShareError::NoShares);
+    }
+    if total <= 0.0 {
+        return Err(ShareError::Mismatch { sum, total });
+    }
+    match residual {
+        Residual::Renormalize => Ok(shares.iter().map(|s| s / sum * total).collect()),
+        Residual::Reject(tolerance) => {
+            if (sum - total).abs() > tolerance {
+                Err(ShareError::Mismatch { sum, total })
+            } else {
+                Ok(shares.to_vec())
+            }
+        }
+        Residual::Spread => Ok(spread(shares, total - sum)),
+    }
+}
+
+/// Add `difference` in equal parts to all positive shares, without letting any of them fall below zero.
+fn spread(shares: &[f64], mut difference: f64) -> Vec<f64> {
+    let mut result = shares.to_vec();
+    let mut receiving: Vec<usize> = (0..shares.len()).filter(|i| shares[*i] > 0.0).collect();
+    loop {
+        let part = difference / receiving.len() as f64;
+        let (emptied, kept): (Vec<usize>, Vec<usize>) =
+            receiving.iter().partition(|i| result[**i] + part < 0.0);
+        // a negative difference is at most the sum of all shares, so some party always keeps its share
+        if emptied.is_empty() || kept.is_empty() {
+            for i in receiving.iter() {
+                result[*i] = (result[*i] + part).max(0.0);
+            }
+            return result;
+        }
+        for i in emptied {
+            difference += result[i];
+            result[i] = 0.0;
+        }
+        receiving = kept;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{merge_others, reconcile, rescale, shares, Residual, ShareError};
+    use crate::distribute;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn invalid_input() {
+        assert_eq!(shares(&[]), None);
+        assert_eq!(shares(&[0.0, 0.0]), None);
+        assert_eq!(shares(&[1.0, -1.0]), None);
+        assert_eq!(rescale(&[1.0], -1.0), None);
+        assert_eq!(merge_others(&[0.0], 0.1), None);
+    }
+
+    #[test]
+    fn shares_add_up() {
+        let shares = shares(&[1.0, 2.0, 5.0]).unwrap();
+        assert_eq!(shares, vec![0.125, 0.25, 0.625]);
+    }
+
+    #[test]
+    fn normalization_keeps_distribution() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..500 {
+            let party_count = rng.gen_range(1..10);
+            let votes: Vec<f64> = (0..party_count)
+                .map(|_| rng.gen_range(0..1_000_000) as f64)
+                .collect();
+            let seats = rng.gen_range(1..200);
+            let expected = match distribute(&votes, &seats, &false) {
+                Ok(d) => d,
+                // ties and empty inputs are not interesting here
+                Err(_) => continue,
+            };
+            assert_eq!(
+                distribute(&shares(&votes).unwrap(), &seats, &false),
+                Ok(expected.clone())
+            );
+            assert_eq!(
+                distribute(&rescale(&votes, 100.0).unwrap(), &seats, &false),
+                Ok(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn merged_parties_lose_seats_only() {
+        let votes = [40.0, 30.0, 20.0, 6.0, 4.0];
+        let (kept, others) = merge_others(&votes, 0.05).unwrap();
+        assert_eq!(others, 4.0);
+        assert_eq!(
+            distribute(&kept, &50, &false),
+            distribute(&votes[..4], &50, &false).map(|mut d| {
+                d.push(0);
+                d
+            })
+        );
+    }
+
+    #[test]
+    fn residuals() {
+        let shares = [60.0, 30.0, 9.0];
+        assert_eq!(
+            reconcile(&shares, 100.0, Residual::Reject(1.0)),
+            Ok(shares.to_vec())
+        );
+        assert_eq!(
+            reconcile(&shares, 100.0, Residual::Reject(0.5)),
+            Err(ShareError::Mismatch {
+                sum: 99.0,
+                total: 100.0
+            })
+        );
+        assert_eq!(
+            reconcile(&[3.0, 1.0], 8.0, Residual::Renormalize),
+            Ok(vec![6.0, 2.0])
+        );
+        assert_eq!(
+            reconcile(&[3.0, 0.0, 1.0], 10.0, Residual::Spread),
+            Ok(vec![6.0, 0.0, 4.0])
+        );
+        // the smallest party can't give up its share of the excess
+        assert_eq!(
+            reconcile(&[5.0, 0.5, 1.5], 4.0, Residual::Spread),
+            Ok(vec![3.75, 0.0, 0.25])
+        );
+        assert_eq!(
+            reconcile(&[1.0, -1.0], 1.0, Residual::Spread),
+            Err(ShareError::NegativeShares)
+        );
+        assert_eq!(
+            reconcile(&[0.0], 1.0, Residual::Renormalize),
+            Err(ShareError::NoShares)
+        );
+        assert_eq!(
+            ShareError::Mismatch {
+                sum: 101.0,
+                total: 100.0
+            }
+            .to_string(),
+            "Invalid shares, they add up to 101 instead of 100."
+        );
+    }
+}
@@ -0,0 +1,318 @@
+//! Checks for the classic apportionment paradoxes.
+//!
+//! Each check runs an [`Allocator`] on a scenario and a modified version of it, returning a machine-readable [`Verdict`] that tells whether the paradox occurred and which parties were affected. Any [`Method`](crate::Method) can be checked, as can closures wrapping constrained configurations.
+//!
+//! ```
+//! use sainte_lague::{paradox, Method};
+//!
+//! let votes = [362.0, 318.0, 126.0, 62.0, 53.0];
+//! let verdict = paradox::alabama(&Method::SainteLague, &votes, &101).unwrap();
+//! assert!(!verdict.occurred());
+//! ```
+//!
+//! To search for violations systematically, [`check_house_monotonicity`] and [`check_population_monotonicity`] run many of these checks at once. This is especially useful for constrained configurations, since constraints can break the monotonicity of otherwise well-behaved methods.
+
+use crate::{Allocator, DistributionError};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// The apportionment paradoxes that can be checked.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Paradox {
+    /// A party loses a seat when the size of the parliament increases.
+    Alabama,
+
+    /// A party loses a seat to another party, although its votes grew at a higher rate.
+    Population,
+
+    /// Adding a new party (together with the seats it is entitled to) changes the seats of the existing parties.
+    NewState,
+}
+
+/// The result of a paradox check.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Verdict {
+    /// The paradox that was checked for.
+    pub paradox: Paradox,
+    /// The distribution of the original scenario.
+    pub before: Vec<usize>,
+    /// The distribution of the modified scenario.
+    pub after: Vec<usize>,
+    /// Indices of the parties affected by the paradox, in ascending order. Empty if the paradox didn't occur.
+    pub affected: Vec<usize>,
+}
+
+impl Verdict {
+    /// Whether the paradox occurred.
+    pub fn occurred(&self) -> bool {
+        !self.affected.is_empty()
+    }
+}
+
+/// Check whether any party loses a seat when the parliament grows from `seat_count` to `seat_count + 1` seats.
+pub fn alabama<A: Allocator>(
+    allocator: &A,
+    votes: &[f64],
+    seat_count: &usize,
+) -> Result<Verdict, A::Error> {
+    let before = allocator.allocate(votes, seat_count)?;
+    let after = allocator.allocate(votes, &(seat_count + 1))?;
+    let affected = (0..votes.len())
+        .filter(|i| after[*i] < before[*i])
+        .collect();
+    Ok(Verdict {
+        paradox: Paradox::Alabama,
+        before,
+        after,
+        affected,
+    })
+}
+
+/// Check whether, when the votes change from `votes_before` to `votes_after` with a constant `seat_count`, a party loses a seat while another party whose votes grew at a lower rate gains one. Returns `Ok(None)` if the vote slices have different lengths.
+pub fn population<A: Allocator>(
+    allocator: &A,
+    votes_before: &[f64],
+    votes_after: &[f64],
+    seat_count: &usize,
+) -> Result<Option<Verdict>, A::Error> {
+    if votes_before.len() != votes_after.len() {
+        return Ok(None);
+    }
+    let before = allocator.allocate(votes_before, seat_count)?;
+    let after = allocator.allocate(votes_after, seat_count)?;
+    let growth: Vec<f64> = votes_before
+        .iter()
+        .zip(votes_after)
+        .map(|(b, a)| a / b)
+        .collect();
+
+    let losers = (0..before.len()).filter(|i| after[*i] < before[*i]);
+    let gainers: Vec<usize> = (0..before.len())
+        .filter(|i| after[*i] > before[*i])
+        .collect();
+    let affected = losers
+        .filter(|l| gainers.iter().any(|g| growth[*l] > growth[*g]))
+        .collect();
+    Ok(Some(Verdict {
+        paradox: Paradox::Population,
+        before,
+        after,
+        affected,
+    }))
+}
+
+/// Check whether adding a new party with `new_votes` votes and enlarging the parliament by `added_seats` seats changes the seats of any existing party. The `after` distribution of the verdict contains the new party as its last entry.
+///
+/// `added_seats` is usually the number of seats the new party is entitled to, e.g. the seats it receives in the enlarged parliament.
+pub fn new_state<A: Allocator>(
+    allocator: &A,
+    votes: &[f64],
+    new_votes: f64,
+    seat_count: &usize,
+    added_seats: &usize,
+) -> Result<Verdict, A::Error> {
+    let before = allocator.allocate(votes, seat_count)?;
+    let mut enlarged = votes.to_vec();
+    enlarged.push(new_votes);
+    let after = allocator.allocate(&enlarged, &(seat_count + added_seats))?;
+    let affected = (0..votes.len())
+        .filter(|i| after[*i] != before[*i])
+        .collect();
+    Ok(Verdict {
+        paradox: Paradox::NewState,
+        before,
+        after,
+        affected,
+    })
+}
+
+/// The result of a series of paradox checks.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct MonotonicityReport {
+    /// The number of checks that were performed.
+    pub checked: usize,
+    /// The number of checks that were skipped, because one of the distributions was tied.
+    pub skipped: usize,
+    /// The verdicts of all checks in which the paradox occurred.
+    pub violations: Vec<Verdict>,
+}
+
+impl MonotonicityReport {
+    /// Whether none of the checks found a violation.
+    pub fn is_monotone(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    fn record<E>(&mut self, verdict: Result<Verdict, E>) -> Result<(), E>
+    where
+        E: From<DistributionError> + PartialEq,
+    {
+        self.checked += 1;
+        match verdict {
+            Ok(v) if v.occurred() => self.violations.push(v),
+            Ok(_) => (),
+            Err(e) if e == E::from(DistributionError::Tied) => self.skipped += 1,
+            Err(e) => return Err(e),
+        }
+        Ok(())
+    }
+}
+
+/// Exhaustively check for the [Alabama paradox](Paradox::Alabama) for every parliament size from one seat up to `max_seats` seats.
+///
+/// Sizes at which the allocation is tied are skipped, all other errors are returned.
+///
+/// ```
+/// use sainte_lague::{paradox, Method};
+///
+/// let report = paradox::check_house_monotonicity(&Method::SainteLague, &[41.5, 25.7, 8.6], &200).unwrap();
+/// assert!(report.is_monotone());
+/// ```
+pub fn check_house_monotonicity<A: Allocator>(
+    allocator: &A,
+    votes: &[f64],
+    max_seats: &usize,
+) -> Result<MonotonicityReport, A::Error> {
+    let mut report = MonotonicityReport {
+        checked: 0,
+        skipped: 0,
+        violations: vec![],
+    };
+    for seat_count in 1..*max_seats {
+        report.record(alabama(allocator, votes, &seat_count))?;
+    }
+    Ok(report)
+}
+
+/// Statistically check for the [population paradox](Paradox::Population): in each of the `trials`, the votes of every party are multiplied by a random factor between 0.5 and 1.5 and the result is compared to the original distribution. The random numbers are drawn from a generator seeded with `seed`, so reports are reproducible.
+///
+/// Trials in which either allocation is tied are skipped, all other errors are returned.
+pub fn check_population_monotonicity<A: Allocator>(
+    allocator: &A,
+    votes: &[f64],
+    seat_count: &usize,
+    trials: usize,
+    seed: u64,
+) -> Result<MonotonicityReport, A::Error> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut report = MonotonicityReport {
+        checked: 0,
+        skipped: 0,
+        violations: vec![],
+    };
+    for _ in 0..trials {
+        let changed: Vec<f64> = votes.iter().map(|v| v * rng.gen_range(0.5..1.5)).collect();
+        let verdict = population(allocator, votes, &changed, seat_count)
+            .map(|v| v.expect("the changed votes have the same length"));
+        report.record(verdict)?;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        alabama, check_house_monotonicity, check_population_monotonicity, new_state, population,
+        Paradox,
+    };
+    use crate::methods::LargestRemainder;
+    use crate::{DistributionError, Method};
+
+    /// Hamilton's method (largest remainders), which is known to exhibit all three paradoxes.
+    fn hamilton(votes: &[f64], seat_count: &usize) -> Result<Vec<usize>, DistributionError> {
+        LargestRemainder::hare().distribute(votes, seat_count, &false)
+    }
+
+    #[test]
+    fn alabama_paradox() {
+        let votes = [1.0, 10.0, 12.0];
+        let sainte_lague = alabama(&Method::SainteLague, &votes, &10).unwrap();
+        assert!(!sainte_lague.occurred());
+
+        let verdict = alabama(&hamilton, &votes, &10).unwrap();
+        assert_eq!(verdict.paradox, Paradox::Alabama);
+        assert_eq!(verdict.before, vec![1, 4, 5]);
+        assert_eq!(verdict.after, vec![0, 5, 6]);
+        assert_eq!(verdict.affected, vec![0]);
+        assert!(verdict.occurred());
+    }
+
+    #[test]
+    fn population_paradox() {
+        let before = [72.0, 179.0, 17.0];
+        let after = [91.0, 184.0, 17.0];
+
+        let verdict = population(&hamilton, &before, &after, &10)
+            .unwrap()
+            .unwrap();
+        assert_eq!(verdict.paradox, Paradox::Population);
+        assert_eq!(verdict.before, vec![3, 7, 0]);
+        assert_eq!(verdict.after, vec![3, 6, 1]);
+        assert_eq!(verdict.affected, vec![1]);
+
+        let sainte_lague = population(&Method::SainteLague, &before, &after, &10).unwrap();
+        assert!(!sainte_lague.unwrap().occurred());
+        assert_eq!(population(&hamilton, &before, &after[..2], &10), Ok(None));
+    }
+
+    #[test]
+    fn new_state_paradox() {
+        let votes = [147.0, 25.0];
+        let verdict = new_state(&hamilton, &votes, 38.0, &10, &2).unwrap();
+        assert_eq!(verdict.paradox, Paradox::NewState);
+        assert_eq!(verdict.before, vec![9, 1]);
+        assert_eq!(verdict.after, vec![8, 2, 2]);
+        assert_eq!(verdict.affected, vec![0, 1]);
+
+        let sainte_lague = new_state(&Method::SainteLague, &votes, 38.0, &10, &2).unwrap();
+        assert_eq!(sainte_lague.after, vec![9, 1, 2]);
+        assert!(!sainte_lague.occurred());
+    }
+
+    #[test]
+    fn errors_are_propagated() {
+        assert_eq!(
+            alabama(&Method::SainteLague, &[1.0, 1.0], &1),
+            Err(DistributionError::Tied)
+        );
+    }
+
+    #[test]
+    fn house_monotonicity() {
+        let votes = [1.0, 10.0, 12.0];
+        let report = check_house_monotonicity(&Method::SainteLague, &votes, &60).unwrap();
+        assert_eq!(report.checked, 59);
+        assert!(report.is_monotone());
+
+        let report = check_house_monotonicity(&hamilton, &votes, &60).unwrap();
+        assert!(!report.is_monotone());
+        assert!(report.violations.iter().any(|v| v.before == vec![1, 4, 5]));
+
+        // 3:1 is tied at 2, 6 and 10 seats, so five of the nine checks are skipped
+        let tied = check_house_monotonicity(&Method::SainteLague, &[3.0, 1.0], &10).unwrap();
+        assert_eq!(tied.checked, 9);
+        assert_eq!(tied.skipped, 5);
+    }
+
+    #[test]
+    fn population_monotonicity() {
+        let votes = [72.0, 179.0, 17.0];
+        let report =
+            check_population_monotonicity(&Method::SainteLague, &votes, &10, 500, 42).unwrap();
+        assert_eq!(report.checked, 500);
+        assert!(report.is_monotone());
+
+        let report = check_population_monotonicity(&hamilton, &votes, &10, 500, 42).unwrap();
+        assert!(!report.is_monotone());
+        let again = check_population_monotonicity(&hamilton, &votes, &10, 500, 42).unwrap();
+        assert_eq!(report, again);
+    }
+
+    #[test]
+    fn monotonicity_errors_are_propagated() {
+        assert_eq!(
+            check_house_monotonicity(&Method::SainteLague, &[0.0], &10),
+            Err(DistributionError::NoVotes)
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! Parallel voting, also known as mixed-member majoritarian representation.
+//!
+//! In parallel systems such as those of Japan or Russia, part of the seats is won in single-member districts and the remaining list seats are distributed proportionally to the list votes. Unlike mixed-member proportional representation, the list seats don't compensate for the district seats: both tiers are computed independently and summed up.
+//!
+//! ```
+//! use sainte_lague::constraints::{Constraint, Rules};
+//! use sainte_lague::parallel::Parallel;
+//!
+//! let system = Parallel::new(Rules::default().with(Constraint::Threshold(0.05)), 10);
+//! let outcome = system.distribute(&[47.0, 35.0, 14.0, 4.0], &[7, 3, 0, 0], &false).unwrap();
+//! assert_eq!(outcome.list_seats, vec![5, 4, 1, 0]);
+//! assert_eq!(outcome.seats(), vec![12, 7, 1, 0]);
+//! ```
+
+use crate::constraints::{ConstraintError, Rules};
+use crate::pipeline::{Context, PipelineError};
+use crate::plurality;
+
+/// A parallel voting system, see the [module documentation](self).
+#[derive(Clone, PartialEq, Debug)]
+pub struct Parallel {
+    /// The rules for distributing the list seats.
+    pub rules: Rules,
+    /// The number of list seats.
+    pub list_seats: usize,
+}
+
+/// The seats of each party in both tiers of a [`Parallel`] system.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct ParallelOutcome {
+    /// The number of districts won by each party.
+    pub district_seats: Vec<usize>,
+    /// The number of list seats of each party.
+    pub list_seats: Vec<usize>,
+}
+
+impl ParallelOutcome {
+    /// The total number of seats of each party.
+    pub fn seats(&self) -> Vec<usize> {
+        self.district_seats
+            .iter()
+            .zip(self.list_seats.iter())
+            .map(|(d, l)| d + l)
+            .collect()
+    }
+}
+
+impl Parallel {
+    /// Create a parallel system with the given number of list seats, distributed according to `rules`.
+    pub fn new(rules: Rules, list_seats: usize) -> Parallel {
+        Parallel { rules, list_seats }
+    }
+
+    /// Combine the district seats won by each party with its share of the list seats, distributed according to `list_votes`. Both slices contain one entry per party, in the same order. Errors are reported with step 1 for the district tier and step 2 for the list tier.
+    pub fn distribute(
+        &self,
+        list_votes: &[f64],
+        district_seats: &[usize],
+        draw_on_tie: &bool,
+    ) -> Result<ParallelOutcome, PipelineError> {
+        if district_seats.len() != list_votes.len() {
+            let party = district_seats.len().min(list_votes.len());
+            return Err(ConstraintError::UnknownParty(party)).context(1, "district seats");
+        }
+        let list_seats = self
+            .rules
+            .distribute(list_votes, &self.list_seats, draw_on_tie)
+            .context(2, "list seats")?;
+        Ok(ParallelOutcome {
+            district_seats: district_seats.to_vec(),
+            list_seats,
+        })
+    }
+
+    /// Same as [`Parallel::distribute`], but the district seats are determined by [plurality](crate::plurality) from the votes of each district. Ties in the districts are handled according to `draw_on_tie_in_districts`, ties of the list seats according to `draw_on_tie`.
+    ///
+    /// ```
+    /// use sainte_lague::constraints::Rules;
+    /// use sainte_lague::parallel::Parallel;
+    ///
+    /// let system = Parallel::new(Rules::default(), 4);
+    /// let districts = [vec![40.0, 35.0, 25.0], vec![45.0, 30.0, 25.0]];
+    /// let outcome = system
+    ///     .distribute_districts(&[40.0, 35.0, 25.0], &districts, &false, &false)
+    ///     .unwrap();
+    /// assert_eq!(outcome.seats(), vec![4, 1, 1]);
+    /// ```
+    pub fn distribute_districts(
+        &self,
+        list_votes: &[f64],
+        districts: &[Vec<f64>],
+        draw_on_tie_in_districts: &bool,
+        draw_on_tie: &bool,
+    ) -> Result<ParallelOutcome, PipelineError> {
+        let district_seats =
+            plurality::seats(districts, list_votes.len(), draw_on_tie_in_districts)
+                .context(1, "district seats")?;
+        self.distribute(list_votes, &district_seats, draw_on_tie)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Parallel, ParallelOutcome};
+    use crate::constraints::{ConstraintError, Rules};
+    use crate::DistributionError;
+
+    #[test]
+    fn tiers_are_independent() {
+        let system = Parallel::new(Rules::default(), 6);
+        let votes = [50.0, 30.0, 20.0];
+        // the district seats don't change the list seats, unlike in compensatory systems
+        for district_seats in [[0, 0, 0], [10, 0, 0], [0, 0, 10]].iter() {
+            let outcome = system.distribute(&votes, district_seats, &false).unwrap();
+            assert_eq!(outcome.list_seats, vec![3, 2, 1]);
+            assert_eq!(&outcome.district_seats, district_seats);
+        }
+        let outcome = ParallelOutcome {
+            district_seats: vec![2, 0],
+            list_seats: vec![1, 1],
+        };
+        assert_eq!(outcome.seats(), vec![3, 1]);
+    }
+
+    #[test]
+    fn errors() {
+        let system = Parallel::new(Rules::default(), 1);
+        let error = system.distribute(&[1.0, 1.0], &[1], &false).unwrap_err();
+        assert_eq!((error.step(), error.label()), (1, "district seats"));
+        assert_eq!(error.root_cause(), &ConstraintError::UnknownParty(1));
+
+        let error = system.distribute(&[1.0, 1.0], &[1, 0], &false).unwrap_err();
+        assert_eq!(error.step(), 2);
+        assert_eq!(error.root_cause(), &DistributionError::Tied.into());
+
+        let districts = [vec![1.0, 1.0]];
+        let error = system
+            .distribute_districts(&[2.0, 1.0], &districts, &false, &false)
+            .unwrap_err();
+        assert_eq!(error.step(), 1);
+        assert_eq!(error.root_cause(), &DistributionError::Tied.into());
+        let outcome = system.distribute_districts(&[2.0, 1.0], &districts, &true, &false);
+        assert_eq!(outcome.unwrap().list_seats, vec![1, 0]);
+
+        let error = system
+            .distribute_districts(&[2.0, 1.0], &[vec![0.0, 0.0, 1.0]], &false, &false)
+            .unwrap_err();
+        assert_eq!(error.root_cause(), &ConstraintError::UnknownParty(2));
+    }
+}
@@ -0,0 +1,387 @@
+//! Party metadata and the named distribution API.
+//!
+//! A [`Party`] bundles everything the textual and visual outputs of this crate need to know about a list: a stable identifier, a display name, an abbreviation and an optional color. Using the same type everywhere keeps tables, reports and charts consistent with each other.
+//!
+//! The named distribution functions treat every entry as a separate list, even if two entries share an identifier. Inputs assembled from files should be passed through [`deduplicate`] first, which either rejects or merges duplicate identifiers.
+
+use crate::locale::{text_with_all, Locale, Localize, Text};
+use crate::{distribute, DistributionError};
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+
+/// An RGB color, usually the color a party uses in its campaign material.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Color {
+    /// Red channel.
+    pub red: u8,
+    /// Green channel.
+    pub green: u8,
+    /// Blue channel.
+    pub blue: u8,
+}
+
+impl Color {
+    /// Create a color from its red, green and blue channels.
+    pub fn new(red: u8, green: u8, blue: u8) -> Color {
+        Color { red, green, blue }
+    }
+
+    /// Parse a hexadecimal color of the form `#rrggbb` (the leading `#` is optional). Returns [`None`] for any other input.
+    ///
+    /// ```
+    /// use sainte_lague::party::Color;
+    ///
+    /// assert_eq!(Color::from_hex("#e3000f"), Some(Color::new(227, 0, 15)));
+    /// assert_eq!(Color::from_hex("red"), None);
+    /// ```
+    pub fn from_hex(hex: &str) -> Option<Color> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        Some(Color::new(channel(0)?, channel(2)?, channel(4)?))
+    }
+}
+
+/// Formats the color as lowercase `#rrggbb`, which is understood by SVG, HTML and most plotting tools.
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+    }
+}
+
+/// A party (or list) taking part in an election.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Party {
+    /// A stable, machine-readable identifier such as `"spd"`.
+    pub id: String,
+    /// The full name, e.g. `"Sozialdemokratische Partei Deutschlands"`.
+    pub name: String,
+    /// An abbreviation used where space is scarce, e.g. `"SPD"`.
+    pub short_name: String,
+    /// The color used to represent the party, if any.
+    pub color: Option<Color>,
+}
+
+impl Party {
+    /// Create a party without color whose short name equals its name.
+    pub fn new(id: &str, name: &str) -> Party {
+        Party {
+            id: id.to_string(),
+            name: name.to_string(),
+            short_name: name.to_string(),
+            color: None,
+        }
+    }
+}
+
+impl fmt::Display for Party {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.short_name)
+    }
+}
+
+/// Which parties to include in the result of [`distribute_named_filtered`].
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Include {
+    /// Include every party, even those without votes.
+    AllParties,
+
+    /// Include only parties with more than zero votes.
+    PartiesWithVotes,
+
+    /// Include only parties that received at least one seat.
+    PartiesWithSeats,
+}
+
+/// Same as [`distribute`], but for votes attached to a [`Party`] instead of plain positions in a slice. The result contains every party in input order, together with its number of seats. Use [`distribute_named_filtered`] to leave out parties without votes or seats.
+///
+/// # Examples
+///
+/// ```
+/// use sainte_lague::party::{distribute_named, Party};
+///
+/// let votes = [
+///     (Party::new("a", "Party A"), 362.0),
+///     (Party::new("b", "Party B"), 318.0),
+///     (Party::new("c", "Party C"), 126.0),
+/// ];
+///
+/// let distribution = distribute_named(&votes, &10, &false).unwrap();
+/// let seats: Vec<(&str, usize)> = distribution
+///     .iter()
+///     .map(|(party, seats)| (party.id.as_str(), *seats))
+///     .collect();
+/// assert_eq!(seats, vec![("a", 4), ("b", 4), ("c", 2)]);
+/// ```
+pub fn distribute_named<'a>(
+    votes: &'a [(Party, f64)],
+    seat_count: &usize,
+    draw_on_tie: &bool,
+) -> Result<Vec<(&'a Party, usize)>, DistributionError> {
+    distribute_named_filtered(votes, seat_count, draw_on_tie, &Include::AllParties)
+}
+
+/// Same as [`distribute_named`], but only the parties selected by `include` are part of the result. Parties without votes never receive seats, so [`Include::PartiesWithSeats`] never contains any of them.
+///
+/// ```
+/// use sainte_lague::party::{distribute_named_filtered, Include, Party};
+///
+/// let votes = [
+///     (Party::new("a", "Party A"), 90.0),
+///     (Party::new("b", "Party B"), 10.0),
+///     (Party::new("c", "Party C"), 0.0),
+/// ];
+///
+/// let with_votes = distribute_named_filtered(&votes, &4, &false, &Include::PartiesWithVotes);
+/// assert_eq!(with_votes.unwrap().len(), 2);
+///
+/// let with_seats = distribute_named_filtered(&votes, &4, &false, &Include::PartiesWithSeats);
+/// assert_eq!(with_seats.unwrap(), vec![(&votes[0].0, 4)]);
+/// ```
+pub fn distribute_named_filtered<'a>(
+    votes: &'a [(Party, f64)],
+    seat_count: &usize,
+    draw_on_tie: &bool,
+    include: &Include,
+) -> Result<Vec<(&'a Party, usize)>, DistributionError> {
+    let plain_votes: Vec<f64> = votes.iter().map(|(_, v)| *v).collect();
+    let distribution = distribute(&plain_votes, seat_count, draw_on_tie)?;
+    Ok(votes
+        .iter()
+        .zip(distribution)
+        .filter(|((_, v), s)| match include {
+            Include::AllParties => true,
+            Include::PartiesWithVotes => *v > 0.0,
+            Include::PartiesWithSeats => *s > 0,
+        })
+        .map(|((p, _), s)| (p, s))
+        .collect())
+}
+
+/// How [`deduplicate`] handles parties sharing an identifier.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub enum Duplicates {
+    /// Fail with the first duplicate found.
+    #[default]
+    Reject,
+
+    /// Add the votes of every duplicate to its first occurrence, and report each merged entry.
+    Merge,
+}
+
+/// Two entries sharing an identifier, given by their positions in the input.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct DuplicateParty {
+    /// The position of the first entry with this identifier.
+    pub first: usize,
+    /// The position of the later entry.
+    pub duplicate: usize,
+}
+
+impl fmt::Display for DuplicateParty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.localize(&Locale::English))
+    }
+}
+
+impl Localize for DuplicateParty {
+    fn localize(&self, locale: &Locale) -> String {
+        text_with_all(
+            locale,
+            Text::DuplicateParty,
+            &[&self.duplicate, &self.first],
+        )
+    }
+}
+
+impl error::Error for DuplicateParty {}
+
+/// The result of [`deduplicate`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct Deduplicated {
+    /// The votes with one entry per identifier, in the order of their first occurrence.
+    pub votes: Vec<(Party, f64)>,
+    /// Every entry that was merged into an earlier one, in input order.
+    pub merged: Vec<DuplicateParty>,
+}
+
+/// Check `votes` for parties sharing an identifier, and handle them as configured by `duplicates`. If `case_insensitive` is set, identifiers differing only in case, such as `"spd"` and `"SPD"`, are duplicates as well. Merged entries keep the metadata of the first occurrence.
+///
+/// ```
+/// use sainte_lague::party::{deduplicate, DuplicateParty, Duplicates, Party};
+///
+/// let votes = vec![
+///     (Party::new("a", "Party A"), 300.0),
+///     (Party::new("b", "Party B"), 200.0),
+///     (Party::new("A", "Party A"), 50.0),
+/// ];
+///
+/// assert!(deduplicate(&votes, &Duplicates::Reject, &false).is_ok());
+/// assert_eq!(
+///     deduplicate(&votes, &Duplicates::Reject, &true),
+///     Err(DuplicateParty { first: 0, duplicate: 2 })
+/// );
+///
+/// let merged = deduplicate(&votes, &Duplicates::Merge, &true).unwrap();
+/// assert_eq!(merged.votes[0].1, 350.0);
+/// assert_eq!(merged.merged, vec![DuplicateParty { first: 0, duplicate: 2 }]);
+/// ```
+pub fn deduplicate(
+    votes: &[(Party, f64)],
+    duplicates: &Duplicates,
+    case_insensitive: &bool,
+) -> Result<Deduplicated, DuplicateParty> {
+    // maps identifiers to their first position in the input and in the output
+    let mut seen: HashMap<String, (usize, usize)> = HashMap::new();
+    let mut deduplicated = Deduplicated {
+        votes: vec![],
+        merged: vec![],
+    };
+    for (i, (party, v)) in votes.iter().enumerate() {
+        let id = if *case_insensitive {
+            party.id.to_lowercase()
+        } else {
+            party.id.clone()
+        };
+        match seen.get(&id) {
+            Some((first, position)) => {
+                let duplicate = DuplicateParty {
+                    first: *first,
+                    duplicate: i,
+                };
+                match duplicates {
+                    Duplicates::Reject => return Err(duplicate),
+                    Duplicates::Merge => {
+                        deduplicated.votes[*position].1 += v;
+                        deduplicated.merged.push(duplicate);
+                    }
+                }
+            }
+            None => {
+                seen.insert(id, (i, deduplicated.votes.len()));
+                deduplicated.votes.push((party.clone(), *v));
+            }
+        }
+    }
+    Ok(deduplicated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        deduplicate, distribute_named, distribute_named_filtered, Color, DuplicateParty,
+        Duplicates, Include, Party,
+    };
+    use crate::locale::{Locale, Localize};
+    use crate::DistributionError;
+
+    #[test]
+    fn color_hex_round_trip() {
+        let color = Color::new(0, 128, 255);
+        assert_eq!(color.to_string(), "#0080ff");
+        assert_eq!(Color::from_hex(&color.to_string()), Some(color));
+        assert_eq!(Color::from_hex("0080FF"), Some(color));
+        assert_eq!(Color::from_hex("#0080f"), None);
+        assert_eq!(Color::from_hex("#0080fg"), None);
+    }
+
+    #[test]
+    fn named_distribution_keeps_order() {
+        let votes = [
+            (Party::new("x", "X"), 3.0),
+            (Party::new("y", "Y"), 1.0),
+            (Party::new("z", "Z"), 0.0),
+        ];
+
+        let distribution = distribute_named(&votes, &8, &false).unwrap();
+        let ids: Vec<&str> = distribution.iter().map(|(p, _)| p.id.as_str()).collect();
+        let seats: Vec<usize> = distribution.iter().map(|(_, s)| *s).collect();
+        assert_eq!(ids, vec!["x", "y", "z"]);
+        assert_eq!(seats, vec![6, 2, 0]);
+    }
+
+    #[test]
+    fn named_distribution_errors() {
+        let votes = [(Party::new("x", "X"), 3.0), (Party::new("y", "Y"), 3.0)];
+        assert_eq!(
+            distribute_named(&votes, &1, &false),
+            Err(DistributionError::Tied)
+        );
+    }
+
+    #[test]
+    fn named_distribution_filters() {
+        let votes = [
+            (Party::new("x", "X"), 0.0),
+            (Party::new("y", "Y"), 1.0),
+            (Party::new("z", "Z"), 100.0),
+        ];
+        let ids = |include: Include| -> Vec<String> {
+            distribute_named_filtered(&votes, &5, &false, &include)
+                .unwrap()
+                .into_iter()
+                .map(|(p, _)| p.id.clone())
+                .collect()
+        };
+
+        assert_eq!(ids(Include::AllParties), vec!["x", "y", "z"]);
+        assert_eq!(ids(Include::PartiesWithVotes), vec!["y", "z"]);
+        assert_eq!(ids(Include::PartiesWithSeats), vec!["z"]);
+    }
+
+    #[test]
+    fn duplicates() {
+        let votes = vec![
+            (Party::new("x", "X"), 1.0),
+            (Party::new("y", "Y"), 2.0),
+            (Party::new("x", "X2"), 3.0),
+            (Party::new("Y", "Y2"), 4.0),
+            (Party::new("x", "X3"), 5.0),
+        ];
+
+        let merged = deduplicate(&votes, &Duplicates::Merge, &false).unwrap();
+        let ids: Vec<(&str, &str, f64)> = merged
+            .votes
+            .iter()
+            .map(|(p, v)| (p.id.as_str(), p.name.as_str(), *v))
+            .collect();
+        assert_eq!(
+            ids,
+            vec![("x", "X", 9.0), ("y", "Y", 2.0), ("Y", "Y2", 4.0)]
+        );
+        assert_eq!(
+            merged.merged,
+            vec![
+                DuplicateParty {
+                    first: 0,
+                    duplicate: 2
+                },
+                DuplicateParty {
+                    first: 0,
+                    duplicate: 4
+                },
+            ]
+        );
+
+        let merged = deduplicate(&votes, &Duplicates::Merge, &true).unwrap();
+        assert_eq!(merged.votes.len(), 2);
+        assert_eq!(merged.votes[1].1, 6.0);
+
+        let error = deduplicate(&votes, &Duplicates::default(), &false).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid parties, party 2 has the same identifier as party 0."
+        );
+        assert_eq!(
+            error.localize(&Locale::German),
+            "Ungültige Parteien, Partei 2 hat dieselbe Kennung wie Partei 0."
+        );
+
+        let unique = deduplicate(&votes[..2], &Duplicates::Reject, &true).unwrap();
+        assert_eq!(unique.votes, votes[..2].to_vec());
+        assert!(unique.merged.is_empty());
+    }
+}
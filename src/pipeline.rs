@@ -0,0 +1,191 @@
+//! Errors of distributions that consist of multiple steps.
+//!
+//! Many electoral systems allocate seats in several steps, e.g. first among the parties nationally and then among the regional lists of each party. If one of these steps fails, the underlying [`ConstraintError`] or [`DistributionError`] alone doesn't tell which step it was. A [`PipelineError`] wraps it together with the number and a description of the step, and can itself be wrapped by an enclosing step. The chain is available through [`Error::source`](std::error::Error::source).
+//!
+//! ```
+//! use sainte_lague::pipeline::{Context, PipelineError};
+//! use sainte_lague::{distribute, DistributionError};
+//! use std::error::Error;
+//!
+//! fn sub_allocation(votes: &[f64]) -> Result<Vec<usize>, PipelineError> {
+//!     distribute(votes, &3, &false).context(3, "sub-allocation for party A")
+//! }
+//!
+//! let error = sub_allocation(&[1.0, 1.0]).unwrap_err();
+//! assert_eq!(error.to_string(), "Step 3 failed: sub-allocation for party A.");
+//! assert_eq!(error.root_cause(), &DistributionError::Tied.into());
+//! assert!(error.source().is_some());
+//! ```
+
+use crate::constraints::ConstraintError;
+use crate::locale::{text_with_all, Locale, Localize, Text};
+use crate::DistributionError;
+use std::error;
+use std::fmt;
+
+/// The reason a step failed.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Cause {
+    /// The distribution of this step failed.
+    Constraint(ConstraintError),
+
+    /// A nested step failed.
+    Step(PipelineError),
+}
+
+impl From<ConstraintError> for Cause {
+    fn from(error: ConstraintError) -> Cause {
+        Cause::Constraint(error)
+    }
+}
+
+impl From<DistributionError> for Cause {
+    fn from(error: DistributionError) -> Cause {
+        Cause::Constraint(error.into())
+    }
+}
+
+impl From<PipelineError> for Cause {
+    fn from(error: PipelineError) -> Cause {
+        Cause::Step(error)
+    }
+}
+
+/// A failed step of a multi-step distribution, see the [module documentation](self).
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct PipelineError {
+    step: usize,
+    label: String,
+    cause: Box<Cause>,
+}
+
+impl PipelineError {
+    /// Create an error for the step with the given number and description.
+    pub fn new(step: usize, label: &str, cause: impl Into<Cause>) -> PipelineError {
+        PipelineError {
+            step,
+            label: label.to_string(),
+            cause: Box::new(cause.into()),
+        }
+    }
+
+    /// The number of the step that failed.
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// The description of the step that failed.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// The reason the step failed.
+    pub fn cause(&self) -> &Cause {
+        &self.cause
+    }
+
+    /// The innermost step that failed, which is `self` unless the cause is a nested step.
+    pub fn innermost(&self) -> &PipelineError {
+        match self.cause() {
+            Cause::Step(e) => e.innermost(),
+            Cause::Constraint(_) => self,
+        }
+    }
+
+    /// The error of the distribution that caused the innermost step to fail.
+    pub fn root_cause(&self) -> &ConstraintError {
+        match self.innermost().cause() {
+            Cause::Constraint(e) => e,
+            Cause::Step(_) => unreachable!("the innermost step has no nested cause"),
+        }
+    }
+}
+
+impl fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.localize(&Locale::English))
+    }
+}
+
+impl Localize for PipelineError {
+    fn localize(&self, locale: &Locale) -> String {
+        text_with_all(locale, Text::StepFailed, &[&self.step, &self.label])
+    }
+}
+
+impl error::Error for PipelineError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self.cause() {
+            Cause::Constraint(e) => Some(e),
+            Cause::Step(e) => Some(e),
+        }
+    }
+}
+
+/// Attach the step to the error of a [`Result`].
+pub trait Context<T> {
+    /// Wrap the error, if any, in a [`PipelineError`] for the step with the given number and description.
+    fn context(self, step: usize, label: &str) -> Result<T, PipelineError>;
+}
+
+impl<T, E: Into<Cause>> Context<T> for Result<T, E> {
+    fn context(self, step: usize, label: &str) -> Result<T, PipelineError> {
+        self.map_err(|e| PipelineError::new(step, label, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cause, Context, PipelineError};
+    use crate::constraints::{Constraint, ConstraintError, Rules};
+    use crate::locale::{Locale, Localize};
+    use crate::DistributionError;
+    use std::error::Error;
+
+    fn nested() -> PipelineError {
+        let rules = Rules::default().with(Constraint::MinSeats(2, 1));
+        let inner = rules
+            .distribute(&[1.0, 2.0], &3, &false)
+            .context(2, "Land-level sub-allocation for party X")
+            .unwrap_err();
+        Err::<(), _>(inner)
+            .context(1, "national allocation")
+            .unwrap_err()
+    }
+
+    #[test]
+    fn chain() {
+        let error = nested();
+        assert_eq!(error.step(), 1);
+        assert_eq!(error.innermost().step(), 2);
+        assert_eq!(error.root_cause(), &ConstraintError::UnknownParty(2));
+
+        let mut messages = vec![];
+        let mut current: Option<&dyn Error> = Some(&error);
+        while let Some(e) = current {
+            messages.push(e.to_string());
+            current = e.source();
+        }
+        assert_eq!(
+            messages,
+            vec![
+                "Step 1 failed: national allocation.",
+                "Step 2 failed: Land-level sub-allocation for party X.",
+                "Invalid constraint, there is no party 2.",
+            ]
+        );
+    }
+
+    #[test]
+    fn distribution_errors() {
+        let error = PipelineError::new(1, "x", DistributionError::NoVotes);
+        assert_eq!(
+            error.cause(),
+            &Cause::Constraint(ConstraintError::Distribution(DistributionError::NoVotes))
+        );
+        assert_eq!(
+            error.localize(&Locale::German),
+            "Schritt 1 fehlgeschlagen: x."
+        );
+    }
+}
@@ -0,0 +1,83 @@
+//! Winners of single-member districts by plurality.
+//!
+//! Mixed systems such as [parallel voting](crate::parallel) combine proportional list seats with districts won by the candidate with the most votes. A district is tied if several candidates share the highest number of votes; like [`distribute`](crate::distribute), the functions of this module either fail with [`DistributionError::Tied`] or draw lots, independently of the tie policy of the list tier. Districts are given by the votes of each party's candidate, with the same party order in every district.
+//!
+//! ```
+//! use sainte_lague::plurality;
+//!
+//! let districts = [vec![40.0, 35.0, 25.0], vec![30.0, 45.0, 25.0], vec![50.0, 20.0, 30.0]];
+//! assert_eq!(plurality::winner(&districts[1], &false), Ok(1));
+//! assert_eq!(plurality::seats(&districts, 3, &false), Ok(vec![2, 1, 0]));
+//! ```
+
+use crate::methods::allocate;
+use crate::DistributionError;
+
+/// The index of the party winning a district with the given votes. Fails with the same errors as [`distribute`](crate::distribute).
+pub fn winner(votes: &[f64], draw_on_tie: &bool) -> Result<usize, DistributionError> {
+    // any divisor method awards a single seat to the party with the most votes
+    let outcome = allocate(votes, &1, draw_on_tie)?;
+    let winner = outcome.seats().iter().position(|s| *s == 1);
+    Ok(winner.expect("a single seat is always awarded"))
+}
+
+/// The winner of each district, or the error that occurred in it.
+pub fn winners(
+    districts: &[Vec<f64>],
+    draw_on_tie: &bool,
+) -> Vec<Result<usize, DistributionError>> {
+    districts
+        .iter()
+        .map(|votes| winner(votes, draw_on_tie))
+        .collect()
+}
+
+/// The number of districts won by each party, for at least `party_count` parties. Fails with the first error of any district.
+pub fn seats(
+    districts: &[Vec<f64>],
+    party_count: usize,
+    draw_on_tie: &bool,
+) -> Result<Vec<usize>, DistributionError> {
+    let longest = districts.iter().map(Vec::len).max().unwrap_or(0);
+    let mut seats = vec![0; party_count.max(longest)];
+    for votes in districts.iter() {
+        seats[winner(votes, draw_on_tie)?] += 1;
+    }
+    Ok(seats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{seats, winner, winners};
+    use crate::DistributionError;
+
+    #[test]
+    fn ties() {
+        assert_eq!(
+            winner(&[2.0, 3.0, 3.0], &false),
+            Err(DistributionError::Tied)
+        );
+        let mut won = [0, 0, 0];
+        for _ in 0..100 {
+            won[winner(&[2.0, 3.0, 3.0], &true).unwrap()] += 1;
+        }
+        assert_eq!(won[0], 0);
+        assert!(won[1] > 0 && won[2] > 0);
+    }
+
+    #[test]
+    fn districts() {
+        let districts = vec![vec![1.0, 0.0], vec![0.0, 0.0], vec![0.0, 1.0, 2.0]];
+        assert_eq!(
+            winners(&districts, &false),
+            vec![Ok(0), Err(DistributionError::NoVotes), Ok(2)]
+        );
+        assert_eq!(
+            seats(&districts, 2, &false),
+            Err(DistributionError::NoVotes)
+        );
+        assert_eq!(seats(&[districts[0].clone()], 3, &false), Ok(vec![1, 0, 0]));
+        assert_eq!(seats(&districts[2..], 2, &false), Ok(vec![0, 0, 1]));
+        assert_eq!(seats(&[], 2, &false), Ok(vec![0, 0]));
+    }
+}
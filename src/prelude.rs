@@ -0,0 +1,17 @@
+//! The most commonly used items, for glob imports.
+//!
+//! ```
+//! use sainte_lague::prelude::*;
+//!
+//! let rules = Rules::new(Method::SainteLague).with(Constraint::Threshold(0.05));
+//! let shares = normalize::shares(&[362.0, 318.0, 126.0, 40.0]).unwrap();
+//! assert_eq!(rules.distribute(&shares, &10, &false), Ok(vec![4, 4, 2, 0]));
+//! ```
+
+pub use crate::constraints::{Constraint, ConstraintError, Rules};
+pub use crate::election::Election;
+pub use crate::locale::{Locale, Localize};
+pub use crate::normalize;
+pub use crate::party::{deduplicate, distribute_named, Duplicates, Include, Party};
+pub use crate::pipeline::{Context, PipelineError};
+pub use crate::{distribute, Allocator, DistributionError, Draw, Method, Outcome, Scenario};
@@ -0,0 +1,78 @@
+//! The parliaments of Bosnia and Herzegovina, its entities and its cantons.
+//!
+//! All of them distribute their seats with the Sainte-Laguë method among the parties receiving at least 3 % of the valid votes in the respective electoral unit. District seats and the compensatory seats of the House of Representatives are not modelled separately: the presets distribute all seats of an entity in one go. The law doesn't resolve ties by drawing lots, so ties are reported as errors.
+
+use crate::constraints::Constraint;
+use crate::methods::Stationary;
+use crate::system::{ElectoralSystem, Tier};
+
+/// The threshold of all electoral units.
+const THRESHOLD: f64 = 0.03;
+
+fn assembly(name: &str, seat_count: usize) -> ElectoralSystem {
+    let sainte_lague = Stationary::new(0.5).expect("0.5 is a valid offset");
+    ElectoralSystem::new(name, sainte_lague, Tier::National(seat_count))
+        .with(Constraint::Threshold(THRESHOLD))
+}
+
+/// The 28 seats of the Federation of Bosnia and Herzegovina in the House of Representatives of Bosnia and Herzegovina.
+pub fn house_of_representatives_federation() -> ElectoralSystem {
+    assembly("Predstavnički dom PSBiH (Federacija BiH)", 28)
+}
+
+/// The 14 seats of Republika Srpska in the House of Representatives of Bosnia and Herzegovina.
+pub fn house_of_representatives_republika_srpska() -> ElectoralSystem {
+    assembly("Predstavnički dom PSBiH (Republika Srpska)", 14)
+}
+
+/// The House of Representatives of the Federation of Bosnia and Herzegovina with its 98 seats.
+pub fn federation() -> ElectoralSystem {
+    assembly("Predstavnički dom Parlamenta FBiH", 98)
+}
+
+/// The National Assembly of Republika Srpska with its 83 seats.
+pub fn republika_srpska() -> ElectoralSystem {
+    assembly("Narodna skupština Republike Srpske", 83)
+}
+
+/// The assembly of a canton with the given number of seats, e.g. 35 for the Canton of Sarajevo.
+///
+/// ```
+/// use sainte_lague::presets::bosnia_and_herzegovina::canton;
+///
+/// // the fourth party fails the threshold of 3 %
+/// assert_eq!(canton(35).distribute_votes(&[450.0, 300.0, 225.0, 25.0]), Ok(vec![16, 11, 8, 0]));
+/// ```
+pub fn canton(seat_count: usize) -> ElectoralSystem {
+    assembly("Skupština kantona", seat_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        canton, federation, house_of_representatives_federation,
+        house_of_representatives_republika_srpska, republika_srpska,
+    };
+    use crate::DistributionError;
+
+    #[test]
+    fn entity_thresholds() {
+        // each entity applies the threshold to its own votes
+        let votes = [600.0, 370.0, 30.0];
+        assert_eq!(
+            house_of_representatives_federation().distribute_votes(&votes),
+            Ok(vec![17, 10, 1])
+        );
+        let votes = [600.0, 371.0, 29.0];
+        assert_eq!(
+            house_of_representatives_republika_srpska().distribute_votes(&votes),
+            Ok(vec![9, 5, 0])
+        );
+        assert_eq!(
+            federation().seat_count() + republika_srpska().seat_count(),
+            181
+        );
+        let error = canton(2).distribute_votes(&[1.0, 1.0, 1.0]).unwrap_err();
+        assert_eq!(error.root_cause(), &DistributionError::Tied.into());
+    }
+}
@@ -0,0 +1,125 @@
+//! French regional, municipal and territorial assemblies.
+//!
+//! French councils distribute their seats with the highest averages method, which is the D'Hondt method, a [stationary divisor method](Stationary) with an offset of `1.0`. Regional and municipal councils award a [majority bonus](crate::bonus) to the list with the most votes in the decisive round. The sub-allocation of regional seats to the sections of each département is not modelled.
+//!
+//! ```
+//! use sainte_lague::presets::france::regional_council;
+//! use sainte_lague::system::Ballots;
+//!
+//! // 209 seats, a bonus of 53 seats for the strongest list
+//! let council = regional_council(209);
+//! let outcome = council.distribute(&Ballots::new(vec![45.9, 33.7, 10.8, 9.6])).unwrap();
+//! assert_eq!(outcome.bonus, vec![53, 0, 0, 0]);
+//! assert_eq!(outcome.seats(), vec![125, 53, 16, 15]);
+//! ```
+
+use crate::constraints::Constraint;
+use crate::methods::Stationary;
+use crate::system::{ElectoralSystem, ThresholdBase, Tier};
+
+/// The threshold for proportional seats of regional and municipal councils.
+const THRESHOLD: f64 = 0.05;
+
+fn council(name: &str, seat_count: usize) -> ElectoralSystem {
+    let highest_averages = Stationary::new(1.0).expect("1.0 is a valid offset");
+    ElectoralSystem::new(name, highest_averages, Tier::National(seat_count))
+        .with(Constraint::Threshold(THRESHOLD))
+}
+
+/// A regional council with the given number of seats: a quarter of the seats, rounded up, go to the list with the most votes, the remaining seats are distributed among the lists with at least 5 % of the votes.
+pub fn regional_council(seat_count: usize) -> ElectoralSystem {
+    council("Conseil régional", seat_count).with_bonus(seat_count.div_ceil(4))
+}
+
+/// The council of a municipality with at least 1000 inhabitants: half of the seats go to the list with the most votes, rounded up if there are more than four seats and rounded down otherwise. The remaining seats are distributed among the lists with at least 5 % of the votes.
+///
+/// ```
+/// use sainte_lague::presets::france::municipal_council;
+///
+/// let outcome = municipal_council(27).distribute_votes(&[52.0, 30.0, 18.0]).unwrap();
+/// assert_eq!(outcome, vec![21, 4, 2]);
+/// ```
+pub fn municipal_council(seat_count: usize) -> ElectoralSystem {
+    let bonus = match seat_count {
+        0..=4 => seat_count / 2,
+        _ => seat_count.div_ceil(2),
+    };
+    council("Conseil municipal", seat_count).with_bonus(bonus)
+}
+
+/// The three provinces of New Caledonia.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Province {
+    /// The South Province, with 40 seats.
+    South,
+    /// The North Province, with 22 seats.
+    North,
+    /// The Loyalty Islands Province, with 14 seats.
+    LoyaltyIslands,
+}
+
+impl Province {
+    /// The number of seats of the provincial assembly.
+    pub fn seat_count(&self) -> usize {
+        match self {
+            Province::South => 40,
+            Province::North => 22,
+            Province::LoyaltyIslands => 14,
+        }
+    }
+}
+
+/// The assembly of a province of New Caledonia. There is no majority bonus, but the threshold of 5 % applies to the number of registered voters, see [`Ballots::with_electorate`](crate::system::Ballots::with_electorate), instead of the votes cast, so low turnout makes it harder to pass. Ties are reported as errors.
+///
+/// ```
+/// use sainte_lague::presets::france::{new_caledonia, Province};
+/// use sainte_lague::system::Ballots;
+///
+/// // with 10 000 registered voters, 500 votes are needed
+/// let ballots = Ballots::new(vec![3_000.0, 2_500.0, 480.0]).with_electorate(10_000.0);
+/// let outcome = new_caledonia(Province::LoyaltyIslands).distribute(&ballots).unwrap();
+/// assert_eq!(outcome.seats(), vec![8, 6, 0]);
+/// ```
+pub fn new_caledonia(province: Province) -> ElectoralSystem {
+    council("Assemblée de province", province.seat_count())
+        .with_threshold_base(ThresholdBase::Electorate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{municipal_council, new_caledonia, regional_council, Province};
+    use crate::constraints::Constraint;
+    use crate::system::Ballots;
+    use crate::DistributionError;
+
+    #[test]
+    fn bonus_sizes() {
+        let bonuses: Vec<usize> = [1, 3, 4, 5, 15, 27]
+            .iter()
+            .map(|n| municipal_council(*n).bonus)
+            .collect();
+        assert_eq!(bonuses, vec![0, 1, 2, 3, 8, 14]);
+        assert_eq!(regional_council(69).bonus, 18);
+        assert_eq!(
+            regional_council(69).rules.constraints(),
+            &[Constraint::Threshold(0.05)]
+        );
+    }
+
+    #[test]
+    fn registered_voters() {
+        let ballots = Ballots::new(vec![3_000.0, 2_500.0, 520.0]).with_electorate(10_000.0);
+        let outcome = new_caledonia(Province::LoyaltyIslands).distribute(&ballots);
+        assert_eq!(outcome.map(|o| o.seats()), Ok(vec![7, 6, 1]));
+        let total: usize = [Province::South, Province::North, Province::LoyaltyIslands]
+            .iter()
+            .map(Province::seat_count)
+            .sum();
+        assert_eq!(total, 76);
+        let ballots = Ballots::new(vec![1.0, 1.0, 1.0]).with_electorate(3.0);
+        let error = new_caledonia(Province::North)
+            .distribute(&ballots)
+            .unwrap_err();
+        assert_eq!(error.root_cause(), &DistributionError::Tied.into());
+    }
+}
@@ -0,0 +1,92 @@
+//! German state parliaments using the Sainte-Laguë method.
+//!
+//! All presets distribute the regular number of seats among the parties passing a threshold of 5 % of the valid list votes. Overhang and leveling seats are not modelled: the result is the distribution before district winners exceeding the proportional seats of their party are taken into account. The laws define the method by rounding the quotients of a common divisor, and quotients exactly at a half are rounded so that the number of seats is met, or by drawing lots if that leaves several possibilities. The presets compute the seats the same way, with a [divisor search](DivisorSearch) detecting these quotients exactly.
+
+use crate::constraints::Constraint;
+use crate::methods::{DivisorSearch, Rounding};
+use crate::system::{ElectoralSystem, Tier};
+
+/// The threshold shared by all German state parliaments.
+const THRESHOLD: f64 = 0.05;
+
+fn landtag(name: &str, seat_count: usize) -> ElectoralSystem {
+    let sainte_lague = DivisorSearch::new(Rounding::Signpost(0.5)).expect("0.5 is a valid offset");
+    ElectoralSystem::new(name, sainte_lague, Tier::National(seat_count))
+        .with(Constraint::Threshold(THRESHOLD))
+        .with_draw_on_tie(true)
+}
+
+/// The Landtag of Rhineland-Palatinate with its 101 regular seats.
+pub fn rhineland_palatinate() -> ElectoralSystem {
+    landtag("Landtag Rheinland-Pfalz", 101)
+}
+
+/// The Landtag of Schleswig-Holstein with its 69 regular seats. The party of the Danish minority (SSW), at index `ssw` of the votes if it runs, is exempt from the threshold.
+///
+/// ```
+/// use sainte_lague::presets::germany::schleswig_holstein;
+///
+/// let votes = [308.0, 304.0, 132.0, 82.0, 82.0, 46.0];
+/// assert_eq!(schleswig_holstein(Some(5)).distribute_votes(&votes), Ok(vec![22, 22, 10, 6, 6, 3]));
+/// assert_eq!(schleswig_holstein(None).distribute_votes(&votes), Ok(vec![24, 23, 10, 6, 6, 0]));
+/// ```
+pub fn schleswig_holstein(ssw: Option<usize>) -> ElectoralSystem {
+    let system = landtag("Landtag Schleswig-Holstein", 69);
+    match ssw {
+        Some(ssw) => system.with(Constraint::Exemption(ssw)),
+        None => system,
+    }
+}
+
+/// The 72 seats of the electoral area of the city of Bremen in the Bürgerschaft of Bremen. The city of Bremen and Bremerhaven are separate electoral areas, each with its own threshold: a party passing it in one area only receives seats there.
+pub fn bremen() -> ElectoralSystem {
+    landtag("Bremische Bürgerschaft (Wahlbereich Bremen)", 72)
+}
+
+/// The 15 seats of the electoral area of Bremerhaven in the Bürgerschaft of Bremen, see [`bremen`].
+pub fn bremerhaven() -> ElectoralSystem {
+    landtag("Bremische Bürgerschaft (Wahlbereich Bremerhaven)", 15)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bremen, bremerhaven, rhineland_palatinate, schleswig_holstein};
+    use crate::constraints::ConstraintError;
+    use crate::methods::{DivisorSearch, Rounding};
+    use crate::system::ListMethod;
+
+    #[test]
+    fn same_as_plain_method() {
+        // the same results as the tests of the plain method, all parties pass the threshold
+        let votes = [362.0, 318.0, 126.0, 62.0, 53.0];
+        assert_eq!(
+            rhineland_palatinate().distribute_votes(&votes),
+            Ok(vec![39, 35, 14, 7, 6])
+        );
+        let votes = [308.0, 304.0, 132.0, 82.0, 82.0, 46.0];
+        assert_eq!(
+            schleswig_holstein(Some(5)).distribute_votes(&votes),
+            Ok(vec![22, 22, 10, 6, 6, 3])
+        );
+        let error = schleswig_holstein(Some(6))
+            .distribute_votes(&votes)
+            .unwrap_err();
+        assert_eq!(error.root_cause(), &ConstraintError::UnknownParty(6));
+    }
+
+    #[test]
+    fn separate_areas() {
+        // the third party only passes the threshold in Bremerhaven
+        let city = bremen().distribute_votes(&[500.0, 460.0, 40.0]).unwrap();
+        let port = bremerhaven()
+            .distribute_votes(&[450.0, 450.0, 100.0])
+            .unwrap();
+        assert_eq!((city[2], port[2]), (0, 1));
+        assert_eq!(bremen().seat_count() + bremerhaven().seat_count(), 87);
+        let search = DivisorSearch::new(Rounding::Signpost(0.5)).unwrap();
+        assert_eq!(bremen().method, ListMethod::Search(search));
+        // ties are drawn
+        let tied = bremerhaven().distribute_votes(&[1.0, 1.0]).unwrap();
+        assert!(tied == vec![8, 7] || tied == vec![7, 8]);
+    }
+}
@@ -0,0 +1,72 @@
+//! The People's Representative Council (DPR) of Indonesia.
+//!
+//! Since 2019, the seats of each electoral district are distributed with the Sainte-Laguë method among the parties that received at least 4 % of the valid votes nationwide. The magnitudes of the districts change between elections, so they are not built in: [`Tier::parse_districts`] reads them from the list published by the election commission, and [`Tier::Districts`] accepts them from any other source.
+//!
+//! ```
+//! use sainte_lague::presets::indonesia::dpr;
+//! use sainte_lague::system::{Ballots, Tier};
+//!
+//! let dpr = dpr(Tier::parse_districts("Aceh I,7\nAceh II,6").unwrap());
+//! assert_eq!(dpr.seat_count(), 13);
+//!
+//! // the third party passes the threshold nationwide, the fourth one doesn't
+//! let votes = vec![vec![500.0, 300.0, 150.0, 50.0], vec![300.0, 500.0, 20.0, 30.0]];
+//! let outcome = dpr.distribute(&Ballots::districts(votes)).unwrap();
+//! assert_eq!(outcome.proportional, vec![vec![4, 2, 1, 0], vec![2, 4, 0, 0]]);
+//! assert_eq!(outcome.seats(), vec![6, 6, 1, 0]);
+//! ```
+
+use crate::constraints::Constraint;
+use crate::methods::Stationary;
+use crate::system::{ElectoralSystem, Tier};
+
+/// The national threshold.
+pub const THRESHOLD: f64 = 0.04;
+
+/// The DPR with the given electoral districts, see the [module documentation](self). The votes are given in the order of the districts, with the same party order everywhere. Ties are reported as errors.
+pub fn dpr(districts: Tier) -> ElectoralSystem {
+    let sainte_lague = Stationary::new(0.5).expect("0.5 is a valid offset");
+    ElectoralSystem::new("Dewan Perwakilan Rakyat", sainte_lague, districts)
+        .with(Constraint::Threshold(THRESHOLD))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::dpr;
+    use crate::constraints::ConstraintError;
+    use crate::system::{Ballots, Tier};
+    use crate::DistributionError;
+
+    #[test]
+    fn parse() {
+        let tier = Tier::parse_districts("# name,seats\n\nJawa Barat I, 7\nDKI Jakarta, III,8\n");
+        assert_eq!(
+            tier,
+            Some(Tier::Districts(vec![
+                ("Jawa Barat I".to_string(), 7),
+                ("DKI Jakarta, III".to_string(), 8)
+            ]))
+        );
+        assert_eq!(Tier::parse_districts("Papua"), None);
+        assert_eq!(Tier::parse_districts("Papua,many"), None);
+        assert_eq!(Tier::parse_districts("").map(|t| t.seat_count()), Some(0));
+    }
+
+    #[test]
+    fn errors() {
+        let dpr = dpr(Tier::Districts(vec![
+            ("A".to_string(), 3),
+            ("B".to_string(), 2),
+        ]));
+        let ballots = Ballots::districts(vec![vec![1.0, 2.0], vec![1.0]]);
+        let error = dpr.distribute(&ballots).unwrap_err();
+        assert_eq!(error.step(), 2);
+        assert_eq!(error.root_cause(), &ConstraintError::UnknownParty(1));
+
+        // the second party is not eligible, leaving no votes in the second district
+        let ballots = Ballots::districts(vec![vec![100.0, 0.0], vec![0.0, 1.0]]);
+        let error = dpr.distribute(&ballots).unwrap_err();
+        assert_eq!((error.step(), error.label()), (5, "B"));
+        assert_eq!(error.root_cause(), &DistributionError::NoVotes.into());
+    }
+}
@@ -0,0 +1,102 @@
+//! The Assembly of Kosovo.
+//!
+//! Of its 120 seats, 100 are distributed with the Sainte-Laguë method among the lists receiving at least 5 % of the valid votes. The remaining 20 seats are reserved for the non-majority [`Community`] lists, which compete only for the reserved seats of their community. Their votes still count towards the total the threshold is applied to.
+//!
+//! ```
+//! use sainte_lague::presets::kosovo::{assembly, reserved, Community};
+//!
+//! // the last two lists represent the Serb community
+//! let votes = [260.0, 250.0, 240.0, 120.0, 30.0, 60.0, 5.0];
+//! assert_eq!(assembly(&[5, 6]).distribute_votes(&votes), Ok(vec![30, 29, 27, 14, 0, 0, 0]));
+//! assert_eq!(reserved(Community::Serb).distribute_votes(&[60.0, 5.0]), Ok(vec![9, 1]));
+//! ```
+
+use crate::constraints::Constraint;
+use crate::methods::Stationary;
+use crate::system::{ElectoralSystem, Tier};
+
+/// The non-majority communities with reserved seats in the Assembly of Kosovo.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Community {
+    /// The Serb community, with 10 reserved seats.
+    Serb,
+    /// The Roma, Ashkali and Egyptian communities, with 4 reserved seats. The law guarantees one of them to each of the three communities, which this preset doesn't model.
+    RomaAshkaliEgyptian,
+    /// The Bosniak community, with 3 reserved seats.
+    Bosniak,
+    /// The Turkish community, with 2 reserved seats.
+    Turkish,
+    /// The Gorani community, with 1 reserved seat.
+    Gorani,
+}
+
+impl Community {
+    /// The number of seats reserved for the community.
+    pub fn seat_count(&self) -> usize {
+        match self {
+            Community::Serb => 10,
+            Community::RomaAshkaliEgyptian => 4,
+            Community::Bosniak => 3,
+            Community::Turkish => 2,
+            Community::Gorani => 1,
+        }
+    }
+}
+
+fn sainte_lague() -> Stationary {
+    Stationary::new(0.5).expect("0.5 is a valid offset")
+}
+
+/// The 100 seats distributed among all lists passing the threshold of 5 %. The lists at the indices in `community_lists` represent non-majority communities: their votes count towards the threshold, but they don't receive any of these seats. Ties are reported as errors.
+pub fn assembly(community_lists: &[usize]) -> ElectoralSystem {
+    let system = ElectoralSystem::new("Kuvendi i Kosovës", sainte_lague(), Tier::National(100))
+        .with(Constraint::Threshold(0.05));
+    community_lists.iter().fold(system, |system, list| {
+        system.with(Constraint::Others(*list))
+    })
+}
+
+/// The seats reserved for the given community, distributed among the lists of this community without a threshold. Ties are reported as errors.
+pub fn reserved(community: Community) -> ElectoralSystem {
+    let tier = Tier::National(community.seat_count());
+    ElectoralSystem::new("Kuvendi i Kosovës (reserved seats)", sainte_lague(), tier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{assembly, reserved, Community};
+    use crate::constraints::ConstraintError;
+
+    #[test]
+    fn reserved_seats() {
+        let communities = [
+            Community::Serb,
+            Community::RomaAshkaliEgyptian,
+            Community::Bosniak,
+            Community::Turkish,
+            Community::Gorani,
+        ];
+        let total: usize = communities.iter().map(Community::seat_count).sum();
+        assert_eq!(total + assembly(&[]).seat_count(), 120);
+        assert_eq!(
+            reserved(Community::Gorani).distribute_votes(&[1.0, 2.0]),
+            Ok(vec![0, 1])
+        );
+    }
+
+    #[test]
+    fn community_votes_count_towards_threshold() {
+        // 55 of 1000 votes pass the threshold only if the community votes are counted as well
+        let votes = [500.0, 55.0, 345.0, 100.0];
+        assert_eq!(
+            assembly(&[3]).distribute_votes(&votes),
+            Ok(vec![56, 6, 38, 0])
+        );
+        assert_eq!(
+            assembly(&[3]).distribute_votes(&[500.0, 45.0, 355.0, 100.0]),
+            Ok(vec![58, 0, 42, 0])
+        );
+        let error = assembly(&[4]).distribute_votes(&votes).unwrap_err();
+        assert_eq!(error.root_cause(), &ConstraintError::UnknownParty(4));
+    }
+}
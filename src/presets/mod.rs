@@ -0,0 +1,20 @@
+//! The electoral rules of specific parliaments.
+//!
+//! Every preset is an [`ElectoralSystem`](crate::system::ElectoralSystem) describing the divisor method, the number of seats, thresholds, districts, bonuses and the handling of ties prescribed by an electoral law, so the seats of a parliament can be calculated from its votes alone. Since presets are plain data, they can also serve as a starting point for the laws of other parliaments. Presets are grouped by country.
+//!
+//! ```
+//! use sainte_lague::presets::germany;
+//!
+//! let system = germany::rhineland_palatinate();
+//! assert_eq!(system.distribute_votes(&[362.0, 318.0, 126.0, 62.0, 53.0]), Ok(vec![39, 35, 14, 7, 6]));
+//! ```
+//!
+//! Presets only cover what this crate models. Check the documentation of each preset for the parts of the law it leaves out, and the electoral law itself before relying on the numbers.
+
+pub mod bosnia_and_herzegovina;
+pub mod france;
+pub mod germany;
+pub mod indonesia;
+pub mod kosovo;
+pub mod nepal;
+pub mod turkey;
@@ -0,0 +1,113 @@
+//! The House of Representatives of Nepal.
+//!
+//! Nepal uses a parallel system: 165 members are elected by plurality in single-member districts, and 110 list seats are distributed with the [modified Sainte-Laguë method](Stationary::modified_sainte_lague), with a first divisor of 1.4, among the parties receiving at least 3 % of the valid list votes. The list seats don't compensate for the district seats, see [parallel voting](crate::parallel).
+//!
+//! ```
+//! use sainte_lague::presets::nepal::house_of_representatives;
+//! use sainte_lague::system::Ballots;
+//!
+//! let ballots = Ballots::new(vec![35.0, 30.0, 28.0, 4.0, 3.0]).with_district_winners(vec![90, 50, 20, 5, 0]);
+//! let outcome = house_of_representatives().distribute(&ballots).unwrap();
+//! assert_eq!(outcome.list_seats(), vec![39, 33, 31, 4, 3]);
+//! assert_eq!(outcome.seats().iter().sum::<usize>(), 275);
+//! ```
+//!
+//! The lists are subject to quotas for women and for the inclusion clusters (Khas Arya, Madhesi, Dalit, Adibasi Janajati, Tharu and Muslim), which decide which candidates fill the seats of a party, but not how many seats it receives. [`women_from_list`] calculates how many of a party's list seats must go to women.
+
+use crate::constraints::Constraint;
+use crate::methods::Stationary;
+use crate::system::{ElectoralSystem, Tier};
+use crate::DistributionError;
+
+/// The House of Representatives as elected since 2017, see the [module documentation](self). Ties are reported as errors, in the districts as well as for the list seats.
+pub fn house_of_representatives() -> ElectoralSystem {
+    let method = Stationary::modified_sainte_lague();
+    ElectoralSystem::new("Pratinidhi Sabha", method, Tier::National(110))
+        .with(Constraint::Threshold(0.03))
+        .with_plurality_seats(165)
+}
+
+/// The number of a party's `list_seats` that must be filled by women, so that at least one third of all its seats are held by women, given the number of `district_seats` it won and how many of them were won by women. Fails with [`DistributionError::InvalidSeatCount`] if more women than district seats are given.
+///
+/// ```
+/// use sainte_lague::presets::nepal::women_from_list;
+///
+/// // 90 district seats, 10 of them won by women, and 39 list seats: 43 of 129 seats must be held by women
+/// assert_eq!(women_from_list(90, 10, 39), Ok(33));
+/// // the list can't make up for more than its own seats
+/// assert_eq!(women_from_list(90, 0, 20), Ok(20));
+/// ```
+pub fn women_from_list(
+    district_seats: usize,
+    women_in_districts: usize,
+    list_seats: usize,
+) -> Result<usize, DistributionError> {
+    if women_in_districts > district_seats {
+        return Err(DistributionError::InvalidSeatCount);
+    }
+    let required = (district_seats + list_seats).div_ceil(3);
+    Ok(required.saturating_sub(women_in_districts).min(list_seats))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{house_of_representatives, women_from_list};
+    use crate::constraints::ConstraintError;
+    use crate::methods::Stationary;
+    use crate::system::{Ballots, ElectoralSystem, Tier};
+    use crate::DistributionError;
+
+    #[test]
+    fn list_tier() {
+        let house = ElectoralSystem {
+            tier: Tier::National(10),
+            ..house_of_representatives().with_plurality_seats(3)
+        };
+        // the fourth party fails the threshold
+        let votes = vec![600.0, 300.0, 80.0, 20.0];
+        let ballots = Ballots::new(votes.clone()).with_district_winners(vec![3, 0, 0, 0]);
+        let outcome = house.distribute(&ballots).unwrap();
+        let eligible = [600.0, 300.0, 80.0, 0.0];
+        let expected = Stationary::modified_sainte_lague().distribute(&eligible, &10, &false);
+        assert_eq!(Ok(outcome.list_seats()), expected);
+
+        let districts = vec![vec![1.0, 2.0, 0.0, 0.0]; 3];
+        let outcome = house
+            .distribute_districts(&Ballots::new(votes), &districts)
+            .unwrap();
+        assert_eq!(outcome.district_seats, vec![0, 3, 0, 0]);
+    }
+
+    #[test]
+    fn errors() {
+        let house = house_of_representatives();
+        let step = |votes: Vec<f64>, winners: Vec<usize>| {
+            let ballots = Ballots::new(votes).with_district_winners(winners);
+            let error = house.distribute(&ballots).unwrap_err();
+            (error.step(), error.label().to_string(), *error.root_cause())
+        };
+        let district_seats = "district seats".to_string();
+        assert_eq!(
+            step(vec![1.0, 1.0], vec![165]),
+            (1, district_seats.clone(), ConstraintError::UnknownParty(1))
+        );
+        assert_eq!(
+            step(vec![1.0, 1.0], vec![100, 64]),
+            (1, district_seats, ConstraintError::Infeasible)
+        );
+        assert_eq!(
+            step(vec![1.0, 1.0, 1.0], vec![100, 65, 0]),
+            (
+                4,
+                "proportional seats".to_string(),
+                DistributionError::Tied.into()
+            )
+        );
+
+        assert_eq!(
+            women_from_list(1, 2, 0),
+            Err(DistributionError::InvalidSeatCount)
+        );
+        assert_eq!(women_from_list(3, 3, 3), Ok(0));
+    }
+}
@@ -0,0 +1,61 @@
+//! The Grand National Assembly of Turkey.
+//!
+//! The 600 seats are distributed with the D'Hondt method, a [stationary divisor method](Stationary) with an offset of `1.0`, in each of the electoral districts. Only parties receiving at least 7 % of the valid votes nationwide take part, but parties running in an alliance pass the threshold together if the alliance does. Since 2022, the seats of a district are distributed to each allied party by its own votes. Independent candidates are not modelled.
+//!
+//! ```
+//! use sainte_lague::presets::turkey::grand_national_assembly;
+//! use sainte_lague::system::{Ballots, Tier};
+//!
+//! // the second and third party form an alliance
+//! let districts = Tier::parse_districts("Adana,15\nAnkara (1),12").unwrap();
+//! let assembly = grand_national_assembly(districts, &[vec![1, 2]]);
+//! let votes = vec![vec![500.0, 300.0, 70.0, 60.0], vec![450.0, 400.0, 60.0, 70.0]];
+//! let outcome = assembly.distribute(&Ballots::districts(votes)).unwrap();
+//! // with 6.8 % each, the third party passes the threshold thanks to the alliance, the fourth one fails it
+//! assert_eq!(outcome.seats(), vec![15, 11, 1, 0]);
+//! ```
+
+use crate::constraints::Constraint;
+use crate::methods::Stationary;
+use crate::system::{ElectoralSystem, Tier};
+
+/// The national threshold.
+pub const THRESHOLD: f64 = 0.07;
+
+/// The Grand National Assembly with the given electoral districts and the indices of the parties of each alliance, see the [module documentation](self). The votes are given in the order of the districts, with the same party order everywhere. Ties are reported as errors, overlapping alliances as [`ConstraintError::OverlappingAlliances`](crate::constraints::ConstraintError::OverlappingAlliances).
+pub fn grand_national_assembly(districts: Tier, alliances: &[Vec<usize>]) -> ElectoralSystem {
+    let dhondt = Stationary::new(1.0).expect("1.0 is a valid offset");
+    let system = ElectoralSystem::new("Türkiye Büyük Millet Meclisi", dhondt, districts)
+        .with(Constraint::Threshold(THRESHOLD));
+    alliances.iter().fold(system, |system, alliance| {
+        system.with(Constraint::Alliance(alliance.clone()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::grand_national_assembly;
+    use crate::constraints::ConstraintError;
+    use crate::system::{Ballots, ElectoralSystem, Tier};
+
+    #[test]
+    fn alliances() {
+        let districts = Tier::parse_districts("A,20").unwrap();
+        let assembly = grand_national_assembly(districts.clone(), &[]);
+        assert_eq!(assembly.seat_count(), 20);
+        let ballots = Ballots::districts(vec![vec![600.0, 335.0, 65.0]]);
+        let seats = |assembly: &ElectoralSystem| assembly.distribute(&ballots).map(|o| o.seats());
+        assert_eq!(seats(&assembly), Ok(vec![13, 7, 0]));
+        // an alliance with the largest party lifts the smallest one above the threshold
+        let allied = grand_national_assembly(districts.clone(), &[vec![0, 2]]);
+        assert_eq!(seats(&allied), Ok(vec![12, 7, 1]));
+
+        let overlapping = grand_national_assembly(districts, &[vec![0, 2], vec![1, 2]]);
+        let error = overlapping.distribute(&ballots).unwrap_err();
+        assert_eq!(error.step(), 2);
+        assert_eq!(
+            error.root_cause(),
+            &ConstraintError::OverlappingAlliances(2)
+        );
+    }
+}
@@ -0,0 +1,216 @@
+//! Differential privacy for published precinct counts.
+//!
+//! Statistical offices publishing results per precinct have to make sure that small counts don't reveal how individual voters voted. [`Noise`] applies the Laplace mechanism to every count up to a configurable size: a count changes by at most one if a single voter changes their vote, so noise with a scale of `1 / epsilon` makes the published counts `epsilon`-differentially private with respect to these voters. Larger counts are published unchanged.
+//!
+//! Since the noisy counts no longer add up to the official result, [`Noise::impact`] estimates the probability that a distribution based on the published counts differs from the official one.
+//!
+//! ```
+//! use sainte_lague::privacy::Noise;
+//! use sainte_lague::Method;
+//!
+//! let precincts = vec![vec![420.0, 310.0, 3.0], vec![12.0, 25.0, 1.0], vec![380.0, 395.0, 2.0]];
+//! let noise = Noise::new(0.5, 50.0).unwrap();
+//!
+//! let published = noise.apply(&precincts, 7);
+//! assert_eq!(published[0][..2], precincts[0][..2]);
+//!
+//! let impact = noise.impact(&Method::SainteLague, &precincts, &10, 1_000, 7).unwrap();
+//! assert_eq!(impact.seats, vec![5, 5, 0]);
+//! assert!(impact.probability() < 0.1);
+//! ```
+
+use crate::{Allocator, DistributionError};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// The Laplace mechanism for small counts, see the [module documentation](self).
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Noise {
+    epsilon: f64,
+    small_count: f64,
+}
+
+impl Noise {
+    /// Add noise with privacy parameter `epsilon` to all counts up to `small_count`. Smaller values of `epsilon` protect voters better, but add more noise. Returns [`None`] if `epsilon` isn't positive and finite, or `small_count` is negative.
+    pub fn new(epsilon: f64, small_count: f64) -> Option<Noise> {
+        if !(epsilon.is_finite() && epsilon > 0.0) || small_count.is_nan() || small_count < 0.0 {
+            return None;
+        }
+        Some(Noise {
+            epsilon,
+            small_count,
+        })
+    }
+
+    /// The privacy parameter.
+    pub fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+
+    /// The largest count that receives noise.
+    pub fn small_count(&self) -> f64 {
+        self.small_count
+    }
+
+    /// The votes of each party in each precinct as published, reproducibly seeded with `seed`. Noisy counts are rounded to whole votes and never negative.
+    pub fn apply(&self, precincts: &[Vec<f64>], seed: u64) -> Vec<Vec<f64>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        self.apply_with(precincts, &mut rng)
+    }
+
+    /// Estimate how the noise affects the distribution of `seat_count` seats according to the votes of all precincts combined, by publishing the counts `trials` times, reproducibly seeded with `seed`.
+    ///
+    /// Trials resulting in a tie are skipped, all other errors are returned. Precincts with fewer parties than the first one panic.
+    pub fn impact<A: Allocator>(
+        &self,
+        allocator: &A,
+        precincts: &[Vec<f64>],
+        seat_count: &usize,
+        trials: usize,
+        seed: u64,
+    ) -> Result<Impact, A::Error> {
+        let seats = allocator.allocate(&total(precincts), seat_count)?;
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut changed = 0;
+        let mut skipped = 0;
+        for _ in 0..trials {
+            let published = self.apply_with(precincts, &mut rng);
+            match allocator.allocate(&total(&published), seat_count) {
+                Ok(noisy) if noisy != seats => changed += 1,
+                Ok(_) => (),
+                Err(e) if e == A::Error::from(DistributionError::Tied) => skipped += 1,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(Impact {
+            seats,
+            trials,
+            changed,
+            skipped,
+        })
+    }
+
+    fn apply_with(&self, precincts: &[Vec<f64>], rng: &mut StdRng) -> Vec<Vec<f64>> {
+        let scale = 1.0 / self.epsilon;
+        precincts
+            .iter()
+            .map(|votes| {
+                votes
+                    .iter()
+                    .map(|v| match *v <= self.small_count {
+                        true => (v + scale * laplace(rng)).round().max(0.0),
+                        false => *v,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// The result of [`Noise::impact`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Impact {
+    /// The distribution according to the exact counts.
+    pub seats: Vec<usize>,
+    /// The number of trials.
+    pub trials: usize,
+    /// The number of trials in which the distribution differed from the exact one.
+    pub changed: usize,
+    /// The number of trials that were skipped because of a tie.
+    pub skipped: usize,
+}
+
+impl Impact {
+    /// The estimated probability that publishing changes the distribution, among the trials without a tie. This is `0.0` if there were no such trials.
+    pub fn probability(&self) -> f64 {
+        match self.trials - self.skipped {
+            0 => 0.0,
+            trials => self.changed as f64 / trials as f64,
+        }
+    }
+}
+
+/// The votes of each party, added up over all precincts.
+fn total(precincts: &[Vec<f64>]) -> Vec<f64> {
+    let mut total = vec![0.0; precincts.first().map_or(0, |p| p.len())];
+    for votes in precincts.iter() {
+        for (t, v) in total.iter_mut().zip(votes.iter()) {
+            *t += v;
+        }
+        assert!(votes.len() >= total.len(), "one count per party");
+    }
+    total
+}
+
+/// Draw from the standard Laplace distribution by inverting its distribution function.
+fn laplace(rng: &mut StdRng) -> f64 {
+    let u: f64 = rng.gen_range(-0.5..0.5);
+    -u.signum() * (1.0 - 2.0 * u.abs()).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{laplace, Impact, Noise};
+    use crate::Method;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn laplace_distribution() {
+        let mut rng = StdRng::seed_from_u64(466);
+        let samples: Vec<f64> = (0..100_000).map(|_| laplace(&mut rng)).collect();
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let variance = samples.iter().map(|s| s * s).sum::<f64>() / samples.len() as f64;
+        assert!(mean.abs() < 0.02);
+        // the variance of the standard Laplace distribution is 2
+        assert!((variance - 2.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn small_counts_only() {
+        let noise = Noise::new(0.1, 10.0).unwrap();
+        let precincts = vec![vec![1000.0, 10.0, 0.0], vec![11.0, 5.0, 2.0]];
+        let published = noise.apply(&precincts, 1);
+        assert_eq!(published, noise.apply(&precincts, 1));
+        assert_eq!((published[0][0], published[1][0]), (1000.0, 11.0));
+        assert!(published
+            .iter()
+            .flatten()
+            .all(|v| *v >= 0.0 && v.fract() == 0.0));
+        assert_ne!(published, precincts);
+
+        let unchanged = Noise::new(0.1, 0.0).unwrap().apply(&[vec![3.0, 0.5]], 1);
+        assert_eq!(unchanged, vec![vec![3.0, 0.5]]);
+    }
+
+    #[test]
+    fn close_results() {
+        let noise = Noise::new(0.2, 100.0).unwrap();
+        // the last seat is decided by a handful of votes in small precincts
+        let precincts = vec![vec![50.0, 48.0], vec![30.0, 31.0]];
+        let impact = noise
+            .impact(&Method::SainteLague, &precincts, &3, 500, 3)
+            .unwrap();
+        assert_eq!(impact.seats, vec![2, 1]);
+        assert!(impact.changed > 0);
+        assert_eq!(impact.trials, 500);
+
+        let impact = Impact {
+            seats: vec![],
+            trials: 2,
+            changed: 0,
+            skipped: 2,
+        };
+        assert_eq!(impact.probability(), 0.0);
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        assert_eq!(Noise::new(0.0, 1.0), None);
+        assert_eq!(Noise::new(f64::INFINITY, 1.0), None);
+        assert_eq!(Noise::new(1.0, -1.0), None);
+        assert_eq!(Noise::new(1.0, f64::NAN), None);
+        assert_eq!(Noise::new(1.0, 2.0).map(|n| n.epsilon()), Some(1.0));
+        assert_eq!(Noise::new(1.0, 2.0).map(|n| n.small_count()), Some(2.0));
+    }
+}
@@ -0,0 +1,117 @@
+//! Progress reporting and cancellation of long-running computations.
+//!
+//! Functions that may run for a long time, such as [seat count sweeps](crate::Method::sweep_seats_with_progress), accept a callback that is called with the [`Progress`] after every item. The callback decides whether to [`Continue`](Control::Continue) or to [`Cancel`](Control::Cancel) the computation, e.g. because the user closed the window showing the progress bar.
+//!
+//! ```
+//! use sainte_lague::progress::{Control, Progress};
+//! use sainte_lague::Method;
+//!
+//! let mut reported = vec![];
+//! let sweep = Method::SainteLague.sweep_seats_with_progress(&[3.0, 2.0], &10, |p: Progress| {
+//!     reported.push(p.done);
+//!     if p.done < 4 { Control::Continue } else { Control::Cancel }
+//! });
+//! // the computation stopped after the fourth distribution
+//! assert_eq!(sweep.len(), 4);
+//! assert_eq!(reported, vec![1, 2, 3, 4]);
+//! ```
+
+use std::time::{Duration, Instant};
+
+/// The progress of a computation, see the [module documentation](self).
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Progress {
+    /// The number of items done so far.
+    pub done: usize,
+    /// The total number of items.
+    pub total: usize,
+    /// The time since the computation started.
+    pub elapsed: Duration,
+}
+
+impl Progress {
+    /// The share of items done, between `0.0` and `1.0`. This is `1.0` if there are no items at all.
+    pub fn fraction(&self) -> f64 {
+        match self.total {
+            0 => 1.0,
+            total => self.done as f64 / total as f64,
+        }
+    }
+
+    /// The estimated remaining time, assuming all remaining items take as long as the average item so far. Returns [`None`] before the first item is done.
+    pub fn eta(&self) -> Option<Duration> {
+        if self.done == 0 {
+            return None;
+        }
+        let remaining = self.total.saturating_sub(self.done) as f64;
+        Some(self.elapsed.mul_f64(remaining / self.done as f64))
+    }
+}
+
+/// What a computation should do after reporting its progress.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Control {
+    /// Go on with the next item.
+    Continue,
+    /// Stop as soon as possible.
+    Cancel,
+}
+
+/// Reports the progress of a computation with `total` items to a callback.
+pub(crate) struct Tracker<P> {
+    callback: P,
+    start: Instant,
+    done: usize,
+    total: usize,
+}
+
+impl<P: FnMut(Progress) -> Control> Tracker<P> {
+    pub(crate) fn new(total: usize, callback: P) -> Tracker<P> {
+        Tracker {
+            callback,
+            start: Instant::now(),
+            done: 0,
+            total,
+        }
+    }
+
+    /// Mark `items` more items as done and report the progress. Returns whether the computation should go on.
+    pub(crate) fn advance(&mut self, items: usize) -> bool {
+        self.done += items;
+        let progress = Progress {
+            done: self.done,
+            total: self.total,
+            elapsed: self.start.elapsed(),
+        };
+        (self.callback)(progress) == Control::Continue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Progress;
+    use std::time::Duration;
+
+    #[test]
+    fn estimates() {
+        let progress = Progress {
+            done: 2,
+            total: 8,
+            elapsed: Duration::from_secs(10),
+        };
+        assert_eq!(progress.fraction(), 0.25);
+        assert_eq!(progress.eta(), Some(Duration::from_secs(30)));
+
+        let started = Progress {
+            done: 0,
+            ..progress
+        };
+        assert_eq!(started.eta(), None);
+        let empty = Progress {
+            done: 0,
+            total: 0,
+            ..progress
+        };
+        assert_eq!(empty.fraction(), 1.0);
+    }
+}
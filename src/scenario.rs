@@ -0,0 +1,159 @@
+use crate::{DistributionError, Method};
+
+/// All inputs of a single distribution, bundled so they can be stored, compared and replayed.
+///
+/// ```
+/// use sainte_lague::Scenario;
+///
+/// let scenario = Scenario::new(vec![41.5, 25.7, 8.6, 8.4], 631);
+/// let outcome = scenario.evaluate().unwrap();
+/// assert_eq!(outcome.seats(), &[311, 193, 64, 63]);
+/// assert_eq!(outcome.draw(), None);
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct Scenario {
+    /// The votes of each party, see [`distribute`](crate::distribute).
+    pub votes: Vec<f64>,
+    /// The size of the parliament.
+    pub seat_count: usize,
+    /// Whether ties for the last seats should be resolved by drawing lots.
+    pub draw_on_tie: bool,
+    /// The allocation method.
+    pub method: Method,
+}
+
+impl Scenario {
+    /// Create a Sainte-Laguë scenario that doesn't resolve ties by drawing lots.
+    pub fn new(votes: Vec<f64>, seat_count: usize) -> Scenario {
+        Scenario {
+            votes,
+            seat_count,
+            draw_on_tie: false,
+            method: Method::SainteLague,
+        }
+    }
+
+    /// Calculate the distribution for this scenario.
+    pub fn evaluate(&self) -> Result<Outcome, DistributionError> {
+        self.method
+            .evaluate(&self.votes, &self.seat_count, &self.draw_on_tie)
+    }
+}
+
+/// Details about seats that had to be awarded by drawing lots.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Draw {
+    candidates: Vec<usize>,
+    seats: usize,
+}
+
+impl Draw {
+    pub(crate) fn new(candidates: Vec<usize>, seats: usize) -> Draw {
+        Draw { candidates, seats }
+    }
+
+    /// Indices of all parties that were tied for the last seats, in ascending order.
+    pub fn candidates(&self) -> &[usize] {
+        &self.candidates
+    }
+
+    /// The number of seats that were awarded by drawing lots among the candidates.
+    pub fn seats(&self) -> usize {
+        self.seats
+    }
+}
+
+/// The result of a distribution: the seats of every party, plus details about a possible draw.
+///
+/// Expected outcomes, e.g. in tests, start from the seats and add a draw if needed, so they don't depend on how the outcome is stored.
+///
+/// ```
+/// use sainte_lague::{Outcome, Scenario};
+///
+/// let mut scenario = Scenario::new(vec![3.0, 1.0, 3.0], 7);
+/// assert_eq!(scenario.evaluate(), Ok(Outcome::from_seats(vec![3, 1, 3])));
+///
+/// // parties 0 and 2 are tied for the last of 8 seats
+/// scenario.seat_count = 8;
+/// scenario.draw_on_tie = true;
+/// let outcome = scenario.evaluate().unwrap();
+/// let expected = Outcome::from_seats(outcome.seats().to_vec()).with_draw(vec![2, 0], 1);
+/// assert_eq!(outcome, expected);
+/// ```
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Outcome {
+    seats: Vec<usize>,
+    draw: Option<Draw>,
+}
+
+impl Outcome {
+    pub(crate) fn new(seats: Vec<usize>, draw: Option<Draw>) -> Outcome {
+        Outcome { seats, draw }
+    }
+
+    /// Create an outcome with the given seats of each party that didn't need a draw.
+    pub fn from_seats(seats: Vec<usize>) -> Outcome {
+        Outcome::new(seats, None)
+    }
+
+    /// Set the draw: lots were drawn among the tied `candidates` for `seats` seats. The candidates may be given in any order.
+    pub fn with_draw(mut self, mut candidates: Vec<usize>, seats: usize) -> Outcome {
+        candidates.sort_unstable();
+        self.draw = Some(Draw::new(candidates, seats));
+        self
+    }
+
+    /// The number of seats of each party, in the order of the given votes.
+    pub fn seats(&self) -> &[usize] {
+        &self.seats
+    }
+
+    /// Information about the draw that was needed to resolve a tie, if any.
+    pub fn draw(&self) -> Option<&Draw> {
+        self.draw.as_ref()
+    }
+
+    /// Consume the outcome, returning the seats of each party.
+    pub fn into_seats(self) -> Vec<usize> {
+        self.seats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Outcome, Scenario};
+
+    #[test]
+    fn draw_details() {
+        let mut scenario = Scenario::new(vec![3.0, 3.0, 1.0], 8);
+        scenario.draw_on_tie = true;
+
+        let outcome = scenario.evaluate().unwrap();
+        let draw = outcome.draw().unwrap();
+        assert_eq!(draw.candidates(), &[0, 1]);
+        assert_eq!(draw.seats(), 1);
+        assert_eq!(outcome.seats().iter().sum::<usize>(), 8);
+    }
+
+    #[test]
+    fn expected_outcomes() {
+        let outcome = Outcome::from_seats(vec![2, 1]).with_draw(vec![1, 0], 1);
+        assert_eq!(outcome.seats(), &[2, 1]);
+        assert_eq!(outcome.draw().unwrap().candidates(), &[0, 1]);
+        assert_eq!(outcome.draw().unwrap().seats(), 1);
+        assert_eq!(Outcome::from_seats(vec![2, 1]).draw(), None);
+
+        let scenario = Scenario::new(vec![5.0, 3.0, 2.0], 10);
+        assert_eq!(scenario.evaluate(), Ok(Outcome::from_seats(vec![5, 3, 2])));
+    }
+
+    #[test]
+    fn no_draw_details_without_tie() {
+        let mut scenario = Scenario::new(vec![2.0, 2.0], 2);
+        scenario.draw_on_tie = true;
+
+        let outcome = scenario.evaluate().unwrap();
+        assert_eq!(outcome.draw(), None);
+        assert_eq!(outcome.into_seats(), vec![1, 1]);
+    }
+}
@@ -0,0 +1,1424 @@
+//! Projections and simulations of election results.
+//!
+//! While votes are being counted, the districts that report first are rarely representative of the whole country. [`project`] therefore extrapolates every district to its expected number of votes separately, instead of adding up the raw counts.
+//!
+//! ```
+//! use sainte_lague::distribute;
+//! use sainte_lague::simulation::{project, District};
+//!
+//! let districts = [
+//!     // a small rural district, fully counted
+//!     District { counted: vec![30_000.0, 10_000.0], expected_votes: 40_000.0 },
+//!     // a large city, 10% counted
+//!     District { counted: vec![4_000.0, 6_000.0], expected_votes: 100_000.0 },
+//! ];
+//! let projected = project(&districts).unwrap();
+//! assert_eq!(projected, vec![70_000.0, 70_000.0]);
+//! assert_eq!(distribute(&projected, &10, &false), Ok(vec![5, 5]));
+//! ```
+//!
+//! On election night, [`fuse_exit_poll`] additionally combines the partial count with an exit poll.
+//!
+//! Before the election, [`average_polls`] combines multiple polls into a single estimate, which [`project_seats`] turns into a distribution of seats, together with the ranges of seats each party can plausibly expect given the sampling error of the polls.
+//!
+//! ```
+//! use sainte_lague::simulation::{average_polls, project_seats, Poll};
+//! use sainte_lague::Method;
+//!
+//! let polls = [
+//!     Poll { shares: vec![0.42, 0.38, 0.2], sample_size: 1_000.0, age: 2.0 },
+//!     Poll { shares: vec![0.4, 0.4, 0.2], sample_size: 2_000.0, age: 9.0 },
+//! ];
+//! let average = average_polls(&polls, 7.0).unwrap();
+//! let projection = project_seats(&Method::SainteLague, &average, &100, 1_000, 42).unwrap();
+//! assert_eq!(projection.seats, vec![41, 39, 20]);
+//! assert!(projection.ranges[0].0 <= 41 && 41 <= projection.ranges[0].1);
+//! ```
+//!
+//! The error between the expected and the simulated results is described by a [`VoteErrorModel`]. [`project_seats_with_model`] and [`simulate_with_model`] accept the built-in [`Normal`] and [`Multinomial`] models as well as user-supplied ones. For elections with many districts, [`simulate_districts`] draws all districts together, so that the [`CorrelatedSwing`] model can share most of the error between them.
+
+use crate::io::wire;
+use crate::progress::{Control, Progress, Tracker};
+use crate::{Allocator, DistributionError};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::iter::FromIterator;
+use std::path::Path;
+use std::thread;
+
+/// The partial count of a district.
+#[derive(Clone, PartialEq, Debug)]
+pub struct District {
+    /// The votes of each party counted so far.
+    pub counted: Vec<f64>,
+    /// The number of valid votes expected once counting is complete, e.g. the number of registered voters times the historical turnout.
+    pub expected_votes: f64,
+}
+
+/// Project the final votes of each party from partially counted districts.
+///
+/// Every district with counted votes is scaled to its expected number of votes. Districts without any counted votes are assumed to vote like the projection of all other districts. Returns [`None`] if no votes were counted at all, some votes are negative or the districts contain different numbers of parties.
+pub fn project(districts: &[District]) -> Option<Vec<f64>> {
+    let party_count = districts.first()?.counted.len();
+    let mut projected = vec![0.0; party_count];
+    let mut uncounted_votes = 0.0;
+    for district in districts.iter() {
+        if district.counted.len() != party_count
+            || district.expected_votes < 0.0
+            || district.counted.iter().any(|v| *v < 0.0)
+        {
+            return None;
+        }
+        let counted: f64 = district.counted.iter().sum();
+        if counted == 0.0 {
+            uncounted_votes += district.expected_votes;
+            continue;
+        }
+        for (p, v) in projected.iter_mut().zip(district.counted.iter()) {
+            *p += v / counted * district.expected_votes;
+        }
+    }
+
+    let projected_votes: f64 = projected.iter().sum();
+    if projected_votes == 0.0 {
+        return None;
+    }
+    Some(
+        projected
+            .iter()
+            .map(|p| p + p / projected_votes * uncounted_votes)
+            .collect(),
+    )
+}
+
+/// An estimate of the shares of all parties together with their uncertainty, e.g. an exit poll. See [`fuse_exit_poll`].
+///
+/// An estimate is also a [`VoteErrorModel`] which draws the share of every party from a normal distribution with the standard error of that party, so seats can be projected directly from it with [`project_seats_with_model`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct Estimate {
+    /// The estimated share of each party.
+    pub shares: Vec<f64>,
+    /// The standard error of each share, in the same unit as the shares.
+    pub standard_errors: Vec<f64>,
+}
+
+impl VoteErrorModel for Estimate {
+    fn sample(&self, shares: &[f64], rng: &mut StdRng) -> Vec<f64> {
+        shares
+            .iter()
+            .zip(self.standard_errors.iter())
+            .map(|(p, error)| (p + error * standard_normal(rng)).max(0.0))
+            .collect()
+    }
+}
+
+/// Combine partially counted districts with an exit poll, weighting both sources of each party by their precision, i.e. the inverse of their variance.
+///
+/// The counted districts are extrapolated with [`project`]. Their standard error is the sampling error of the votes counted so far, which shrinks to zero as the count approaches the expected number of votes, so the exit poll matters less and less as counting progresses. The shares of the exit poll don't need to add up to `1.0`, its standard errors are scaled along with them. The fused shares add up to `1.0`.
+///
+/// Returns [`None`] if [`project`] does, if any share or standard error of the exit poll is negative, or if the exit poll doesn't contain one share and one standard error for each party of the districts.
+///
+/// ```
+/// use sainte_lague::simulation::{fuse_exit_poll, project_seats_with_model, District, Estimate};
+/// use sainte_lague::Method;
+///
+/// let districts = [
+///     District { counted: vec![300.0, 200.0], expected_votes: 10_000.0 },
+///     District { counted: vec![0.0, 0.0], expected_votes: 10_000.0 },
+/// ];
+/// let exit_poll = Estimate { shares: vec![50.0, 50.0], standard_errors: vec![2.0, 2.0] };
+/// let fused = fuse_exit_poll(&districts, &exit_poll).unwrap();
+/// // the exit poll is more precise than the first 500 votes
+/// assert!(0.5 < fused.shares[0] && fused.shares[0] < 0.55);
+/// assert!(fused.standard_errors[0] < 0.02);
+///
+/// let projection = project_seats_with_model(&Method::SainteLague, &fused, &fused.shares, &100, 200, 1).unwrap();
+/// assert_eq!(projection.seats.iter().sum::<usize>(), 100);
+/// ```
+pub fn fuse_exit_poll(districts: &[District], exit_poll: &Estimate) -> Option<Estimate> {
+    let projected = project(districts)?;
+    if exit_poll.shares.len() != projected.len()
+        || exit_poll.standard_errors.len() != projected.len()
+        || exit_poll.shares.iter().any(|s| *s < 0.0)
+        || exit_poll.standard_errors.iter().any(|e| *e < 0.0)
+    {
+        return None;
+    }
+
+    let projected_votes: f64 = projected.iter().sum();
+    let counted: f64 = districts.iter().flat_map(|d| d.counted.iter()).sum();
+    let expected: f64 = districts.iter().map(|d| d.expected_votes).sum();
+    // the finite population correction vanishes once all expected votes are counted
+    let remaining = (1.0 - counted / expected).max(0.0);
+    let poll_total: f64 = exit_poll.shares.iter().sum();
+
+    let (shares, standard_errors): (Vec<f64>, Vec<f64>) = projected
+        .iter()
+        .zip(
+            exit_poll
+                .shares
+                .iter()
+                .zip(exit_poll.standard_errors.iter()),
+        )
+        .map(|(v, (s, e))| {
+            let count_share = v / projected_votes;
+            let count_error = (count_share * (1.0 - count_share) / counted * remaining).sqrt();
+            if poll_total == 0.0 {
+                return (count_share, count_error);
+            }
+            let (poll_share, poll_error) = (s / poll_total, e / poll_total);
+            if count_error == 0.0 || poll_error == 0.0 {
+                // an exact source outweighs any other, the count is preferred if both are exact
+                let exact = if count_error == 0.0 {
+                    count_share
+                } else {
+                    poll_share
+                };
+                return (exact, 0.0);
+            }
+            let (count_precision, poll_precision) = (count_error.powi(-2), poll_error.powi(-2));
+            let precision = count_precision + poll_precision;
+            (
+                (count_share * count_precision + poll_share * poll_precision) / precision,
+                precision.sqrt().recip(),
+            )
+        })
+        .unzip();
+
+    let total: f64 = shares.iter().sum();
+    Some(Estimate {
+        shares: shares.iter().map(|s| s / total).collect(),
+        standard_errors,
+    })
+}
+
+/// A poll, see [`average_polls`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct Poll {
+    /// The share of each party, in the same order for all polls.
+    pub shares: Vec<f64>,
+    /// The number of respondents.
+    pub sample_size: f64,
+    /// The number of days since the poll was conducted.
+    pub age: f64,
+}
+
+/// The weighted average of multiple polls, see [`average_polls`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct PollAverage {
+    /// The average share of each party, adding up to `1.0`.
+    pub shares: Vec<f64>,
+    /// The effective sample size of the average, i.e. the sum of the weights of all polls.
+    pub sample_size: f64,
+}
+
+/// Combine multiple polls into a weighted average. Each poll is weighted by its sample size, halved for every `half_life` days of its age. Use [`f64::INFINITY`] to weight all polls equally regardless of their age.
+///
+/// Returns [`None`] if there are no polls, any share, sample size or age is negative, the polls contain different numbers of parties, or the total weight is zero.
+pub fn average_polls(polls: &[Poll], half_life: f64) -> Option<PollAverage> {
+    let party_count = polls.first()?.shares.len();
+    let mut sums = vec![0.0; party_count];
+    let mut sample_size = 0.0;
+    for poll in polls.iter() {
+        if poll.shares.len() != party_count
+            || poll.sample_size < 0.0
+            || poll.age < 0.0
+            || poll.shares.iter().any(|s| *s < 0.0)
+        {
+            return None;
+        }
+        let poll_total: f64 = poll.shares.iter().sum();
+        if poll_total == 0.0 {
+            continue;
+        }
+        let weight = poll.sample_size * 0.5f64.powf(poll.age / half_life);
+        for (sum, share) in sums.iter_mut().zip(poll.shares.iter()) {
+            *sum += weight * share / poll_total;
+        }
+        sample_size += weight;
+    }
+    if sample_size == 0.0 {
+        return None;
+    }
+    Some(PollAverage {
+        shares: sums.iter().map(|s| s / sample_size).collect(),
+        sample_size,
+    })
+}
+
+/// A model of the error between the expected votes and an actual result, used to simulate elections in [`project_seats_with_model`] and [`simulate_with_model`].
+///
+/// Besides the built-in [`Normal`] and [`Multinomial`] models, any type implementing this trait can be used, e.g. to model a known bias of the polls.
+///
+/// ```
+/// use rand::rngs::StdRng;
+/// use sainte_lague::simulation::{project_seats_with_model, VoteErrorModel};
+/// use sainte_lague::Method;
+///
+/// /// Polls underestimate the first party by up to two percentage points.
+/// struct Bias;
+///
+/// impl VoteErrorModel for Bias {
+///     fn sample(&self, shares: &[f64], rng: &mut StdRng) -> Vec<f64> {
+///         use rand::Rng;
+///         let mut shares = shares.to_vec();
+///         shares[0] += rng.gen_range(0.0..0.02);
+///         shares
+///     }
+/// }
+///
+/// let projection = project_seats_with_model(&Method::SainteLague, &Bias, &[0.5, 0.5], &100, 100, 1).unwrap();
+/// assert_eq!(projection.seats, vec![50, 50]);
+/// assert_eq!(projection.ranges[1], (49, 50));
+/// ```
+pub trait VoteErrorModel {
+    /// Draw one simulated result from the expected `shares` of each party. The result must contain one non-negative value per party, but doesn't need to add up to `1.0`.
+    fn sample(&self, shares: &[f64], rng: &mut StdRng) -> Vec<f64>;
+
+    /// Draw one simulated result for each region from its expected shares. By default, every region is drawn independently with [`VoteErrorModel::sample`]. Models with errors correlated between regions override this method.
+    fn sample_regions(&self, regions: &[Vec<f64>], rng: &mut StdRng) -> Vec<Vec<f64>> {
+        regions
+            .iter()
+            .map(|shares| self.sample(shares, rng))
+            .collect()
+    }
+}
+
+/// The sampling error of a poll with `sample_size` respondents: the share `p` of every party is drawn independently from a normal distribution with standard deviation `sqrt(p (1 - p) / n)`, negative shares are set to zero. This is the model used by [`project_seats`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Normal {
+    /// The number of respondents.
+    pub sample_size: f64,
+}
+
+impl VoteErrorModel for Normal {
+    fn sample(&self, shares: &[f64], rng: &mut StdRng) -> Vec<f64> {
+        shares
+            .iter()
+            .map(|p| {
+                let error = (p * (1.0 - p) / self.sample_size).sqrt();
+                (p + error * standard_normal(rng)).max(0.0)
+            })
+            .collect()
+    }
+}
+
+/// The exact sampling error of a poll with `sample_size` respondents: every respondent picks a party with a probability proportional to its share. Unlike [`Normal`], small parties can't fall below zero and the shares of all parties are negatively correlated. Takes time proportional to the sample size.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Multinomial {
+    /// The number of respondents.
+    pub sample_size: usize,
+}
+
+impl VoteErrorModel for Multinomial {
+    fn sample(&self, shares: &[f64], rng: &mut StdRng) -> Vec<f64> {
+        let total: f64 = shares.iter().sum();
+        if total <= 0.0 || self.sample_size == 0 {
+            return shares.to_vec();
+        }
+        let mut counts = vec![0usize; shares.len()];
+        for _ in 0..self.sample_size {
+            let mut pick = rng.gen::<f64>() * total;
+            let party = shares
+                .iter()
+                .position(|s| {
+                    pick -= s;
+                    pick < 0.0
+                })
+                // rounding may leave a tiny remainder, which belongs to the last party with votes
+                .unwrap_or_else(|| shares.iter().rposition(|s| *s > 0.0).unwrap_or(0));
+            counts[party] += 1;
+        }
+        counts
+            .iter()
+            .map(|c| *c as f64 / self.sample_size as f64)
+            .collect()
+    }
+}
+
+/// Swings of the parties that are correlated between regions: a national swing, drawn once per simulation and shared by all regions, plus independent regional noise. The share of every party changes by both amounts, shares below zero are set to zero.
+///
+/// Drawing each district independently averages out most of the error over many districts and badly underestimates the variance of the total number of seats. In reality, most of the error is shared by all districts, e.g. because all polls missed the same late swing.
+///
+/// ```
+/// use sainte_lague::simulation::{substream, CorrelatedSwing, VoteErrorModel};
+///
+/// let model = CorrelatedSwing { national: 0.03, regional: 0.0 };
+/// let regions = model.sample_regions(&[vec![0.6, 0.4], vec![0.3, 0.7]], &mut substream(1, 0));
+/// // without regional noise, every region swings by the same amount
+/// assert!((regions[0][0] - 0.6 - (regions[1][0] - 0.3)).abs() < 1e-12);
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct CorrelatedSwing {
+    /// The standard deviation of the national swing of each party, e.g. `0.02` for two percentage points.
+    pub national: f64,
+    /// The standard deviation of the additional swing of each party in each region.
+    pub regional: f64,
+}
+
+impl VoteErrorModel for CorrelatedSwing {
+    fn sample(&self, shares: &[f64], rng: &mut StdRng) -> Vec<f64> {
+        self.sample_regions(&[shares.to_vec()], rng)
+            .pop()
+            .unwrap_or_default()
+    }
+
+    fn sample_regions(&self, regions: &[Vec<f64>], rng: &mut StdRng) -> Vec<Vec<f64>> {
+        let party_count = regions.first().map_or(0, Vec::len);
+        let national: Vec<f64> = (0..party_count)
+            .map(|_| self.national * standard_normal(rng))
+            .collect();
+        regions
+            .iter()
+            .map(|shares| {
+                shares
+                    .iter()
+                    .zip(national.iter())
+                    .map(|(p, swing)| (p + swing + self.regional * standard_normal(rng)).max(0.0))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// The result of [`project_seats`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct SeatProjection {
+    /// The distribution for the average shares.
+    pub seats: Vec<usize>,
+    /// The lowest and highest number of seats of each party in the central 90 % of the simulations.
+    pub ranges: Vec<(usize, usize)>,
+    /// The number of simulations that were skipped because of a tie.
+    pub skipped: usize,
+}
+
+/// Distribute `seat_count` seats according to a poll average, and estimate the range of seats of each party by simulating `trials` polls of the same sample size, reproducibly seeded with `seed`.
+///
+/// Each simulated poll draws the share of every party from a [`Normal`] approximation of its sampling error, `sqrt(p (1 - p) / n)`, using its own [`substream`] of the seed. Simulations resulting in a tie are skipped, all other errors are returned. If no simulation succeeds, the ranges only contain the point estimate.
+pub fn project_seats<A: Allocator>(
+    allocator: &A,
+    average: &PollAverage,
+    seat_count: &usize,
+    trials: usize,
+    seed: u64,
+) -> Result<SeatProjection, A::Error> {
+    let model = Normal {
+        sample_size: average.sample_size,
+    };
+    project_seats_with_model(allocator, &model, &average.shares, seat_count, trials, seed)
+}
+
+/// Same as [`project_seats`], but the `trials` simulated results are drawn from the expected `shares` with the given [`VoteErrorModel`].
+///
+/// ```
+/// use sainte_lague::simulation::{project_seats_with_model, Multinomial};
+/// use sainte_lague::Method;
+///
+/// let model = Multinomial { sample_size: 1_000 };
+/// let projection = project_seats_with_model(&Method::SainteLague, &model, &[0.42, 0.38, 0.2], &100, 200, 42).unwrap();
+/// assert_eq!(projection.seats, vec![42, 38, 20]);
+/// assert!(projection.ranges[2].0 < 20 && 20 < projection.ranges[2].1);
+/// ```
+pub fn project_seats_with_model<A, M>(
+    allocator: &A,
+    model: &M,
+    shares: &[f64],
+    seat_count: &usize,
+    trials: usize,
+    seed: u64,
+) -> Result<SeatProjection, A::Error>
+where
+    A: Allocator,
+    M: VoteErrorModel + ?Sized,
+{
+    let seats = allocator.allocate(shares, seat_count)?;
+    let simulated =
+        (0..trials).map(|trial| simulate_result(allocator, model, shares, seat_count, seed, trial));
+    summarize(seats, simulated)
+}
+
+/// Same as [`project_seats`], but reports the [`Progress`] to the given callback after every simulation. Returns [`None`] if the callback cancels the projection.
+pub fn project_seats_with_progress<A, P>(
+    allocator: &A,
+    average: &PollAverage,
+    seat_count: &usize,
+    trials: usize,
+    seed: u64,
+    progress: P,
+) -> Result<Option<SeatProjection>, A::Error>
+where
+    A: Allocator,
+    P: FnMut(Progress) -> Control,
+{
+    let seats = allocator.allocate(&average.shares, seat_count)?;
+    let mut tracker = Tracker::new(trials, progress);
+    let mut simulated = Vec::with_capacity(trials);
+    for trial in 0..trials {
+        simulated.push(simulate_poll(allocator, average, seat_count, seed, trial));
+        if !tracker.advance(1) {
+            return Ok(None);
+        }
+    }
+    summarize(seats, simulated.into_iter()).map(Some)
+}
+
+/// The state of a [`project_seats`] run that can be interrupted and resumed, e.g. for overnight jobs with millions of simulations.
+///
+/// [`Checkpoint::run`] continues the simulations in batches. In between, the checkpoint can be [saved](Checkpoint::save) to disk in the versioned [wire](crate::io::wire) format and [loaded](Checkpoint::load) again after an interruption. Since every simulation has its own [`substream`], the final projection is identical to [`project_seats`] with the same seed, no matter how often the run was interrupted.
+///
+/// ```
+/// use sainte_lague::simulation::{project_seats, Checkpoint, PollAverage};
+/// use sainte_lague::Method;
+///
+/// let average = PollAverage { shares: vec![0.42, 0.38, 0.2], sample_size: 1_000.0 };
+/// let mut checkpoint = Checkpoint::new(average.clone(), 100, 1_000, 42);
+/// assert_eq!(checkpoint.run(&Method::SainteLague, 400), Ok(None));
+/// assert_eq!(checkpoint.done(), 400);
+///
+/// // e.g. checkpoint.save(Path::new("projection.checkpoint")) and exit
+/// let bytes = sainte_lague::io::wire::encode_checkpoint(&checkpoint);
+/// let mut resumed = sainte_lague::io::wire::decode_checkpoint(&bytes).unwrap();
+/// let projection = resumed.run(&Method::SainteLague, usize::MAX).unwrap();
+/// assert_eq!(projection, Some(project_seats(&Method::SainteLague, &average, &100, 1_000, 42).unwrap()));
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct Checkpoint {
+    pub(crate) average: PollAverage,
+    pub(crate) seat_count: usize,
+    pub(crate) trials: usize,
+    pub(crate) seed: u64,
+    pub(crate) done: usize,
+    pub(crate) samples: Vec<Vec<usize>>,
+    pub(crate) skipped: usize,
+}
+
+impl Checkpoint {
+    /// Start a projection with the same arguments as [`project_seats`].
+    pub fn new(average: PollAverage, seat_count: usize, trials: usize, seed: u64) -> Checkpoint {
+        let samples = vec![vec![]; average.shares.len()];
+        Checkpoint {
+            average,
+            seat_count,
+            trials,
+            seed,
+            done: 0,
+            samples,
+            skipped: 0,
+        }
+    }
+
+    /// The number of simulations done so far.
+    pub fn done(&self) -> usize {
+        self.done
+    }
+
+    /// The total number of simulations.
+    pub fn trials(&self) -> usize {
+        self.trials
+    }
+
+    /// Run at most `batch` more simulations. Returns the projection once all simulations are done, and [`None`] before. Fails with the same errors as [`project_seats`], in which case the failed simulation is not counted as done.
+    pub fn run<A: Allocator>(
+        &mut self,
+        allocator: &A,
+        batch: usize,
+    ) -> Result<Option<SeatProjection>, A::Error> {
+        let seats = allocator.allocate(&self.average.shares, &self.seat_count)?;
+        let end = self.done.saturating_add(batch).min(self.trials);
+        while self.done < end {
+            let simulated = simulate_poll(
+                allocator,
+                &self.average,
+                &self.seat_count,
+                self.seed,
+                self.done,
+            );
+            record(&mut self.samples, &mut self.skipped, simulated)?;
+            self.done += 1;
+        }
+        if self.done < self.trials {
+            return Ok(None);
+        }
+        Ok(Some(SeatProjection {
+            ranges: ranges(&seats, self.samples.clone()),
+            seats,
+            skipped: self.skipped,
+        }))
+    }
+
+    /// Write the checkpoint to the file at `path`. The file is replaced atomically, so an interruption while saving leaves the previous checkpoint intact.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        fs::write(&temporary, wire::encode_checkpoint(self))?;
+        fs::rename(&temporary, path)
+    }
+
+    /// Read a checkpoint previously [saved](Checkpoint::save) to the file at `path`. Malformed files fail with [`io::ErrorKind::InvalidData`].
+    pub fn load(path: &Path) -> io::Result<Checkpoint> {
+        let bytes = fs::read(path)?;
+        wire::decode_checkpoint(&bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// Same as [`project_seats`], but with the simulations split among `threads` threads. Since every simulation has its own [`substream`], the result is identical to [`project_seats`] with the same seed, regardless of the number of threads. Zero threads are treated as one.
+///
+/// ```
+/// use sainte_lague::simulation::{project_seats, project_seats_parallel, PollAverage};
+/// use sainte_lague::Method;
+///
+/// let average = PollAverage { shares: vec![0.42, 0.38, 0.2], sample_size: 1_000.0 };
+/// let serial = project_seats(&Method::SainteLague, &average, &100, 1_000, 42);
+/// let parallel = project_seats_parallel(&Method::SainteLague, &average, &100, 1_000, 42, 4);
+/// assert_eq!(serial, parallel);
+/// ```
+pub fn project_seats_parallel<A>(
+    allocator: &A,
+    average: &PollAverage,
+    seat_count: &usize,
+    trials: usize,
+    seed: u64,
+    threads: usize,
+) -> Result<SeatProjection, A::Error>
+where
+    A: Allocator + Sync,
+    A::Error: Send,
+{
+    let seats = allocator.allocate(&average.shares, seat_count)?;
+    let chunk = trials.div_ceil(threads.max(1));
+    let simulated: Vec<Result<Vec<usize>, A::Error>> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..trials)
+            .step_by(chunk.max(1))
+            .map(|start| {
+                let end = (start + chunk).min(trials);
+                scope.spawn(move || {
+                    (start..end)
+                        .map(|trial| simulate_poll(allocator, average, seat_count, seed, trial))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().expect("simulation thread panicked"))
+            .collect()
+    });
+    summarize(seats, simulated.into_iter())
+}
+
+/// An independent random number generator for stream number `stream` of the master `seed`. Different streams of the same seed, as well as the same stream of different seeds, never share a generator seed, so simulations can draw from their own stream in any order or on any thread.
+pub fn substream(seed: u64, stream: u64) -> StdRng {
+    let mut bytes = [0u8; 32];
+    let mut seed_state = seed;
+    let mut stream_state = stream;
+    let (seed_bytes, stream_bytes) = bytes.split_at_mut(16);
+    for chunk in seed_bytes.chunks_mut(8) {
+        chunk.copy_from_slice(&splitmix64(&mut seed_state).to_le_bytes());
+    }
+    for chunk in stream_bytes.chunks_mut(8) {
+        chunk.copy_from_slice(&splitmix64(&mut stream_state).to_le_bytes());
+    }
+    StdRng::from_seed(bytes)
+}
+
+/// The next output of the SplitMix64 generator, a bijective mixing of its state.
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Simulate poll number `trial` with the [`Normal`] model of its sample size and distribute the seats according to it.
+fn simulate_poll<A: Allocator>(
+    allocator: &A,
+    average: &PollAverage,
+    seat_count: &usize,
+    seed: u64,
+    trial: usize,
+) -> Result<Vec<usize>, A::Error> {
+    let model = Normal {
+        sample_size: average.sample_size,
+    };
+    simulate_result(allocator, &model, &average.shares, seat_count, seed, trial)
+}
+
+/// Draw result number `trial` from the `model` and distribute the seats according to it.
+fn simulate_result<A, M>(
+    allocator: &A,
+    model: &M,
+    shares: &[f64],
+    seat_count: &usize,
+    seed: u64,
+    trial: usize,
+) -> Result<Vec<usize>, A::Error>
+where
+    A: Allocator,
+    M: VoteErrorModel + ?Sized,
+{
+    let mut rng = substream(seed, trial as u64);
+    allocator.allocate(&model.sample(shares, &mut rng), seat_count)
+}
+
+/// Collect the simulated distributions, in the order of the simulations, into seat ranges.
+fn summarize<E: From<DistributionError> + PartialEq>(
+    seats: Vec<usize>,
+    simulated: impl Iterator<Item = Result<Vec<usize>, E>>,
+) -> Result<SeatProjection, E> {
+    let mut samples: Vec<Vec<usize>> = vec![vec![]; seats.len()];
+    let mut skipped = 0;
+    for result in simulated {
+        record(&mut samples, &mut skipped, result)?;
+    }
+    Ok(SeatProjection {
+        ranges: ranges(&seats, samples),
+        seats,
+        skipped,
+    })
+}
+
+/// Add a simulated distribution to the samples of each party, or count it as skipped if it was tied. Other errors are returned as they are.
+fn record<E: From<DistributionError> + PartialEq>(
+    samples: &mut [Vec<usize>],
+    skipped: &mut usize,
+    result: Result<Vec<usize>, E>,
+) -> Result<(), E> {
+    match result {
+        Ok(simulated) => {
+            for (sample, s) in samples.iter_mut().zip(simulated) {
+                sample.push(s);
+            }
+        }
+        Err(e) if e == E::from(DistributionError::Tied) => *skipped += 1,
+        Err(e) => return Err(e),
+    }
+    Ok(())
+}
+
+/// The central 90 % of the samples of each party, or its point estimate if there are no samples.
+fn ranges(seats: &[usize], mut samples: Vec<Vec<usize>>) -> Vec<(usize, usize)> {
+    samples
+        .iter_mut()
+        .zip(seats.iter())
+        .map(|(sample, s)| {
+            if sample.is_empty() {
+                return (*s, *s);
+            }
+            sample.sort_unstable();
+            let quantile = |q: f64| sample[(q * (sample.len() - 1) as f64).round() as usize];
+            (quantile(0.05), quantile(0.95))
+        })
+        .collect()
+}
+
+/// The distribution of parliaments over many simulations, see [`simulate`].
+///
+/// A summary stores each distinct parliament once, together with the number of simulations that produced it. This is usually far less data than the raw simulations, but still answers questions about the joint distribution, such as the probability that a coalition has a majority.
+///
+/// ```
+/// use sainte_lague::simulation::Summary;
+///
+/// let summary: Summary = vec![vec![5, 4, 1], vec![4, 5, 1], vec![5, 4, 1], vec![6, 3, 1]].into_iter().collect();
+/// assert_eq!(summary.trials(), 4);
+/// assert_eq!(summary.most_likely(), Some(&[5, 4, 1][..]));
+/// assert_eq!(summary.histogram(0), vec![0, 0, 0, 0, 1, 2, 1]);
+/// assert_eq!(summary.quantile(0, 0.5), Some(5));
+/// assert_eq!(summary.majority_probability(&[0]), 0.25);
+/// assert_eq!(summary.majority_probability(&[1, 2]), 0.25);
+/// ```
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct Summary {
+    parliaments: Vec<(Vec<usize>, usize)>,
+}
+
+impl Summary {
+    /// Create a summary from distinct parliaments and the number of simulations that produced each of them. Repeated parliaments are merged.
+    pub fn from_counts(counts: Vec<(Vec<usize>, usize)>) -> Summary {
+        let mut totals: BTreeMap<Vec<usize>, usize> = BTreeMap::new();
+        for (parliament, count) in counts {
+            *totals.entry(parliament).or_insert(0) += count;
+        }
+        let mut merged: Vec<(Vec<usize>, usize)> =
+            totals.into_iter().filter(|(_, count)| *count > 0).collect();
+        // most likely parliaments first, a stable sort keeps ties in lexicographic order so the
+        // summary is canonical
+        merged.sort_by_key(|(_, count)| Reverse(*count));
+        Summary {
+            parliaments: merged,
+        }
+    }
+
+    /// Each distinct parliament and its number of simulations, from the most to the least likely one.
+    pub fn parliaments(&self) -> &[(Vec<usize>, usize)] {
+        &self.parliaments
+    }
+
+    /// The number of simulations.
+    pub fn trials(&self) -> usize {
+        self.parliaments.iter().map(|(_, count)| count).sum()
+    }
+
+    /// The parliament produced by the most simulations, or [`None`] if there were none.
+    pub fn most_likely(&self) -> Option<&[usize]> {
+        self.parliaments.first().map(|(p, _)| p.as_slice())
+    }
+
+    /// The number of simulations in which the party with index `party` received `s` seats, at index `s`. Parties that don't exist never receive seats.
+    pub fn histogram(&self, party: usize) -> Vec<usize> {
+        let mut histogram = vec![];
+        for (parliament, count) in self.parliaments.iter() {
+            let seats = parliament.get(party).cloned().unwrap_or(0);
+            if histogram.len() <= seats {
+                histogram.resize(seats + 1, 0);
+            }
+            histogram[seats] += count;
+        }
+        histogram
+    }
+
+    /// The lowest number of seats of the party with index `party` in at least the share `q` (between `0.0` and `1.0`) of all simulations, e.g. `0.5` for the median. Returns [`None`] if there were no simulations or `q` is out of range.
+    pub fn quantile(&self, party: usize, q: f64) -> Option<usize> {
+        let trials = self.trials();
+        if trials == 0 || !(0.0..=1.0).contains(&q) {
+            return None;
+        }
+        let needed = ((q * trials as f64).ceil() as usize).max(1);
+        let mut seen = 0;
+        self.histogram(party).iter().position(|count| {
+            seen += count;
+            seen >= needed
+        })
+    }
+
+    /// The share of simulations in which the parties with the given indices together hold more than half of the seats. This is `0.0` if there were no simulations.
+    pub fn majority_probability(&self, coalition: &[usize]) -> f64 {
+        let trials = self.trials();
+        if trials == 0 {
+            return 0.0;
+        }
+        let majorities: usize = self
+            .parliaments
+            .iter()
+            .filter(|(parliament, _)| {
+                let total: usize = parliament.iter().sum();
+                let seats: usize = coalition.iter().filter_map(|p| parliament.get(*p)).sum();
+                2 * seats > total
+            })
+            .map(|(_, count)| count)
+            .sum();
+        majorities as f64 / trials as f64
+    }
+}
+
+impl FromIterator<Vec<usize>> for Summary {
+    fn from_iter<I: IntoIterator<Item = Vec<usize>>>(parliaments: I) -> Summary {
+        Summary::from_counts(parliaments.into_iter().map(|p| (p, 1)).collect())
+    }
+}
+
+/// One line per party with its median and central 90 % range of seats, followed by the most likely parliament and its probability.
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let trials = self.trials();
+        let (parliament, count) = match self.parliaments.first() {
+            Some(first) => first,
+            None => return writeln!(f, "no simulations"),
+        };
+        for party in 0..parliament.len() {
+            let quantile = |q: f64| self.quantile(party, q).unwrap_or(0);
+            writeln!(
+                f,
+                "party {}: {} seats ({}–{})",
+                party,
+                quantile(0.5),
+                quantile(0.05),
+                quantile(0.95)
+            )?;
+        }
+        let seats: Vec<String> = parliament.iter().map(|s| s.to_string()).collect();
+        writeln!(
+            f,
+            "most likely: {} ({:.1} %)",
+            seats.join(", "),
+            100.0 * *count as f64 / trials as f64
+        )
+    }
+}
+
+/// Serialized as the list of distinct parliaments and their counts.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Summary {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.parliaments.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Summary {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Summary, D::Error> {
+        Vec::<(Vec<usize>, usize)>::deserialize(deserializer).map(Summary::from_counts)
+    }
+}
+
+/// Simulate `trials` polls like [`project_seats`] and summarize the resulting parliaments. Simulations resulting in a tie are not part of the summary, all other errors are returned.
+pub fn simulate<A: Allocator>(
+    allocator: &A,
+    average: &PollAverage,
+    seat_count: &usize,
+    trials: usize,
+    seed: u64,
+) -> Result<Summary, A::Error> {
+    let model = Normal {
+        sample_size: average.sample_size,
+    };
+    simulate_with_model(allocator, &model, &average.shares, seat_count, trials, seed)
+}
+
+/// Same as [`simulate`], but the `trials` simulated results are drawn from the expected `shares` with the given [`VoteErrorModel`].
+pub fn simulate_with_model<A, M>(
+    allocator: &A,
+    model: &M,
+    shares: &[f64],
+    seat_count: &usize,
+    trials: usize,
+    seed: u64,
+) -> Result<Summary, A::Error>
+where
+    A: Allocator,
+    M: VoteErrorModel + ?Sized,
+{
+    let mut parliaments = vec![];
+    for trial in 0..trials {
+        match simulate_result(allocator, model, shares, seat_count, seed, trial) {
+            Ok(parliament) => parliaments.push((parliament, 1)),
+            Err(e) if e == A::Error::from(DistributionError::Tied) => (),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(Summary::from_counts(parliaments))
+}
+
+/// Apply a uniform swing to the votes of a district: the share of each party changes by the same amount in every district, e.g. `0.02` for a gain of two percentage points. Shares that would fall below zero are set to zero, the total number of votes stays the same unless no votes remain at all.
+///
+/// Returns [`None`] if the `swing` doesn't contain one value per party.
+///
+/// ```
+/// use sainte_lague::simulation::swing_votes;
+///
+/// assert_eq!(swing_votes(&[600.0, 400.0], &[-0.1, 0.1]), Some(vec![500.0, 500.0]));
+/// assert_eq!(swing_votes(&[950.0, 50.0], &[0.1, -0.1]), Some(vec![1000.0, 0.0]));
+/// assert_eq!(swing_votes(&[950.0, 50.0], &[0.1]), None);
+/// ```
+pub fn swing_votes(votes: &[f64], swing: &[f64]) -> Option<Vec<f64>> {
+    if votes.len() != swing.len() {
+        return None;
+    }
+    let total: f64 = votes.iter().sum();
+    if total <= 0.0 {
+        return Some(votes.to_vec());
+    }
+    let shares: Vec<f64> = votes
+        .iter()
+        .zip(swing.iter())
+        .map(|(v, s)| (v / total + s).max(0.0))
+        .collect();
+    let share_total: f64 = shares.iter().sum();
+    Some(shares.iter().map(|s| s / share_total * total).collect())
+}
+
+/// The result of [`uniform_swing`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct SwingResult {
+    /// The seats of each party in each district.
+    pub districts: Vec<Vec<usize>>,
+    /// The seats of each party over all districts.
+    pub seats: Vec<usize>,
+}
+
+/// Apply a uniform national swing to the results of a baseline election and distribute the seats of every district again. Each district is given by its votes and its number of seats, the swing is applied to each of them with [`swing_votes`]. Returns `Ok(None)` if the swing doesn't contain one value per party of a district.
+///
+/// ```
+/// use sainte_lague::simulation::uniform_swing;
+/// use sainte_lague::Method;
+///
+/// let baseline = [(vec![600.0, 400.0], 5), (vec![300.0, 700.0], 5)];
+/// let result = uniform_swing(&Method::SainteLague, &baseline, &[0.1, -0.1]).unwrap().unwrap();
+/// assert_eq!(result.districts, vec![vec![3, 2], vec![2, 3]]);
+/// assert_eq!(result.seats, vec![5, 5]);
+/// ```
+pub fn uniform_swing<A: Allocator>(
+    allocator: &A,
+    baseline: &[(Vec<f64>, usize)],
+    swing: &[f64],
+) -> Result<Option<SwingResult>, A::Error> {
+    let mut seats = vec![0; swing.len()];
+    let mut districts = vec![];
+    for (votes, seat_count) in baseline.iter() {
+        let votes = match swing_votes(votes, swing) {
+            Some(votes) => votes,
+            None => return Ok(None),
+        };
+        let district = allocator.allocate(&votes, seat_count)?;
+        for (total, s) in seats.iter_mut().zip(district.iter()) {
+            *total += s;
+        }
+        districts.push(district);
+    }
+    Ok(Some(SwingResult { districts, seats }))
+}
+
+/// Simulate `trials` elections from the results of a baseline election and summarize the total number of seats of each party over all districts. Each district is given by its votes and its number of seats like in [`uniform_swing`]. In every simulation, the shares of all districts are drawn together with [`VoteErrorModel::sample_regions`], so models like [`CorrelatedSwing`] can correlate the errors between districts. Each simulation uses its own [`substream`] of the seed.
+///
+/// Simulations resulting in a tie in any district are not part of the summary, all other errors are returned. All districts must contain the same number of parties.
+///
+/// ```
+/// use sainte_lague::simulation::{simulate_districts, CorrelatedSwing};
+/// use sainte_lague::Method;
+///
+/// let baseline: Vec<(Vec<f64>, usize)> = (0..20).map(|_| (vec![520.0, 480.0], 1)).collect();
+/// let independent = CorrelatedSwing { national: 0.0, regional: 0.03 };
+/// let correlated = CorrelatedSwing { national: 0.03, regional: 0.0 };
+/// let independent = simulate_districts(&Method::SainteLague, &independent, &baseline, 500, 7).unwrap();
+/// let correlated = simulate_districts(&Method::SainteLague, &correlated, &baseline, 500, 7).unwrap();
+/// // a shared swing sweeps all districts at once
+/// assert!(correlated.quantile(0, 0.05) < independent.quantile(0, 0.05));
+/// assert!(correlated.quantile(0, 0.95) > independent.quantile(0, 0.95));
+/// ```
+pub fn simulate_districts<A, M>(
+    allocator: &A,
+    model: &M,
+    baseline: &[(Vec<f64>, usize)],
+    trials: usize,
+    seed: u64,
+) -> Result<Summary, A::Error>
+where
+    A: Allocator,
+    M: VoteErrorModel + ?Sized,
+{
+    let party_count = baseline.first().map_or(0, |(votes, _)| votes.len());
+    let shares: Vec<Vec<f64>> = baseline
+        .iter()
+        .map(|(votes, _)| {
+            assert_eq!(
+                votes.len(),
+                party_count,
+                "same number of parties in every district"
+            );
+            let total: f64 = votes.iter().sum();
+            votes.iter().map(|v| v / total).collect()
+        })
+        .collect();
+    let mut parliaments = vec![];
+    'trials: for trial in 0..trials {
+        let mut rng = substream(seed, trial as u64);
+        let mut seats = vec![0; party_count];
+        for (simulated, (_, seat_count)) in
+            model.sample_regions(&shares, &mut rng).iter().zip(baseline)
+        {
+            match allocator.allocate(simulated, seat_count) {
+                Ok(district) => {
+                    for (total, s) in seats.iter_mut().zip(district) {
+                        *total += s;
+                    }
+                }
+                Err(e) if e == A::Error::from(DistributionError::Tied) => continue 'trials,
+                Err(e) => return Err(e),
+            }
+        }
+        parliaments.push((seats, 1));
+    }
+    Ok(Summary::from_counts(parliaments))
+}
+
+/// Draw from the standard normal distribution using the Box-Muller transform.
+pub(crate) fn standard_normal(rng: &mut StdRng) -> f64 {
+    let u: f64 = 1.0 - rng.gen::<f64>();
+    let v: f64 = rng.gen();
+    (-2.0 * u.ln()).sqrt() * (2.0 * std::f64::consts::PI * v).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        average_polls, fuse_exit_poll, project, project_seats, project_seats_parallel,
+        project_seats_with_model, project_seats_with_progress, simulate, simulate_districts,
+        simulate_with_model, substream, swing_votes, uniform_swing, Checkpoint, CorrelatedSwing,
+        District, Estimate, Multinomial, Normal, Poll, PollAverage, Summary, VoteErrorModel,
+    };
+    use crate::{DistributionError, Method};
+
+    fn district(counted: Vec<f64>, expected_votes: f64) -> District {
+        District {
+            counted,
+            expected_votes,
+        }
+    }
+
+    #[test]
+    fn uncounted_districts() {
+        let districts = [
+            district(vec![10.0, 30.0], 200.0),
+            district(vec![0.0, 0.0], 200.0),
+        ];
+        assert_eq!(project(&districts), Some(vec![100.0, 300.0]));
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert_eq!(project(&[]), None);
+        assert_eq!(project(&[district(vec![0.0], 10.0)]), None);
+        assert_eq!(project(&[district(vec![-1.0, 2.0], 10.0)]), None);
+        assert_eq!(project(&[district(vec![1.0], -10.0)]), None);
+        let ragged = [district(vec![1.0], 1.0), district(vec![1.0, 2.0], 1.0)];
+        assert_eq!(project(&ragged), None);
+    }
+
+    #[test]
+    fn exit_poll_fusion() {
+        let exit_poll = Estimate {
+            shares: vec![0.4, 0.6],
+            standard_errors: vec![0.01, 0.01],
+        };
+        // fully counted districts are exact
+        let counted = [district(vec![700.0, 300.0], 1000.0)];
+        let fused = fuse_exit_poll(&counted, &exit_poll).unwrap();
+        assert_eq!(fused.shares, vec![0.7, 0.3]);
+        assert_eq!(fused.standard_errors, vec![0.0, 0.0]);
+
+        // with equal precision, both sources count the same
+        let partial = [district(vec![4.0, 6.0], 20.0)];
+        let error = (0.24f64 / 10.0 * 0.5).sqrt();
+        let equal = Estimate {
+            shares: vec![0.6, 0.4],
+            standard_errors: vec![error, error],
+        };
+        let fused = fuse_exit_poll(&partial, &equal).unwrap();
+        assert!((fused.shares[0] - 0.5).abs() < 1e-9);
+        assert!((fused.standard_errors[0] - error / 2f64.sqrt()).abs() < 1e-9);
+
+        let exact = Estimate {
+            shares: vec![30.0, 70.0],
+            standard_errors: vec![0.0, 0.0],
+        };
+        let fused = fuse_exit_poll(&partial, &exact).unwrap();
+        assert!((fused.shares[0] - 0.3).abs() < 1e-12);
+        let empty = Estimate {
+            shares: vec![0.0, 0.0],
+            standard_errors: vec![0.0, 0.0],
+        };
+        assert!((fuse_exit_poll(&partial, &empty).unwrap().shares[0] - 0.4).abs() < 1e-12);
+
+        let negative = Estimate {
+            shares: vec![0.5, 0.5],
+            standard_errors: vec![-0.1, 0.1],
+        };
+        assert_eq!(fuse_exit_poll(&partial, &negative), None);
+        assert_eq!(fuse_exit_poll(&[], &exit_poll), None);
+        let short = Estimate {
+            shares: vec![0.5],
+            standard_errors: vec![0.1, 0.1],
+        };
+        assert_eq!(fuse_exit_poll(&partial, &short), None);
+    }
+
+    fn poll(shares: Vec<f64>, sample_size: f64, age: f64) -> Poll {
+        Poll {
+            shares,
+            sample_size,
+            age,
+        }
+    }
+
+    #[test]
+    fn poll_weights() {
+        let polls = [
+            poll(vec![60.0, 40.0], 1000.0, 0.0),
+            poll(vec![0.4, 0.6], 2000.0, 10.0),
+        ];
+        // the second poll has half the weight of the first one after one half-life
+        let average = average_polls(&polls, 10.0).unwrap();
+        assert_eq!(average.sample_size, 2000.0);
+        assert_eq!(average.shares, vec![0.5, 0.5]);
+
+        let unweighted = average_polls(&polls, f64::INFINITY).unwrap();
+        assert_eq!(unweighted.sample_size, 3000.0);
+        assert!((unweighted.shares[0] - 1.4 / 3.0).abs() < 1e-12);
+
+        assert_eq!(average_polls(&[], 10.0), None);
+        assert_eq!(average_polls(&[poll(vec![1.0], -1.0, 0.0)], 10.0), None);
+        assert_eq!(average_polls(&[poll(vec![0.0], 1.0, 0.0)], 10.0), None);
+        let ragged = [poll(vec![1.0], 1.0, 0.0), poll(vec![1.0, 2.0], 1.0, 0.0)];
+        assert_eq!(average_polls(&ragged, 10.0), None);
+    }
+
+    #[test]
+    fn seat_ranges() {
+        let average = PollAverage {
+            shares: vec![0.5, 0.3, 0.2],
+            sample_size: 1000.0,
+        };
+        let projection = project_seats(&Method::SainteLague, &average, &100, 500, 1).unwrap();
+        assert_eq!(projection.seats, vec![50, 30, 20]);
+        for (s, (low, high)) in projection.seats.iter().zip(projection.ranges.iter()) {
+            assert!(low < s && s < high);
+        }
+        assert_eq!(
+            project_seats(&Method::SainteLague, &average, &100, 500, 1),
+            Ok(projection)
+        );
+
+        // larger samples give narrower ranges
+        let precise = PollAverage {
+            sample_size: 1_000_000.0,
+            ..average.clone()
+        };
+        let projection = project_seats(&Method::SainteLague, &precise, &100, 500, 1).unwrap();
+        assert_eq!(projection.ranges, vec![(50, 50), (30, 30), (20, 20)]);
+
+        let no_trials = project_seats(&Method::SainteLague, &average, &100, 0, 1).unwrap();
+        assert_eq!(no_trials.ranges, vec![(50, 50), (30, 30), (20, 20)]);
+        assert_eq!(
+            project_seats(&Method::SainteLague, &average, &0, 10, 1),
+            Err(DistributionError::InvalidSeatCount)
+        );
+    }
+
+    #[test]
+    fn parallel_is_reproducible() {
+        let average = PollAverage {
+            shares: vec![0.35, 0.34, 0.2, 0.11],
+            sample_size: 800.0,
+        };
+        let serial = project_seats(&Method::SainteLague, &average, &20, 301, 9);
+        for threads in 0..6 {
+            assert_eq!(
+                project_seats_parallel(&Method::SainteLague, &average, &20, 301, 9, threads),
+                serial
+            );
+        }
+        assert_eq!(
+            project_seats_parallel(&Method::SainteLague, &average, &0, 10, 9, 2),
+            Err(DistributionError::InvalidSeatCount)
+        );
+        let no_trials = project_seats_parallel(&Method::SainteLague, &average, &20, 0, 9, 4);
+        assert_eq!(
+            no_trials,
+            project_seats(&Method::SainteLague, &average, &20, 0, 9)
+        );
+    }
+
+    #[test]
+    fn progress_and_cancellation() {
+        use crate::progress::Control;
+        let average = PollAverage {
+            shares: vec![0.5, 0.3, 0.2],
+            sample_size: 1000.0,
+        };
+        let mut reports = 0;
+        let projection =
+            project_seats_with_progress(&Method::SainteLague, &average, &100, 50, 1, |p| {
+                reports += 1;
+                assert_eq!((p.done, p.total), (reports, 50));
+                Control::Continue
+            });
+        assert_eq!(reports, 50);
+        assert_eq!(
+            projection,
+            project_seats(&Method::SainteLague, &average, &100, 50, 1).map(Some)
+        );
+        let cancelled =
+            project_seats_with_progress(&Method::SainteLague, &average, &100, 50, 1, |p| {
+                match p.done {
+                    10 => Control::Cancel,
+                    _ => Control::Continue,
+                }
+            });
+        assert_eq!(cancelled, Ok(None));
+    }
+
+    #[test]
+    fn resume_from_checkpoint() {
+        let average = PollAverage {
+            shares: vec![0.35, 0.34, 0.2, 0.11],
+            sample_size: 800.0,
+        };
+        let expected = project_seats(&Method::SainteLague, &average, &20, 250, 5).unwrap();
+
+        let mut checkpoint = Checkpoint::new(average.clone(), 20, 250, 5);
+        let path =
+            std::env::temp_dir().join(format!("sainte_lague_{}.checkpoint", std::process::id()));
+        loop {
+            checkpoint.save(&path).unwrap();
+            checkpoint = Checkpoint::load(&path).unwrap();
+            if let Some(projection) = checkpoint.run(&Method::SainteLague, 60).unwrap() {
+                assert_eq!(projection, expected);
+                break;
+            }
+        }
+        assert_eq!((checkpoint.done(), checkpoint.trials()), (250, 250));
+        std::fs::write(&path, b"SL\x01\x01").unwrap();
+        assert_eq!(
+            Checkpoint::load(&path).unwrap_err().kind(),
+            std::io::ErrorKind::InvalidData
+        );
+        std::fs::remove_file(&path).unwrap();
+
+        let mut invalid = Checkpoint::new(average, 0, 10, 5);
+        assert_eq!(
+            invalid.run(&Method::SainteLague, 10),
+            Err(DistributionError::InvalidSeatCount)
+        );
+        assert_eq!(invalid.done(), 0);
+    }
+
+    #[test]
+    fn summaries() {
+        let summary = Summary::from_counts(vec![
+            (vec![3, 2], 1),
+            (vec![2, 3], 2),
+            (vec![3, 2], 1),
+            (vec![5, 0], 0),
+        ]);
+        assert_eq!(summary.parliaments(), &[(vec![2, 3], 2), (vec![3, 2], 2)]);
+        assert_eq!(summary.histogram(1), vec![0, 0, 2, 2]);
+        assert_eq!(summary.histogram(7), vec![4]);
+        assert_eq!(summary.quantile(0, 0.0), Some(2));
+        assert_eq!(summary.quantile(0, 0.5), Some(2));
+        assert_eq!(summary.quantile(0, 0.51), Some(3));
+        assert_eq!(summary.quantile(0, 1.5), None);
+        assert_eq!(summary.majority_probability(&[0]), 0.5);
+        assert_eq!(summary.majority_probability(&[0, 1]), 1.0);
+        assert_eq!(
+            summary.to_string(),
+            "party 0: 2 seats (2–3)\nparty 1: 2 seats (2–3)\nmost likely: 2, 3 (50.0 %)\n"
+        );
+
+        let empty = Summary::default();
+        assert_eq!((empty.trials(), empty.most_likely()), (0, None));
+        assert_eq!(empty.quantile(0, 0.5), None);
+        assert_eq!(empty.majority_probability(&[0]), 0.0);
+        assert_eq!(empty.to_string(), "no simulations\n");
+    }
+
+    #[test]
+    fn simulated_summary() {
+        let average = PollAverage {
+            shares: vec![0.5, 0.3, 0.2],
+            sample_size: 1000.0,
+        };
+        let summary = simulate(&Method::SainteLague, &average, &100, 500, 1).unwrap();
+        let projection = project_seats(&Method::SainteLague, &average, &100, 500, 1).unwrap();
+        assert_eq!(summary.trials() + projection.skipped, 500);
+        for (p, range) in projection.ranges.iter().enumerate() {
+            let low = summary.quantile(p, 0.05).unwrap();
+            let high = summary.quantile(p, 0.95).unwrap();
+            assert!(low <= range.0 + 1 && range.1 <= high + 1);
+        }
+    }
+
+    #[test]
+    fn error_models() {
+        let average = PollAverage {
+            shares: vec![0.5, 0.3, 0.2],
+            sample_size: 1000.0,
+        };
+        let normal = Normal {
+            sample_size: 1000.0,
+        };
+        assert_eq!(
+            project_seats_with_model(&Method::SainteLague, &normal, &average.shares, &100, 200, 3),
+            project_seats(&Method::SainteLague, &average, &100, 200, 3)
+        );
+        assert_eq!(
+            simulate_with_model(&Method::SainteLague, &normal, &average.shares, &100, 200, 3),
+            simulate(&Method::SainteLague, &average, &100, 200, 3)
+        );
+
+        let mut rng = substream(0, 0);
+        let multinomial = Multinomial { sample_size: 10 };
+        let sample = multinomial.sample(&[3.0, 0.0, 1.0], &mut rng);
+        assert_eq!(sample.iter().sum::<f64>(), 1.0);
+        assert_eq!(sample[1], 0.0);
+        assert!(sample.iter().all(|s| (s * 10.0).fract() == 0.0));
+        assert_eq!(multinomial.sample(&[0.0, 0.0], &mut rng), vec![0.0, 0.0]);
+        let regions = multinomial.sample_regions(&[vec![1.0, 0.0], vec![0.0, 1.0]], &mut rng);
+        assert_eq!(regions, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+
+        let boxed: Box<dyn VoteErrorModel> = Box::new(Multinomial { sample_size: 1000 });
+        let projection = project_seats_with_model(
+            &Method::SainteLague,
+            boxed.as_ref(),
+            &average.shares,
+            &100,
+            200,
+            3,
+        )
+        .unwrap();
+        assert_eq!(projection.seats, vec![50, 30, 20]);
+        for (s, (low, high)) in projection.seats.iter().zip(projection.ranges.iter()) {
+            assert!(low < s && s < high);
+        }
+    }
+
+    #[test]
+    fn correlated_districts() {
+        let baseline = [(vec![620.0, 380.0], 5), (vec![280.0, 720.0], 5)];
+        let fixed = CorrelatedSwing {
+            national: 0.0,
+            regional: 0.0,
+        };
+        let summary = simulate_districts(&Method::SainteLague, &fixed, &baseline, 10, 1).unwrap();
+        assert_eq!(summary.parliaments(), &[(vec![4, 6], 10)]);
+        assert_eq!(
+            simulate_districts(&Method::SainteLague, &fixed, &[(vec![1.0, 1.0], 0)], 10, 1),
+            Err(DistributionError::InvalidSeatCount)
+        );
+        // every simulation of an even district is tied
+        let tied = simulate_districts(&Method::SainteLague, &fixed, &[(vec![1.0, 1.0], 1)], 10, 1);
+        assert_eq!(tied.map(|s| s.trials()), Ok(0));
+
+        let model = CorrelatedSwing {
+            national: 0.05,
+            regional: 0.01,
+        };
+        let mut rng = substream(3, 0);
+        let regions = model.sample_regions(&[vec![0.5, 0.5], vec![0.5, 0.5]], &mut rng);
+        let difference = (regions[0][0] - regions[1][0]).abs();
+        assert!(difference < 0.1 && difference > 0.0);
+        assert_eq!(model.sample(&[0.0, 0.0, 0.0], &mut rng).len(), 3);
+        assert!(model.sample_regions(&[], &mut rng).is_empty());
+    }
+
+    #[test]
+    fn independent_substreams() {
+        use rand::Rng;
+        let first = |seed: u64, stream: u64| substream(seed, stream).gen::<u64>();
+        assert_eq!(first(1, 2), first(1, 2));
+        assert_ne!(first(1, 2), first(2, 1));
+        assert_ne!(first(0, 0), first(0, 1));
+        assert_ne!(first(0, 0), first(1, 0));
+    }
+
+    #[test]
+    fn swing() {
+        assert_eq!(swing_votes(&[0.0, 0.0], &[0.1, -0.1]), Some(vec![0.0, 0.0]));
+        let baseline = [(vec![500.0, 500.0], 2), (vec![800.0, 200.0], 3)];
+        let unchanged = uniform_swing(&Method::SainteLague, &baseline, &[0.0, 0.0]);
+        assert_eq!(unchanged.map(|r| r.map(|r| r.seats)), Ok(Some(vec![3, 2])));
+        assert_eq!(
+            uniform_swing(&Method::SainteLague, &baseline, &[0.05, -0.05])
+                .map(|r| r.map(|r| r.districts)),
+            Ok(Some(vec![vec![1, 1], vec![3, 0]]))
+        );
+        assert_eq!(
+            uniform_swing(&Method::SainteLague, &baseline, &[0.05]),
+            Ok(None)
+        );
+    }
+}
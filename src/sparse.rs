@@ -0,0 +1,153 @@
+//! Sparse votes, for universes with many parties (or candidates) without votes.
+//!
+//! Parties without votes never receive seats with any of the [methods](crate::methods), so they don't need to take part in the allocation at all. [`SparseVotes`] only stores the parties with votes, and [`distribute`] only returns the parties with seats, both by their index in the full universe. Even with millions of parties, memory and time only depend on the number of parties with votes.
+//!
+//! ```
+//! use sainte_lague::sparse::{self, SparseVotes};
+//! use sainte_lague::Method;
+//!
+//! let votes = SparseVotes::new(1_000_000, vec![(17, 362.0), (4_711, 318.0), (999_999, 126.0)]).unwrap();
+//! let seats = sparse::distribute(&Method::SainteLague, &votes, &10, &false).unwrap();
+//! assert_eq!(seats, vec![(17, 4), (4_711, 4), (999_999, 2)]);
+//! ```
+
+use crate::{DistributionError, Method};
+
+/// The votes of a universe of parties, most of which have no votes, see the [module documentation](self).
+#[derive(Clone, PartialEq, Debug)]
+pub struct SparseVotes {
+    len: usize,
+    entries: Vec<(usize, f64)>,
+}
+
+impl SparseVotes {
+    /// Create sparse votes for a universe of `len` parties, from the index and votes of every party with votes. Entries may be given in any order, and entries with zero votes are dropped. Returns [`None`] if an index is out of range or appears twice.
+    pub fn new(len: usize, mut entries: Vec<(usize, f64)>) -> Option<SparseVotes> {
+        entries.retain(|(_, v)| *v != 0.0);
+        entries.sort_by_key(|(p, _)| *p);
+        let unique = entries.windows(2).all(|w| w[0].0 < w[1].0);
+        if !unique || matches!(entries.last(), Some((p, _)) if *p >= len) {
+            return None;
+        }
+        Some(SparseVotes { len, entries })
+    }
+
+    /// Create sparse votes from dense ones.
+    pub fn from_dense(votes: &[f64]) -> SparseVotes {
+        SparseVotes {
+            len: votes.len(),
+            entries: votes
+                .iter()
+                .enumerate()
+                .filter(|(_, v)| **v != 0.0)
+                .map(|(p, v)| (p, *v))
+                .collect(),
+        }
+    }
+
+    /// The number of parties in the universe, including those without votes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the universe contains no parties.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The index and votes of every party with votes, ordered by index.
+    pub fn entries(&self) -> &[(usize, f64)] {
+        &self.entries
+    }
+
+    /// The votes of the party with the given index, zero if it has none.
+    pub fn get(&self, party: usize) -> f64 {
+        match self.entries.binary_search_by_key(&party, |(p, _)| *p) {
+            Ok(i) => self.entries[i].1,
+            Err(_) => 0.0,
+        }
+    }
+
+    /// The votes of all parties, as passed to [`distribute`](crate::distribute).
+    pub fn to_dense(&self) -> Vec<f64> {
+        let mut votes = vec![0.0; self.len];
+        for (p, v) in self.entries.iter() {
+            votes[*p] = *v;
+        }
+        votes
+    }
+}
+
+/// Same as [`Method::distribute`], but for sparse votes. Returns the index and number of seats of every party with seats, ordered by index.
+pub fn distribute(
+    method: &Method,
+    votes: &SparseVotes,
+    seat_count: &usize,
+    draw_on_tie: &bool,
+) -> Result<Vec<(usize, usize)>, DistributionError> {
+    let dense: Vec<f64> = votes.entries.iter().map(|(_, v)| *v).collect();
+    let seats = method.distribute(&dense, seat_count, draw_on_tie)?;
+    Ok(votes
+        .entries
+        .iter()
+        .zip(seats)
+        .filter(|(_, s)| *s > 0)
+        .map(|((p, _), s)| (*p, s))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{distribute, SparseVotes};
+    use crate::{DistributionError, Method};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn same_as_dense() {
+        let mut rng = StdRng::seed_from_u64(460);
+        for _ in 0..500 {
+            let len = rng.gen_range(0..50);
+            let votes: Vec<f64> = (0..len)
+                .map(|_| match rng.gen_bool(0.2) {
+                    true => rng.gen_range(1..1000) as f64,
+                    false => 0.0,
+                })
+                .collect();
+            let seat_count = rng.gen_range(0..30);
+            let sparse = SparseVotes::from_dense(&votes);
+            assert_eq!(sparse.to_dense(), votes);
+
+            let expected = Method::SainteLague.distribute(&votes, &seat_count, &false);
+            let seats = distribute(&Method::SainteLague, &sparse, &seat_count, &false);
+            match expected {
+                Ok(expected) => {
+                    let mut dense = vec![0; len];
+                    for (p, s) in seats.unwrap() {
+                        dense[p] = s;
+                    }
+                    assert_eq!(dense, expected);
+                }
+                Err(e) => assert_eq!(seats, Err(e)),
+            }
+        }
+    }
+
+    #[test]
+    fn construction() {
+        let votes = SparseVotes::new(5, vec![(3, 2.0), (1, 1.0), (4, 0.0)]).unwrap();
+        assert_eq!(votes.entries(), &[(1, 1.0), (3, 2.0)]);
+        assert_eq!((votes.get(3), votes.get(4)), (2.0, 0.0));
+        assert_eq!(votes.len(), 5);
+        assert!(SparseVotes::new(0, vec![]).unwrap().is_empty());
+
+        assert_eq!(SparseVotes::new(3, vec![(3, 1.0)]), None);
+        assert_eq!(SparseVotes::new(3, vec![(1, 1.0), (1, 2.0)]), None);
+
+        let negative = SparseVotes::new(3, vec![(1, -1.0), (2, 2.0)]).unwrap();
+        assert_eq!(
+            distribute(&Method::SainteLague, &negative, &1, &false),
+            Err(DistributionError::NegativeVotes)
+        );
+    }
+}
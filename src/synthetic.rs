@@ -0,0 +1,181 @@
+//! Reproducible synthetic elections for benchmarks and tests.
+//!
+//! A [`Generator`] draws a national party system with a few large parties and a long tail of small ones, and then varies the strength of each party between regions and between the districts of each region. The same seed always produces the same election.
+//!
+//! ```
+//! use sainte_lague::synthetic::Generator;
+//! use sainte_lague::distribute;
+//!
+//! let generator = Generator::default();
+//! let election = generator.generate(7);
+//! assert_eq!(election, generator.generate(7));
+//! assert_eq!(election.districts.len(), generator.district_count);
+//!
+//! for (votes, seat_count) in election.districts.iter() {
+//!     assert!(distribute(votes, seat_count, &true).is_ok());
+//! }
+//! ```
+
+use crate::simulation::standard_normal;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Parameters of the synthetic elections, see the [module documentation](self).
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Generator {
+    /// The number of parties.
+    pub party_count: usize,
+    /// The number of districts.
+    pub district_count: usize,
+    /// The number of regions the districts are grouped into. Districts of the same region vote alike.
+    pub region_count: usize,
+    /// The minimum and maximum number of seats of a district, inclusive.
+    pub seats: (usize, usize),
+    /// The minimum and maximum number of valid votes in a district, inclusive.
+    pub votes: (u64, u64),
+    /// How much the strength of a party varies between regions, as the standard deviation of a factor on the logarithmic scale.
+    pub regional_variation: f64,
+    /// How much the strength of a party varies between the districts of a region, on the same scale as the regional variation.
+    pub local_variation: f64,
+}
+
+impl Default for Generator {
+    /// Eight parties in 20 districts of 2 to 12 seats, grouped into 4 regions.
+    fn default() -> Generator {
+        Generator {
+            party_count: 8,
+            district_count: 20,
+            region_count: 4,
+            seats: (2, 12),
+            votes: (20_000, 500_000),
+            regional_variation: 0.3,
+            local_variation: 0.1,
+        }
+    }
+}
+
+/// A synthetic election, see [`Generator::generate`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct SyntheticElection {
+    /// The votes of each party and the number of seats of each district.
+    pub districts: Vec<(Vec<f64>, usize)>,
+    /// The region of each district.
+    pub regions: Vec<usize>,
+}
+
+impl SyntheticElection {
+    /// The votes of each party, added up over all districts.
+    pub fn national_votes(&self) -> Vec<f64> {
+        let party_count = self.districts.first().map_or(0, |(v, _)| v.len());
+        (0..party_count)
+            .map(|p| self.districts.iter().map(|(v, _)| v[p]).sum())
+            .collect()
+    }
+
+    /// The number of seats of all districts.
+    pub fn seat_count(&self) -> usize {
+        self.districts.iter().map(|(_, s)| s).sum()
+    }
+}
+
+impl Generator {
+    /// Generate an election, reproducibly seeded with `seed`. Votes are whole numbers and parties are sorted by their national strength, largest first.
+    ///
+    /// Panics if there are no parties, districts or regions, or one of the ranges is empty.
+    pub fn generate(&self, seed: u64) -> SyntheticElection {
+        assert!(self.party_count > 0, "at least one party");
+        assert!(self.district_count > 0, "at least one district");
+        assert!(self.region_count > 0, "at least one region");
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        // squared exponential draws give a few large parties and many small ones
+        let mut strengths: Vec<f64> = (0..self.party_count)
+            .map(|_| (1.0 - rng.gen::<f64>()).ln().powi(2))
+            .collect();
+        strengths.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+        let regional_effects: Vec<Vec<f64>> = (0..self.region_count)
+            .map(|_| {
+                (0..self.party_count)
+                    .map(|_| self.regional_variation * standard_normal(&mut rng))
+                    .collect()
+            })
+            .collect();
+
+        let mut districts = vec![];
+        let mut regions = vec![];
+        for d in 0..self.district_count {
+            let region = d % self.region_count;
+            let weights: Vec<f64> = strengths
+                .iter()
+                .zip(regional_effects[region].iter())
+                .map(|(s, r)| s * (r + self.local_variation * standard_normal(&mut rng)).exp())
+                .collect();
+            let total_weight: f64 = weights.iter().sum();
+            let total_votes = rng.gen_range(self.votes.0..=self.votes.1) as f64;
+            let votes = weights
+                .iter()
+                .map(|w| (w / total_weight * total_votes).round())
+                .collect();
+            let seat_count = rng.gen_range(self.seats.0..=self.seats.1);
+            districts.push((votes, seat_count));
+            regions.push(region);
+        }
+        SyntheticElection { districts, regions }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Generator;
+
+    #[test]
+    fn reproducible() {
+        let generator = Generator {
+            party_count: 5,
+            district_count: 10,
+            region_count: 3,
+            seats: (1, 4),
+            votes: (1_000, 2_000),
+            ..Generator::default()
+        };
+        let election = generator.generate(1);
+        assert_eq!(election, generator.generate(1));
+        assert_ne!(election, generator.generate(2));
+
+        assert_eq!(election.regions, vec![0, 1, 2, 0, 1, 2, 0, 1, 2, 0]);
+        for (votes, seat_count) in election.districts.iter() {
+            assert_eq!(votes.len(), 5);
+            assert!((1..=4).contains(seat_count));
+            assert!(votes.iter().all(|v| v.fract() == 0.0 && *v >= 0.0));
+            let total: f64 = votes.iter().sum();
+            assert!((995.0..=2005.0).contains(&total));
+        }
+        let national = election.national_votes();
+        assert!(national[0] >= national[4]);
+        assert!(election.seat_count() >= 10 && election.seat_count() <= 40);
+    }
+
+    #[test]
+    fn regional_variation() {
+        let generator = Generator {
+            region_count: 1,
+            regional_variation: 0.0,
+            local_variation: 0.0,
+            votes: (1_000_000, 1_000_000),
+            ..Generator::default()
+        };
+        let election = generator.generate(3);
+        let first = &election.districts[0].0;
+        assert!(election.districts.iter().all(|(v, _)| v == first));
+    }
+
+    #[test]
+    #[should_panic]
+    fn no_parties() {
+        let generator = Generator {
+            party_count: 0,
+            ..Generator::default()
+        };
+        generator.generate(0);
+    }
+}
@@ -0,0 +1,591 @@
+//! A declarative description of electoral systems and an interpreter for it.
+//!
+//! Instead of writing code for every electoral law, an [`ElectoralSystem`] describes it as data: the divisor method, the [`Tier`] in which the list seats are distributed, thresholds and other [constraints](crate::constraints), the handling of ties, single-member districts and majority bonuses. [`ElectoralSystem::distribute`] executes the description in the following steps:
+//!
+//! 1. District seats: the number of single-member districts won by each party is checked against the number of such districts.
+//! 2. Threshold: the [`Threshold`](Constraint::Threshold), [`Alliance`](Constraint::Alliance), [`Exemption`](Constraint::Exemption) and [`Others`](Constraint::Others) constraints determine the qualified parties, based on the list votes over all districts.
+//! 3. Majority bonus: the party with the most list votes receives the bonus seats.
+//! 4. Proportional seats: the remaining list seats are distributed among the qualified parties, nationally or in each district, respecting their [`Weight`](Constraint::Weight) and seat bounds.
+//!
+//! Errors are reported as a [`PipelineError`] with the number of the step. The proportional seats of the district with index `i` are step `4 + i`.
+//!
+//! All [presets](crate::presets) are expressed as electoral systems, and systems of other countries can be described the same way:
+//!
+//! ```
+//! use sainte_lague::constraints::Constraint;
+//! use sainte_lague::methods::Stationary;
+//! use sainte_lague::system::{Ballots, ElectoralSystem, Tier};
+//!
+//! let system = ElectoralSystem::new("Example", Stationary::new(0.5).unwrap(), Tier::National(20))
+//!     .with(Constraint::Threshold(0.05))
+//!     .with_bonus(4);
+//! let outcome = system.distribute(&Ballots::new(vec![45.0, 35.0, 16.0, 4.0])).unwrap();
+//! assert_eq!(outcome.bonus, vec![4, 0, 0, 0]);
+//! assert_eq!(outcome.seats(), vec![11, 6, 3, 0]);
+//! ```
+
+use crate::constraints::{infeasibility, Constraint, ConstraintError, Rules};
+use crate::methods::{DivisorSearch, Stationary};
+use crate::pipeline::{Context, PipelineError};
+use crate::plurality;
+use crate::validate;
+
+/// Where the list seats of an [`ElectoralSystem`] are distributed.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Tier {
+    /// All list seats are distributed nationally.
+    National(usize),
+    /// The seats of each district, given by its name and number of seats, are distributed separately among the parties qualified nationally.
+    Districts(Vec<(String, usize)>),
+}
+
+impl Tier {
+    /// Parse districts from CSV lines of the form `name,seats`, e.g. the district magnitudes published by an election commission. Empty lines and lines starting with `#` are ignored. Returns [`None`] for malformed lines.
+    ///
+    /// ```
+    /// use sainte_lague::system::Tier;
+    ///
+    /// let tier = Tier::parse_districts("# name,seats\nAceh I,7\nAceh II,6").unwrap();
+    /// assert_eq!(tier.seat_count(), 13);
+    /// ```
+    pub fn parse_districts(csv: &str) -> Option<Tier> {
+        let mut districts = vec![];
+        for line in csv.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, seats) = line.rsplit_once(',')?;
+            districts.push((name.trim().to_string(), seats.trim().parse().ok()?));
+        }
+        Some(Tier::Districts(districts))
+    }
+
+    /// The total number of list seats.
+    pub fn seat_count(&self) -> usize {
+        match self {
+            Tier::National(seats) => *seats,
+            Tier::Districts(districts) => districts.iter().map(|(_, seats)| seats).sum(),
+        }
+    }
+
+    /// The number of rows of list votes expected by [`ElectoralSystem::distribute`].
+    fn district_count(&self) -> usize {
+        match self {
+            Tier::National(_) => 1,
+            Tier::Districts(districts) => districts.len(),
+        }
+    }
+}
+
+/// The method distributing the list seats of an [`ElectoralSystem`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ListMethod {
+    /// A stationary divisor method, awarding the seats one by one.
+    Stationary(Stationary),
+    /// A divisor search, for laws defining the method by rounding quotients with a common divisor. Its [rounding rule](crate::methods::Rounding) decides quotients exactly at a signpost as the law prescribes, and they are detected exactly. Only the quotients the rule leaves tied are resolved according to [`draw_on_tie`](ElectoralSystem::draw_on_tie).
+    Search(DivisorSearch),
+}
+
+impl From<Stationary> for ListMethod {
+    fn from(method: Stationary) -> ListMethod {
+        ListMethod::Stationary(method)
+    }
+}
+
+impl From<DivisorSearch> for ListMethod {
+    fn from(search: DivisorSearch) -> ListMethod {
+        ListMethod::Search(search)
+    }
+}
+
+/// What the threshold of an [`ElectoralSystem`] is a share of.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub enum ThresholdBase {
+    /// The list votes of all parties.
+    #[default]
+    Votes,
+    /// The number of registered voters, see [`Ballots::electorate`].
+    Electorate,
+}
+
+/// The description of an electoral system, see the [module documentation](self).
+#[derive(Clone, PartialEq, Debug)]
+pub struct ElectoralSystem {
+    /// The name of the system or parliament, e.g. `"Landtag Rheinland-Pfalz"`.
+    pub name: String,
+    /// The divisor method used for the list seats.
+    pub method: ListMethod,
+    /// Where the list seats are distributed.
+    pub tier: Tier,
+    /// The rules qualifying parties for list seats and bounding their seats. Seat bounds are only supported with a [national](Tier::National) tier. The method of the rules is not used.
+    pub rules: Rules,
+    /// What the threshold is a share of.
+    pub threshold_base: ThresholdBase,
+    /// The number of single-member districts won by plurality, in addition to the list seats.
+    pub plurality_seats: usize,
+    /// The number of list seats awarded to the party with the most list votes before the proportional distribution. Only supported with a [national](Tier::National) tier.
+    pub bonus: usize,
+    /// Whether the law resolves ties by drawing lots. Otherwise, ties are reported as errors.
+    pub draw_on_tie: bool,
+}
+
+/// The votes of an election, as needed by [`ElectoralSystem::distribute`].
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Ballots {
+    /// The list votes of each party, with one row per district of a [`Tier::Districts`] in the same order, or a single row for a [`Tier::National`]. All rows have the same party order.
+    pub votes: Vec<Vec<f64>>,
+    /// The number of single-member districts won by each party, or empty if the system has none.
+    pub district_winners: Vec<usize>,
+    /// The number of registered voters, required if the threshold is a share of the [electorate](ThresholdBase::Electorate).
+    pub electorate: Option<f64>,
+}
+
+impl Ballots {
+    /// The list votes of each party for a national tier.
+    pub fn new(votes: Vec<f64>) -> Ballots {
+        Ballots::districts(vec![votes])
+    }
+
+    /// The list votes of each party in each district.
+    pub fn districts(votes: Vec<Vec<f64>>) -> Ballots {
+        Ballots {
+            votes,
+            ..Ballots::default()
+        }
+    }
+
+    /// Add the number of single-member districts won by each party.
+    pub fn with_district_winners(self, district_winners: Vec<usize>) -> Ballots {
+        Ballots {
+            district_winners,
+            ..self
+        }
+    }
+
+    /// Add the number of registered voters.
+    pub fn with_electorate(self, electorate: f64) -> Ballots {
+        Ballots {
+            electorate: Some(electorate),
+            ..self
+        }
+    }
+}
+
+/// The seats of each party in an [`ElectoralSystem`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct SystemOutcome {
+    /// The number of single-member districts won by each party.
+    pub district_seats: Vec<usize>,
+    /// The bonus seats of each party.
+    pub bonus: Vec<usize>,
+    /// The proportional seats of each party, with one row per district of a [`Tier::Districts`] or a single row for a [`Tier::National`].
+    pub proportional: Vec<Vec<usize>>,
+}
+
+impl SystemOutcome {
+    /// The list seats of each party, i.e. its bonus and proportional seats.
+    pub fn list_seats(&self) -> Vec<usize> {
+        let mut seats = self.bonus.clone();
+        for row in self.proportional.iter() {
+            for (total, s) in seats.iter_mut().zip(row.iter()) {
+                *total += s;
+            }
+        }
+        seats
+    }
+
+    /// The total number of seats of each party.
+    pub fn seats(&self) -> Vec<usize> {
+        self.list_seats()
+            .iter()
+            .zip(self.district_seats.iter())
+            .map(|(l, d)| l + d)
+            .collect()
+    }
+}
+
+impl ElectoralSystem {
+    /// Describe a system distributing the seats of `tier` with `method`, a [`Stationary`] method or a [`DivisorSearch`], without any constraints, single-member districts or bonus, and reporting ties as errors.
+    pub fn new(name: &str, method: impl Into<ListMethod>, tier: Tier) -> ElectoralSystem {
+        ElectoralSystem {
+            name: name.to_string(),
+            method: method.into(),
+            tier,
+            rules: Rules::default(),
+            threshold_base: ThresholdBase::Votes,
+            plurality_seats: 0,
+            bonus: 0,
+            draw_on_tie: false,
+        }
+    }
+
+    /// Add a constraint.
+    pub fn with(mut self, constraint: Constraint) -> ElectoralSystem {
+        self.rules = self.rules.with(constraint);
+        self
+    }
+
+    /// Apply the threshold to the given base.
+    pub fn with_threshold_base(self, threshold_base: ThresholdBase) -> ElectoralSystem {
+        ElectoralSystem {
+            threshold_base,
+            ..self
+        }
+    }
+
+    /// Add the given number of single-member districts won by plurality.
+    pub fn with_plurality_seats(self, plurality_seats: usize) -> ElectoralSystem {
+        ElectoralSystem {
+            plurality_seats,
+            ..self
+        }
+    }
+
+    /// Award the given number of list seats as a bonus to the party with the most list votes.
+    pub fn with_bonus(self, bonus: usize) -> ElectoralSystem {
+        ElectoralSystem { bonus, ..self }
+    }
+
+    /// Resolve ties by drawing lots.
+    pub fn with_draw_on_tie(self, draw_on_tie: bool) -> ElectoralSystem {
+        ElectoralSystem {
+            draw_on_tie,
+            ..self
+        }
+    }
+
+    /// Distribute the list seats with a divisor search instead of the current method.
+    pub fn with_search(self, search: DivisorSearch) -> ElectoralSystem {
+        ElectoralSystem {
+            method: ListMethod::Search(search),
+            ..self
+        }
+    }
+
+    /// The total number of seats, including single-member districts.
+    pub fn seat_count(&self) -> usize {
+        self.plurality_seats + self.tier.seat_count()
+    }
+
+    /// Distribute the seats according to `ballots`, see the [module documentation](self) for the steps and how their errors are reported.
+    ///
+    /// Fails in step 1 with [`ConstraintError::UnknownParty`] if the district winners are given for a different number of parties than the list votes, or with [`ConstraintError::Infeasible`] if they don't add up to the number of single-member districts. Fails in step 2 with [`ConstraintError::UnknownDistrict`] if the number of rows of list votes doesn't match the tier, with [`ConstraintError::UnknownParty`] if the rows differ in length, or with [`ConstraintError::InvalidThreshold`] if the threshold is a share of an unknown electorate. Fails in step 3 with [`ConstraintError::Infeasible`] if the bonus exceeds the list seats or the list seats are distributed in districts.
+    pub fn distribute(&self, ballots: &Ballots) -> Result<SystemOutcome, PipelineError> {
+        let party_count = ballots.votes.first().map_or(0, Vec::len);
+
+        let district_seats = if ballots.district_winners.is_empty() {
+            vec![0; party_count]
+        } else {
+            ballots.district_winners.clone()
+        };
+        if district_seats.len() != party_count {
+            let party = district_seats.len().min(party_count);
+            return Err(ConstraintError::UnknownParty(party)).context(1, "district seats");
+        }
+        if district_seats.iter().sum::<usize>() != self.plurality_seats {
+            return Err(ConstraintError::Infeasible).context(1, "district seats");
+        }
+
+        let district_count = self.tier.district_count();
+        if ballots.votes.len() != district_count {
+            let district = ballots.votes.len().min(district_count);
+            return Err(ConstraintError::UnknownDistrict(district)).context(2, "list votes");
+        }
+        if let Some(row) = ballots.votes.iter().find(|v| v.len() != party_count) {
+            let party = row.len().min(party_count);
+            return Err(ConstraintError::UnknownParty(party)).context(2, "list votes");
+        }
+        let mut totals = vec![0.0; party_count];
+        for row in ballots.votes.iter() {
+            for (total, v) in totals.iter_mut().zip(row.iter()) {
+                *total += v;
+            }
+        }
+        let base = match self.threshold_base {
+            ThresholdBase::Votes => Some(totals.iter().sum()),
+            ThresholdBase::Electorate => ballots.electorate,
+        };
+        let base = base
+            .ok_or(ConstraintError::InvalidThreshold)
+            .context(2, "threshold")?;
+        let qualified = self
+            .rules
+            .qualified_with_total(&totals, base)
+            .context(2, "threshold")?;
+        let bounds = self
+            .rules
+            .qualified_bounds(&totals, &qualified)
+            .context(2, "threshold")?;
+
+        let mut bonus = vec![0; party_count];
+        if self.bonus > 0 {
+            let national = matches!(self.tier, Tier::National(_));
+            if self.bonus > self.tier.seat_count() || !national {
+                return Err(ConstraintError::Infeasible).context(3, "majority bonus");
+            }
+            // pseudo-parties never win the bonus
+            let candidates: Vec<f64> = totals
+                .iter()
+                .enumerate()
+                .map(
+                    |(p, v)| match self.rules.constraints().contains(&Constraint::Others(p)) {
+                        true => 0.0,
+                        false => *v,
+                    },
+                )
+                .collect();
+            let winner =
+                plurality::winner(&candidates, &self.draw_on_tie).context(3, "majority bonus")?;
+            bonus[winner] = self.bonus;
+        }
+
+        // the bounds are applied exactly like the seat bounds of rules
+        let allocate = |votes: &[f64], seat_count: &usize| match &self.method {
+            ListMethod::Stationary(method) => {
+                method.distribute_bounded(votes, seat_count, &self.draw_on_tie, &bounds)
+            }
+            ListMethod::Search(search) => {
+                search.distribute_bounded(votes, seat_count, &self.draw_on_tie, &bounds)
+            }
+        };
+        let mut proportional = vec![];
+        let rows = match &self.tier {
+            Tier::National(seats) => vec![("proportional seats", seats - self.bonus)],
+            Tier::Districts(districts) => districts
+                .iter()
+                .map(|(name, seats)| (name.as_str(), *seats))
+                .collect(),
+        };
+        let bounded = bounds.iter().any(|b| *b != (0, 0) && *b != (0, usize::MAX));
+        for (i, ((label, seat_count), row)) in rows.iter().zip(ballots.votes.iter()).enumerate() {
+            let step = 4 + i;
+            if *seat_count == 0 {
+                proportional.push(vec![0; party_count]);
+                continue;
+            }
+            if bounded && rows.len() > 1 {
+                return Err(ConstraintError::Infeasible).context(step, label);
+            }
+            let eligible: Vec<f64> = self
+                .rules
+                .weighted(row)
+                .iter()
+                .zip(qualified.iter())
+                .map(|(v, q)| if *q { *v } else { 0.0 })
+                .collect();
+            validate(&eligible, seat_count)
+                .map_err(ConstraintError::from)
+                .context(step, label)?;
+            if infeasibility(&eligible, &bounds, seat_count).is_some() {
+                return Err(ConstraintError::Infeasible).context(step, label);
+            }
+            let seats = allocate(&eligible, seat_count).context(step, label)?;
+            proportional.push(seats);
+        }
+
+        Ok(SystemOutcome {
+            district_seats,
+            bonus,
+            proportional,
+        })
+    }
+
+    /// Same as [`ElectoralSystem::distribute`] for a system with a single national tier, without single-member districts and with the threshold applied to the votes, returning the total number of seats of each party.
+    pub fn distribute_votes(&self, votes: &[f64]) -> Result<Vec<usize>, PipelineError> {
+        self.distribute(&Ballots::new(votes.to_vec()))
+            .map(|outcome| outcome.seats())
+    }
+
+    /// Same as [`ElectoralSystem::distribute`], but the single-member districts are won by [`plurality`](crate::plurality::seats) of the candidate votes in each district, with the same party order as the list votes. Ties in the districts are handled like all other ties. Errors of the districts are reported with step 1.
+    pub fn distribute_districts(
+        &self,
+        ballots: &Ballots,
+        districts: &[Vec<f64>],
+    ) -> Result<SystemOutcome, PipelineError> {
+        let party_count = ballots.votes.first().map_or(0, Vec::len);
+        let winners = plurality::seats(districts, party_count, &self.draw_on_tie)
+            .context(1, "district seats")?;
+        let ballots = Ballots {
+            district_winners: winners,
+            ..ballots.clone()
+        };
+        self.distribute(&ballots)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Ballots, ElectoralSystem, ListMethod, SystemOutcome, ThresholdBase, Tier};
+    use crate::constraints::{Constraint, ConstraintError, Rules};
+    use crate::methods::{DivisorSearch, Rounding, Stationary};
+    use crate::{DistributionError, Method};
+
+    fn sainte_lague() -> Stationary {
+        Stationary::new(0.5).unwrap()
+    }
+
+    #[test]
+    fn same_as_rules() {
+        let constraints = [
+            Constraint::Threshold(0.05),
+            Constraint::Exemption(4),
+            Constraint::MinSeats(2, 3),
+            Constraint::Weight(1, 1.2),
+        ];
+        let system = constraints.iter().fold(
+            ElectoralSystem::new("test", sainte_lague(), Tier::National(30)),
+            |system, c| system.with(c.clone()),
+        );
+        let rules = constraints
+            .iter()
+            .fold(Rules::new(Method::SainteLague), |rules, c| {
+                rules.with(c.clone())
+            });
+        let votes = [500.0, 300.0, 100.0, 60.0, 40.0];
+        assert_eq!(
+            system.distribute_votes(&votes).unwrap(),
+            rules.distribute(&votes, &30, &false).unwrap()
+        );
+        assert_eq!(system.seat_count(), 30);
+    }
+
+    #[test]
+    fn tiers() {
+        let tier = Tier::parse_districts("A,3\nB, 2").unwrap();
+        let system = ElectoralSystem::new("test", sainte_lague(), tier)
+            .with(Constraint::Threshold(0.1))
+            .with_plurality_seats(2);
+        let ballots = Ballots::districts(vec![vec![60.0, 35.0, 5.0], vec![40.0, 55.0, 8.0]])
+            .with_district_winners(vec![0, 1, 1]);
+        let outcome = system.distribute(&ballots).unwrap();
+        assert_eq!(
+            outcome,
+            SystemOutcome {
+                district_seats: vec![0, 1, 1],
+                bonus: vec![0, 0, 0],
+                proportional: vec![vec![2, 1, 0], vec![1, 1, 0]],
+            }
+        );
+        assert_eq!(outcome.list_seats(), vec![3, 2, 0]);
+        assert_eq!(outcome.seats(), vec![3, 3, 1]);
+        assert_eq!(system.seat_count(), 7);
+
+        let districts = [vec![1.0, 2.0, 0.0], vec![0.0, 1.0, 3.0]];
+        let outcome = system.distribute_districts(&ballots, &districts).unwrap();
+        assert_eq!(outcome.district_seats, vec![0, 1, 1]);
+        assert_eq!(Tier::parse_districts("A"), None);
+    }
+
+    #[test]
+    fn electorate() {
+        let system = ElectoralSystem::new("test", sainte_lague(), Tier::National(10))
+            .with(Constraint::Threshold(0.1))
+            .with_threshold_base(ThresholdBase::Electorate);
+        let ballots = Ballots::new(vec![60.0, 30.0, 10.0]);
+        let error = system.distribute(&ballots).unwrap_err();
+        assert_eq!(
+            (error.step(), error.root_cause()),
+            (2, &ConstraintError::InvalidThreshold)
+        );
+        let outcome = system.distribute(&ballots.with_electorate(200.0)).unwrap();
+        assert_eq!(outcome.seats(), vec![7, 3, 0]);
+    }
+
+    #[test]
+    fn errors() {
+        let system = ElectoralSystem::new("test", sainte_lague(), Tier::National(4));
+        let step = |ballots: Ballots, system: &ElectoralSystem| {
+            let error = system.distribute(&ballots).unwrap_err();
+            (error.step(), *error.root_cause())
+        };
+        let unknown = ConstraintError::UnknownParty(1);
+        let ballots = Ballots::new(vec![1.0, 2.0]).with_district_winners(vec![0]);
+        assert_eq!(step(ballots, &system), (1, unknown));
+        let ballots = Ballots::new(vec![1.0, 2.0]).with_district_winners(vec![0, 1]);
+        assert_eq!(step(ballots, &system), (1, ConstraintError::Infeasible));
+
+        let bonus = system.clone().with_bonus(5);
+        assert_eq!(
+            step(Ballots::new(vec![1.0]), &bonus),
+            (3, ConstraintError::Infeasible)
+        );
+        // the bonus is only supported nationally, even with a single district
+        let single = Tier::Districts(vec![("A".to_string(), 10)]);
+        let single = ElectoralSystem::new("test", sainte_lague(), single).with_bonus(4);
+        assert_eq!(
+            step(Ballots::new(vec![5.0, 3.0, 2.0]), &single),
+            (3, ConstraintError::Infeasible)
+        );
+        let bonus = system.clone().with_bonus(1);
+        let tied = DistributionError::Tied.into();
+        assert_eq!(step(Ballots::new(vec![1.0, 1.0]), &bonus), (3, tied));
+        let outcome = bonus
+            .with_draw_on_tie(true)
+            .distribute(&Ballots::new(vec![1.0, 1.0]));
+        assert_eq!(outcome.unwrap().seats().iter().sum::<usize>(), 4);
+
+        let tier = Tier::Districts(vec![("A".to_string(), 2), ("B".to_string(), 1)]);
+        let districts = ElectoralSystem::new("test", sainte_lague(), tier.clone());
+        let ballots = Ballots::districts(vec![vec![1.0, 2.0], vec![1.0]]);
+        assert_eq!(step(ballots, &districts), (2, unknown));
+        let ballots = Ballots::new(vec![1.0, 2.0]);
+        let error = districts.distribute(&ballots).unwrap_err();
+        assert_eq!((error.step(), error.label()), (2, "list votes"));
+        assert_eq!(error.root_cause(), &ConstraintError::UnknownDistrict(1));
+        let ballots = Ballots::districts(vec![vec![1.0, 2.0], vec![1.0, 1.0]]);
+        let error = districts.distribute(&ballots).unwrap_err();
+        assert_eq!((error.step(), error.label()), (5, "B"));
+        let bounded = districts.with(Constraint::MaxSeats(0, 1));
+        let ballots = Ballots::districts(vec![vec![1.0, 2.0], vec![2.0, 1.0]]);
+        assert_eq!(step(ballots, &bounded), (4, ConstraintError::Infeasible));
+    }
+
+    #[test]
+    fn binding_bounds() {
+        let votes = [90.0, 10.0];
+        let constraints = [Constraint::MaxSeats(0, 8), Constraint::MinSeats(1, 3)];
+        let rules = constraints
+            .iter()
+            .fold(Rules::default(), |rules, c| rules.with(c.clone()));
+        assert_eq!(rules.distribute(&votes, &10, &false), Ok(vec![7, 3]));
+        let system = constraints.iter().fold(
+            ElectoralSystem::new("test", sainte_lague(), Tier::National(10)),
+            |system, c| system.with(c.clone()),
+        );
+        assert_eq!(system.distribute_votes(&votes), Ok(vec![7, 3]));
+        let search = DivisorSearch::new(Rounding::HalfUp).unwrap();
+        let system = system.with_search(search);
+        assert_eq!(system.distribute_votes(&votes), Ok(vec![7, 3]));
+
+        let infeasible = ElectoralSystem::new("test", sainte_lague(), Tier::National(10))
+            .with(Constraint::MaxSeats(0, 8))
+            .with(Constraint::MaxSeats(1, 1));
+        let error = infeasible.distribute_votes(&votes).unwrap_err();
+        assert_eq!(
+            (error.step(), error.root_cause()),
+            (4, &ConstraintError::Infeasible)
+        );
+    }
+
+    #[test]
+    fn rounding_at_signposts() {
+        // with a divisor of 2, the quotients are 2.5, 1.5 and 1
+        let votes = [5.0, 3.0, 2.0];
+        let system = ElectoralSystem::new("test", sainte_lague(), Tier::National(5));
+        let tied = DistributionError::Tied.into();
+        let root_cause =
+            |system: &ElectoralSystem| *system.distribute_votes(&votes).unwrap_err().root_cause();
+        assert_eq!(root_cause(&system), tied);
+
+        let half_even = DivisorSearch::new(Rounding::HalfEven).unwrap();
+        let rounded = system.clone().with_search(half_even);
+        assert_eq!(rounded.method, ListMethod::Search(half_even));
+        assert_eq!(rounded.distribute_votes(&votes), Ok(vec![2, 2, 1]));
+        // the rule leaves no tie, so no lots are drawn
+        let rounded = rounded.with_draw_on_tie(true);
+        assert_eq!(rounded.distribute_votes(&votes), Ok(vec![2, 2, 1]));
+
+        let half_up = DivisorSearch::new(Rounding::HalfUp).unwrap();
+        assert_eq!(root_cause(&system.with_search(half_up)), tied);
+    }
+}
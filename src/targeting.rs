@@ -0,0 +1,146 @@
+//! How many additional votes each party needs for one more seat.
+//!
+//! With a divisor method, the votes of one party don't change the quotients of any other party. A party therefore wins an additional seat as soon as the quotient for its next seat exceeds the lowest quotient that currently wins a seat for any other party. Thresholds and other [constraints](crate::constraints) are not taken into account.
+//!
+//! ```
+//! use sainte_lague::targeting::votes_for_next_seat;
+//! use sainte_lague::{distribute, Method};
+//!
+//! let votes = [362.0, 318.0, 126.0];
+//! assert_eq!(distribute(&votes, &10, &false), Ok(vec![4, 4, 2]));
+//!
+//! let needed = votes_for_next_seat(&Method::SainteLague, &votes, &10).unwrap();
+//! assert_eq!(needed[0], Some(16.0));
+//! assert_eq!(distribute(&[379.0, 318.0, 126.0], &10, &false), Ok(vec![5, 4, 1]));
+//! ```
+
+use crate::{DistributionError, Method};
+use std::cmp::Ordering;
+
+/// For each party, the number of additional votes it needs to win one more seat, with the votes of all other parties unchanged. Exactly this number of additional votes results in a tie, any number above wins the seat. Parties already holding all seats have [`None`].
+///
+/// Fails with the same errors as [`Method::distribute`], ties in the current distribution are not resolved.
+pub fn votes_for_next_seat(
+    method: &Method,
+    votes: &[f64],
+    seat_count: &usize,
+) -> Result<Vec<Option<f64>>, DistributionError> {
+    let seats = method.distribute(votes, seat_count, &false)?;
+    // the lowest quotient currently winning a seat for each party
+    let lowest_quotients: Vec<Option<f64>> = votes
+        .iter()
+        .zip(seats.iter())
+        .map(|(v, s)| match s {
+            0 => None,
+            _ => Some(v / method.divisor(s - 1)),
+        })
+        .collect();
+    Ok(votes
+        .iter()
+        .zip(seats.iter())
+        .enumerate()
+        .map(|(party, (v, s))| {
+            let competitor = lowest_quotients
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != party)
+                .filter_map(|(_, q)| *q)
+                .fold(None, |min: Option<f64>, q| match min {
+                    Some(m) if m <= q => Some(m),
+                    _ => Some(q),
+                })?;
+            Some((competitor * method.divisor(*s) - v).max(0.0))
+        })
+        .collect())
+}
+
+/// An opportunity to win one more seat, see [`rank`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Target {
+    /// The index of the district.
+    pub district: usize,
+    /// The index of the party within the district.
+    pub party: usize,
+    /// The number of additional votes needed, see [`votes_for_next_seat`].
+    pub votes: f64,
+}
+
+/// Rank the opportunities of all parties in all districts to win one more seat, cheapest first. Each district is given by its votes and its number of seats.
+///
+/// ```
+/// use sainte_lague::targeting::rank;
+/// use sainte_lague::Method;
+///
+/// let districts = [(vec![362.0, 318.0, 126.0], 10), (vec![50.0, 50.0, 30.0], 3)];
+/// let targets = rank(&Method::SainteLague, &districts).unwrap();
+/// assert_eq!((targets[0].district, targets[0].party, targets[0].votes), (0, 0, 16.0));
+/// ```
+pub fn rank(
+    method: &Method,
+    districts: &[(Vec<f64>, usize)],
+) -> Result<Vec<Target>, DistributionError> {
+    let mut targets = vec![];
+    for (district, (votes, seat_count)) in districts.iter().enumerate() {
+        let needed = votes_for_next_seat(method, votes, seat_count)?;
+        for (party, votes) in needed.into_iter().enumerate() {
+            if let Some(votes) = votes {
+                targets.push(Target {
+                    district,
+                    party,
+                    votes,
+                });
+            }
+        }
+    }
+    targets.sort_by(|a, b| {
+        a.votes
+            .partial_cmp(&b.votes)
+            .unwrap_or(Ordering::Equal)
+            .then(a.district.cmp(&b.district))
+            .then(a.party.cmp(&b.party))
+    });
+    Ok(targets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rank, votes_for_next_seat};
+    use crate::{distribute, DistributionError, Method};
+
+    #[test]
+    fn next_seat() {
+        let votes = [362.0, 318.0, 126.0];
+        let needed = votes_for_next_seat(&Method::SainteLague, &votes, &10).unwrap();
+        assert_eq!(needed[0], Some(16.0));
+        assert_eq!(needed[1], Some(60.0));
+        assert!((needed[2].unwrap() - 101.142857).abs() < 1e-6);
+
+        for (party, extra) in needed.iter().enumerate() {
+            let mut more = votes.to_vec();
+            more[party] += extra.unwrap();
+            assert_eq!(distribute(&more, &10, &false), Err(DistributionError::Tied));
+            more[party] += 0.01;
+            let before = distribute(&votes, &10, &false).unwrap();
+            let after = distribute(&more, &10, &false).unwrap();
+            assert_eq!(after[party], before[party] + 1);
+        }
+    }
+
+    #[test]
+    fn all_seats() {
+        let needed = votes_for_next_seat(&Method::SainteLague, &[10.0, 0.0], &3);
+        assert_eq!(needed, Ok(vec![None, Some(2.0)]));
+    }
+
+    #[test]
+    fn ranking() {
+        let districts = [(vec![362.0, 318.0, 126.0], 10), (vec![50.0, 0.0], 1)];
+        let targets = rank(&Method::SainteLague, &districts).unwrap();
+        let order: Vec<(usize, usize)> = targets.iter().map(|t| (t.district, t.party)).collect();
+        assert_eq!(order, vec![(0, 0), (1, 1), (0, 1), (0, 2)]);
+        assert_eq!(
+            rank(&Method::SainteLague, &[(vec![1.0, 1.0], 1)]),
+            Err(DistributionError::Tied)
+        );
+    }
+}